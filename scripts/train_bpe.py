@@ -0,0 +1,127 @@
+#!/usr/bin/env python3
+"""Train the byte-pair encoding bundled as src/bpe.txt for `aether stats`.
+
+Usage: scripts/train_bpe.py <corpus dir>... [--merges N] > src/bpe.txt
+
+The corpus is every .rs and .md file under the given directories; the
+bundled vocabulary was trained with 8000 merges on the sources of the
+crate's dependencies (~/.cargo/registry/src). Like the tokenizers LLMs use,
+it has never seen Aether, so it measures what a model would pay for a
+program rather than what Aether would like it to pay.
+
+Text is split into pieces the way `tokenizer::pieces` does, then each
+piece's UTF-8 bytes are merged pair by pair. Each output line is one merge,
+`left right`, most frequent first, with bytes outside printable ASCII and
+the backslash written as \\xNN.
+"""
+
+import collections
+import os
+import sys
+
+
+def pieces(text):
+    """Mirror of `tokenizer::pieces` in src/tokenizer.rs"""
+    out = []
+    i, n = 0, len(text)
+    while i < n:
+        start = i
+        c = text[i]
+        if c.isspace():
+            j = i
+            while j < n and text[j].isspace():
+                j += 1
+            if j < n and text[j - 1] == ' ':
+                if j - i > 1:
+                    out.append(text[start:j - 1])
+                    start = j - 1
+                i = j
+            else:
+                out.append(text[start:j])
+                i = j
+                continue
+        c = text[i]
+        j = i + 1
+        if c.isalpha():
+            while j < n and text[j].isalpha():
+                j += 1
+        elif c.isnumeric():
+            while j < n and j - i < 3 and text[j].isnumeric():
+                j += 1
+        else:
+            while j < n and not (text[j].isspace() or text[j].isalnum()):
+                j += 1
+        out.append(text[start:j])
+        i = j
+    return out
+
+
+def escape(token):
+    return ''.join(chr(b) if 0x21 <= b <= 0x7e and b != 0x5c else '\\x%02x' % b for b in token)
+
+
+def train(words, merges):
+    vocab = [(tuple(bytes([b]) for b in word), count) for word, count in words.items()]
+    pairs = collections.Counter()
+    where = collections.defaultdict(set)
+    for index, (parts, count) in enumerate(vocab):
+        for pair in zip(parts, parts[1:]):
+            pairs[pair] += count
+            where[pair].add(index)
+
+    result = []
+    for _ in range(merges):
+        if not pairs:
+            break
+        best = max(pairs, key=lambda pair: (pairs[pair], pair))
+        if pairs[best] < 2:
+            break
+        result.append(best)
+        merged = best[0] + best[1]
+        for index in list(where[best]):
+            parts, count = vocab[index]
+            for pair in zip(parts, parts[1:]):
+                pairs[pair] -= count
+                if pairs[pair] <= 0:
+                    del pairs[pair]
+                where[pair].discard(index)
+            new = []
+            k = 0
+            while k < len(parts):
+                if k + 1 < len(parts) and (parts[k], parts[k + 1]) == best:
+                    new.append(merged)
+                    k += 2
+                else:
+                    new.append(parts[k])
+                    k += 1
+            parts = tuple(new)
+            vocab[index] = (parts, count)
+            for pair in zip(parts, parts[1:]):
+                pairs[pair] += count
+                where[pair].add(index)
+        where.pop(best, None)
+    return result
+
+
+def main():
+    args = sys.argv[1:]
+    merges = 8000
+    if '--merges' in args:
+        at = args.index('--merges')
+        merges = int(args[at + 1])
+        del args[at:at + 2]
+
+    words = collections.Counter()
+    for root in args:
+        for directory, _, files in os.walk(root):
+            for name in files:
+                if name.endswith(('.rs', '.md')):
+                    with open(os.path.join(directory, name), encoding='utf-8', errors='ignore') as f:
+                        words.update(piece.encode('utf-8') for piece in pieces(f.read()))
+
+    for left, right in train(words, merges):
+        print(escape(left), escape(right))
+
+
+if __name__ == '__main__':
+    main()
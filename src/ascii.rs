@@ -0,0 +1,187 @@
+//! Conversion between the glyph and ASCII spellings of Aether source
+//!
+//! `asciify` rewrites each symbol as its `Symbol::ascii` spelling and
+//! `glyphify` rewrites it back as its `Symbol::to_str` glyph. Nothing else
+//! changes: whitespace, comments and literals are copied as written. Where
+//! an ASCII spelling would run into its neighbour, as `ƒname` would become
+//! `fnname`, a backtick keeps the two apart (`fn`name`) and `glyphify` drops
+//! it again, so converting a file and back gives the text it started with.
+//! A name spelled like a symbol, such as `len`, is escaped the same way
+//! (`` `len ``), so every program converts.
+
+use crate::error::{AetherError, Result};
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::normalize;
+use crate::symbols::Symbol;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Rewrite glyph source with ASCII spellings
+///
+/// Names spelled like a symbol get a backtick in front, since they would
+/// otherwise read as the symbol once converted.
+pub fn asciify(source: &str) -> Result<String> {
+    convert(source, true)
+}
+
+/// Rewrite source that uses ASCII spellings with glyphs
+pub fn glyphify(source: &str) -> Result<String> {
+    convert(source, false)
+}
+
+fn lex(source: &str, ascii: bool) -> Result<Vec<Token>> {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.set_ascii_aliases(ascii);
+    lexer.tokenize()
+}
+
+fn convert(source: &str, to_ascii: bool) -> Result<String> {
+    normalize::check_legacy_verify(source)?;
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let tokens = lex(source, !to_ascii)?;
+
+    // Tokens that cover source text, which come in order and never overlap;
+    // the zero-length ones made for interpolation have no text to convert
+    let mut output = String::new();
+    let mut position = 0;
+    let mut previous: Option<String> = None;
+    for token in tokens.iter().filter(|token| token.length > 0) {
+        let gap = &graphemes[position..token.position];
+        if to_ascii {
+            output.push_str(&gap.concat());
+        } else {
+            output.push_str(&without_separators(gap));
+        }
+
+        let written = graphemes[token.position..token.position + token.length].concat();
+        let text = match &token.token_type {
+            TokenType::Symbol(Symbol::Identifier(name)) if to_ascii && Symbol::from_ascii(name).is_some() => {
+                format!("`{}", name)
+            }
+            TokenType::Symbol(symbol) => match (symbol.ascii(), to_ascii) {
+                (Some(ascii), true) => ascii.to_string(),
+                (Some(_), false) => symbol.to_str(),
+                (None, _) => written,
+            },
+            _ => written,
+        };
+        if let Some(before) = previous.filter(|_| gap.is_empty()) {
+            // An escaped name needs a separator too, or its backtick would
+            // only separate
+            if to_ascii && runs_together(&before, text.trim_start_matches('`')) {
+                output.push('`');
+            }
+        }
+        output.push_str(&text);
+        position = token.position + token.length;
+        previous = Some(text);
+    }
+    let rest = &graphemes[position..];
+    if to_ascii {
+        output.push_str(&rest.concat());
+    } else {
+        output.push_str(&without_separators(rest));
+    }
+
+    // The converted text must read as the same tokens
+    let converted = lex(&output, to_ascii)?;
+    let kinds = |tokens: &[Token]| tokens.iter().map(|token| token.token_type.clone()).collect::<Vec<_>>();
+    if kinds(&converted) != kinds(&tokens) {
+        let at = tokens.iter().zip(&converted).find(|(a, b)| a.token_type != b.token_type).map_or(0, |(a, _)| a.position);
+        return Err(AetherError::LexerError(format!(
+            "Converting would change the meaning of the program near position {}",
+            at
+        )));
+    }
+    Ok(output)
+}
+
+/// Whether two pieces of ASCII source read differently side by side than
+/// apart, like `fn` and `name` or `-` and `>`
+fn runs_together(left: &str, right: &str) -> bool {
+    let joinable = |c: Option<char>| c.is_some_and(|c| c.is_ascii_graphic() && c != '"');
+    if !joinable(left.chars().last()) || !joinable(right.chars().next()) {
+        return false;
+    }
+    let kinds = |source: &str| {
+        lex(source, true).map(|tokens| {
+            tokens.into_iter().map(|token| token.token_type).filter(|kind| *kind != TokenType::Eof).collect::<Vec<_>>()
+        })
+    };
+    match (kinds(left), kinds(right), kinds(&format!("{}{}", left, right))) {
+        (Ok(mut apart), Ok(right), Ok(together)) => {
+            apart.extend(right);
+            apart != together
+        }
+        _ => true,
+    }
+}
+
+/// Text between tokens with the backtick separators removed, leaving
+/// comments as written
+fn without_separators(gap: &[&str]) -> String {
+    let mut text = String::new();
+    let mut in_comment = false;
+    for (i, grapheme) in gap.iter().enumerate() {
+        if *grapheme == "/" && gap.get(i + 1) == Some(&"/") {
+            in_comment = true;
+        } else if grapheme.contains(['\n', '\r']) {
+            in_comment = false;
+        }
+        if in_comment || *grapheme != "`" {
+            text.push_str(grapheme);
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_asciify_and_glyphify_round_trip() {
+        let source = "// ƒ stays in comments\nƒgreet: 📥 ⇢ J ▷ u ⨠\n  ◇(u.age ≥ 18): 📤\"ok {u.name ⧺ \"!\"}\" ◆: 🛑400 ⨠\n  ♻3: 🌐\"x\" ⨠ d⟦0⟧ ⨠ [[1]] ->2";
+        let ascii = asciify(source).unwrap();
+        assert_eq!(
+            ascii,
+            "// ƒ stays in comments\nfn`greet: input -> J |> u >>\n  if(u.age >= 18): output\"ok {u.name ++ \"!\"}\" else: halt`400 >>\n  retry`3: http\"x\" >> d[|0|] >> [[1]] -`>2"
+        );
+        assert_eq!(glyphify(&ascii).unwrap(), source);
+
+        // Hand-written ASCII, glyphs mixed in, converts the other way
+        assert_eq!(glyphify("fn f: x |> y >> ⁇ y ^ 2 // `kept`").unwrap(), "ƒ f: x ▷ y ⨠ ⁇ y ↑ 2 // `kept`");
+    }
+
+    #[test]
+    fn test_names_spelled_like_symbols_are_escaped() {
+        let source = "📥 ▷ retry ⨠ 📏len ⨠ ƒlog: 📤log";
+        let ascii = asciify(source).unwrap();
+        assert_eq!(ascii, "input |> `retry >> len``len >> fn``log: output``log");
+        assert_eq!(glyphify(&ascii).unwrap(), source);
+        // Separators only join spellings; between plain names they change the program
+        assert!(glyphify("a`b").is_err());
+        assert!(glyphify("📤 `").is_ok());
+    }
+
+    #[test]
+    fn test_examples_round_trip() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let mut converted = 0;
+        for entry in std::fs::read_dir(root).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "ae") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            // Some examples don't lex yet; every one that does converts
+            if Lexer::new(source.clone()).tokenize().is_err() {
+                continue;
+            }
+            let ascii = asciify(&source).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+            assert_eq!(glyphify(&ascii).unwrap(), source, "{} does not round-trip", path.display());
+            converted += 1;
+        }
+        assert!(converted > 40, "only {} examples converted", converted);
+    }
+}
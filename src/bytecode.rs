@@ -3,14 +3,40 @@
 //! This module defines the bytecode instruction set and provides
 //! serialization/deserialization for .aeb (Aether Bytecode) files.
 
+use crate::bytes;
+use crate::crypto;
 use crate::error::{AetherError, Result};
+use crate::numeric::Decimal;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
 use std::io::{Read, Write};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Magic number for .aeb files: "AEB\0"
 pub const MAGIC_NUMBER: [u8; 4] = [0x41, 0x45, 0x42, 0x00];
 
-/// Bytecode format version
-pub const VERSION: u8 = 1;
+/// Bytecode format version written by `serialize`
+///
+/// Version 1 files (a string pool and the code) are still read.
+pub const VERSION: u8 = 2;
+
+// Section ids of version 2 files
+const SECTION_COMPILER: u8 = 0x01;
+const SECTION_SOURCE_HASH: u8 = 0x02;
+const SECTION_CONSTANTS: u8 = 0x03;
+const SECTION_CODE: u8 = 0x04;
+const SECTION_FUNCTIONS: u8 = 0x05;
+const SECTION_LINES: u8 = 0x06;
+const SECTION_CHECKSUM: u8 = 0x07;
+const SECTION_SIGNATURE: u8 = 0x08;
+
+// Constant type tags in the constants section
+const CONSTANT_STRING: u8 = 0x00;
+const CONSTANT_INTEGER: u8 = 0x01;
+const CONSTANT_DECIMAL: u8 = 0x02;
+const CONSTANT_NUMBER: u8 = 0x03;
+const CONSTANT_BYTES: u8 = 0x04;
 
 /// Bytecode instruction opcodes
 #[derive(Debug, Clone, PartialEq)]
@@ -20,7 +46,7 @@ pub enum Opcode {
     PushNull,
     /// Push boolean onto stack (followed by 1 byte: 0=false, 1=true)
     PushBool,
-    /// Push number onto stack (followed by 8 bytes: f64)
+    /// Push float onto stack (followed by 8 bytes: f64)
     PushNumber,
     /// Push string from constant pool (followed by 4 bytes: index)
     PushString,
@@ -28,6 +54,14 @@ pub enum Opcode {
     Pop,
     /// Duplicate top value on stack
     Dup,
+    /// Swap the top two values on stack
+    Swap,
+    /// Push integer onto stack (followed by 8 bytes: i64)
+    PushInteger,
+    /// Push decimal onto stack (followed by 16 bytes: i128 mantissa, 1 byte: scale)
+    PushDecimal,
+    /// Push typed constant from constant pool (followed by 4 bytes: index)
+    PushConstant,
     
     // Variable operations
     /// Load variable (followed by 4 bytes: name index in constant pool)
@@ -46,6 +80,8 @@ pub enum Opcode {
     Mul,
     /// Divide top two values
     Div,
+    /// Remainder of dividing top two values
+    Mod,
     /// Power operation
     Power,
     /// Square root
@@ -254,6 +290,18 @@ pub enum Opcode {
     EndOfFile,
     /// Skip bytes
     SkipBytes,
+    /// Convert value to bytes (value, encoding on stack)
+    ToBytes,
+    /// Convert value to string (value, encoding on stack)
+    ToText,
+    /// Concatenate two strings or two byte strings (`⧺`)
+    Concat,
+    /// Length of a string, array or byte string (`📏`)
+    Length,
+    /// Pop an index and a value, push the item at that index (`⟦⟧`)
+    Index,
+    /// Pop an end (null for open), a start and a value, push that part (`⊢⊣`)
+    Slice,
     
     // Concurrency operations
     /// Create thread
@@ -291,6 +339,10 @@ impl Opcode {
             Opcode::PushString => 0x03,
             Opcode::Pop => 0x04,
             Opcode::Dup => 0x05,
+            Opcode::Swap => 0x06,
+            Opcode::PushInteger => 0x07,
+            Opcode::PushDecimal => 0x08,
+            Opcode::PushConstant => 0x09,
             
             Opcode::LoadVar => 0x10,
             Opcode::StoreVar => 0x11,
@@ -409,6 +461,12 @@ impl Opcode {
             Opcode::FlushBuffer => 0x84,
             Opcode::EndOfFile => 0x85,
             Opcode::SkipBytes => 0x86,
+            Opcode::ToBytes => 0x87,
+            Opcode::ToText => 0x88,
+            Opcode::Concat => 0x89,
+            Opcode::Length => 0x8A,
+            Opcode::Index => 0x8B,
+            Opcode::Slice => 0x8C,
             
             // Concurrency operations - using 0xBx range (after Await)
             Opcode::Thread => 0xB2,
@@ -423,6 +481,7 @@ impl Opcode {
             
             // Math operations - using 0x2x range (after Root)
             Opcode::Infinity => 0x26,
+            Opcode::Mod => 0x27,
             
             Opcode::End => 0xFF,
         }
@@ -437,6 +496,10 @@ impl Opcode {
             0x03 => Ok(Opcode::PushString),
             0x04 => Ok(Opcode::Pop),
             0x05 => Ok(Opcode::Dup),
+            0x06 => Ok(Opcode::Swap),
+            0x07 => Ok(Opcode::PushInteger),
+            0x08 => Ok(Opcode::PushDecimal),
+            0x09 => Ok(Opcode::PushConstant),
             
             0x10 => Ok(Opcode::LoadVar),
             0x11 => Ok(Opcode::StoreVar),
@@ -555,6 +618,12 @@ impl Opcode {
             0x84 => Ok(Opcode::FlushBuffer),
             0x85 => Ok(Opcode::EndOfFile),
             0x86 => Ok(Opcode::SkipBytes),
+            0x87 => Ok(Opcode::ToBytes),
+            0x88 => Ok(Opcode::ToText),
+            0x89 => Ok(Opcode::Concat),
+            0x8A => Ok(Opcode::Length),
+            0x8B => Ok(Opcode::Index),
+            0x8C => Ok(Opcode::Slice),
             
             // Concurrency operations
             0xB2 => Ok(Opcode::Thread),
@@ -569,6 +638,7 @@ impl Opcode {
             
             // Math operations
             0x26 => Ok(Opcode::Infinity),
+            0x27 => Ok(Opcode::Mod),
             
             0xFF => Ok(Opcode::End),
             
@@ -577,13 +647,231 @@ impl Opcode {
     }
 }
 
+/// Kind of an inline operand following an opcode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperandKind {
+    /// 1 byte: 0=false, 1=true
+    Bool,
+    /// 8 bytes: f64
+    Number,
+    /// 8 bytes: i64
+    Integer,
+    /// 17 bytes: i128 mantissa and scale
+    Decimal,
+    /// 4 bytes: index into the constant pool
+    Constant,
+    /// 4 bytes: absolute code position
+    Target,
+    /// 4 bytes: element count or other plain number
+    Count,
+    /// 1 byte: plain number
+    Byte,
+}
+
+impl OperandKind {
+    /// Encoded size in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            OperandKind::Bool | OperandKind::Byte => 1,
+            OperandKind::Number | OperandKind::Integer => 8,
+            OperandKind::Decimal => 17,
+            OperandKind::Constant | OperandKind::Target | OperandKind::Count => 4,
+        }
+    }
+}
+
+impl Opcode {
+    /// Inline operands that follow this opcode in the code
+    pub fn operand_kinds(&self) -> &'static [OperandKind] {
+        match self {
+            Opcode::PushBool => &[OperandKind::Bool],
+            Opcode::PushNumber => &[OperandKind::Number],
+            Opcode::PushInteger => &[OperandKind::Integer],
+            Opcode::PushDecimal => &[OperandKind::Decimal],
+            Opcode::PushString
+            | Opcode::PushConstant
+            | Opcode::LoadVar
+            | Opcode::StoreVar
+            | Opcode::StoreImmutable
+            | Opcode::ForEach
+            | Opcode::Import
+            | Opcode::TestStart
+            | Opcode::Mock => &[OperandKind::Constant],
+            Opcode::Jump
+            | Opcode::JumpIfFalse
+            | Opcode::JumpIfNull
+            | Opcode::LoopStart
+            | Opcode::LoopEnd
+            | Opcode::TryStart => &[OperandKind::Target],
+            Opcode::MakeArray | Opcode::MakeObject => &[OperandKind::Count],
+            Opcode::Call => &[OperandKind::Count, OperandKind::Byte],
+            Opcode::Retry => &[OperandKind::Byte],
+            _ => &[],
+        }
+    }
+    
+    /// Mnemonic used in disassembly
+    pub fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+    
+    /// Look up an opcode by its mnemonic
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..=u8::MAX)
+            .filter_map(|byte| Opcode::from_byte(byte).ok())
+            .find(|opcode| opcode.name() == name)
+    }
+}
+
+/// Decoded inline operand
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Bool(bool),
+    Number(f64),
+    Integer(i64),
+    Decimal(Decimal),
+    Constant(u32),
+    Target(u32),
+    Count(u32),
+    Byte(u8),
+}
+
+/// A decoded instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    /// Code position of the opcode
+    pub offset: usize,
+    pub opcode: Opcode,
+    pub operands: Vec<Operand>,
+    /// Encoded size including operands
+    pub size: usize,
+}
+
+/// Entry in the constant pool
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    String(String),
+    Integer(i64),
+    Decimal(Decimal),
+    Number(f64),
+    Bytes(Vec<u8>),
+}
+
+impl Constant {
+    /// The string, if this is a string constant
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Constant::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    
+    /// Name of the constant's type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Constant::String(_) => "string",
+            Constant::Integer(_) => "integer",
+            Constant::Decimal(_) => "decimal",
+            Constant::Number(_) => "number",
+            Constant::Bytes(_) => "bytes",
+        }
+    }
+    
+    /// Identical constants, unlike `==` which compares decimals and floats by value
+    fn same(&self, other: &Constant) -> bool {
+        match (self, other) {
+            (Constant::Decimal(a), Constant::Decimal(b)) => a.mantissa() == b.mantissa() && a.scale() == b.scale(),
+            (Constant::Number(a), Constant::Number(b)) => a.to_bits() == b.to_bits(),
+            _ => self == other,
+        }
+    }
+}
+
+impl From<String> for Constant {
+    fn from(value: String) -> Self {
+        Constant::String(value)
+    }
+}
+
+impl From<&str> for Constant {
+    fn from(value: &str) -> Self {
+        Constant::String(value.to_string())
+    }
+}
+
+/// Written as in assembly text: `"text"`, `integer(42)`, `decimal(19.99)`,
+/// `number(0.5)` or `bytes(cafe)`
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::String(s) => write!(f, "{:?}", s),
+            Constant::Integer(n) => write!(f, "integer({})", n),
+            Constant::Decimal(d) => write!(f, "decimal({})", d),
+            Constant::Number(n) => write!(f, "number({:?})", n),
+            Constant::Bytes(b) => write!(f, "bytes({})", bytes::hex(b)),
+        }
+    }
+}
+
+/// Region of source code: 1-based lines and columns, the end column exclusive.
+/// A column of 0 means only the line is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl SourceSpan {
+    /// A span covering a whole line
+    pub fn line(line: u32) -> Self {
+        SourceSpan { line, column: 0, end_line: line, end_column: 0 }
+    }
+
+    /// The part of the span's first line, given as `line_text`, that it covers
+    pub fn excerpt(&self, line_text: &str) -> String {
+        let graphemes: Vec<&str> = line_text.graphemes(true).collect();
+        let start = (self.column as usize).saturating_sub(1).min(graphemes.len());
+        let end = if self.end_line == self.line && self.end_column > self.column {
+            (self.end_column as usize - 1).min(graphemes.len())
+        } else {
+            graphemes.len()
+        };
+        graphemes[start..end].concat().trim().to_string()
+    }
+}
+
+/// `line:column-end_line:end_column`, or just the line when columns are unknown
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == SourceSpan::line(self.line) {
+            write!(f, "{}", self.line)
+        } else {
+            write!(f, "{}:{}-{}:{}", self.line, self.column, self.end_line, self.end_column)
+        }
+    }
+}
+
 /// Bytecode program structure
 #[derive(Debug, Clone)]
 pub struct BytecodeProgram {
-    /// Constant pool (strings)
-    pub constants: Vec<String>,
+    /// Constant pool
+    pub constants: Vec<Constant>,
     /// Bytecode instructions
     pub code: Vec<u8>,
+    /// Function table: (name, entry code position)
+    pub functions: Vec<(String, u32)>,
+    /// Debug info: (code position, source span) pairs in code order; empty when absent
+    pub lines: Vec<(u32, SourceSpan)>,
+    /// Version of the compiler that produced the program
+    pub compiler_version: Option<String>,
+    /// SHA-256 of the source the program was compiled from
+    pub source_hash: Option<[u8; 32]>,
+    /// Public key whose signature was checked when the program was loaded
+    pub signed_by: Option<[u8; 32]>,
+    /// Format version the program was loaded from; `VERSION` when built in memory
+    pub format_version: u8,
 }
 
 impl BytecodeProgram {
@@ -592,13 +880,20 @@ impl BytecodeProgram {
         BytecodeProgram {
             constants: Vec::new(),
             code: Vec::new(),
+            functions: Vec::new(),
+            lines: Vec::new(),
+            compiler_version: None,
+            source_hash: None,
+            signed_by: None,
+            format_version: VERSION,
         }
     }
     
     /// Add a constant to the pool and return its index
-    pub fn add_constant(&mut self, value: String) -> u32 {
+    pub fn add_constant(&mut self, value: impl Into<Constant>) -> u32 {
+        let value = value.into();
         // Check if constant already exists
-        if let Some(idx) = self.constants.iter().position(|s| s == &value) {
+        if let Some(idx) = self.constants.iter().position(|c| c.same(&value)) {
             return idx as u32;
         }
         
@@ -607,6 +902,11 @@ impl BytecodeProgram {
         idx
     }
     
+    /// String constant at an index
+    pub fn string_constant(&self, idx: usize) -> Option<&str> {
+        self.constants.get(idx).and_then(Constant::as_str)
+    }
+    
     /// Emit an opcode
     pub fn emit_opcode(&mut self, opcode: Opcode) {
         self.code.push(opcode.to_byte());
@@ -627,6 +927,29 @@ impl BytecodeProgram {
         self.code.extend_from_slice(&value.to_be_bytes());
     }
     
+    /// Emit an i64 value (big-endian)
+    pub fn emit_i64(&mut self, value: i64) {
+        self.code.extend_from_slice(&value.to_be_bytes());
+    }
+    
+    /// Emit a decimal as its i128 mantissa (big-endian) and scale byte
+    pub fn emit_decimal(&mut self, value: Decimal) {
+        self.code.extend_from_slice(&value.mantissa().to_be_bytes());
+        self.code.push(value.scale() as u8);
+    }
+    
+    /// Emit a decoded operand in its encoded form
+    pub fn emit_operand(&mut self, operand: &Operand) {
+        match operand {
+            Operand::Bool(b) => self.emit_u8(*b as u8),
+            Operand::Number(n) => self.emit_f64(*n),
+            Operand::Integer(n) => self.emit_i64(*n),
+            Operand::Decimal(d) => self.emit_decimal(*d),
+            Operand::Constant(n) | Operand::Target(n) | Operand::Count(n) => self.emit_u32(*n),
+            Operand::Byte(b) => self.emit_u8(*b),
+        }
+    }
+    
     /// Get current code position
     pub fn position(&self) -> usize {
         self.code.len()
@@ -638,44 +961,410 @@ impl BytecodeProgram {
         self.code[position..position + 4].copy_from_slice(&bytes);
     }
     
-    /// Serialize bytecode to writer
-    pub fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
-        // Write magic number
-        writer.write_all(&MAGIC_NUMBER)
-            .map_err(|e| AetherError::IoError(format!("Failed to write magic number: {}", e)))?;
+    /// Record that code emitted from here on comes from a source span
+    pub fn mark_span(&mut self, span: SourceSpan) {
+        let position = self.code.len() as u32;
+        match self.lines.last_mut() {
+            Some((_, last)) if *last == span => {}
+            Some((offset, last)) if *offset == position => *last = span,
+            _ => self.lines.push((position, span)),
+        }
+    }
+    
+    /// Source span of the code at a position, if debug info is present
+    pub fn span_at(&self, position: usize) -> Option<SourceSpan> {
+        self.lines
+            .iter()
+            .take_while(|(offset, _)| *offset as usize <= position)
+            .last()
+            .map(|(_, span)| *span)
+    }
+    
+    /// Decode the instruction at a code position
+    pub fn decode(&self, offset: usize) -> Result<Instruction> {
+        let byte = *self.code.get(offset).ok_or_else(|| {
+            AetherError::BytecodeError(format!("No instruction at position {}", offset))
+        })?;
+        let opcode = Opcode::from_byte(byte)?;
+        let mut position = offset + 1;
+        let mut operands = Vec::new();
+        
+        for kind in opcode.operand_kinds() {
+            let bytes = self.code.get(position..position + kind.size()).ok_or_else(|| {
+                AetherError::BytecodeError(format!(
+                    "Truncated {:?} operand at position {}",
+                    opcode, offset
+                ))
+            })?;
+            let word = || u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            operands.push(match kind {
+                OperandKind::Bool => Operand::Bool(bytes[0] != 0),
+                OperandKind::Byte => Operand::Byte(bytes[0]),
+                OperandKind::Number => Operand::Number(f64::from_be_bytes(bytes.try_into().unwrap())),
+                OperandKind::Integer => Operand::Integer(i64::from_be_bytes(bytes.try_into().unwrap())),
+                OperandKind::Decimal => {
+                    let mantissa = i128::from_be_bytes(bytes[..16].try_into().unwrap());
+                    Operand::Decimal(Decimal::new(mantissa, bytes[16] as u32))
+                }
+                OperandKind::Constant => Operand::Constant(word()),
+                OperandKind::Target => Operand::Target(word()),
+                OperandKind::Count => Operand::Count(word()),
+            });
+            position += kind.size();
+        }
         
-        // Write version
-        writer.write_all(&[VERSION])
-            .map_err(|e| AetherError::IoError(format!("Failed to write version: {}", e)))?;
+        Ok(Instruction {
+            offset,
+            opcode,
+            operands,
+            size: position - offset,
+        })
+    }
+    
+    /// Render the program as assembly text that `assemble` reads back
+    ///
+    /// Jump targets and function entries become labels and constant operands
+    /// are shown as their values. With the original source, `.line` directives
+    /// carry the line text.
+    pub fn disassemble(&self, source: Option<&str>) -> String {
+        let source_lines: Vec<&str> = source.map(|s| s.lines().collect()).unwrap_or_default();
+        
+        // Decode everything first so jump targets can be labelled
+        let mut instructions = Vec::new();
+        let mut offset = 0;
+        while offset < self.code.len() {
+            match self.decode(offset) {
+                Ok(instruction) => {
+                    offset += instruction.size;
+                    instructions.push(Ok(instruction));
+                }
+                Err(err) => {
+                    instructions.push(Err((offset, err)));
+                    offset += 1;
+                }
+            }
+        }
         
-        // Write constant pool size
-        let const_count = self.constants.len() as u32;
-        writer.write_all(&const_count.to_be_bytes())
-            .map_err(|e| AetherError::IoError(format!("Failed to write constant count: {}", e)))?;
+        let starts: Vec<usize> = instructions
+            .iter()
+            .filter_map(|i| i.as_ref().ok().map(|i| i.offset))
+            .chain(std::iter::once(self.code.len()))
+            .collect();
+        let mut targets: Vec<usize> = instructions
+            .iter()
+            .filter_map(|i| i.as_ref().ok())
+            .flat_map(|i| i.operands.iter())
+            .filter_map(|operand| match operand {
+                Operand::Target(target) => Some(*target as usize),
+                _ => None,
+            })
+            .chain(self.functions.iter().map(|(_, entry)| *entry as usize))
+            .filter(|target| starts.contains(target))
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        let label = |target: usize| targets.binary_search(&target).ok().map(|n| format!("L{}", n));
+        let target = |target: usize| label(target).unwrap_or_else(|| format!("@{}", target));
         
-        // Write constants
+        let mut out = String::new();
+        out.push_str(&format!(
+            "; Aether bytecode v{}: {} constants, {} bytes of code\n",
+            self.format_version,
+            self.constants.len(),
+            self.code.len()
+        ));
+        if let Some(version) = &self.compiler_version {
+            out.push_str(&format!(".compiler {:?}\n", version));
+        }
+        if let Some(hash) = &self.source_hash {
+            out.push_str(&format!(".source_hash {}\n", bytes::hex(hash)));
+        }
+        if let Some(public_key) = &self.signed_by {
+            // Assembling does not re-sign, so this is informational only
+            out.push_str(&format!("; signed by {}\n", bytes::hex(public_key)));
+        }
         for constant in &self.constants {
-            let bytes = constant.as_bytes();
-            let len = bytes.len() as u32;
-            writer.write_all(&len.to_be_bytes())
-                .map_err(|e| AetherError::IoError(format!("Failed to write constant length: {}", e)))?;
-            writer.write_all(bytes)
-                .map_err(|e| AetherError::IoError(format!("Failed to write constant: {}", e)))?;
+            out.push_str(&format!(".const {}\n", constant));
+        }
+        for (name, entry) in &self.functions {
+            out.push_str(&format!(".function {:?} {}\n", name, target(*entry as usize)));
         }
+        out.push('\n');
         
-        // Write code size
-        let code_size = self.code.len() as u32;
-        writer.write_all(&code_size.to_be_bytes())
-            .map_err(|e| AetherError::IoError(format!("Failed to write code size: {}", e)))?;
+        let mut current_span = None;
+        for entry in &instructions {
+            let offset = match entry {
+                Ok(instruction) => instruction.offset,
+                Err((offset, _)) => *offset,
+            };
+            
+            if let Some(&(_, span)) = self.lines.iter().find(|(position, _)| *position as usize == offset) {
+                if current_span != Some(span) {
+                    current_span = Some(span);
+                    match source_lines.get((span.line as usize).wrapping_sub(1)) {
+                        Some(text) => out.push_str(&format!(".line {}  ; {}\n", span, span.excerpt(text))),
+                        None => out.push_str(&format!(".line {}\n", span)),
+                    }
+                }
+            }
+            if let Some(name) = label(offset) {
+                out.push_str(&format!("{}:\n", name));
+            }
+            
+            match entry {
+                Ok(instruction) => {
+                    let mut text = format!("{:04}  {}", offset, instruction.opcode.name());
+                    for operand in &instruction.operands {
+                        text.push(' ');
+                        text.push_str(&match operand {
+                            Operand::Bool(value) => value.to_string(),
+                            Operand::Number(value) => format!("{:?}", value),
+                            Operand::Integer(value) => value.to_string(),
+                            Operand::Decimal(value) => value.to_string(),
+                            Operand::Constant(index) => match self.constants.get(*index as usize) {
+                                Some(constant) => constant.to_string(),
+                                None => format!("#{}", index),
+                            },
+                            Operand::Target(position) => target(*position as usize),
+                            Operand::Count(value) => value.to_string(),
+                            Operand::Byte(value) => value.to_string(),
+                        });
+                    }
+                    out.push_str(&text);
+                }
+                Err((_, err)) => {
+                    out.push_str(&format!("{:04}  .byte 0x{:02X}  ; {}", offset, self.code[offset], err));
+                }
+            }
+            out.push('\n');
+        }
         
-        // Write code
-        writer.write_all(&self.code)
-            .map_err(|e| AetherError::IoError(format!("Failed to write code: {}", e)))?;
+        if let Some(name) = label(self.code.len()) {
+            out.push_str(&format!("{}:\n", name));
+        }
         
-        Ok(())
+        out
+    }
+    
+    /// Build a program from assembly text in the format `disassemble` produces
+    ///
+    /// Each line holds an optional code position, then an opcode mnemonic and its
+    /// operands, a `label:`, or a `.const`, `.function`, `.line`, `.byte`,
+    /// `.compiler` or `.source_hash` directive. Constant operands are written as
+    /// values or `#index`; targets are labels or `@position`.
+    /// Everything after `;` is a comment.
+    pub fn assemble(text: &str) -> Result<Self> {
+        let mut program = BytecodeProgram::new();
+        let mut labels: HashMap<String, u32> = HashMap::new();
+        // Label references: (code position or function index, label, source line, is a function entry)
+        let mut patches: Vec<(usize, String, usize, bool)> = Vec::new();
+        
+        for (number, raw) in text.lines().enumerate() {
+            let line_number = number + 1;
+            let error = |message: String| AetherError::BytecodeError(format!("line {}: {}", line_number, message));
+            let mut words = asm_words(raw).map_err(error)?.into_iter().peekable();
+            
+            // Code positions printed by the disassembler are informational
+            words.next_if(|word| word.chars().all(|c| c.is_ascii_digit()));
+            let Some(head) = words.next() else { continue };
+            let mut operand = |what: &str| {
+                words.next().ok_or_else(|| error(format!("{} expects {}", head, what)))
+            };
+            
+            if let Some(name) = head.strip_suffix(':') {
+                if labels.insert(name.to_string(), program.position() as u32).is_some() {
+                    return Err(error(format!("Duplicate label '{}'", name)));
+                }
+            } else if head == ".const" {
+                let word = operand("a constant")?;
+                program.constants.push(asm_constant(&word).map_err(error)?);
+            } else if head == ".function" {
+                let name = asm_string(&operand("a name")?).map_err(error)?;
+                let entry = operand("an entry label")?;
+                match entry.strip_prefix('@') {
+                    Some(position) => {
+                        let position = position.parse().map_err(|_| error(format!("Invalid position '{}'", entry)))?;
+                        program.functions.push((name, position));
+                    }
+                    None => {
+                        patches.push((program.functions.len(), entry, line_number, true));
+                        program.functions.push((name, 0));
+                    }
+                }
+            } else if head == ".line" {
+                let word = operand("a line or span")?;
+                program.mark_span(asm_span(&word).ok_or_else(|| error(format!("Invalid span '{}'", word)))?);
+            } else if head == ".compiler" {
+                program.compiler_version = Some(asm_string(&operand("a version")?).map_err(error)?);
+            } else if head == ".source_hash" {
+                let word = operand("a SHA-256 hash")?;
+                let hash = bytes::decode(&word, "hex").ok().and_then(|h| h.try_into().ok())
+                    .ok_or_else(|| error(format!("Invalid SHA-256 hash '{}'", word)))?;
+                program.source_hash = Some(hash);
+            } else if head == ".byte" {
+                for word in words.by_ref() {
+                    program.emit_u8(asm_integer(&word).ok_or_else(|| error(format!("Invalid byte '{}'", word)))?);
+                }
+            } else {
+                let opcode = Opcode::from_name(&head).ok_or_else(|| error(format!("Unknown opcode '{}'", head)))?;
+                program.emit_opcode(opcode.clone());
+                
+                for kind in opcode.operand_kinds() {
+                    let word = words.next()
+                        .ok_or_else(|| error(format!("{} expects a {:?} operand", head, kind)))?;
+                    let invalid = || error(format!("Invalid {:?} operand '{}'", kind, word));
+                    match kind {
+                        OperandKind::Bool => match word.as_str() {
+                            "true" => program.emit_u8(1),
+                            "false" => program.emit_u8(0),
+                            _ => return Err(invalid()),
+                        },
+                        OperandKind::Number => program.emit_f64(word.parse().map_err(|_| invalid())?),
+                        OperandKind::Integer => program.emit_i64(word.parse().map_err(|_| invalid())?),
+                        OperandKind::Decimal => {
+                            let value = Decimal::parse(&word).ok_or_else(invalid)?;
+                            program.emit_decimal(value);
+                        }
+                        OperandKind::Constant => {
+                            let index = match word.strip_prefix('#') {
+                                Some(index) => index.parse().map_err(|_| invalid())?,
+                                None => program.add_constant(asm_constant(&word).map_err(error)?),
+                            };
+                            program.emit_u32(index);
+                        }
+                        OperandKind::Target => {
+                            match word.strip_prefix('@') {
+                                Some(target) => program.emit_u32(target.parse().map_err(|_| invalid())?),
+                                None => {
+                                    patches.push((program.position(), word.clone(), line_number, false));
+                                    program.emit_u32(0);
+                                }
+                            }
+                        }
+                        OperandKind::Count => program.emit_u32(word.parse().map_err(|_| invalid())?),
+                        OperandKind::Byte => program.emit_u8(asm_integer(&word).ok_or_else(invalid)?),
+                    }
+                }
+            }
+            
+            if let Some(extra) = words.next() {
+                return Err(error(format!("Unexpected '{}'", extra)));
+            }
+        }
+        
+        for (position, name, line_number, is_function) in patches {
+            let target = *labels.get(&name).ok_or_else(|| {
+                AetherError::BytecodeError(format!("line {}: Undefined label '{}'", line_number, name))
+            })?;
+            if is_function {
+                program.functions[position].1 = target;
+            } else {
+                program.patch_u32(position, target);
+            }
+        }
+        
+        Ok(program)
+    }
+    
+    /// Serialize bytecode to writer in the current (version 2) format
+    ///
+    /// After the magic number and version comes a section count, then each
+    /// section as a 1-byte id, a 4-byte length and its contents. The last
+    /// section is a SHA-256 checksum of everything before it.
+    pub fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.write_sections(writer, None)
+    }
+    
+    /// Serialize like [`serialize`](Self::serialize), then append an Ed25519
+    /// signature of the whole file made with a secret key
+    pub fn serialize_signed<W: Write>(&self, writer: &mut W, secret_key: &[u8; 32]) -> Result<()> {
+        self.write_sections(writer, Some(secret_key))
+    }
+    
+    fn write_sections<W: Write>(&self, writer: &mut W, secret_key: Option<&[u8; 32]>) -> Result<()> {
+        let mut sections: Vec<(u8, Vec<u8>)> = Vec::new();
+        
+        if let Some(version) = &self.compiler_version {
+            sections.push((SECTION_COMPILER, version.as_bytes().to_vec()));
+        }
+        if let Some(hash) = &self.source_hash {
+            sections.push((SECTION_SOURCE_HASH, hash.to_vec()));
+        }
+        
+        let mut constants = (self.constants.len() as u32).to_be_bytes().to_vec();
+        for constant in &self.constants {
+            match constant {
+                Constant::String(s) => {
+                    constants.push(CONSTANT_STRING);
+                    constants.extend_from_slice(&(s.len() as u32).to_be_bytes());
+                    constants.extend_from_slice(s.as_bytes());
+                }
+                Constant::Integer(n) => {
+                    constants.push(CONSTANT_INTEGER);
+                    constants.extend_from_slice(&n.to_be_bytes());
+                }
+                Constant::Decimal(d) => {
+                    constants.push(CONSTANT_DECIMAL);
+                    constants.extend_from_slice(&d.mantissa().to_be_bytes());
+                    constants.push(d.scale() as u8);
+                }
+                Constant::Number(n) => {
+                    constants.push(CONSTANT_NUMBER);
+                    constants.extend_from_slice(&n.to_be_bytes());
+                }
+                Constant::Bytes(b) => {
+                    constants.push(CONSTANT_BYTES);
+                    constants.extend_from_slice(&(b.len() as u32).to_be_bytes());
+                    constants.extend_from_slice(b);
+                }
+            }
+        }
+        sections.push((SECTION_CONSTANTS, constants));
+        sections.push((SECTION_CODE, self.code.clone()));
+        
+        if !self.functions.is_empty() {
+            let mut functions = (self.functions.len() as u32).to_be_bytes().to_vec();
+            for (name, entry) in &self.functions {
+                functions.extend_from_slice(&(name.len() as u32).to_be_bytes());
+                functions.extend_from_slice(name.as_bytes());
+                functions.extend_from_slice(&entry.to_be_bytes());
+            }
+            sections.push((SECTION_FUNCTIONS, functions));
+        }
+        if !self.lines.is_empty() {
+            let mut lines = (self.lines.len() as u32).to_be_bytes().to_vec();
+            for (position, span) in &self.lines {
+                for value in [*position, span.line, span.column, span.end_line, span.end_column] {
+                    lines.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+            sections.push((SECTION_LINES, lines));
+        }
+        
+        // The checksum and signature sections are counted but written last,
+        // each covering every byte before it
+        let trailing = 1 + secret_key.is_some() as u32;
+        let mut out = MAGIC_NUMBER.to_vec();
+        out.push(VERSION);
+        out.extend_from_slice(&(sections.len() as u32 + trailing).to_be_bytes());
+        for (id, contents) in &sections {
+            push_section(&mut out, *id, contents);
+        }
+        let checksum = Sha256::digest(&out);
+        push_section(&mut out, SECTION_CHECKSUM, &checksum);
+        if let Some(secret_key) = secret_key {
+            let mut signature = crypto::public_key(secret_key).to_vec();
+            signature.extend_from_slice(&crypto::sign_bytes(secret_key, &out));
+            push_section(&mut out, SECTION_SIGNATURE, &signature);
+        }
+        
+        writer.write_all(&out)
+            .map_err(|e| AetherError::IoError(format!("Failed to write bytecode: {}", e)))
     }
     
     /// Deserialize bytecode from reader
+    ///
+    /// Reads version 2 files and version 1 files, which have only string constants and code.
     pub fn deserialize<R: Read>(reader: &mut R) -> Result<Self> {
         // Read and verify magic number
         let mut magic = [0u8; 4];
@@ -691,13 +1380,18 @@ impl BytecodeProgram {
         reader.read_exact(&mut version)
             .map_err(|e| AetherError::IoError(format!("Failed to read version: {}", e)))?;
         
-        if version[0] != VERSION {
-            return Err(AetherError::BytecodeError(format!(
+        match version[0] {
+            1 => Self::deserialize_v1(reader),
+            2 => Self::deserialize_v2(reader),
+            other => Err(AetherError::BytecodeError(format!(
                 "Unsupported bytecode version: {}",
-                version[0]
-            )));
+                other
+            ))),
         }
-        
+    }
+    
+    /// Read the body of a version 1 file: string constant pool, then code
+    fn deserialize_v1<R: Read>(reader: &mut R) -> Result<Self> {
         // Read constant pool size
         let mut const_count_bytes = [0u8; 4];
         reader.read_exact(&mut const_count_bytes)
@@ -705,7 +1399,7 @@ impl BytecodeProgram {
         let const_count = u32::from_be_bytes(const_count_bytes) as usize;
         
         // Read constants
-        let mut constants = Vec::with_capacity(const_count);
+        let mut constants = Vec::with_capacity(const_count.min(1024));
         for _ in 0..const_count {
             let mut len_bytes = [0u8; 4];
             reader.read_exact(&mut len_bytes)
@@ -718,7 +1412,7 @@ impl BytecodeProgram {
             
             let string = String::from_utf8(bytes)
                 .map_err(|e| AetherError::BytecodeError(format!("Invalid UTF-8 in constant: {}", e)))?;
-            constants.push(string);
+            constants.push(Constant::String(string));
         }
         
         // Read code size
@@ -732,7 +1426,294 @@ impl BytecodeProgram {
         reader.read_exact(&mut code)
             .map_err(|e| AetherError::IoError(format!("Failed to read code: {}", e)))?;
         
-        Ok(BytecodeProgram { constants, code })
+        Ok(BytecodeProgram { constants, code, format_version: 1, ..BytecodeProgram::new() })
+    }
+    
+    /// Read the sections of a version 2 file; unknown sections are skipped
+    ///
+    /// The checksum is required and a signature, when present, must match the
+    /// public key stored with it. Nothing may follow either of them.
+    fn deserialize_v2<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut data = MAGIC_NUMBER.to_vec();
+        data.push(2);
+        let header = data.len();
+        reader.read_to_end(&mut data)
+            .map_err(|e| AetherError::IoError(format!("Failed to read bytecode: {}", e)))?;
+        let mut file = SectionReader::new("file", &data[header..]);
+        let mut program = BytecodeProgram::new();
+        let mut checked = false;
+        
+        for _ in 0..file.u32()? {
+            // Everything before this section, as covered by a checksum or signature
+            let covered = &data[..header + file.position];
+            let id = file.u8()?;
+            let length = file.u32()? as usize;
+            let contents = file.take(length)?;
+            
+            if program.signed_by.is_some() {
+                return Err(AetherError::BytecodeError("Unexpected section after the signature".to_string()));
+            }
+            if checked && id != SECTION_SIGNATURE {
+                return Err(AetherError::BytecodeError("Unexpected section after the checksum".to_string()));
+            }
+            
+            match id {
+                SECTION_CHECKSUM => {
+                    if contents != Sha256::digest(covered).as_slice() {
+                        return Err(AetherError::BytecodeError(
+                            "Checksum mismatch: the bytecode file is corrupt".to_string(),
+                        ));
+                    }
+                    checked = true;
+                }
+                SECTION_SIGNATURE => {
+                    let invalid = || AetherError::BytecodeError("Invalid signature: the bytecode file has been modified".to_string());
+                    if contents.len() != 96 {
+                        return Err(invalid());
+                    }
+                    let (public, signature) = contents.split_at(32);
+                    let public: [u8; 32] = public.try_into().map_err(|_| invalid())?;
+                    let signature: [u8; 64] = signature.try_into().map_err(|_| invalid())?;
+                    if !crypto::verify_bytes(&public, covered, &signature) {
+                        return Err(invalid());
+                    }
+                    program.signed_by = Some(public);
+                }
+                SECTION_COMPILER => {
+                    program.compiler_version = Some(SectionReader::new("compiler", contents).string(length)?);
+                }
+                SECTION_SOURCE_HASH => {
+                    let hash = contents.try_into().map_err(|_| {
+                        AetherError::BytecodeError("Source hash section must be 32 bytes".to_string())
+                    })?;
+                    program.source_hash = Some(hash);
+                }
+                SECTION_CONSTANTS => {
+                    let mut section = SectionReader::new("constants", contents);
+                    for _ in 0..section.u32()? {
+                        let constant = match section.u8()? {
+                            CONSTANT_STRING => {
+                                let len = section.u32()? as usize;
+                                Constant::String(section.string(len)?)
+                            }
+                            CONSTANT_INTEGER => Constant::Integer(i64::from_be_bytes(section.array()?)),
+                            CONSTANT_DECIMAL => {
+                                let mantissa = i128::from_be_bytes(section.array()?);
+                                Constant::Decimal(Decimal::new(mantissa, section.u8()? as u32))
+                            }
+                            CONSTANT_NUMBER => Constant::Number(f64::from_be_bytes(section.array()?)),
+                            CONSTANT_BYTES => {
+                                let len = section.u32()? as usize;
+                                Constant::Bytes(section.take(len)?.to_vec())
+                            }
+                            tag => {
+                                return Err(AetherError::BytecodeError(format!("Unknown constant type: 0x{:02X}", tag)));
+                            }
+                        };
+                        program.constants.push(constant);
+                    }
+                }
+                SECTION_CODE => program.code = contents.to_vec(),
+                SECTION_FUNCTIONS => {
+                    let mut section = SectionReader::new("functions", contents);
+                    for _ in 0..section.u32()? {
+                        let len = section.u32()? as usize;
+                        let name = section.string(len)?;
+                        program.functions.push((name, section.u32()?));
+                    }
+                }
+                SECTION_LINES => {
+                    let mut section = SectionReader::new("lines", contents);
+                    for _ in 0..section.u32()? {
+                        let position = section.u32()?;
+                        let span = SourceSpan {
+                            line: section.u32()?,
+                            column: section.u32()?,
+                            end_line: section.u32()?,
+                            end_column: section.u32()?,
+                        };
+                        program.lines.push((position, span));
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        if file.position < file.data.len() {
+            return Err(AetherError::BytecodeError("Unexpected data after the last section".to_string()));
+        }
+        if !checked {
+            return Err(AetherError::BytecodeError("Missing checksum section".to_string()));
+        }
+        Ok(program)
+    }
+}
+
+fn push_section(out: &mut Vec<u8>, id: u8, contents: &[u8]) {
+    out.push(id);
+    out.extend_from_slice(&(contents.len() as u32).to_be_bytes());
+    out.extend_from_slice(contents);
+}
+
+/// Bounds-checked reads from a version 2 file or one of its sections
+struct SectionReader<'a> {
+    name: &'static str,
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SectionReader<'a> {
+    fn new(name: &'static str, data: &'a [u8]) -> Self {
+        SectionReader { name, data, position: 0 }
+    }
+    
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.data.len()).ok_or_else(|| {
+            AetherError::BytecodeError(format!("Truncated {} section at byte {}", self.name, self.position))
+        })?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+    
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+    
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+    
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.array()?))
+    }
+    
+    fn string(&mut self, len: usize) -> Result<String> {
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|e| {
+            AetherError::BytecodeError(format!("Invalid UTF-8 in {} section: {}", self.name, e))
+        })
+    }
+}
+
+/// Parse a constant written as in `Constant`'s display form
+fn asm_constant(word: &str) -> std::result::Result<Constant, String> {
+    if word.starts_with('"') {
+        return asm_string(word).map(Constant::String);
+    }
+    let invalid = || format!("Invalid constant '{}'", word);
+    let (kind, value) = word
+        .strip_suffix(')')
+        .and_then(|w| w.split_once('('))
+        .ok_or_else(invalid)?;
+    match kind {
+        "integer" => value.parse().map(Constant::Integer).map_err(|_| invalid()),
+        "decimal" => Decimal::parse(value).map(Constant::Decimal).ok_or_else(invalid),
+        "number" => value.parse().map(Constant::Number).map_err(|_| invalid()),
+        "bytes" => bytes::decode(value, "hex").map(Constant::Bytes).map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse a span written as `line` or `line:column-end_line:end_column`
+fn asm_span(word: &str) -> Option<SourceSpan> {
+    if let Ok(line) = word.parse() {
+        return Some(SourceSpan::line(line));
+    }
+    let (start, end) = word.split_once('-')?;
+    let (line, column) = start.split_once(':')?;
+    let (end_line, end_column) = end.split_once(':')?;
+    Some(SourceSpan {
+        line: line.parse().ok()?,
+        column: column.parse().ok()?,
+        end_line: end_line.parse().ok()?,
+        end_column: end_column.parse().ok()?,
+    })
+}
+
+/// Split an assembly line into words, keeping quoted strings whole and dropping comments
+fn asm_words(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == ';' {
+            break;
+        } else if c == '"' {
+            let mut word = String::from(chars.next().unwrap());
+            loop {
+                match chars.next() {
+                    Some('\\') => {
+                        word.push('\\');
+                        word.extend(chars.next());
+                    }
+                    Some('"') => {
+                        word.push('"');
+                        break;
+                    }
+                    Some(c) => word.push(c),
+                    None => return Err("Unterminated string".to_string()),
+                }
+            }
+            words.push(word);
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == ';' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            words.push(word);
+        }
+    }
+    
+    Ok(words)
+}
+
+/// Decode a quoted string written with Rust-style escapes
+fn asm_string(word: &str) -> std::result::Result<String, String> {
+    let inner = word
+        .strip_prefix('"')
+        .and_then(|w| w.strip_suffix('"'))
+        .ok_or_else(|| format!("Expected a quoted string, found '{}'", word))?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('0') => out.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => out.push(c),
+            Some('u') => {
+                let rest: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("Invalid unicode escape in {}", word))?;
+                out.push(code);
+            }
+            other => return Err(format!("Invalid escape '\\{}' in {}", other.map(String::from).unwrap_or_default(), word)),
+        }
+    }
+    
+    Ok(out)
+}
+
+/// Parse a byte written in decimal or as `0x..` hex
+fn asm_integer(word: &str) -> Option<u8> {
+    match word.strip_prefix("0x") {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => word.parse().ok(),
     }
 }
 
@@ -803,4 +1784,165 @@ mod tests {
         assert_eq!(idx1, idx2);
         assert_eq!(program.constants.len(), 1);
     }
+    
+    #[test]
+    fn test_disassemble_resolves_constants_and_labels() {
+        let program = crate::compiler::Compiler::new()
+            .compile_source("5 ▷ x\n◇(x > 1): 📤\"big\" ◆: 📤\"small\"")
+            .unwrap();
+        let text = program.disassemble(Some("5 ▷ x\n◇(x > 1): 📤\"big\" ◆: 📤\"small\""));
+        
+        assert!(text.contains(".const \"big\""));
+        assert!(text.contains("StoreVar \"x\""));
+        assert!(text.contains("JumpIfFalse L0"));
+        assert!(text.contains("Jump L1"));
+        assert!(text.contains("\nL1:\n"));
+        assert!(text.contains(".line 2:1-2:29  ; ◇(x > 1)"));
+        assert_eq!(program.span_at(0), Some(SourceSpan { line: 1, column: 1, end_line: 1, end_column: 6 }));
+        assert_eq!(program.span_at(program.code.len() - 1).map(|span| span.line), Some(2));
+    }
+    
+    #[test]
+    fn test_assemble_roundtrip() {
+        let mut program = crate::compiler::Compiler::new()
+            .compile_source("\"a\\tb\" ▷ s ⨠ [1, 2.5, ∅] ⇢ ∀i: 📤i\n↻(s ≠ \"\"): 📤\"🖊️\"")
+            .unwrap();
+        program.emit_opcode(Opcode::Call);
+        program.emit_u32(7);
+        program.emit_u8(2);
+        program.emit_opcode(Opcode::PushNumber);
+        program.emit_f64(f64::INFINITY);
+        program.emit_opcode(Opcode::PushBool);
+        program.emit_u8(1);
+        program.emit_u8(0xEE);
+        
+        let text = program.disassemble(None);
+        assert!(text.contains(".byte 0xEE"));
+        let assembled = BytecodeProgram::assemble(&text).unwrap();
+        assert_eq!(assembled.constants, program.constants);
+        assert_eq!(assembled.code, program.code);
+        assert_eq!(assembled.lines, program.lines);
+    }
+    
+    #[test]
+    fn test_assemble_errors() {
+        let error = |text: &str| BytecodeProgram::assemble(text).unwrap_err().to_string();
+        assert!(error("Nop").contains("line 1: Unknown opcode 'Nop'"));
+        assert!(error("Pop\nJump L9").contains("line 2: Undefined label 'L9'"));
+        assert!(error("PushBool yes").contains("Invalid Bool operand 'yes'"));
+        assert!(error("PushString \"open").contains("Unterminated string"));
+        assert!(error("Pop 1").contains("Unexpected '1'"));
+        assert!(error("L0:\nL0:").contains("Duplicate label"));
+    }
+    
+    #[test]
+    fn test_v2_serialization_keeps_typed_constants_and_debug_info() {
+        let source = "ƒ greet: 📤\"hi\"\n19.90 ▷ price ⨠ hex\"cafe\" ▷ tag ⨠ 7";
+        let mut program = crate::compiler::Compiler::new().compile_source(source).unwrap();
+        program.add_constant(Constant::Number(0.5));
+        
+        let mut buffer = Vec::new();
+        program.serialize(&mut buffer).unwrap();
+        assert_eq!(buffer[4], 2);
+        let loaded = BytecodeProgram::deserialize(&mut Cursor::new(buffer)).unwrap();
+        
+        assert_eq!(loaded.code, program.code);
+        assert_eq!(loaded.lines, program.lines);
+        assert_eq!(loaded.functions, vec![("greet".to_string(), program.functions[0].1)]);
+        assert_eq!(loaded.compiler_version.as_deref(), Some(crate::VERSION));
+        assert_eq!(loaded.source_hash, program.source_hash);
+        assert!(loaded.constants.contains(&Constant::Bytes(vec![0xca, 0xfe])));
+        assert!(loaded.constants.contains(&Constant::Integer(7)));
+        assert!(loaded.constants.contains(&Constant::Number(0.5)));
+        assert!(loaded.constants.iter().any(|c| matches!(c, Constant::Decimal(d) if d.to_string() == "19.90")));
+        
+        // The assembly text carries the same information
+        let assembled = BytecodeProgram::assemble(&loaded.disassemble(None)).unwrap();
+        assert_eq!(assembled.constants, loaded.constants);
+        assert_eq!(assembled.functions, loaded.functions);
+        assert_eq!(assembled.source_hash, loaded.source_hash);
+    }
+    
+    #[test]
+    fn test_v1_files_still_load() {
+        let mut file = Cursor::new(include_bytes!("../examples/hello.aeb").to_vec());
+        let program = BytecodeProgram::deserialize(&mut file).unwrap();
+        
+        assert_eq!(program.constants, vec![Constant::from("Hello, Aether!")]);
+        assert!(program.lines.is_empty() && program.compiler_version.is_none());
+        assert!(program.disassemble(None).starts_with("; Aether bytecode v1:"));
+        assert!(crate::VM::new(program).execute().is_ok());
+    }
+    
+    #[test]
+    fn test_v2_sections_are_checked_and_unknown_ones_skipped() {
+        let mut program = BytecodeProgram::new();
+        program.emit_opcode(Opcode::End);
+        let mut buffer = Vec::new();
+        program.serialize(&mut buffer).unwrap();
+        
+        // Insert an unknown section before the checksum, bump the section
+        // count and checksum the result again
+        let mut extended = buffer[..buffer.len() - 37].to_vec();
+        extended[8] += 1;
+        extended.extend_from_slice(&[0x7F, 0, 0, 0, 2, 0xAB, 0xCD]);
+        let checksum = Sha256::digest(&extended);
+        push_section(&mut extended, SECTION_CHECKSUM, &checksum);
+        let loaded = BytecodeProgram::deserialize(&mut Cursor::new(extended)).unwrap();
+        assert_eq!(loaded.code, program.code);
+        
+        buffer.pop();
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(buffer)).unwrap_err();
+        assert!(err.to_string().contains("Truncated file section"));
+    }
+    
+    #[test]
+    fn test_checksum_catches_corruption() {
+        let program = BytecodeProgram::assemble("PushString \"hi\"\nOutput").unwrap();
+        let mut buffer = Vec::new();
+        program.serialize(&mut buffer).unwrap();
+        assert!(BytecodeProgram::deserialize(&mut Cursor::new(buffer.clone())).unwrap().signed_by.is_none());
+        
+        let mut corrupt = buffer.clone();
+        let code = corrupt.len() - 40;
+        corrupt[code] ^= 0x01;
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(corrupt)).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        
+        // Drop the checksum section and its entry in the section count
+        let mut unchecked = buffer[..buffer.len() - 37].to_vec();
+        unchecked[8] -= 1;
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(unchecked)).unwrap_err();
+        assert!(err.to_string().contains("Missing checksum section"));
+    }
+    
+    #[test]
+    fn test_signed_bytecode_records_signer_and_rejects_tampering() {
+        let secret = crypto::generate_secret_key();
+        let program = BytecodeProgram::assemble("PushString \"hi\"\nOutput").unwrap();
+        let mut buffer = Vec::new();
+        program.serialize_signed(&mut buffer, &secret).unwrap();
+        let loaded = BytecodeProgram::deserialize(&mut Cursor::new(buffer.clone())).unwrap();
+        assert_eq!(loaded.signed_by, Some(crypto::public_key(&secret)));
+        assert!(loaded.disassemble(None).contains("; signed by "));
+        
+        // Changing the code and fixing up the checksum still breaks the signature
+        let mut tampered = buffer[..buffer.len() - 101 - 37].to_vec();
+        let code = tampered.len() - 1;
+        tampered[code] = Opcode::Pop.to_byte();
+        let checksum = Sha256::digest(&tampered);
+        push_section(&mut tampered, SECTION_CHECKSUM, &checksum);
+        tampered.extend_from_slice(&buffer[buffer.len() - 101..]);
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(tampered)).unwrap_err();
+        assert!(err.to_string().contains("Invalid signature"));
+        
+        // Nothing can be appended after the signature
+        let mut appended = buffer.clone();
+        appended.extend_from_slice(&[0x7F, 0, 0, 0, 0]);
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(appended.clone())).unwrap_err();
+        assert!(err.to_string().contains("Unexpected data after the last section"));
+        appended[8] += 1;
+        let err = BytecodeProgram::deserialize(&mut Cursor::new(appended)).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
 }
@@ -0,0 +1,366 @@
+//! Binary data backing `Value::Bytes` and the buffer symbol (🧱)
+//!
+//! Bytes are raw octets with no implied text encoding. Converting between
+//! bytes and strings always names an encoding: `text ⇢ 🧱"hex"` decodes text
+//! into bytes and `data ⇢ S"base64"` encodes bytes as text. Both default to
+//! UTF-8. The same helpers decode `hex"…"` and `b64"…"` literals and turn
+//! values into the octets written to files, sockets and crypto primitives.
+
+use crate::error::{AetherError, Result};
+use crate::limits::{Limit, Meter};
+use crate::numeric;
+use crate::runtime::Value;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+/// Encoding used when 🧱 or S is given none
+pub const DEFAULT_ENCODING: &str = "utf8";
+
+/// A named text encoding for bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Utf8,
+    Hex,
+    Base64,
+    /// One character per byte (ISO-8859-1)
+    Latin1,
+}
+
+impl Encoding {
+    fn named(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "utf8" | "utf-8" => Ok(Encoding::Utf8),
+            "hex" => Ok(Encoding::Hex),
+            "base64" | "b64" => Ok(Encoding::Base64),
+            "latin1" | "iso-8859-1" => Ok(Encoding::Latin1),
+            _ => Err(AetherError::RuntimeError(format!(
+                "Unknown encoding '{}'; expected utf8, hex, base64 or latin1",
+                name
+            ))),
+        }
+    }
+}
+
+/// Decode text written in `encoding` into bytes
+pub fn decode(text: &str, encoding: &str) -> Result<Vec<u8>> {
+    match Encoding::named(encoding)? {
+        Encoding::Utf8 => Ok(text.as_bytes().to_vec()),
+        Encoding::Hex => decode_hex(text),
+        Encoding::Base64 => BASE64.decode(text.trim())
+            .map_err(|e| AetherError::RuntimeError(format!("Invalid base64: {}", e))),
+        Encoding::Latin1 => text.chars()
+            .map(|c| u8::try_from(c as u32).map_err(|_| AetherError::RuntimeError(
+                format!("Character '{}' cannot be encoded as latin1", c)
+            )))
+            .collect(),
+    }
+}
+
+/// Lowercase hex digits of some bytes
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Encode bytes as text in `encoding`
+pub fn encode(bytes: &[u8], encoding: &str) -> Result<String> {
+    match Encoding::named(encoding)? {
+        Encoding::Utf8 => String::from_utf8(bytes.to_vec())
+            .map_err(|e| AetherError::RuntimeError(format!("Bytes are not valid utf8: {}", e))),
+        Encoding::Hex => Ok(hex(bytes)),
+        Encoding::Base64 => Ok(BASE64.encode(bytes)),
+        Encoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>> {
+    // Whitespace and underscores may group digits: hex"dead beef", hex"ff_00"
+    let digits: Vec<u8> = text.bytes()
+        .filter(|b| !b.is_ascii_whitespace() && *b != b'_')
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err(AetherError::RuntimeError("Hex data must have an even number of digits".to_string()));
+    }
+    digits.chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).unwrap_or("");
+            u8::from_str_radix(pair, 16)
+                .map_err(|_| AetherError::RuntimeError(format!("Invalid hex digits '{}'", pair)))
+        })
+        .collect()
+}
+
+/// Convert a value to bytes (`value ⇢ 🧱"encoding"`)
+///
+/// Strings are decoded with `encoding`; bytes pass through unchanged and
+/// arrays of numbers 0-255 become their octets.
+pub fn to_bytes(value: &Value, encoding: &Value) -> Result<Value> {
+    let encoding = encoding_name(encoding)?;
+    match value {
+        Value::String(s) => Ok(Value::Bytes(decode(s, encoding)?)),
+        Value::Bytes(_) => Ok(value.clone()),
+        Value::Array(items) => Ok(Value::Bytes(octets(items)?)),
+        _ => Err(AetherError::RuntimeError(
+            "Only strings, bytes and arrays of numbers convert to bytes".to_string()
+        )),
+    }
+}
+
+/// Convert a value to a string (`value ⇢ S"encoding"`)
+///
+/// Bytes are encoded with `encoding`; other values are formatted as text,
+/// which only makes sense without an explicit encoding.
+pub fn to_text(value: &Value, encoding: &Value) -> Result<Value> {
+    match (value, encoding) {
+        (Value::Bytes(b), _) => Ok(Value::String(encode(b, encoding_name(encoding)?)?)),
+        (_, Value::Null) => Ok(Value::String(display(value))),
+        _ => Err(AetherError::RuntimeError("Only bytes can be encoded as text".to_string())),
+    }
+}
+
+/// Concatenate two values (`a ⧺ b`)
+///
+/// Two byte strings make a byte string. Otherwise both sides are formatted as
+/// text, so `"total: " ⧺ 3` is `"total: 3"`; bytes never mix with text. The
+/// result's length is checked against `meter` before it is built.
+pub fn concat(left: &Value, right: &Value, meter: &Meter) -> Result<Value> {
+    match (left, right) {
+        (Value::Bytes(l), Value::Bytes(r)) => {
+            meter.check_length(Limit::StringLength, "byte string", l.len().saturating_add(r.len()))?;
+            Ok(Value::Bytes([l.as_slice(), r].concat()))
+        }
+        (Value::Bytes(_), _) | (_, Value::Bytes(_)) => Err(AetherError::RuntimeError(
+            "Cannot concatenate bytes with other values; convert with 🧱 or S first".to_string(),
+        )),
+        _ => {
+            let (left, right) = (display(left), display(right));
+            meter.check_length(Limit::StringLength, "string", left.len().saturating_add(right.len()))?;
+            Ok(Value::String(left + &right))
+        }
+    }
+}
+
+/// Length of a string in characters, an array in items or bytes in octets (📏)
+pub fn length(value: &Value) -> Result<Value> {
+    match value {
+        Value::String(s) => Ok(Value::Integer(s.chars().count() as i64)),
+        Value::Array(arr) => Ok(Value::Integer(arr.len() as i64)),
+        Value::Bytes(b) => Ok(Value::Integer(b.len() as i64)),
+        _ => Err(AetherError::RuntimeError("Length requires string, array or bytes".to_string())),
+    }
+}
+
+/// Item `index` of a value (`target⟦index⟧`): a character of a string, an
+/// element of an array or a byte as a number 0-255
+pub fn index(target: &Value, index: &Value) -> Result<Value> {
+    let i = position(index, "Index")?;
+    match target {
+        Value::String(s) => s.chars().nth(i)
+            .map(|c| Value::String(c.to_string()))
+            .ok_or_else(|| AetherError::RuntimeError(format!("String index {} out of bounds", i))),
+        Value::Array(arr) => arr.get(i).cloned()
+            .ok_or_else(|| AetherError::RuntimeError(format!("Array index {} out of bounds", i))),
+        Value::Bytes(b) => b.get(i)
+            .map(|byte| Value::Integer(*byte as i64))
+            .ok_or_else(|| AetherError::RuntimeError(format!("Bytes index {} out of bounds", i))),
+        _ => Err(AetherError::RuntimeError("Index access requires string, array or bytes".to_string())),
+    }
+}
+
+/// The part of a value from `start` up to `end` (`target⊢start:end⊣`); a
+/// null `end` runs to the end
+pub fn slice(target: &Value, start: &Value, end: &Value) -> Result<Value> {
+    let start = position(start, "Slice start")?;
+    let end = match end {
+        Value::Null => None,
+        end => Some(position(end, "Slice end")?),
+    };
+    let range = |len: usize| {
+        let end = end.unwrap_or(len);
+        if start <= end && end <= len {
+            Ok(start..end)
+        } else {
+            Err(AetherError::RuntimeError("Slice indices out of bounds".to_string()))
+        }
+    };
+    match target {
+        Value::String(s) => {
+            let chars: Vec<char> = s.chars().collect();
+            Ok(Value::String(chars[range(chars.len())?].iter().collect()))
+        }
+        Value::Array(arr) => Ok(Value::Array(arr[range(arr.len())?].to_vec())),
+        Value::Bytes(b) => Ok(Value::Bytes(b[range(b.len())?].to_vec())),
+        _ => Err(AetherError::RuntimeError("Slice requires string, array or bytes".to_string())),
+    }
+}
+
+/// A non-negative whole number used as an index or slice bound
+pub fn position(value: &Value, what: &str) -> Result<usize> {
+    if !value.is_numeric() {
+        return Err(AetherError::RuntimeError(format!("{} must be a number", what)));
+    }
+    numeric::as_integer(value)
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| AetherError::RuntimeError(format!(
+            "{} must be a non-negative integer, got {}",
+            what,
+            numeric::display(value).unwrap_or_default()
+        )))
+}
+
+/// Create a buffer (🧱size): zeroed bytes of a size, or a copy of existing data
+///
+/// The size is checked against `meter` before anything is allocated.
+pub fn allocate(size: &Value, meter: &Meter) -> Result<Value> {
+    match size {
+        n if n.is_numeric() => match numeric::as_integer(n).and_then(|n| usize::try_from(n).ok()) {
+            Some(size) => {
+                meter.check_length(Limit::StringLength, "byte string", size)?;
+                // The size comes from the program, so a failed allocation is
+                // its error rather than an abort
+                let mut buffer = Vec::new();
+                buffer.try_reserve_exact(size).map_err(|_| {
+                    AetherError::RuntimeError(format!("Cannot allocate a buffer of {} bytes", size))
+                })?;
+                buffer.resize(size, 0);
+                Ok(Value::Bytes(buffer))
+            }
+            None => Err(AetherError::RuntimeError(format!(
+                "Buffer size must be a non-negative integer, got {}",
+                numeric::display(n).unwrap_or_default()
+            ))),
+        },
+        _ => to_bytes(size, &Value::Null),
+    }
+}
+
+/// The octets a value stands for when written to a file, socket or hash
+///
+/// Strings are written as UTF-8 and numbers and booleans as their text.
+pub fn raw(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Bytes(b) => Some(b.clone()),
+        Value::String(s) => Some(s.as_bytes().to_vec()),
+        Value::Number(_) | Value::Integer(_) | Value::Decimal(_) => numeric::display(value).map(String::into_bytes),
+        Value::Boolean(b) => Some(b.to_string().into_bytes()),
+        _ => None,
+    }
+}
+
+/// Data read from outside the program: a string when it is valid UTF-8,
+/// bytes otherwise, so binary data is never mangled
+pub fn text_or_bytes(data: Vec<u8>) -> Value {
+    match String::from_utf8(data) {
+        Ok(s) => Value::String(s),
+        Err(e) => Value::Bytes(e.into_bytes()),
+    }
+}
+
+fn encoding_name(encoding: &Value) -> Result<&str> {
+    match encoding {
+        Value::Null => Ok(DEFAULT_ENCODING),
+        Value::String(s) => Ok(s),
+        _ => Err(AetherError::RuntimeError("Encoding must be a string".to_string())),
+    }
+}
+
+fn octets(items: &[Value]) -> Result<Vec<u8>> {
+    items.iter()
+        .map(|item| numeric::as_integer(item)
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or_else(|| AetherError::RuntimeError(format!("Byte values must be integers 0-255, got {:?}", item))))
+        .collect()
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Integer(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::Limits;
+
+    fn unmetered() -> Meter {
+        Meter::new(Limits::unlimited())
+    }
+
+    #[test]
+    fn test_concat() {
+        let meter = unmetered();
+        let text = |s: &str| Value::String(s.to_string());
+        assert_eq!(concat(&text("n = "), &Value::Integer(3), &meter).unwrap(), text("n = 3"));
+        assert_eq!(concat(&Value::Null, &Value::Boolean(true), &meter).unwrap(), text("nulltrue"));
+        assert_eq!(concat(&Value::Bytes(vec![1]), &Value::Bytes(vec![2]), &meter).unwrap(), Value::Bytes(vec![1, 2]));
+        assert!(concat(&Value::Bytes(vec![1]), &text("x"), &meter).is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = decode("DEAD beef", "hex").unwrap();
+        assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(encode(&bytes, "hex").unwrap(), "deadbeef");
+        assert!(decode("abc", "hex").is_err());
+        assert!(decode("zz", "hex").is_err());
+    }
+
+    #[test]
+    fn test_base64_and_latin1() {
+        assert_eq!(decode("SGVsbG8=", "base64").unwrap(), b"Hello".to_vec());
+        assert_eq!(encode(b"Hello", "b64").unwrap(), "SGVsbG8=");
+        assert_eq!(decode("é", "latin1").unwrap(), vec![0xe9]);
+        assert_eq!(encode(&[0xe9], "latin1").unwrap(), "é");
+        assert!(decode("€", "latin1").is_err());
+    }
+
+    #[test]
+    fn test_utf8_requires_valid_text() {
+        assert_eq!(encode("héllo".as_bytes(), "UTF-8").unwrap(), "héllo");
+        assert!(encode(&[0xff, 0xfe], "utf8").is_err());
+        assert!(encode(b"x", "ebcdic").is_err());
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        let hi = Value::String("hi".to_string());
+        assert_eq!(to_bytes(&hi, &Value::Null).unwrap(), Value::Bytes(b"hi".to_vec()));
+        let arr = Value::Array(vec![Value::Number(1.0), Value::Number(255.0)]);
+        assert_eq!(to_bytes(&arr, &Value::Null).unwrap(), Value::Bytes(vec![1, 255]));
+        assert!(to_bytes(&Value::Array(vec![Value::Number(256.0)]), &Value::Null).is_err());
+
+        let data = Value::Bytes(vec![0, 1]);
+        assert_eq!(to_text(&data, &Value::String("hex".to_string())).unwrap(), Value::String("0001".to_string()));
+        assert_eq!(to_text(&Value::Number(3.0), &Value::Null).unwrap(), Value::String("3".to_string()));
+        assert!(to_text(&Value::Number(3.0), &Value::String("hex".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_allocate_and_raw() {
+        let meter = unmetered();
+        assert_eq!(allocate(&Value::Number(3.0), &meter).unwrap(), Value::Bytes(vec![0, 0, 0]));
+        assert!(allocate(&Value::Number(-1.0), &meter).is_err());
+        assert!(allocate(&Value::Integer(i64::MAX), &meter).is_err());
+        assert_eq!(raw(&Value::Bytes(vec![7])), Some(vec![7]));
+        assert_eq!(raw(&Value::Null), None);
+        assert_eq!(text_or_bytes(b"ok".to_vec()), Value::String("ok".to_string()));
+        assert_eq!(text_or_bytes(vec![0xff]), Value::Bytes(vec![0xff]));
+    }
+
+    #[test]
+    fn test_sizes_are_limited_before_allocating() {
+        let meter = Meter::new(Limits { max_string_length: Some(10), ..Limits::unlimited() });
+        assert!(matches!(
+            allocate(&Value::Integer(100_000_000_000), &meter),
+            Err(AetherError::LimitExceeded(Limit::StringLength, _))
+        ));
+        let long = Value::String("x".repeat(6));
+        assert!(concat(&long, &long, &meter).is_err());
+        assert!(concat(&long, &Value::Integer(1), &meter).is_ok());
+    }
+}
@@ -0,0 +1,499 @@
+//! Static type checker for Aether programs (`aether check`)
+//!
+//! The checker infers a type for every expression without running it and
+//! reports operations that are bound to fail, such as `📏` on a number or
+//! `+` on an object. Bindings may state the type they expect with the type
+//! symbols, `▷ name:S` for a string and `▷ total:N` for a number, and a value
+//! of another type is reported too. Types that cannot be known before running
+//! (input, HTTP responses, array elements) are `Unknown` and never reported,
+//! so a program without diagnostics may still fail at runtime.
+
+use crate::constants::PIPE_VARIABLE;
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::normalize;
+use crate::parser::{AstNode, LiteralValue, Parser, TypeAnnotation};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The static type of an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    String,
+    /// Integer, decimal or float
+    Number,
+    Boolean,
+    Bytes,
+    Array,
+    Object,
+    Null,
+    /// Not known before running; accepted everywhere
+    Unknown,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Type::String => "string",
+            Type::Number => "number",
+            Type::Boolean => "boolean",
+            Type::Bytes => "bytes",
+            Type::Array => "array",
+            Type::Object => "object",
+            Type::Null => "null",
+            Type::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl From<TypeAnnotation> for Type {
+    fn from(annotation: TypeAnnotation) -> Self {
+        match annotation {
+            TypeAnnotation::String => Type::String,
+            TypeAnnotation::Number => Type::Number,
+        }
+    }
+}
+
+/// What a diagnostic is about
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// An operator was given an operand it cannot accept
+    OperandMismatch {
+        operator: &'static str,
+        expected: Vec<Type>,
+        found: Type,
+    },
+    /// A `▷ name:T` binding received a value of another type
+    AnnotationMismatch {
+        variable: String,
+        expected: Type,
+        found: Type,
+    },
+}
+
+/// A type error found before running
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Type check a parsed program
+pub fn check(program: &[AstNode]) -> Vec<Diagnostic> {
+    check_each(program).into_iter().map(|(_, diagnostic)| diagnostic).collect()
+}
+
+/// Type check a parsed program, pairing each diagnostic with the index of
+/// the top-level statement it was found in
+pub fn check_each(program: &[AstNode]) -> Vec<(usize, Diagnostic)> {
+    let mut checker = Checker::default();
+    let mut found = Vec::new();
+    for (index, node) in program.iter().enumerate() {
+        checker.infer(node);
+        found.extend(checker.diagnostics.drain(..).map(|diagnostic| (index, diagnostic)));
+    }
+    found
+}
+
+/// Lex, parse and type check source code
+///
+/// Lexer and parser errors are returned as errors; type errors as diagnostics.
+pub fn check_source(source: &str) -> Result<Vec<Diagnostic>> {
+    normalize::check_legacy_verify(source)?;
+    let tokens = Lexer::new(source.to_string()).tokenize()?;
+    let program = Parser::new(tokens).parse()?;
+    Ok(check(&program))
+}
+
+const NUMBER: &[Type] = &[Type::Number];
+const STRING: &[Type] = &[Type::String];
+const ARRAY: &[Type] = &[Type::Array];
+const BYTES: &[Type] = &[Type::Bytes];
+const SEQUENCE: &[Type] = &[Type::String, Type::Array, Type::Bytes];
+const DATA: &[Type] = &[Type::String, Type::Number, Type::Bytes];
+const BYTES_SOURCE: &[Type] = &[Type::String, Type::Bytes, Type::Array];
+
+#[derive(Default)]
+struct Checker {
+    variables: HashMap<String, Type>,
+    /// Type of the value piped into the current operation
+    pipe: Option<Type>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn infer(&mut self, node: &AstNode) -> Type {
+        match node {
+            AstNode::Literal(lit) => match lit {
+                LiteralValue::String(_) => Type::String,
+                LiteralValue::Number(_) | LiteralValue::Integer(_) | LiteralValue::Decimal(_) => Type::Number,
+                LiteralValue::Bytes(_) => Type::Bytes,
+            },
+            AstNode::ArrayLiteral { elements } => {
+                self.infer_all(elements.iter());
+                Type::Array
+            }
+            AstNode::ObjectLiteral { pairs } => {
+                self.infer_all(pairs.iter().map(|(_, value)| value));
+                Type::Object
+            }
+            AstNode::Variable(name) if name == PIPE_VARIABLE => self.piped(),
+            AstNode::Variable(name) => self.variables.get(name).copied().unwrap_or(Type::Unknown),
+            AstNode::Empty => self.piped(),
+
+            AstNode::Sequence(nodes) => nodes.iter().fold(Type::Null, |_, n| self.infer(n)),
+            AstNode::Output(value) => self.infer(value),
+            AstNode::Pipe { source, operation } => {
+                let source = self.infer(source);
+                let outer = self.pipe.replace(source);
+                let result = self.infer(operation);
+                self.pipe = outer;
+                result
+            }
+            AstNode::PipeInto { value, variable, annotation } => {
+                let found = self.infer(value);
+                let bound = match annotation {
+                    Some(annotation) => {
+                        let expected = Type::from(*annotation);
+                        if !accepts(&[expected], found) {
+                            self.report(DiagnosticKind::AnnotationMismatch {
+                                variable: variable.clone(),
+                                expected,
+                                found,
+                            });
+                        }
+                        expected
+                    }
+                    None => found,
+                };
+                self.variables.insert(variable.clone(), bound);
+                found
+            }
+            AstNode::Immutable { name, value } => {
+                let found = self.infer(value);
+                self.variables.insert(name.clone(), found);
+                found
+            }
+
+            AstNode::Add { left, right } => self.binary("+", NUMBER, left, right, Type::Number),
+            AstNode::Subtract { left, right } => self.binary("-", NUMBER, left, right, Type::Number),
+            AstNode::Multiply { left, right } => self.binary("*", NUMBER, left, right, Type::Number),
+            AstNode::Divide { left, right } => self.binary("/", NUMBER, left, right, Type::Number),
+            AstNode::Modulo { left, right } => self.binary("%", NUMBER, left, right, Type::Number),
+            AstNode::Comparison { left, right, .. } => self.binary("comparison", NUMBER, left, right, Type::Boolean),
+            AstNode::Approx { left, right } => self.binary("≈", NUMBER, left, right, Type::Boolean),
+            AstNode::Power { base, exponent } => self.binary("↑", NUMBER, base, exponent, Type::Number),
+            AstNode::Root { value } => {
+                self.expect("√", NUMBER, value);
+                Type::Number
+            }
+            AstNode::Equal { left, right } | AstNode::NotEqual { left, right }
+            | AstNode::And { left, right } | AstNode::Or { left, right } => {
+                self.infer(left);
+                self.infer(right);
+                Type::Boolean
+            }
+            AstNode::Not { operand } => {
+                self.infer(operand);
+                Type::Boolean
+            }
+
+            AstNode::StringConcat { left, right } => {
+                let l = self.infer(left);
+                let r = self.infer(right);
+                match (l, r) {
+                    (Type::Bytes, Type::Bytes) => Type::Bytes,
+                    (Type::Bytes, other) | (other, Type::Bytes) if other != Type::Unknown => {
+                        self.mismatch("⧺", BYTES, other);
+                        Type::Unknown
+                    }
+                    (Type::Bytes, _) | (_, Type::Bytes) | (Type::Unknown, _) | (_, Type::Unknown) => Type::Unknown,
+                    _ => Type::String,
+                }
+            }
+            AstNode::Length { value } => {
+                self.expect("📏", SEQUENCE, value);
+                Type::Number
+            }
+            AstNode::Index { target, index } => {
+                let target = self.expect("⟦⟧", SEQUENCE, target);
+                self.expect("⟦⟧ index", NUMBER, index);
+                match target {
+                    Type::String => Type::String,
+                    Type::Bytes => Type::Number,
+                    _ => Type::Unknown,
+                }
+            }
+            AstNode::CharAt { target, index } => {
+                self.expect("char at", STRING, target);
+                self.expect("char at index", NUMBER, index);
+                Type::String
+            }
+            AstNode::Slice { target, start, end } => {
+                let target = self.expect("⊢⊣", SEQUENCE, target);
+                self.expect("⊢⊣ start", NUMBER, start);
+                if let Some(end) = end {
+                    self.expect("⊢⊣ end", NUMBER, end);
+                }
+                target
+            }
+            AstNode::Split { target, delimiter } => {
+                self.expect("split", STRING, target);
+                if let Some(delimiter) = delimiter {
+                    self.infer(delimiter);
+                }
+                Type::Array
+            }
+            AstNode::Join { elements, separator } => {
+                self.expect("join", ARRAY, elements);
+                if let Some(separator) = separator {
+                    self.infer(separator);
+                }
+                Type::String
+            }
+            AstNode::ToBytes { value, encoding } => {
+                self.expect("🧱", BYTES_SOURCE, value);
+                self.infer(encoding);
+                Type::Bytes
+            }
+            AstNode::ToText { value, encoding } => {
+                if matches!(encoding.as_ref(), AstNode::Empty) {
+                    self.infer(value);
+                } else {
+                    self.expect("S", BYTES, value);
+                    self.infer(encoding);
+                }
+                Type::String
+            }
+
+            AstNode::Hash { data } => {
+                self.expect("#️⃣", DATA, data);
+                Type::String
+            }
+            AstNode::Encrypt { data, key } | AstNode::Sign { data, key } => {
+                self.expect("🔐/✍️", DATA, data);
+                self.expect("🔐/✍️ key", STRING, key);
+                Type::Bytes
+            }
+            AstNode::VerifySignature { signature, data, key } => {
+                self.infer(signature);
+                self.expect("🔏", DATA, data);
+                self.expect("🔏 key", STRING, key);
+                Type::Boolean
+            }
+
+            AstNode::IfThen { condition, then_branch, else_branch } => {
+                self.infer(condition);
+                let before = self.variables.clone();
+                let then_type = self.infer(then_branch);
+                let after_then = std::mem::replace(&mut self.variables, before);
+                let else_type = match else_branch {
+                    Some(else_branch) => self.infer(else_branch),
+                    None => Type::Null,
+                };
+                self.merge(&after_then);
+                join(then_type, else_type)
+            }
+            AstNode::ForEach { variable, collection, body } => {
+                self.expect("∀", ARRAY, collection);
+                self.widen_assigned(body);
+                self.variables.insert(variable.clone(), Type::Unknown);
+                self.infer(body);
+                self.widen_assigned(body);
+                Type::Array
+            }
+            AstNode::Loop { condition, body } => {
+                self.widen_assigned(body);
+                if let Some(condition) = condition {
+                    self.infer(condition);
+                }
+                self.infer(body);
+                self.widen_assigned(body);
+                Type::Unknown
+            }
+
+            // Everything else is checked inside but its own type is not tracked
+            _ => {
+                self.widen_assigned(node);
+                for child in node.children() {
+                    self.infer(child);
+                }
+                self.widen_assigned(node);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn infer_all<'a>(&mut self, nodes: impl Iterator<Item = &'a AstNode>) {
+        for node in nodes {
+            self.infer(node);
+        }
+    }
+
+    fn piped(&self) -> Type {
+        self.pipe.unwrap_or(Type::Unknown)
+    }
+
+    /// Infer `node` and report it unless its type is one of `expected`
+    fn expect(&mut self, operator: &'static str, expected: &[Type], node: &AstNode) -> Type {
+        let found = self.infer(node);
+        if !accepts(expected, found) {
+            self.mismatch(operator, expected, found);
+        }
+        found
+    }
+
+    fn binary(&mut self, operator: &'static str, expected: &[Type], left: &AstNode, right: &AstNode, result: Type) -> Type {
+        self.expect(operator, expected, left);
+        self.expect(operator, expected, right);
+        result
+    }
+
+    fn mismatch(&mut self, operator: &'static str, expected: &[Type], found: Type) {
+        self.report(DiagnosticKind::OperandMismatch {
+            operator,
+            expected: expected.to_vec(),
+            found,
+        });
+    }
+
+    fn report(&mut self, kind: DiagnosticKind) {
+        let message = match &kind {
+            DiagnosticKind::OperandMismatch { operator, expected, found } => format!(
+                "{} expects {}, found {}",
+                operator,
+                one_of(expected),
+                found
+            ),
+            DiagnosticKind::AnnotationMismatch { variable, expected, found } => format!(
+                "{} is declared {} but bound to {}",
+                variable, expected, found
+            ),
+        };
+        self.diagnostics.push(Diagnostic { kind, message });
+    }
+
+    /// Forget what is known about variables a node may assign, for code
+    /// that runs any number of times (loops, handlers, async bodies)
+    fn widen_assigned(&mut self, node: &AstNode) {
+        let mut names = HashSet::new();
+        assigned(node, &mut names);
+        for name in names {
+            self.variables.insert(name, Type::Unknown);
+        }
+    }
+
+    /// Combine the variables after two alternative branches
+    fn merge(&mut self, other: &HashMap<String, Type>) {
+        for (name, other_type) in other {
+            let merged = match self.variables.get(name) {
+                Some(t) => join(*t, *other_type),
+                None => Type::Unknown,
+            };
+            self.variables.insert(name.clone(), merged);
+        }
+        for (name, t) in self.variables.iter_mut() {
+            if !other.contains_key(name) {
+                *t = Type::Unknown;
+            }
+        }
+    }
+}
+
+fn accepts(expected: &[Type], found: Type) -> bool {
+    found == Type::Unknown || expected.contains(&found)
+}
+
+fn join(a: Type, b: Type) -> Type {
+    if a == b { a } else { Type::Unknown }
+}
+
+fn one_of(types: &[Type]) -> String {
+    let names: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => names.join(""),
+    }
+}
+
+/// Names a node binds with `▷`, `🧊` or `∀`, at any depth
+fn assigned(node: &AstNode, names: &mut HashSet<String>) {
+    match node {
+        AstNode::PipeInto { variable, .. } | AstNode::ForEach { variable, .. } => {
+            names.insert(variable.clone());
+        }
+        AstNode::Immutable { name, .. } => {
+            names.insert(name.clone());
+        }
+        _ => {}
+    }
+    for child in node.children() {
+        assigned(child, names);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        check_source(source).unwrap().into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn test_well_typed_program_has_no_diagnostics() {
+        assert!(messages("\"abc\" ▷ s:S ⨠ 📏s ▷ n:N ⨠ n + 1 ⨠ s ⇢ 📏").is_empty());
+        assert!(messages("19.99 * 3 ▷ total ⨠ total > 50").is_empty());
+        assert!(messages("📥 ▷ u ⨠ 📏u ⨠ u + 1").is_empty());
+    }
+
+    #[test]
+    fn test_operand_mismatches() {
+        assert_eq!(messages("42 ⇢ 📏"), vec!["📏 expects string, array or bytes, found number"]);
+        let diagnostics = check_source("{a: 1} + 2").unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::OperandMismatch {
+            operator: "+",
+            expected: vec![Type::Number],
+            found: Type::Object,
+        });
+        assert_eq!(messages("hex\"00\" ⧺ \"x\""), vec!["⧺ expects bytes, found string"]);
+    }
+
+    #[test]
+    fn test_diagnostics_name_their_statement() {
+        let tokens = Lexer::new("\"a\" ▷ s\ns * 2\n42 ⇢ 📏".to_string()).tokenize().unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let statements: Vec<usize> = check_each(&program).into_iter().map(|(index, _)| index).collect();
+        assert_eq!(statements, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_annotations() {
+        let diagnostics = check_source("42 ▷ u:S").unwrap();
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::AnnotationMismatch {
+            variable: "u".to_string(),
+            expected: Type::String,
+            found: Type::Number,
+        });
+        // An annotation fixes the variable's type for later uses
+        assert_eq!(messages("📥 ▷ u:S ⨠ u * 2"), vec!["* expects number, found string"]);
+    }
+
+    #[test]
+    fn test_branches_and_loops_do_not_guess() {
+        // After the branch x may be a string or a number
+        assert!(messages("1 ▷ x ⨠ ◇(📥): (\"a\" ▷ x) ⨠ x + 1").is_empty());
+        assert_eq!(messages("◇(📥): \"a\" ◆: \"b\" ▷ x ⨠ x + 1"), vec!["+ expects number, found string"]);
+        // A loop body may run again after reassigning n
+        assert!(messages("1 ▷ n ⨠ [1, 2] ⇢ ∀i: (n + 1 ⨠ \"s\" ▷ n)").is_empty());
+    }
+}
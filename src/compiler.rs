@@ -1,9 +1,12 @@
 //! Compiler for converting AST to bytecode
 
-use crate::bytecode::{BytecodeProgram, Opcode};
+use crate::bytecode::{BytecodeProgram, Constant, Opcode, SourceSpan};
 use crate::constants::PIPE_VARIABLE;
 use crate::error::{AetherError, Result};
-use crate::parser::{AstNode, LiteralValue};
+use crate::lexer::Lexer;
+use crate::optimizer::{self, OptLevel};
+use crate::parser::{AstNode, LiteralValue, Parser};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
 /// Compiler context
@@ -11,6 +14,7 @@ pub struct Compiler {
     program: BytecodeProgram,
     functions: HashMap<String, usize>,
     loop_stack: Vec<LoopContext>,
+    opt_level: OptLevel,
 }
 
 #[allow(dead_code)]
@@ -26,17 +30,78 @@ impl Compiler {
             program: BytecodeProgram::new(),
             functions: HashMap::new(),
             loop_stack: Vec::new(),
+            opt_level: OptLevel::O0,
         }
     }
     
+    /// Set how much the compiler optimizes; no optimization by default
+    pub fn set_opt_level(&mut self, level: OptLevel) {
+        self.opt_level = level;
+    }
+    
     /// Compile AST nodes to bytecode
-    pub fn compile(&mut self, nodes: Vec<AstNode>) -> Result<BytecodeProgram> {
+    pub fn compile(&mut self, mut nodes: Vec<AstNode>) -> Result<BytecodeProgram> {
+        optimizer::optimize_ast(&mut nodes, self.opt_level);
         for node in nodes {
             self.compile_node(&node)?;
         }
         
         // Emit end instruction
         self.program.emit_opcode(Opcode::End);
+        optimizer::optimize_bytecode(&mut self.program, self.opt_level);
+        self.program.compiler_version = Some(crate::VERSION.to_string());
+        
+        Ok(self.program.clone())
+    }
+    
+    /// Compile source code, recording the source span of each statement, or
+    /// of each step of a `⨠` sequence, and the source hash as debug info
+    pub fn compile_source(&mut self, source: &str) -> Result<BytecodeProgram> {
+        let mut lexer = Lexer::new(source.to_string());
+        let tokens = lexer.tokenize()?;
+        let mut parser = Parser::new(tokens);
+        let mut nodes = parser.parse()?;
+        optimizer::optimize_ast(&mut nodes, self.opt_level);
+        
+        for (node, steps) in nodes.iter().zip(parser.step_spans()) {
+            let span = |&(start, end): &(usize, usize)| {
+                let (line, column) = lexer.location(start);
+                let (end_line, end_column) = lexer.location(end);
+                SourceSpan {
+                    line: line as u32,
+                    column: column as u32,
+                    end_line: end_line as u32,
+                    end_column: end_column as u32,
+                }
+            };
+            match node {
+                // Each step of a `⨠` sequence gets its own span, so a
+                // debugger can stop on it
+                AstNode::Sequence(operations) if operations.len() == steps.len() && steps.len() > 1 => {
+                    for (operation, step) in operations.iter().zip(steps) {
+                        self.program.mark_span(span(step));
+                        self.compile_node(operation)?;
+                    }
+                }
+                _ => {
+                    let first = steps.first().map(span);
+                    let last = steps.last().map(span);
+                    if let (Some(first), Some(last)) = (first, last) {
+                        self.program.mark_span(SourceSpan {
+                            end_line: last.end_line,
+                            end_column: last.end_column,
+                            ..first
+                        });
+                    }
+                    self.compile_node(node)?;
+                }
+            }
+        }
+        
+        self.program.emit_opcode(Opcode::End);
+        optimizer::optimize_bytecode(&mut self.program, self.opt_level);
+        self.program.source_hash = Some(Sha256::digest(source.as_bytes()).into());
+        self.program.compiler_version = Some(crate::VERSION.to_string());
         
         Ok(self.program.clone())
     }
@@ -76,10 +141,15 @@ impl Compiler {
             
             AstNode::Pipe { source, operation } => {
                 self.compile_node(source)?;
-                self.compile_node(operation)?;
+                // `x ⇢ 🔚` tests the piped value itself; a bare 🔚 is just the marker
+                if matches!(operation.as_ref(), AstNode::EndOfFile) {
+                    self.program.emit_opcode(Opcode::EndOfFile);
+                } else {
+                    self.compile_node(operation)?;
+                }
             }
             
-            AstNode::PipeInto { value, variable } => {
+            AstNode::PipeInto { value, variable, .. } => {
                 self.compile_node(value)?;
                 self.program.emit_opcode(Opcode::Dup);
                 let idx = self.program.add_constant(variable.clone());
@@ -212,6 +282,7 @@ impl Compiler {
                 
                 // Function body
                 let func_start = self.program.position();
+                self.program.functions.push((name.clone(), func_start as u32));
                 self.compile_node(body)?;
                 self.program.emit_opcode(Opcode::Return);
                 
@@ -220,8 +291,7 @@ impl Compiler {
                 self.program.patch_u32(jump_pos, end_pos as u32);
                 
                 // Store function location
-                self.program.emit_opcode(Opcode::PushNumber);
-                self.program.emit_f64(func_start as f64);
+                self.emit_number(func_start as f64);
                 self.program.emit_opcode(Opcode::StoreVar);
                 self.program.emit_u32(func_idx);
             }
@@ -276,14 +346,23 @@ impl Compiler {
             
             AstNode::Equal { left, right } => {
                 self.compile_node(left)?;
-                self.compile_node(right)?;
-                self.program.emit_opcode(Opcode::Equal);
+                if matches!(right.as_ref(), AstNode::EndOfFile) {
+                    self.program.emit_opcode(Opcode::EndOfFile);
+                } else {
+                    self.compile_node(right)?;
+                    self.program.emit_opcode(Opcode::Equal);
+                }
             }
             
             AstNode::NotEqual { left, right } => {
                 self.compile_node(left)?;
-                self.compile_node(right)?;
-                self.program.emit_opcode(Opcode::NotEqual);
+                if matches!(right.as_ref(), AstNode::EndOfFile) {
+                    self.program.emit_opcode(Opcode::EndOfFile);
+                    self.program.emit_opcode(Opcode::Not);
+                } else {
+                    self.compile_node(right)?;
+                    self.program.emit_opcode(Opcode::NotEqual);
+                }
             }
             
             AstNode::And { left, right } => {
@@ -443,24 +522,27 @@ impl Compiler {
             }
             
             AstNode::ReadContent { source } => {
-                self.compile_node(source)?;
+                // Empty source means the piped value is already on the stack
+                if !matches!(source.as_ref(), AstNode::Empty) {
+                    self.compile_node(source)?;
+                }
                 self.program.emit_opcode(Opcode::FileRead);
             }
             
             AstNode::WriteContent { target, content } => {
-                self.compile_node(target)?;
-                self.compile_node(content)?;
+                self.compile_write_operands(target, content)?;
                 self.program.emit_opcode(Opcode::FileWrite);
             }
             
             AstNode::AppendContent { target, content } => {
-                self.compile_node(target)?;
-                self.compile_node(content)?;
+                self.compile_write_operands(target, content)?;
                 self.program.emit_opcode(Opcode::FileAppend);
             }
             
             AstNode::Immutable { name, value } => {
+                // Like ▷, the binding's value is also the expression's
                 self.compile_node(value)?;
+                self.program.emit_opcode(Opcode::Dup);
                 let idx = self.program.add_constant(name.clone());
                 self.program.emit_opcode(Opcode::StoreImmutable);
                 self.program.emit_u32(idx);
@@ -656,8 +738,12 @@ impl Compiler {
             }
             
             // Stream & Buffer operations
-            AstNode::CreateStream { source } => {
-                self.compile_node(source)?;
+            AstNode::CreateStream { source, options } => {
+                // Empty source means the piped value is already on the stack
+                if !matches!(source.as_ref(), AstNode::Empty) {
+                    self.compile_node(source)?;
+                }
+                self.compile_node(options)?;
                 self.program.emit_opcode(Opcode::CreateStream);
             }
             
@@ -666,17 +752,38 @@ impl Compiler {
                 self.program.emit_opcode(Opcode::CreateBuffer);
             }
             
+            AstNode::ToBytes { value, encoding } => {
+                if !matches!(value.as_ref(), AstNode::Empty) {
+                    self.compile_node(value)?;
+                }
+                self.compile_node(encoding)?;
+                self.program.emit_opcode(Opcode::ToBytes);
+            }
+            
+            AstNode::ToText { value, encoding } => {
+                if !matches!(value.as_ref(), AstNode::Empty) {
+                    self.compile_node(value)?;
+                }
+                self.compile_node(encoding)?;
+                self.program.emit_opcode(Opcode::ToText);
+            }
+            
             AstNode::FlushBuffer { target } => {
-                self.compile_node(target)?;
+                if !matches!(target.as_ref(), AstNode::Empty) {
+                    self.compile_node(target)?;
+                }
                 self.program.emit_opcode(Opcode::FlushBuffer);
             }
             
             AstNode::EndOfFile => {
-                self.program.emit_opcode(Opcode::EndOfFile);
+                // The end-of-data marker is null, which 📖 returns at end of stream
+                self.program.emit_opcode(Opcode::PushNull);
             }
             
             AstNode::SkipBytes { source, count } => {
-                self.compile_node(source)?;
+                if !matches!(source.as_ref(), AstNode::Empty) {
+                    self.compile_node(source)?;
+                }
                 self.compile_node(count)?;
                 self.program.emit_opcode(Opcode::SkipBytes);
             }
@@ -754,38 +861,37 @@ impl Compiler {
             }
             
             AstNode::Modulo { left, right } => {
-                // Note: Modulo not in bytecode, use runtime
                 self.compile_node(left)?;
                 self.compile_node(right)?;
-                // Emit as custom handling - will need to add to bytecode
-                return Err(AetherError::CompilerError(
-                    "Modulo not yet supported in bytecode compilation".to_string()
-                ));
+                self.program.emit_opcode(Opcode::Mod);
             }
             
             AstNode::StringConcat { left, right } => {
                 self.compile_node(left)?;
                 self.compile_node(right)?;
-                // String concat needs special handling in bytecode
-                // For now, emit as Join with empty separator
-                self.program.emit_opcode(Opcode::Join);
+                self.program.emit_opcode(Opcode::Concat);
             }
             
             AstNode::Length { value } => {
                 self.compile_node(value)?;
-                // Length needs bytecode support
-                return Err(AetherError::CompilerError(
-                    "Length not yet supported in bytecode compilation".to_string()
-                ));
+                self.program.emit_opcode(Opcode::Length);
             }
             
             AstNode::Index { target, index } => {
                 self.compile_node(target)?;
                 self.compile_node(index)?;
-                // Index needs bytecode support
-                return Err(AetherError::CompilerError(
-                    "Index not yet supported in bytecode compilation".to_string()
-                ));
+                self.program.emit_opcode(Opcode::Index);
+            }
+            
+            AstNode::Slice { target, start, end } => {
+                self.compile_node(target)?;
+                self.compile_node(start)?;
+                // An open end runs to the end of the value
+                match end {
+                    Some(end) => self.compile_node(end)?,
+                    None => self.program.emit_opcode(Opcode::PushNull),
+                }
+                self.program.emit_opcode(Opcode::Slice);
             }
             
             AstNode::ArrayLiteral { elements } => {
@@ -818,6 +924,20 @@ impl Compiler {
         Ok(())
     }
     
+    /// Leave `target, content` on the stack for a write or append
+    ///
+    /// When the content is piped in it is already on the stack below the
+    /// target, so the two are swapped into place.
+    fn compile_write_operands(&mut self, target: &AstNode, content: &AstNode) -> Result<()> {
+        self.compile_node(target)?;
+        if matches!(content, AstNode::Empty) {
+            self.program.emit_opcode(Opcode::Swap);
+        } else {
+            self.compile_node(content)?;
+        }
+        Ok(())
+    }
+
     /// Compile a literal value
     fn compile_literal(&mut self, lit: &LiteralValue) -> Result<()> {
         match lit {
@@ -829,14 +949,23 @@ impl Compiler {
             LiteralValue::Number(n) => {
                 self.emit_number(*n);
             }
+            LiteralValue::Integer(n) => self.emit_constant(Constant::Integer(*n)),
+            LiteralValue::Decimal(d) => self.emit_constant(Constant::Decimal(*d)),
+            LiteralValue::Bytes(b) => self.emit_constant(Constant::Bytes(b.clone())),
         }
         Ok(())
     }
     
     /// Helper to emit a number
     fn emit_number(&mut self, value: f64) {
-        self.program.emit_opcode(Opcode::PushNumber);
-        self.program.emit_f64(value);
+        self.emit_constant(Constant::Number(value));
+    }
+    
+    /// Push a typed constant from the pool
+    fn emit_constant(&mut self, constant: Constant) {
+        let idx = self.program.add_constant(constant);
+        self.program.emit_opcode(Opcode::PushConstant);
+        self.program.emit_u32(idx);
     }
 }
 
@@ -865,7 +994,7 @@ mod tests {
         
         // Should have "Hello" in constants
         assert_eq!(program.constants.len(), 1);
-        assert_eq!(program.constants[0], "Hello");
+        assert_eq!(program.constants[0], Constant::from("Hello"));
         
         // Should have bytecode
         assert!(!program.code.is_empty());
@@ -876,7 +1005,8 @@ mod tests {
         let mut compiler = Compiler::new();
         compiler.compile_literal(&LiteralValue::Number(42.0)).unwrap();
         
-        assert_eq!(compiler.program.code[0], Opcode::PushNumber.to_byte());
+        assert_eq!(compiler.program.code[0], Opcode::PushConstant.to_byte());
+        assert_eq!(compiler.program.constants[0], Constant::Number(42.0));
     }
     
     #[test]
@@ -886,7 +1016,7 @@ mod tests {
         compiler.compile_node(&node).unwrap();
         
         assert_eq!(compiler.program.code[0], Opcode::LoadVar.to_byte());
-        assert_eq!(compiler.program.constants[0], "test");
+        assert_eq!(compiler.program.constants[0], Constant::from("test"));
     }
     
     #[test]
@@ -947,6 +1077,6 @@ mod tests {
         let program = compiler.compile(ast).unwrap();
         
         // Should have variable name in constants
-        assert!(program.constants.contains(&"x".to_string()));
+        assert!(program.constants.contains(&Constant::from("x")));
     }
 }
@@ -0,0 +1,349 @@
+//! Differential tests between the runtime and the VM
+//!
+//! Every program is run twice, once by `Runtime::execute` and once through
+//! `Compiler` and `VM::execute`, and the two runs must agree on the resulting
+//! value, the lines printed by `📤`, `🪵` and `💾`, and the kind of error. The
+//! examples are checked against `conformance/divergences.txt`, which lists the
+//! differences still known between the two; generated programs must agree
+//! outright.
+
+use crate::compiler::Compiler;
+use crate::error::{AetherError, Result};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::runtime::{Runtime, Value};
+use crate::vm::VM;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const DIVERGENCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/conformance/divergences.txt");
+
+/// What one engine made of a program
+#[derive(Debug)]
+struct Outcome {
+    value: Option<Value>,
+    output: Vec<String>,
+    error: Option<String>,
+}
+
+/// A way two outcomes can differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Aspect {
+    Value,
+    Output,
+    Error,
+}
+
+impl fmt::Display for Aspect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Aspect::Value => "value",
+            Aspect::Output => "output",
+            Aspect::Error => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A line of the divergences file
+#[derive(Debug, PartialEq)]
+enum Expected {
+    Skip,
+    Differs(BTreeSet<Aspect>),
+}
+
+fn parse(source: &str) -> Result<Vec<crate::parser::AstNode>> {
+    let tokens = Lexer::new(source.to_string()).tokenize()?;
+    Parser::new(tokens).parse()
+}
+
+fn run_runtime(source: &str) -> Outcome {
+    let ast = match parse(source) {
+        Ok(ast) => ast,
+        Err(err) => return outcome(Err(err), Vec::new()),
+    };
+    let mut runtime = Runtime::new();
+    runtime.capture_output();
+    let result = runtime.execute(ast);
+    outcome(result, runtime.take_output())
+}
+
+fn run_vm(source: &str) -> Outcome {
+    let program = match parse(source).and_then(|ast| Compiler::new().compile(ast)) {
+        Ok(program) => program,
+        Err(err) => return outcome(Err(err), Vec::new()),
+    };
+    let mut vm = VM::new(program);
+    vm.capture_output();
+    let result = vm.execute();
+    outcome(result, vm.take_output())
+}
+
+fn outcome(result: Result<Value>, output: Vec<String>) -> Outcome {
+    match result {
+        Ok(value) => Outcome { value: Some(value), output, error: None },
+        Err(err) => Outcome { value: None, output, error: Some(error_kind(&err)) },
+    }
+}
+
+/// The variant name of an error, such as `RuntimeError`
+fn error_kind(err: &AetherError) -> String {
+    let debug = format!("{:?}", err);
+    debug.split('(').next().unwrap_or_default().to_string()
+}
+
+/// Run a program on both engines and list the aspects that differ
+///
+/// A program the compiler rejects counts as a VM error.
+fn compare(source: &str) -> (BTreeSet<Aspect>, Outcome, Outcome) {
+    let runtime = run_runtime(source);
+    let vm = run_vm(source);
+    let mut differs = BTreeSet::new();
+    if runtime.value != vm.value {
+        differs.insert(Aspect::Value);
+    }
+    if runtime.output != vm.output {
+        differs.insert(Aspect::Output);
+    }
+    if runtime.error != vm.error {
+        differs.insert(Aspect::Error);
+    }
+    (differs, runtime, vm)
+}
+
+/// Read `<program> <aspect>... [# reason]` lines
+fn parse_divergences(text: &str) -> std::result::Result<BTreeMap<String, Expected>, String> {
+    let mut expected = BTreeMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let program = words.next().unwrap_or_default().to_string();
+        let mut aspects = BTreeSet::new();
+        let mut skip = false;
+        for word in words {
+            match word {
+                "value" => aspects.insert(Aspect::Value),
+                "output" => aspects.insert(Aspect::Output),
+                "error" => aspects.insert(Aspect::Error),
+                "skip" => {
+                    skip = true;
+                    true
+                }
+                other => return Err(format!("line {}: unknown aspect '{}'", number + 1, other)),
+            };
+        }
+        let entry = match (skip, aspects.is_empty()) {
+            (true, true) => Expected::Skip,
+            (false, false) => Expected::Differs(aspects),
+            _ => return Err(format!("line {}: give either 'skip' or the aspects that differ", number + 1)),
+        };
+        if expected.insert(program.clone(), entry).is_some() {
+            return Err(format!("line {}: {} is listed twice", number + 1, program));
+        }
+    }
+    Ok(expected)
+}
+
+/// Deterministic generator of small programs in the subset both engines share
+struct Generator {
+    state: u64,
+    variables: Vec<String>,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Generator { state: seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407), variables: Vec::new() }
+    }
+
+    fn next(&mut self, bound: u64) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(2685821657736338717) % bound
+    }
+
+    /// A few statements and a final expression
+    ///
+    /// The final expression gives the program a value in both engines. An
+    /// untaken `◇` or a `↻` that never runs pushes nothing in the VM, so a
+    /// program ending in one returns whatever the statement before it left
+    /// on the stack, where the runtime returns `∅`.
+    fn program(&mut self) -> String {
+        self.variables.clear();
+        let count = self.next(5);
+        let mut statements: Vec<String> = (0..count).map(|_| self.statement()).collect();
+        let last = match self.next(2) {
+            0 => format!("📤{}", self.atom()),
+            _ => self.expression(3),
+        };
+        statements.push(last);
+        statements.join(" ⨠ ")
+    }
+
+    fn statement(&mut self) -> String {
+        match self.next(6) {
+            0 | 1 => {
+                let value = self.expression(2);
+                let name = format!("v{}", self.next(3));
+                if !self.variables.contains(&name) {
+                    self.variables.push(name.clone());
+                }
+                format!("{} ▷ {}", value, name)
+            }
+            2 => format!("📤{}", self.atom()),
+            3 => {
+                let condition = self.condition();
+                let then = format!("📤{}", self.atom());
+                match self.next(3) {
+                    0 => format!("◇({}): {}", condition, then),
+                    1 => format!("◇({}): {} ◆: 📤{}", condition, then, self.atom()),
+                    _ => {
+                        let other = self.condition();
+                        format!("◇({}): {} ◈({}): 📤{} ◆: 📤{}", condition, then, other, self.atom(), self.atom())
+                    }
+                }
+            }
+            4 => {
+                let name = format!("n{}", self.next(100));
+                let limit = self.next(6);
+                self.variables.push(name.clone());
+                format!("0 ▷ {name} ⨠ ↻({name} < {limit}): ({name} + 1 ▷ {name})")
+            }
+            _ => self.expression(3),
+        }
+    }
+
+    fn condition(&mut self) -> String {
+        let operators = ["<", ">", "≡", "≠"];
+        let operator = operators[self.next(operators.len() as u64) as usize];
+        let condition = format!("{} {} {}", self.number(), operator, self.number());
+        match self.next(4) {
+            0 => format!("({}) ⊗ ({} > {})", condition, self.number(), self.number()),
+            1 => format!("({}) ⊕ ({} < {})", condition, self.number(), self.number()),
+            _ => condition,
+        }
+    }
+
+    fn expression(&mut self, depth: u32) -> String {
+        if depth == 0 || self.next(3) == 0 {
+            return self.number();
+        }
+        let operators = ["+", "-", "*", "/", "%"];
+        let operator = operators[self.next(operators.len() as u64) as usize];
+        let left = self.expression(depth - 1);
+        let right = self.expression(depth - 1);
+        format!("({} {} {})", left, operator, right)
+    }
+
+    fn atom(&mut self) -> String {
+        match self.next(6) {
+            0 => format!("\"s{}\"", self.next(10)),
+            1 => format!("\"s{{{}}}\"", self.expression(1)),
+            _ => self.number(),
+        }
+    }
+
+    fn number(&mut self) -> String {
+        if !self.variables.is_empty() && self.next(3) == 0 {
+            let index = self.next(self.variables.len() as u64) as usize;
+            return self.variables[index].clone();
+        }
+        match self.next(4) {
+            0 => format!("{}.{}", self.next(20), self.next(100)),
+            _ => self.next(50).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_examples_match_known_divergences() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let text = std::fs::read_to_string(DIVERGENCES).unwrap();
+        let mut expected = parse_divergences(&text).unwrap();
+
+        let mut examples: Vec<_> = std::fs::read_dir(root.join("examples"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ae"))
+            .collect();
+        examples.sort();
+
+        let mut problems = Vec::new();
+        for path in examples {
+            let name = path.strip_prefix(root).unwrap().to_string_lossy().to_string();
+            let entry = expected.remove(&name);
+            if entry == Some(Expected::Skip) {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            let (differs, _, _) = compare(&source);
+            let listed = match entry {
+                Some(Expected::Differs(aspects)) => aspects,
+                _ => BTreeSet::new(),
+            };
+            if differs != listed {
+                let show = |set: &BTreeSet<Aspect>| {
+                    let words: Vec<String> = set.iter().map(|a| a.to_string()).collect();
+                    if words.is_empty() { "nothing".to_string() } else { words.join(" ") }
+                };
+                problems.push(format!("{}: {} differs, divergences.txt expects {}", name, show(&differs), show(&listed)));
+            }
+        }
+        for name in expected.keys() {
+            problems.push(format!("{}: listed in divergences.txt but not an example", name));
+        }
+
+        assert!(problems.is_empty(), "runtime and VM disagree:\n{}", problems.join("\n"));
+    }
+
+    #[test]
+    fn test_generated_programs_agree() {
+        let mut generator = Generator::new(0xAE7E);
+        for _ in 0..300 {
+            let source = generator.program();
+            let (differs, runtime, vm) = compare(&source);
+            assert!(
+                differs.is_empty(),
+                "runtime and VM disagree on {}:\nruntime: {:?}\nvm: {:?}",
+                source, runtime, vm
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_reports_each_aspect() {
+        // 🪵 prints differently and returns a different value in each engine
+        let (differs, _, _) = compare("🪵\"x\"");
+        assert_eq!(differs, [Aspect::Value, Aspect::Output].into());
+        let (differs, runtime, _) = compare("📤1 ⨠ 📤\"a\"");
+        assert!(differs.is_empty());
+        assert_eq!(runtime.output, ["Output: Integer(1)", "Output: String(\"a\")"]);
+        // Only the VM needs the compiler, which doesn't support ⊞ yet
+        let (differs, _, vm) = compare("[1] ▷ _pipe ⨠ ⊞2");
+        assert_eq!(differs, [Aspect::Value, Aspect::Error].into());
+        assert_eq!(vm.error.as_deref(), Some("CompilerError"));
+        let (differs, runtime, _) = compare("1 +");
+        assert!(differs.is_empty());
+        assert_eq!(runtime.error.as_deref(), Some("ParserError"));
+    }
+
+    #[test]
+    fn test_parse_divergences() {
+        let parsed = parse_divergences("# comment\nexamples/a.ae value output # why\nexamples/b.ae skip\n").unwrap();
+        assert_eq!(parsed["examples/a.ae"], Expected::Differs([Aspect::Value, Aspect::Output].into()));
+        assert_eq!(parsed["examples/b.ae"], Expected::Skip);
+        assert!(parse_divergences("examples/a.ae").is_err());
+        assert!(parse_divergences("examples/a.ae skip value").is_err());
+        assert!(parse_divergences("examples/a.ae colour").is_err());
+        assert!(parse_divergences("examples/a.ae value\nexamples/a.ae error").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Crypto primitives behind 🔐 🔓 #️⃣ ✍️ and 🔏, shared by the runtime and VM,
+//! and the Ed25519 keys that sign bytecode files
+//!
+//! Data may be bytes, strings (taken as UTF-8) or numbers. Ciphertexts and
+//! signatures are returned as `Value::Bytes`; keys are strings from which a
+//! 32-byte key is derived with SHA-256.
+
+use crate::bytes;
+use crate::error::{AetherError, Result};
+use crate::runtime::Value;
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use ed25519_dalek::{Signer, Verifier, SigningKey, Signature, VerifyingKey};
+use rand::Rng;
+use sha2::{Sha256, Digest};
+
+/// Length of the AES-GCM nonce prefixed to every ciphertext
+const NONCE_LEN: usize = 12;
+
+/// SHA-256 of the data as a hex string (#️⃣)
+pub fn hash(data: &Value) -> Result<Value> {
+    let bytes = input(data, "Hash")?;
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    Ok(Value::String(format!("{:x}", hasher.finalize())))
+}
+
+/// Encrypt with AES-256-GCM (🔐), returning nonce followed by ciphertext
+pub fn encrypt(data: &Value, key: &Value) -> Result<Value> {
+    let plaintext = input(data, "Encrypt")?;
+    let cipher = Aes256Gcm::new(&derive_key(key, "Encrypt")?.into());
+
+    let nonce_bytes = rand::thread_rng().gen::<[u8; NONCE_LEN]>();
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher.encrypt(nonce, plaintext.as_ref())
+        .map_err(|e| AetherError::RuntimeError(format!("Encryption failed: {}", e)))?;
+    let mut combined = nonce_bytes.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(Value::Bytes(combined))
+}
+
+/// Decrypt the output of [`encrypt`] (🔓)
+///
+/// Base64 strings from older programs are accepted too. The plaintext is
+/// returned as a string when it is valid UTF-8 and as bytes otherwise.
+pub fn decrypt(data: &Value, key: &Value) -> Result<Value> {
+    let combined = sealed(data, "Decrypt", "encrypted data")?;
+    if combined.len() < NONCE_LEN {
+        return Err(AetherError::RuntimeError("Invalid encrypted data: too short".to_string()));
+    }
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_LEN);
+    let nonce = Nonce::from_slice(nonce_bytes);
+    let cipher = Aes256Gcm::new(&derive_key(key, "Decrypt")?.into());
+
+    let plaintext = cipher.decrypt(nonce, ciphertext)
+        .map_err(|e| AetherError::RuntimeError(format!("Decryption failed: {}", e)))?;
+    Ok(bytes::text_or_bytes(plaintext))
+}
+
+/// Sign with Ed25519 (✍️), returning the 64-byte signature
+pub fn sign(data: &Value, key: &Value) -> Result<Value> {
+    let message = input(data, "Sign")?;
+    let signing_key = SigningKey::from_bytes(&derive_key(key, "Sign")?);
+    Ok(Value::Bytes(signing_key.sign(&message).to_bytes().to_vec()))
+}
+
+/// Check a signature from [`sign`] (🔏)
+pub fn verify(signature: &Value, data: &Value, key: &Value) -> Result<Value> {
+    let sig_bytes = sealed(signature, "Verify", "signature")?;
+    let message = input(data, "Verify")?;
+    let key_bytes = derive_key(key, "Verify")?;
+
+    let sig_array: [u8; 64] = sig_bytes.as_slice().try_into()
+        .map_err(|_| AetherError::RuntimeError("Invalid signature length".to_string()))?;
+    let signature = Signature::from_bytes(&sig_array);
+
+    let verifying_key = SigningKey::from_bytes(&key_bytes).verifying_key();
+    Ok(Value::Boolean(verifying_key.verify(&message, &signature).is_ok()))
+}
+
+/// New random Ed25519 secret key, as used to sign bytecode
+pub fn generate_secret_key() -> [u8; 32] {
+    rand::thread_rng().gen()
+}
+
+/// Ed25519 public key belonging to a secret key
+pub fn public_key(secret: &[u8; 32]) -> [u8; 32] {
+    SigningKey::from_bytes(secret).verifying_key().to_bytes()
+}
+
+/// Ed25519 signature of raw bytes
+pub fn sign_bytes(secret: &[u8; 32], message: &[u8]) -> [u8; 64] {
+    SigningKey::from_bytes(secret).sign(message).to_bytes()
+}
+
+/// Check a signature from [`sign_bytes`] against a public key
+pub fn verify_bytes(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    VerifyingKey::from_bytes(public)
+        .map(|key| key.verify(message, &Signature::from_bytes(signature)).is_ok())
+        .unwrap_or(false)
+}
+
+fn input(data: &Value, op: &str) -> Result<Vec<u8>> {
+    match data {
+        Value::Bytes(_) | Value::String(_) | Value::Number(_) | Value::Integer(_) | Value::Decimal(_) => Ok(bytes::raw(data).unwrap_or_default()),
+        _ => Err(AetherError::RuntimeError(format!("{} requires bytes, string or number data", op))),
+    }
+}
+
+/// Bytes produced by encrypt or sign, or their legacy base64 form
+fn sealed(data: &Value, op: &str, what: &str) -> Result<Vec<u8>> {
+    match data {
+        Value::Bytes(b) => Ok(b.clone()),
+        Value::String(s) => BASE64.decode(s.as_bytes())
+            .map_err(|e| AetherError::RuntimeError(format!("Invalid {}: {}", what, e))),
+        _ => Err(AetherError::RuntimeError(format!("{} requires bytes {}", op, what))),
+    }
+}
+
+fn derive_key(key: &Value, op: &str) -> Result<[u8; 32]> {
+    let key = key.as_string()
+        .ok_or_else(|| AetherError::RuntimeError(format!("{} requires string key", op)))?;
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Value {
+        Value::String("k".to_string())
+    }
+
+    #[test]
+    fn test_encrypt_binary_round_trip() {
+        let data = Value::Bytes(vec![0, 159, 146, 150, 255]);
+        let sealed = encrypt(&data, &key()).unwrap();
+        assert!(matches!(&sealed, Value::Bytes(b) if b.len() == NONCE_LEN + 5 + 16));
+        assert_eq!(decrypt(&sealed, &key()).unwrap(), data);
+        assert!(decrypt(&sealed, &Value::String("other".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_hash_bytes_matches_string() {
+        let from_bytes = hash(&Value::Bytes(b"abc".to_vec())).unwrap();
+        assert_eq!(from_bytes, hash(&Value::String("abc".to_string())).unwrap());
+        assert_eq!(
+            from_bytes,
+            Value::String("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string())
+        );
+    }
+
+    #[test]
+    fn test_signature_accepts_legacy_base64() {
+        let data = Value::Bytes(vec![1, 2, 3]);
+        let sig = sign(&data, &key()).unwrap();
+        let legacy = match &sig {
+            Value::Bytes(b) => Value::String(BASE64.encode(b)),
+            other => panic!("Expected bytes, got {:?}", other),
+        };
+        assert_eq!(verify(&sig, &data, &key()).unwrap(), Value::Boolean(true));
+        assert_eq!(verify(&legacy, &data, &key()).unwrap(), Value::Boolean(true));
+        assert_eq!(verify(&sig, &Value::Bytes(vec![9]), &key()).unwrap(), Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_raw_signatures_check_key_and_message() {
+        let secret = generate_secret_key();
+        let public = public_key(&secret);
+        let signature = sign_bytes(&secret, b"code");
+        assert!(verify_bytes(&public, b"code", &signature));
+        assert!(!verify_bytes(&public, b"c0de", &signature));
+        assert!(!verify_bytes(&public_key(&generate_secret_key()), b"code", &signature));
+    }
+}
@@ -0,0 +1,228 @@
+//! Concrete syntax tree for Aether source
+//!
+//! Where the parser's AST keeps only what a program means, the concrete tree
+//! keeps how it was written: every token with its text and the whitespace
+//! before it, comments and line breaks, and brackets grouping what they
+//! enclose. `Cst::text` gives back the source the tree was built from. A
+//! string with `{…}` interpolation is a single leaf, since the tokens the
+//! lexer makes of it don't cover its text.
+
+use crate::error::{AetherError, Result};
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::symbols::Symbol;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a leaf of the tree is
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    Token(TokenType),
+    /// A string with `{…}` interpolation
+    Interpolated,
+}
+
+/// A token, comment or line break as written
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaf {
+    pub kind: Kind,
+    pub text: String,
+    /// Spaces and tabs between the previous leaf and this one
+    pub leading: String,
+    /// Grapheme position of the text in the source
+    pub position: usize,
+}
+
+/// A node of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Leaf(Leaf),
+    /// A pair of brackets, such as `( … )` or `⟦ … ⟧`, and what is between them
+    Group { open: Leaf, children: Vec<Node>, close: Leaf },
+}
+
+/// Concrete syntax tree of a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cst {
+    pub nodes: Vec<Node>,
+    /// Whitespace after the last leaf
+    pub trailing: String,
+}
+
+impl Cst {
+    /// Build the tree of a source file, which must lex and have its
+    /// brackets balanced
+    pub fn parse(source: &str) -> Result<Cst> {
+        let graphemes: Vec<&str> = source.graphemes(true).collect();
+        let tokens = Lexer::new(source.to_string()).tokenize_with_trivia()?;
+
+        // Groups being built, innermost last
+        let mut open: Vec<(Leaf, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        let mut end = 0;
+        let mut index = 0;
+        while index < tokens.len() {
+            let token = &tokens[index];
+            index += 1;
+            if token.token_type == TokenType::Eof {
+                break;
+            }
+            let leading = graphemes[end..token.position].concat();
+            let leaf = if token.length == 0 {
+                // The zero-length `(` of an interpolated string: its text runs
+                // to the matching zero-length `)`
+                let close = interpolation_end(&tokens, index - 1);
+                index = close + 1;
+                Leaf { kind: Kind::Interpolated, text: String::new(), leading, position: token.position }
+                    .with_text(&graphemes, tokens[close].position)
+            } else {
+                Leaf { kind: Kind::Token(token.token_type.clone()), text: String::new(), leading, position: token.position }
+                    .with_text(&graphemes, token.position + token.length)
+            };
+            end = leaf.position + leaf.text.graphemes(true).count();
+
+            if let Kind::Token(kind) = &leaf.kind {
+                if closer(kind).is_some() {
+                    open.push((leaf, Vec::new()));
+                    continue;
+                }
+                if let Some((opener, _)) = open.last() {
+                    let Kind::Token(opener_kind) = &opener.kind else { unreachable!("groups open on tokens") };
+                    if closer(opener_kind).as_ref() == Some(kind) {
+                        let (opener, children) = open.pop().expect("a group is open");
+                        let group = Node::Group { open: opener, children, close: leaf };
+                        match open.last_mut() {
+                            Some((_, siblings)) => siblings.push(group),
+                            None => nodes.push(group),
+                        }
+                        continue;
+                    }
+                }
+                if is_closer(kind) {
+                    return Err(AetherError::ParserError(format!(
+                        "Unmatched '{}' at position {}",
+                        leaf.text, leaf.position
+                    )));
+                }
+            }
+            match open.last_mut() {
+                Some((_, siblings)) => siblings.push(Node::Leaf(leaf)),
+                None => nodes.push(Node::Leaf(leaf)),
+            }
+        }
+        if let Some((opener, _)) = open.first() {
+            return Err(AetherError::ParserError(format!(
+                "Unclosed '{}' at position {}",
+                opener.text, opener.position
+            )));
+        }
+        Ok(Cst { nodes, trailing: graphemes[end..].concat() })
+    }
+
+    /// The source the tree was built from
+    pub fn text(&self) -> String {
+        fn write(nodes: &[Node], text: &mut String) {
+            for node in nodes {
+                match node {
+                    Node::Leaf(leaf) => {
+                        text.push_str(&leaf.leading);
+                        text.push_str(&leaf.text);
+                    }
+                    Node::Group { open, children, close } => {
+                        text.push_str(&open.leading);
+                        text.push_str(&open.text);
+                        write(children, text);
+                        text.push_str(&close.leading);
+                        text.push_str(&close.text);
+                    }
+                }
+            }
+        }
+        let mut text = String::new();
+        write(&self.nodes, &mut text);
+        text.push_str(&self.trailing);
+        text
+    }
+}
+
+impl Leaf {
+    fn with_text(mut self, graphemes: &[&str], end: usize) -> Self {
+        self.text = graphemes[self.position..end].concat();
+        self
+    }
+}
+
+/// The token closing a group that `kind` opens
+fn closer(kind: &TokenType) -> Option<TokenType> {
+    let close = match kind {
+        TokenType::LeftParen => TokenType::RightParen,
+        TokenType::LeftBracket => TokenType::RightBracket,
+        TokenType::LeftBrace => TokenType::RightBrace,
+        TokenType::Symbol(Symbol::IndexStart) => TokenType::Symbol(Symbol::IndexEnd),
+        TokenType::Symbol(Symbol::CharStart) => TokenType::Symbol(Symbol::CharEnd),
+        TokenType::Symbol(Symbol::SliceStart) => TokenType::Symbol(Symbol::SliceEnd),
+        _ => return None,
+    };
+    Some(close)
+}
+
+fn is_closer(kind: &TokenType) -> bool {
+    matches!(
+        kind,
+        TokenType::RightParen
+            | TokenType::RightBracket
+            | TokenType::RightBrace
+            | TokenType::Symbol(Symbol::IndexEnd | Symbol::CharEnd | Symbol::SliceEnd)
+    )
+}
+
+/// Index of the zero-length `)` closing the interpolated string whose
+/// zero-length `(` is at `start`
+fn interpolation_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        if token.length > 0 {
+            continue;
+        }
+        match token.token_type {
+            TokenType::LeftParen => depth += 1,
+            TokenType::RightParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+    unreachable!("the lexer closes every interpolated string")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_keeps_the_source() {
+        let source = "// sum\n[1, 2] ▷ xs ⨠   // list\n\n◇(xs⟦0⟧ > 0): (\n  📤\"first {xs⟦0⟧ + \"!\"}\"\t\n) ";
+        let cst = Cst::parse(source).unwrap();
+        assert_eq!(cst.text(), source);
+        assert_eq!(cst.trailing, " ");
+
+        let Node::Leaf(comment) = &cst.nodes[0] else { panic!("expected a comment leaf") };
+        assert_eq!(comment.kind, Kind::Token(TokenType::Comment("// sum".to_string())));
+        let Node::Group { open, children, close } = &cst.nodes[2] else { panic!("expected a group") };
+        assert_eq!((open.text.as_str(), close.text.as_str(), children.len()), ("[", "]", 3));
+
+        // The string is one leaf, however the lexer splits it
+        let Node::Group { children, .. } = &cst.nodes[12] else { panic!("expected the block") };
+        let Node::Leaf(string) = &children[2] else { panic!("expected the string") };
+        assert_eq!(string.kind, Kind::Interpolated);
+        assert_eq!(string.text, "\"first {xs⟦0⟧ + \"!\"}\"");
+    }
+
+    #[test]
+    fn test_unbalanced_brackets_are_errors() {
+        assert!(Cst::parse("(1 + 2").unwrap_err().to_string().contains("Unclosed '(' at position 0"));
+        assert!(Cst::parse("[1)").unwrap_err().to_string().contains("Unmatched ')' at position 2"));
+        assert!(Cst::parse("\"unterminated").is_err());
+    }
+}
@@ -0,0 +1,607 @@
+//! Debug Adapter Protocol server for Aether
+//!
+//! `aether dap` speaks DAP over stdio, so editors can launch a program, set
+//! breakpoints, step through it and inspect its variables. Requests are
+//! served by a `Debugger` over the VM; what the program prints is sent to the
+//! editor as `output` events, since stdout carries the protocol.
+//!
+//! Programs run synchronously between requests, so `pause` is not supported.
+
+use crate::bytecode::BytecodeProgram;
+use crate::compiler::Compiler;
+use crate::debugger::{Debugger, Stop};
+use crate::error::{AetherError, Result};
+use crate::limits::Limits;
+use crate::normalize;
+use crate::runtime::Value;
+use crate::vm::VM;
+use serde_json::{json, Value as Json};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+/// The only thread a program has
+const THREAD_ID: i64 = 1;
+/// `variablesReference` of the variables scope
+const VARIABLES_REF: i64 = 1;
+/// `variablesReference` of the stack scope
+const STACK_REF: i64 = 2;
+/// First `variablesReference` handed out for arrays and objects
+const FIRST_HANDLE: i64 = 3;
+
+/// A launched program
+struct Session {
+    debugger: Debugger,
+    path: String,
+    stop_on_entry: bool,
+}
+
+/// Serves DAP requests read from `input`, writing responses and events to `output`
+pub struct DapServer<R, W> {
+    input: R,
+    output: W,
+    seq: i64,
+    session: Option<Session>,
+    /// Breakpoint lines by source path
+    breakpoints: HashMap<String, Vec<u32>>,
+    /// Arrays and objects shown since the program last ran
+    handles: Vec<Value>,
+    limits: Limits,
+}
+
+impl<R: BufRead, W: Write> DapServer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        DapServer {
+            input,
+            output,
+            seq: 0,
+            session: None,
+            breakpoints: HashMap::new(),
+            handles: Vec::new(),
+            limits: Limits::default(),
+        }
+    }
+
+    /// Budgets for the programs this server launches
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Serve requests until `disconnect` or the end of the input
+    pub fn run(&mut self) -> Result<()> {
+        while let Some(request) = read_message(&mut self.input)? {
+            let command = request["command"].as_str().unwrap_or_default().to_string();
+            let arguments = request.get("arguments").cloned().unwrap_or(Json::Null);
+
+            let result = self.handle(&command, &arguments);
+            let (body, after) = match result {
+                Ok(done) => done,
+                Err(err) => {
+                    self.respond(&request, Err(err.to_string()))?;
+                    continue;
+                }
+            };
+            self.respond(&request, Ok(body))?;
+            match after {
+                After::Nothing => {}
+                After::Initialized => self.event("initialized", json!({}))?,
+                After::Start => {
+                    let stop_on_entry = self.session.as_ref().is_some_and(|s| s.stop_on_entry);
+                    if stop_on_entry {
+                        self.stopped("entry", None)?;
+                    } else {
+                        self.run_program(Debugger::resume)?;
+                    }
+                }
+                After::Run(action) => self.run_program(action)?,
+                After::Exit => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one request, returning the response body and what to do once it is sent
+    fn handle(&mut self, command: &str, arguments: &Json) -> Result<(Json, After)> {
+        match command {
+            "initialize" => Ok((json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsEvaluateForHovers": true,
+                "supportsSetVariable": true,
+            }), After::Nothing)),
+            "launch" => {
+                self.launch(arguments)?;
+                Ok((Json::Null, After::Initialized))
+            }
+            "setBreakpoints" => Ok((self.set_breakpoints(arguments), After::Nothing)),
+            "setExceptionBreakpoints" => Ok((json!({ "breakpoints": [] }), After::Nothing)),
+            "configurationDone" => Ok((Json::Null, After::Start)),
+            "threads" => Ok((json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }), After::Nothing)),
+            "stackTrace" => Ok((self.stack_trace()?, After::Nothing)),
+            "scopes" => Ok((json!({ "scopes": [
+                { "name": "Variables", "variablesReference": VARIABLES_REF, "expensive": false },
+                { "name": "Stack", "variablesReference": STACK_REF, "expensive": false },
+            ] }), After::Nothing)),
+            "variables" => Ok((self.variables(arguments)?, After::Nothing)),
+            "setVariable" => Ok((self.set_variable(arguments)?, After::Nothing)),
+            "evaluate" => Ok((self.evaluate(arguments)?, After::Nothing)),
+            "continue" => Ok((json!({ "allThreadsContinued": true }), After::Run(Debugger::resume))),
+            "next" => Ok((Json::Null, After::Run(Debugger::step_over))),
+            "stepIn" => Ok((Json::Null, After::Run(Debugger::step_into))),
+            "stepOut" => Ok((Json::Null, After::Run(Debugger::step_out))),
+            "disconnect" | "terminate" => Ok((Json::Null, After::Exit)),
+            other => Err(AetherError::RuntimeError(format!("Unsupported request '{}'", other))),
+        }
+    }
+
+    fn launch(&mut self, arguments: &Json) -> Result<()> {
+        let path = arguments["program"].as_str()
+            .ok_or_else(|| AetherError::RuntimeError("launch needs a 'program' path".to_string()))?
+            .to_string();
+        let mut vm = VM::new(load(&path)?);
+        vm.set_limits(self.limits.clone());
+        vm.capture_output();
+        let mut debugger = Debugger::new(vm)?;
+        debugger.set_break_on_debug(!arguments["noDebug"].as_bool().unwrap_or(false));
+        // Breakpoints a client set before launching
+        for (source, lines) in &self.breakpoints {
+            if same_file(source, &path) {
+                for line in lines {
+                    debugger.add_breakpoint(*line);
+                }
+            }
+        }
+
+        self.session = Some(Session {
+            debugger,
+            path,
+            stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+        });
+        Ok(())
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Json) -> Json {
+        let path = arguments["source"]["path"].as_str().unwrap_or_default().to_string();
+        let lines: Vec<u32> = match arguments["breakpoints"].as_array() {
+            Some(breakpoints) => breakpoints.iter().filter_map(|b| b["line"].as_u64()).map(|l| l as u32).collect(),
+            None => Vec::new(),
+        };
+
+        let mut verified = vec![false; lines.len()];
+        if let Some(session) = self.session.as_mut().filter(|s| same_file(&s.path, &path)) {
+            let old: Vec<u32> = session.debugger.breakpoints().collect();
+            for line in old {
+                session.debugger.remove_breakpoint(line);
+            }
+            for (line, verified) in lines.iter().zip(verified.iter_mut()) {
+                *verified = session.debugger.add_breakpoint(*line);
+            }
+        }
+        let body = json!({ "breakpoints": lines.iter().zip(&verified).map(|(line, verified)| json!({
+            "verified": verified,
+            "line": line,
+        })).collect::<Vec<_>>() });
+        self.breakpoints.insert(path, lines);
+        body
+    }
+
+    fn stack_trace(&self) -> Result<Json> {
+        let session = self.session()?;
+        let vm = session.debugger.vm();
+        let source = json!({
+            "name": Path::new(&session.path).file_name().map(|n| n.to_string_lossy().to_string()),
+            "path": session.path,
+        });
+        let frame = |id: usize, name: &str, position: usize| {
+            let span = vm.program().span_at(position);
+            json!({
+                "id": id,
+                "name": name,
+                "source": source,
+                "line": span.map_or(0, |s| s.line),
+                "column": span.map_or(0, |s| s.column.max(1)),
+            })
+        };
+
+        let mut frames = vec![frame(0, "main", vm.pc())];
+        for (depth, address) in vm.call_frames().iter().rev().enumerate() {
+            frames.push(frame(depth + 1, "caller", *address));
+        }
+        Ok(json!({ "stackFrames": frames, "totalFrames": frames.len() }))
+    }
+
+    fn variables(&mut self, arguments: &Json) -> Result<Json> {
+        let reference = arguments["variablesReference"].as_i64().unwrap_or(0);
+        let entries: Vec<(String, Value)> = match reference {
+            VARIABLES_REF => {
+                let mut variables: Vec<(String, Value)> = self.session()?.debugger.vm().variables()
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                variables.sort_by(|a, b| a.0.cmp(&b.0));
+                variables
+            }
+            STACK_REF => self.session()?.debugger.vm().stack()
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(depth, value)| (format!("#{}", depth), value.clone()))
+                .collect(),
+            handle => match self.handles.get((handle - FIRST_HANDLE) as usize) {
+                Some(Value::Array(items)) => items.iter()
+                    .enumerate()
+                    .map(|(i, value)| (i.to_string(), value.clone()))
+                    .collect(),
+                Some(Value::Object(fields)) => {
+                    let mut fields: Vec<(String, Value)> = fields.iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect();
+                    fields.sort_by(|a, b| a.0.cmp(&b.0));
+                    fields
+                }
+                _ => Vec::new(),
+            },
+        };
+
+        let variables: Vec<Json> = entries.into_iter()
+            .map(|(name, value)| {
+                let reference = self.handle_for(&value);
+                json!({
+                    "name": name,
+                    "value": display(&value),
+                    "type": type_name(&value),
+                    "variablesReference": reference,
+                })
+            })
+            .collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn set_variable(&mut self, arguments: &Json) -> Result<Json> {
+        if arguments["variablesReference"].as_i64() != Some(VARIABLES_REF) {
+            return Err(AetherError::RuntimeError("Only variables can be changed".to_string()));
+        }
+        let name = arguments["name"].as_str().unwrap_or_default().to_string();
+        let expression = arguments["value"].as_str().unwrap_or_default();
+        let session = self.session_mut()?;
+        let value = session.debugger.evaluate(expression)?;
+        session.debugger.vm_mut().set_variable(name, value.clone())?;
+        let reference = self.handle_for(&value);
+        Ok(json!({ "value": display(&value), "type": type_name(&value), "variablesReference": reference }))
+    }
+
+    fn evaluate(&mut self, arguments: &Json) -> Result<Json> {
+        let expression = arguments["expression"].as_str().unwrap_or_default();
+        let value = self.session()?.debugger.evaluate(expression)?;
+        let reference = self.handle_for(&value);
+        Ok(json!({ "result": display(&value), "type": type_name(&value), "variablesReference": reference }))
+    }
+
+    /// Run the program with one of the `Debugger` controls and report where it stopped
+    fn run_program(&mut self, action: fn(&mut Debugger) -> Result<Stop>) -> Result<()> {
+        self.handles.clear();
+        let session = self.session_mut()?;
+        if session.debugger.has_ended() {
+            return self.exit(1);
+        }
+        let stop = action(&mut session.debugger);
+        let printed = session.debugger.vm_mut().take_output();
+        for line in printed {
+            self.event("output", json!({ "category": "stdout", "output": format!("{}\n", line) }))?;
+        }
+
+        match stop {
+            Ok(Stop::Breakpoint(_)) => self.stopped("breakpoint", None),
+            Ok(Stop::DebugSymbol) => self.stopped("breakpoint", Some("🐛")),
+            Ok(Stop::Step) => self.stopped("step", None),
+            Ok(Stop::Finished(value)) => {
+                self.event("output", json!({ "category": "console", "output": format!("Result: {}\n", display(&value)) }))?;
+                self.exit(0)
+            }
+            Err(err) => {
+                self.event("output", json!({ "category": "stderr", "output": format!("{}\n", err) }))?;
+                // Stop rather than exit, so the state that led to the error can be inspected
+                self.event("stopped", json!({
+                    "reason": "exception",
+                    "description": "Error",
+                    "text": err.to_string(),
+                    "threadId": THREAD_ID,
+                    "allThreadsStopped": true,
+                }))
+            }
+        }
+    }
+
+    fn stopped(&mut self, reason: &str, description: Option<&str>) -> Result<()> {
+        let mut body = json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true });
+        if let Some(description) = description {
+            body["description"] = json!(description);
+        }
+        self.event("stopped", body)
+    }
+
+    fn exit(&mut self, code: i64) -> Result<()> {
+        self.event("exited", json!({ "exitCode": code }))?;
+        self.event("terminated", json!({}))
+    }
+
+    fn session(&self) -> Result<&Session> {
+        self.session.as_ref().ok_or_else(no_session)
+    }
+
+    fn session_mut(&mut self) -> Result<&mut Session> {
+        self.session.as_mut().ok_or_else(no_session)
+    }
+
+    /// A `variablesReference` for arrays and objects with something in them, 0 otherwise
+    fn handle_for(&mut self, value: &Value) -> i64 {
+        match value {
+            Value::Array(items) if !items.is_empty() => {}
+            Value::Object(fields) if !fields.is_empty() => {}
+            _ => return 0,
+        }
+        self.handles.push(value.clone());
+        FIRST_HANDLE + self.handles.len() as i64 - 1
+    }
+
+    fn respond(&mut self, request: &Json, body: std::result::Result<Json, String>) -> Result<()> {
+        let mut message = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": body.is_ok(),
+        });
+        match body {
+            Ok(Json::Null) => {}
+            Ok(body) => message["body"] = body,
+            Err(error) => message["message"] = json!(error),
+        }
+        self.send(message)
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn send(&mut self, mut message: Json) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.output, &message)
+    }
+}
+
+/// Read one `Content-Length` framed message, or `None` at the end of the input
+///
+/// The language server frames its messages the same way.
+pub(crate) fn read_message<R: BufRead>(input: &mut R) -> Result<Option<Json>> {
+    let Some(body) = read_body(input)? else { return Ok(None) };
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| AetherError::RuntimeError(format!("Invalid message: {}", err)))
+}
+
+/// Read the body of one `Content-Length` framed message without parsing it
+pub(crate) fn read_body<R: BufRead>(input: &mut R) -> Result<Option<Vec<u8>>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or(0)];
+    input.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Write one message with its `Content-Length` header
+pub(crate) fn write_message<W: Write>(output: &mut W, message: &Json) -> Result<()> {
+    let text = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", text.len(), text)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// What to do once a response has been sent
+enum After {
+    Nothing,
+    Initialized,
+    Start,
+    Run(fn(&mut Debugger) -> Result<Stop>),
+    Exit,
+}
+
+/// Compile a `.ae` file with line info, or read a `.aeb` file
+fn load(path: &str) -> Result<BytecodeProgram> {
+    if path.ends_with(".ae") {
+        let source = std::fs::read_to_string(path)?;
+        normalize::check_legacy_verify(&source)?;
+        Compiler::new().compile_source(&source)
+    } else {
+        let file = std::fs::File::open(path)?;
+        BytecodeProgram::deserialize(&mut BufReader::new(file))
+    }
+}
+
+/// Whether two paths name the same file
+fn same_file(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn no_session() -> AetherError {
+    AetherError::RuntimeError("No program has been launched".to_string())
+}
+
+/// A value as the editor shows it
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        Value::Number(n) => n.to_string(),
+        Value::Integer(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Null => "∅".to_string(),
+        Value::Array(items) => format!("[{} items]", items.len()),
+        Value::Object(fields) => format!("{{{} fields}}", fields.len()),
+        Value::Bytes(bytes) => format!("hex\"{}\"", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+        Value::AsyncTask(id) => format!("task {}", id),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Integer(_) => "integer",
+        Value::Decimal(_) => "decimal",
+        Value::Boolean(_) => "boolean",
+        Value::Null => "null",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Bytes(_) => "bytes",
+        Value::AsyncTask(_) => "task",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Frame requests as a DAP client would
+    fn script(requests: &[Json]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for (seq, request) in requests.iter().enumerate() {
+            let mut request = request.clone();
+            request["seq"] = json!(seq + 1);
+            request["type"] = json!("request");
+            let text = request.to_string();
+            input.extend(format!("Content-Length: {}\r\n\r\n{}", text.len(), text).into_bytes());
+        }
+        input
+    }
+
+    /// Run a scripted session and split the server's output back into messages
+    fn session(requests: &[Json]) -> Vec<Json> {
+        let input = script(requests);
+        let mut output = Vec::new();
+        DapServer::new(Cursor::new(input), &mut output).run().unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn program(name: &str, source: &str) -> String {
+        let path = std::env::temp_dir().join(format!("aether_dap_{}_{}.ae", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn find<'a>(messages: &'a [Json], kind: &str, name: &str) -> Vec<&'a Json> {
+        let key = if kind == "event" { "event" } else { "command" };
+        messages.iter().filter(|m| m["type"] == kind && m[key] == name).collect()
+    }
+
+    #[test]
+    fn test_breakpoints_stepping_and_inspection() {
+        let path = program("steps", "[1, 2] ▷ items ⨠\n5 ▷ x ⨠\nx * 2 ▷ y ⨠\n📤y");
+        let messages = session(&[
+            json!({ "command": "initialize", "arguments": { "adapterID": "aether" } }),
+            json!({ "command": "launch", "arguments": { "program": path } }),
+            json!({ "command": "setBreakpoints", "arguments": { "source": { "path": path }, "breakpoints": [{ "line": 3 }, { "line": 9 }] } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "scopes", "arguments": { "frameId": 0 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": VARIABLES_REF } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": FIRST_HANDLE } }),
+            json!({ "command": "evaluate", "arguments": { "expression": "x + 1" } }),
+            json!({ "command": "setVariable", "arguments": { "variablesReference": VARIABLES_REF, "name": "x", "value": "20" } }),
+            json!({ "command": "next", "arguments": { "threadId": 1 } }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "disconnect" }),
+        ]);
+
+        assert!(messages.iter().all(|m| m["type"] != "response" || m["success"] == true), "{:#?}", messages);
+        assert_eq!(find(&messages, "response", "initialize")[0]["body"]["supportsConfigurationDoneRequest"], true);
+        assert_eq!(find(&messages, "event", "initialized").len(), 1);
+        let breakpoints = &find(&messages, "response", "setBreakpoints")[0]["body"]["breakpoints"];
+        assert_eq!(*breakpoints, json!([{ "verified": true, "line": 3 }, { "verified": false, "line": 9 }]));
+
+        let stops: Vec<&Json> = find(&messages, "event", "stopped").into_iter().map(|m| &m["body"]["reason"]).collect();
+        assert_eq!(stops, vec!["breakpoint", "step"]);
+        let traces = find(&messages, "response", "stackTrace");
+        assert_eq!(traces[0]["body"]["stackFrames"][0]["line"], 3);
+        assert_eq!(traces[0]["body"]["stackFrames"][0]["source"]["path"], json!(path));
+        assert_eq!(traces[1]["body"]["stackFrames"][0]["line"], 4);
+
+        let variables = find(&messages, "response", "variables");
+        assert_eq!(variables[0]["body"]["variables"], json!([
+            { "name": "items", "value": "[2 items]", "type": "array", "variablesReference": FIRST_HANDLE },
+            { "name": "x", "value": "5", "type": "integer", "variablesReference": 0 },
+        ]));
+        assert_eq!(variables[1]["body"]["variables"][1]["value"], "2");
+        assert_eq!(find(&messages, "response", "evaluate")[0]["body"]["result"], "6");
+
+        let output: Vec<&Json> = find(&messages, "event", "output").into_iter().map(|m| &m["body"]["output"]).collect();
+        assert_eq!(output, vec!["Output: Integer(40)\n", "Result: 40\n"]);
+        assert_eq!(find(&messages, "event", "exited")[0]["body"]["exitCode"], 0);
+        assert_eq!(find(&messages, "event", "terminated").len(), 1);
+    }
+
+    #[test]
+    fn test_stop_on_entry_debug_symbol_and_errors() {
+        let path = program("errors", "1 ▷ a ⨠\n🐛 ⨠\n🛑\"bad\"");
+        let messages = session(&[
+            json!({ "command": "initialize" }),
+            json!({ "command": "variables", "arguments": { "variablesReference": VARIABLES_REF } }),
+            json!({ "command": "launch", "arguments": { "program": path, "stopOnEntry": true } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stepIn", "arguments": { "threadId": 1 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "pause", "arguments": { "threadId": 1 } }),
+        ]);
+
+        // Inspecting before launch and unsupported requests fail without ending the session
+        let failed: Vec<&Json> = messages.iter().filter(|m| m["success"] == false).map(|m| &m["command"]).collect();
+        assert_eq!(failed, vec!["variables", "pause"]);
+
+        let stops: Vec<&Json> = find(&messages, "event", "stopped").into_iter().map(|m| &m["body"]).collect();
+        assert_eq!(stops[0]["reason"], "entry");
+        assert_eq!(stops[1]["reason"], "breakpoint");
+        assert_eq!(stops[1]["description"], "🐛");
+        assert_eq!(stops[2]["reason"], "exception");
+        assert!(stops[2]["text"].as_str().unwrap().contains("bad"));
+        assert_eq!(find(&messages, "event", "exited")[0]["body"]["exitCode"], 1);
+    }
+
+    #[test]
+    fn test_launch_errors_are_reported() {
+        let path = program("broken", "1 +");
+        let messages = session(&[
+            json!({ "command": "launch", "arguments": { "program": path } }),
+            json!({ "command": "launch", "arguments": {} }),
+        ]);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0]["message"].as_str().unwrap().starts_with("Parser error"));
+        assert_eq!(messages[1]["message"], "Runtime error: launch needs a 'program' path");
+    }
+}
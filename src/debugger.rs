@@ -0,0 +1,279 @@
+//! Step debugger for Aether
+//!
+//! `Debugger` runs a `VM` one instruction at a time and stops at line
+//! breakpoints, before `🐛`, or when a step completes, using the program's
+//! line table to find where statements start. The runtime has no source
+//! positions, so it takes a `DebugHook` instead, called before each
+//! expression it evaluates.
+
+use crate::bytecode::{Opcode, SourceSpan};
+use crate::compiler::Compiler;
+use crate::error::{AetherError, Result};
+use crate::parser::AstNode;
+use crate::runtime::Value;
+use crate::vm::VM;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Why the debugger handed control back
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stop {
+    /// About to run the first statement on a line with a breakpoint
+    Breakpoint(u32),
+    /// About to run a `🐛`
+    DebugSymbol,
+    /// A step completed
+    Step,
+    /// The program ran to its end with this result
+    Finished(Value),
+}
+
+/// How far to run before stopping
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Continue,
+    Into,
+    Over,
+    Out,
+}
+
+/// Interactive control over a `VM`
+pub struct Debugger {
+    vm: VM,
+    breakpoints: BTreeSet<u32>,
+    break_on_debug: bool,
+    /// Code positions where a statement starts, from the line table
+    statement_starts: HashSet<usize>,
+    ended: bool,
+}
+
+impl Debugger {
+    /// Verify the VM's program and stop before its first instruction
+    pub fn new(mut vm: VM) -> Result<Self> {
+        vm.start()?;
+        let statement_starts = vm.program().lines.iter().map(|(position, _)| *position as usize).collect();
+        Ok(Debugger {
+            vm,
+            breakpoints: BTreeSet::new(),
+            break_on_debug: true,
+            statement_starts,
+            ended: false,
+        })
+    }
+
+    /// The VM, for inspecting its state
+    pub fn vm(&self) -> &VM {
+        &self.vm
+    }
+
+    /// The VM, for changing its variables
+    pub fn vm_mut(&mut self) -> &mut VM {
+        &mut self.vm
+    }
+
+    /// Stop before statements that start on `line`, returning whether any do
+    pub fn add_breakpoint(&mut self, line: u32) -> bool {
+        self.breakpoints.insert(line);
+        self.vm.program().lines.iter().any(|(_, span)| span.line == line)
+    }
+
+    /// Remove a breakpoint, returning whether it was set
+    pub fn remove_breakpoint(&mut self, line: u32) -> bool {
+        self.breakpoints.remove(&line)
+    }
+
+    /// Lines with breakpoints, in order
+    pub fn breakpoints(&self) -> impl Iterator<Item = u32> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Whether to stop before each `🐛` (on by default)
+    pub fn set_break_on_debug(&mut self, on: bool) {
+        self.break_on_debug = on;
+    }
+
+    /// Whether the program has finished or failed
+    pub fn has_ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Source span of the statement about to run, if the program has a line table
+    pub fn location(&self) -> Option<SourceSpan> {
+        self.vm.program().span_at(self.vm.pc())
+    }
+
+    /// Evaluate an expression, such as `total * 2`, against the program's
+    /// current variables without changing them
+    pub fn evaluate(&self, expression: &str) -> Result<Value> {
+        let program = Compiler::new().compile_source(expression)?;
+        let mut vm = VM::new(program);
+        vm.capture_output();
+        for (name, value) in self.vm.variables() {
+            vm.set_variable(name.clone(), value.clone())?;
+        }
+        vm.execute()
+    }
+
+    /// Run until a breakpoint, a `🐛` or the end of the program
+    pub fn resume(&mut self) -> Result<Stop> {
+        self.run(Mode::Continue)
+    }
+
+    /// Run to the start of the next statement, entering calls
+    pub fn step_into(&mut self) -> Result<Stop> {
+        self.run(Mode::Into)
+    }
+
+    /// Run to the start of the next statement in the current call
+    pub fn step_over(&mut self) -> Result<Stop> {
+        self.run(Mode::Over)
+    }
+
+    /// Run until the current call returns
+    pub fn step_out(&mut self) -> Result<Stop> {
+        self.run(Mode::Out)
+    }
+
+    fn run(&mut self, mode: Mode) -> Result<Stop> {
+        if self.ended {
+            return Err(AetherError::RuntimeError("The program has ended".to_string()));
+        }
+        let depth = self.vm.call_frames().len();
+        let mut moved = false;
+
+        loop {
+            if self.vm.is_finished() {
+                self.ended = true;
+                let result = self.vm.stack().last().cloned().unwrap_or(Value::Null);
+                return Ok(Stop::Finished(result));
+            }
+            // Nothing stops the program where it already stands
+            if moved {
+                if let Some(stop) = self.stop_here(mode, depth) {
+                    return Ok(stop);
+                }
+            }
+            if let Err(err) = self.vm.step() {
+                self.ended = true;
+                return Err(err);
+            }
+            moved = true;
+        }
+    }
+
+    /// Whether to stop before the instruction at the program counter
+    fn stop_here(&self, mode: Mode, depth: usize) -> Option<Stop> {
+        let pc = self.vm.pc();
+        let program = self.vm.program();
+        // Without a line table every instruction is a statement
+        let statement = program.lines.is_empty() || self.statement_starts.contains(&pc);
+        let frames = self.vm.call_frames().len();
+
+        if statement {
+            if let Some(span) = program.span_at(pc) {
+                if self.breakpoints.contains(&span.line) {
+                    return Some(Stop::Breakpoint(span.line));
+                }
+            }
+        }
+        if self.break_on_debug && program.code.get(pc) == Some(&Opcode::Debug.to_byte()) {
+            return Some(Stop::DebugSymbol);
+        }
+        let done = match mode {
+            Mode::Continue => false,
+            Mode::Into => statement,
+            Mode::Over => statement && frames <= depth,
+            Mode::Out => frames < depth,
+        };
+        done.then_some(Stop::Step)
+    }
+}
+
+/// Called by the runtime before it evaluates each expression
+pub trait DebugHook {
+    /// `depth` is 1 for a top-level statement and grows with nesting, so
+    /// stepping over means waiting for a depth no greater than the current
+    /// one. The hook may change variables; returning an error stops the
+    /// program with it.
+    fn before(&mut self, node: &AstNode, depth: usize, variables: &mut HashMap<String, Value>) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "1 ▷ a ⨠\n2 ▷ b ⨠\n🐛 ⨠\na + b ▷ c ⨠\n📤c";
+
+    fn debugger(source: &str) -> Debugger {
+        let program = Compiler::new().compile_source(source).unwrap();
+        let mut vm = VM::new(program);
+        vm.capture_output();
+        Debugger::new(vm).unwrap()
+    }
+
+    #[test]
+    fn test_stepping_stops_at_each_statement() {
+        let mut debugger = debugger(SOURCE);
+        assert_eq!(debugger.location().map(|span| span.line), Some(1));
+
+        assert_eq!(debugger.step_over().unwrap(), Stop::Step);
+        assert_eq!(debugger.location().map(|span| span.line), Some(2));
+        assert_eq!(debugger.vm().get_variable("a"), Some(&Value::Integer(1)));
+
+        assert_eq!(debugger.step_into().unwrap(), Stop::DebugSymbol);
+        assert_eq!(debugger.location().map(|span| span.line), Some(3));
+        assert_eq!(debugger.vm().get_variable("b"), Some(&Value::Integer(2)));
+
+        assert_eq!(debugger.step_over().unwrap(), Stop::Step);
+        assert_eq!(debugger.location().map(|span| span.line), Some(4));
+        assert_eq!(debugger.step_out().unwrap(), Stop::Finished(Value::Integer(3)));
+        assert!(debugger.has_ended());
+        assert!(debugger.step_over().is_err());
+    }
+
+    #[test]
+    fn test_breakpoints_and_changing_variables() {
+        let mut debugger = debugger(SOURCE);
+        debugger.set_break_on_debug(false);
+        assert!(debugger.add_breakpoint(4));
+        assert!(!debugger.add_breakpoint(9));
+        assert!(debugger.remove_breakpoint(9));
+        assert_eq!(debugger.breakpoints().collect::<Vec<_>>(), vec![4]);
+
+        assert_eq!(debugger.resume().unwrap(), Stop::Breakpoint(4));
+        assert_eq!(debugger.evaluate("(b * 10) + a").unwrap(), Value::Integer(21));
+        assert!(debugger.evaluate("1 +").is_err());
+        debugger.vm_mut().set_variable("b".to_string(), Value::Integer(40)).unwrap();
+        assert_eq!(debugger.resume().unwrap(), Stop::Finished(Value::Integer(41)));
+        assert_eq!(debugger.vm_mut().take_output().last().map(String::as_str), Some("Output: Integer(41)"));
+    }
+
+    #[test]
+    fn test_loops_hit_a_breakpoint_each_time_round() {
+        let mut debugger = debugger("0 ▷ i ⨠\n↻(i < 3): (i + 1 ▷ i) ⨠\n📤i");
+        debugger.add_breakpoint(2);
+        let mut hits = 0;
+        while debugger.resume().unwrap() == Stop::Breakpoint(2) {
+            hits += 1;
+        }
+        // Once on entry and once more for each check of the condition
+        assert_eq!(hits, 4);
+        assert_eq!(debugger.vm().get_variable("i"), Some(&Value::Integer(3)));
+
+        // Without a line table each instruction is a step
+        let program = Compiler::new().compile_source("1 + 2").map(|mut program| {
+            program.lines.clear();
+            program
+        });
+        let mut debugger = Debugger::new(VM::new(program.unwrap())).unwrap();
+        assert_eq!(debugger.step_into().unwrap(), Stop::Step);
+        assert_eq!(debugger.vm().stack(), &[Value::Integer(1)]);
+    }
+
+    #[test]
+    fn test_errors_end_the_session() {
+        let mut debugger = debugger("1 ▷ a ⨠\n🛑\"stop\"");
+        assert!(debugger.resume().is_err());
+        assert!(debugger.has_ended());
+        assert_eq!(debugger.vm().get_variable("a"), Some(&Value::Integer(1)));
+    }
+}
@@ -1,5 +1,6 @@
 //! Error types for the Aether language
 
+use crate::limits::Limit;
 use thiserror::Error;
 
 /// Result type for Aether operations
@@ -34,6 +35,9 @@ pub enum AetherError {
 
     #[error("Compiler error: {0}")]
     CompilerError(String),
+
+    #[error("{0} limit exceeded: {1}")]
+    LimitExceeded(Limit, String),
 }
 
 impl From<std::io::Error> for AetherError {
@@ -3,504 +3,965 @@
 //! This module provides projectional editing functionality, translating
 //! Aether's high-density UTF-8 symbols into readable pseudo-code that
 //! humans can understand.
+//!
+//! The wording comes from a message catalog for each `Lang`, keyed by
+//! `AstNode::kind`, with `kind.part` keys for the optional parts of a node.
+//! An explanation can be plain indented text, Markdown with each statement's
+//! source above its explanation, or JSON with the AST for tools.
+//!
+//! `FORMS` gives each message's level and the parts its placeholders take.
+//! Parts are parenthesized when they bind looser than their place allows and
+//! blocks are indented, so an explanation is unambiguous and the `Projector`
+//! can read it back with the same table.
+
+use crate::error::Result;
+use crate::lexer::{quote, Lexer, Token, TokenType};
+use crate::normalize;
+use crate::parser::{AstNode, ComparisonOp, LiteralValue, Parser, TypeAnnotation};
+use crate::constants::PIPE_VARIABLE;
+use crate::symbols::SymbolInfo;
+use serde_json::{json, Value as Json};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Language of an explanation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    /// Simplified Chinese
+    Zh,
+}
+
+impl Lang {
+    /// The language for a code such as `en` or `zh`
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Lang::En),
+            "zh" => Some(Lang::Zh),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => EN,
+            Lang::Zh => ZH,
+        }
+    }
+
+    /// The message for `key`, falling back to English and then to the key
+    pub fn template(self, key: &'static str) -> &'static str {
+        self.catalog().iter().chain(EN).find(|(k, _)| *k == key).map_or(key, |(_, m)| *m)
+    }
+}
+
+/// How an explanation is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Indented pseudo-code
+    #[default]
+    Text,
+    /// Each statement's source in a code block above its explanation
+    Markdown,
+    /// Each statement's source, explanation and AST
+    Json,
+}
+
+impl Format {
+    /// The format for a name such as `text`, `md` or `json`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Format::Text),
+            "md" | "markdown" => Some(Format::Markdown),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// English messages; `{0}`, `{1}` and so on are the parts of the node
+const EN: &[(&str, &str)] = &[
+    ("function", "function {0}:\n{1}"),
+    ("sequence.then", "then"),
+    ("input", "read input"),
+    ("output", "output {0}"),
+    ("pipe", "{0} | {1}"),
+    ("pipe_into", "{0} -> store in {1}{2}"),
+    ("pipe_into.string", " (string)"),
+    ("pipe_into.number", " (number)"),
+    ("guard", "guard (if {0} is null or invalid):\n{1}"),
+    ("halt", "halt with error {0}"),
+    ("persist", "save to database: {0}"),
+    ("json_parse", "parse JSON({0})"),
+    ("literal.bytes", "bytes {0}"),
+    ("empty", "null"),
+    ("if_then", "if {0}:\n{1}"),
+    ("if_then.else", "else:\n{0}"),
+    ("loop", "while {0}:\n{1}"),
+    ("loop.forever", "loop forever:\n{0}"),
+    ("for_each", "for each {0} in {1}:\n{2}"),
+    ("filter", "filter where {0}"),
+    ("reduce", "reduce with {0} starting from {1}"),
+    ("try_rescue", "try:\n{0}"),
+    ("try_rescue.rescue", "on error:\n{0}"),
+    ("retry", "retry up to {0} times:\n{1}"),
+    ("retry.unlimited", "unlimited"),
+    ("async", "execute asynchronously:\n{0}"),
+    ("await", "wait for {0}"),
+    ("import", "import module {0}"),
+    ("split", "split {0}"),
+    ("split.by", "split {0} by {1}"),
+    ("join", "join {0}"),
+    ("join.with", "join {0} with separator {1}"),
+    ("regex_match", "match pattern {0} against {1}"),
+    ("equal", "({0} == {1})"),
+    ("not_equal", "({0} != {1})"),
+    ("and", "({0} and {1})"),
+    ("or", "({0} or {1})"),
+    ("not", "(not {0})"),
+    ("comparison", "({0} {1} {2})"),
+    ("approx", "({0} ≈ {1})"),
+    ("approx.piped", "(piped value) ≈ {0}"),
+    ("http_get", "HTTP GET request to {0}"),
+    ("http_post", "HTTP POST to {0}"),
+    ("http_put", "HTTP PUT to {0}"),
+    ("http_delete", "HTTP DELETE request to {0}"),
+    ("http_patch", "HTTP PATCH to {0}"),
+    ("http_head", "HTTP HEAD request to {0}"),
+    ("http_options", "HTTP OPTIONS request to {0}"),
+    ("http.body", " with body {0}"),
+    ("http.headers", " with headers {0}"),
+    ("random", "generate random number"),
+    ("date_time", "get current date/time"),
+    ("log", "log message: {0}"),
+    ("assert", "assert {0}"),
+    ("hash", "hash {0}"),
+    ("power", "{0} raised to the power of {1}"),
+    ("power.piped", "(piped value) raised to the power of {0}"),
+    ("root", "square root of {0}"),
+    ("root.piped", "square root"),
+    ("read_content", "read from {0}"),
+    ("write_content", "write to {0} content: {1}"),
+    ("shell_exec", "execute shell command: {0}"),
+    ("env_var", "get environment variable {0}"),
+    ("immutable", "define constant {0} = {1}"),
+    ("debug", "enable debug mode"),
+    ("file_handle", "file {0}"),
+    ("directory", "directory {0}"),
+    ("path_resolve", "path {0}"),
+    ("append_content", "append to {0} content: {1}"),
+    ("delete_file", "delete {0}"),
+    ("set_permission", "set permissions of {0} to {1}"),
+    ("create_stream", "create stream from {0}"),
+    ("create_stream.options", " reading {0}"),
+    ("create_buffer", "create buffer {0}"),
+    ("to_bytes", "convert {0} to bytes"),
+    ("to_text", "convert {0} to string"),
+    ("encoding", " with encoding {0}"),
+    ("flush_buffer", "flush {0}"),
+    ("end_of_file", "end of file"),
+    ("skip_bytes", "skip {1} bytes of {0}"),
+    ("create_socket", "create socket {0}"),
+    ("listen_port", "listen on port {0}"),
+    ("connect_remote", "connect to {0}"),
+    ("port_number", "port number {0}"),
+    ("create_packet", "create packet {0}"),
+    ("handshake", "handshake with {0}"),
+    ("process_create", "create process {0}"),
+    ("memory_alloc", "allocate memory {0}"),
+    ("exit_program", "exit program with code {0}"),
+    ("send_signal", "send signal {0} to {1}"),
+    ("property_access", "{0}.{1}"),
+    ("thread", "run in a thread:\n{0}"),
+    ("lock", "holding the lock:\n{0}"),
+    ("emit", "emit event {0}"),
+    ("watch", "watch event {0} with handler {1}"),
+    ("auth", "authenticate with {0}"),
+    ("test", "test {0}:\n{1}"),
+    ("mock", "mock {0}"),
+    ("benchmark", "benchmark:\n{0}"),
+    ("encrypt", "encrypt {0} with key {1}"),
+    ("decrypt", "decrypt {0} with key {1}"),
+    ("sign", "sign {0} with key {1}"),
+    ("verify_signature", "verify signature {0} of {1} with key {2}"),
+    ("infinity", "infinity"),
+    ("delta", "change {0} by {1}"),
+    ("length", "length of {0}"),
+    ("length.piped", "length"),
+    ("index", "{0}[{1}]"),
+    ("array_push", "push {1} onto {0}"),
+    ("array_push.piped", "push {0}"),
+    ("add", "({0} + {1})"),
+    ("subtract", "({0} - {1})"),
+    ("multiply", "({0} * {1})"),
+    ("divide", "({0} / {1})"),
+    ("modulo", "({0} % {1})"),
+    ("string_concat", "({0} ++ {1})"),
+    ("char_at", "character {1} of {0}"),
+    ("slice", "{0}[{1}:{2}]"),
+    ("slice.open", "{0}[{1}:]"),
+    ("array_literal", "[{0}]"),
+    ("object_literal", "{{0}}"),
+    // Headings of `aether explain` output
+    ("heading.file", "File:"),
+    ("heading.code", "Aether Code:"),
+    ("heading.explanation", "Human-Readable Explanation:"),
+    ("heading.symbols", "Symbols Used:"),
+];
+
+/// Chinese messages, with the same keys and parts as `EN`
+const ZH: &[(&str, &str)] = &[
+    ("function", "函数 {0}：\n{1}"),
+    ("sequence.then", "然后"),
+    ("input", "读取输入"),
+    ("output", "输出 {0}"),
+    ("pipe", "{0} | {1}"),
+    ("pipe_into", "{0} -> 存入 {1}{2}"),
+    ("pipe_into.string", "（字符串）"),
+    ("pipe_into.number", "（数字）"),
+    ("guard", "守卫（若 {0} 为空或无效）：\n{1}"),
+    ("halt", "以错误 {0} 终止"),
+    ("persist", "保存到数据库：{0}"),
+    ("json_parse", "解析 JSON({0})"),
+    ("literal.bytes", "字节 {0}"),
+    ("empty", "空值"),
+    ("if_then", "如果 {0}：\n{1}"),
+    ("if_then.else", "否则：\n{0}"),
+    ("loop", "当 {0} 时：\n{1}"),
+    ("loop.forever", "无限循环：\n{0}"),
+    ("for_each", "对 {1} 中的每个 {0}：\n{2}"),
+    ("filter", "筛选满足 {0} 的项"),
+    ("reduce", "以 {1} 为初值，用 {0} 归约"),
+    ("try_rescue", "尝试：\n{0}"),
+    ("try_rescue.rescue", "出错时：\n{0}"),
+    ("retry", "最多重试 {0} 次：\n{1}"),
+    ("retry.unlimited", "无限"),
+    ("async", "异步执行：\n{0}"),
+    ("await", "等待 {0}"),
+    ("import", "导入模块 {0}"),
+    ("split", "拆分 {0}"),
+    ("split.by", "按 {1} 拆分 {0}"),
+    ("join", "连接 {0}"),
+    ("join.with", "用分隔符 {1} 连接 {0}"),
+    ("regex_match", "用模式 {0} 匹配 {1}"),
+    ("equal", "({0} == {1})"),
+    ("not_equal", "({0} != {1})"),
+    ("and", "({0} 且 {1})"),
+    ("or", "({0} 或 {1})"),
+    ("not", "(非 {0})"),
+    ("comparison", "({0} {1} {2})"),
+    ("approx", "({0} ≈ {1})"),
+    ("approx.piped", "(管道值) ≈ {0}"),
+    ("http_get", "向 {0} 发送 HTTP GET 请求"),
+    ("http_post", "向 {0} 发送 HTTP POST 请求"),
+    ("http_put", "向 {0} 发送 HTTP PUT 请求"),
+    ("http_delete", "向 {0} 发送 HTTP DELETE 请求"),
+    ("http_patch", "向 {0} 发送 HTTP PATCH 请求"),
+    ("http_head", "向 {0} 发送 HTTP HEAD 请求"),
+    ("http_options", "向 {0} 发送 HTTP OPTIONS 请求"),
+    ("http.body", "，请求体为 {0}"),
+    ("http.headers", "，附带请求头 {0}"),
+    ("random", "生成随机数"),
+    ("date_time", "获取当前日期/时间"),
+    ("log", "记录日志：{0}"),
+    ("assert", "断言 {0}"),
+    ("hash", "计算 {0} 的哈希"),
+    ("power", "{0} 的 {1} 次方"),
+    ("power.piped", "(管道值) 的 {0} 次方"),
+    ("root", "{0} 的平方根"),
+    ("root.piped", "平方根"),
+    ("read_content", "从 {0} 读取"),
+    ("write_content", "向 {0} 写入内容：{1}"),
+    ("shell_exec", "执行 shell 命令：{0}"),
+    ("env_var", "获取环境变量 {0}"),
+    ("immutable", "定义常量 {0} = {1}"),
+    ("debug", "启用调试模式"),
+    ("file_handle", "文件 {0}"),
+    ("directory", "目录 {0}"),
+    ("path_resolve", "路径 {0}"),
+    ("append_content", "向 {0} 追加内容：{1}"),
+    ("delete_file", "删除 {0}"),
+    ("set_permission", "将 {0} 的权限设为 {1}"),
+    ("create_stream", "从 {0} 创建流"),
+    ("create_stream.options", "，读取方式为 {0}"),
+    ("create_buffer", "创建缓冲区 {0}"),
+    ("to_bytes", "将 {0} 转换为字节"),
+    ("to_text", "将 {0} 转换为字符串"),
+    ("encoding", "，编码为 {0}"),
+    ("flush_buffer", "刷新 {0}"),
+    ("end_of_file", "文件结尾"),
+    ("skip_bytes", "跳过 {0} 的 {1} 个字节"),
+    ("create_socket", "创建套接字 {0}"),
+    ("listen_port", "监听端口 {0}"),
+    ("connect_remote", "连接到 {0}"),
+    ("port_number", "端口号 {0}"),
+    ("create_packet", "创建数据包 {0}"),
+    ("handshake", "与 {0} 握手"),
+    ("process_create", "创建进程 {0}"),
+    ("memory_alloc", "分配内存 {0}"),
+    ("exit_program", "以代码 {0} 退出程序"),
+    ("send_signal", "向 {1} 发送信号 {0}"),
+    ("property_access", "{0}.{1}"),
+    ("thread", "在线程中运行：\n{0}"),
+    ("lock", "持有锁时：\n{0}"),
+    ("emit", "发出事件 {0}"),
+    ("watch", "监听事件 {0}，处理程序为 {1}"),
+    ("auth", "使用 {0} 认证"),
+    ("test", "测试 {0}：\n{1}"),
+    ("mock", "模拟 {0}"),
+    ("benchmark", "基准测试：\n{0}"),
+    ("encrypt", "用密钥 {1} 加密 {0}"),
+    ("decrypt", "用密钥 {1} 解密 {0}"),
+    ("sign", "用密钥 {1} 签名 {0}"),
+    ("verify_signature", "用密钥 {2} 验证 {1} 的签名 {0}"),
+    ("infinity", "无穷大"),
+    ("delta", "将 {0} 改变 {1}"),
+    ("length", "{0} 的长度"),
+    ("length.piped", "长度"),
+    ("index", "{0}[{1}]"),
+    ("array_push", "将 {1} 追加到 {0}"),
+    ("array_push.piped", "追加 {0}"),
+    ("add", "({0} + {1})"),
+    ("subtract", "({0} - {1})"),
+    ("multiply", "({0} * {1})"),
+    ("divide", "({0} / {1})"),
+    ("modulo", "({0} % {1})"),
+    ("string_concat", "({0} ++ {1})"),
+    ("char_at", "{0} 的第 {1} 个字符"),
+    ("slice", "{0}[{1}:{2}]"),
+    ("slice.open", "{0}[{1}:]"),
+    ("array_literal", "[{0}]"),
+    ("object_literal", "{{0}}"),
+    ("heading.file", "文件："),
+    ("heading.code", "Aether 代码："),
+    ("heading.explanation", "可读解释："),
+    ("heading.symbols", "用到的符号："),
+];
+
+/// How tightly an explanation holds together, from steps joined by `then`
+/// to an atom that never needs parentheses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Level {
+    /// Steps on their own lines joined by `then`
+    Sequence,
+    /// A value piped on with `|` or stored with `->`
+    Chain,
+    /// A phrase such as `output x` that runs to the end of its last part
+    Phrase,
+    /// A name, literal, bracketed message or indented block
+    Atom,
+}
+
+/// What fills a placeholder of a message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Part {
+    /// A node explained at this level, in parentheses if it binds looser
+    Node(Level),
+    /// A node's steps indented on the lines below
+    Body,
+    /// A variable, property or module name
+    Name,
+    /// A string, quoted as in Aether
+    Text,
+    /// A retry count, or `retry.unlimited`
+    Count,
+    /// A comparison operator
+    Operator,
+    /// Nothing, or one of the `pipe_into` types
+    Annotation,
+    /// Bytes written as `0x` and hex digits
+    Bytes,
+    /// Nodes separated by commas
+    Elements,
+    /// `key: value` pairs separated by commas
+    Pairs,
+}
+
+/// The level of a message and the parts of its placeholders, with the
+/// optional messages that may follow it, each with one part
+pub(crate) struct Form {
+    pub key: &'static str,
+    pub level: Level,
+    pub parts: &'static [Part],
+    pub extras: &'static [(&'static str, Part)],
+}
+
+const ATOM: Part = Part::Node(Level::Atom);
+const PHRASE: Part = Part::Node(Level::Phrase);
+const CHAIN: Part = Part::Node(Level::Chain);
+const SEQUENCE: Part = Part::Node(Level::Sequence);
+
+const fn form(key: &'static str, level: Level, parts: &'static [Part]) -> Form {
+    Form { key, level, parts, extras: &[] }
+}
+
+const fn with(key: &'static str, level: Level, parts: &'static [Part], extras: &'static [(&'static str, Part)]) -> Form {
+    Form { key, level, parts, extras }
+}
+
+/// Every message that explains a node; where one message begins like
+/// another, the longer comes first
+pub(crate) const FORMS: &[Form] = {
+    use Level::{Atom, Chain, Phrase};
+    use Part::{Annotation, Body, Bytes, Count, Elements, Name, Operator, Pairs, Text};
+    &[
+        form("function", Atom, &[Name, Body]),
+        form("input", Atom, &[]),
+        form("output", Phrase, &[ATOM]),
+        form("pipe", Chain, &[CHAIN, PHRASE]),
+        form("pipe_into", Chain, &[CHAIN, Name, Annotation]),
+        form("guard", Atom, &[PHRASE, Body]),
+        form("halt", Phrase, &[ATOM]),
+        form("persist", Phrase, &[ATOM]),
+        form("json_parse", Atom, &[SEQUENCE]),
+        form("literal.bytes", Atom, &[Bytes]),
+        form("empty", Atom, &[]),
+        with("if_then", Atom, &[PHRASE, Body], &[("if_then.else", Body)]),
+        form("loop", Atom, &[PHRASE, Body]),
+        form("loop.forever", Atom, &[Body]),
+        form("for_each", Atom, &[Name, PHRASE, Body]),
+        form("filter", Phrase, &[ATOM]),
+        form("reduce", Phrase, &[ATOM, ATOM]),
+        with("try_rescue", Atom, &[Body], &[("try_rescue.rescue", Body)]),
+        form("retry", Atom, &[Count, Body]),
+        form("async", Atom, &[Body]),
+        form("await", Phrase, &[ATOM]),
+        form("import", Phrase, &[Name]),
+        form("split.by", Phrase, &[ATOM, ATOM]),
+        form("split", Phrase, &[ATOM]),
+        form("join.with", Phrase, &[ATOM, ATOM]),
+        form("join", Phrase, &[ATOM]),
+        form("regex_match", Phrase, &[ATOM, ATOM]),
+        form("equal", Atom, &[PHRASE, PHRASE]),
+        form("not_equal", Atom, &[PHRASE, PHRASE]),
+        form("and", Atom, &[PHRASE, PHRASE]),
+        form("or", Atom, &[PHRASE, PHRASE]),
+        form("not", Atom, &[PHRASE]),
+        form("comparison", Atom, &[PHRASE, Operator, PHRASE]),
+        form("approx", Atom, &[PHRASE, PHRASE]),
+        form("approx.piped", Phrase, &[ATOM]),
+        with("http_get", Phrase, &[ATOM], &[("http.headers", ATOM)]),
+        with("http_post", Phrase, &[ATOM], &[("http.body", ATOM), ("http.headers", ATOM)]),
+        with("http_put", Phrase, &[ATOM], &[("http.body", ATOM), ("http.headers", ATOM)]),
+        with("http_delete", Phrase, &[ATOM], &[("http.headers", ATOM)]),
+        with("http_patch", Phrase, &[ATOM], &[("http.body", ATOM), ("http.headers", ATOM)]),
+        with("http_head", Phrase, &[ATOM], &[("http.headers", ATOM)]),
+        with("http_options", Phrase, &[ATOM], &[("http.headers", ATOM)]),
+        form("random", Atom, &[]),
+        form("date_time", Atom, &[]),
+        form("log", Phrase, &[ATOM]),
+        form("assert", Phrase, &[ATOM]),
+        form("hash", Phrase, &[ATOM]),
+        form("power", Phrase, &[ATOM, ATOM]),
+        form("power.piped", Phrase, &[ATOM]),
+        form("root", Phrase, &[ATOM]),
+        form("root.piped", Phrase, &[]),
+        form("read_content", Phrase, &[ATOM]),
+        form("write_content", Phrase, &[ATOM, ATOM]),
+        form("shell_exec", Phrase, &[ATOM]),
+        form("env_var", Phrase, &[ATOM]),
+        form("immutable", Phrase, &[Name, ATOM]),
+        form("debug", Atom, &[]),
+        form("file_handle", Phrase, &[ATOM]),
+        form("directory", Phrase, &[ATOM]),
+        form("path_resolve", Phrase, &[ATOM]),
+        form("append_content", Phrase, &[ATOM, ATOM]),
+        form("delete_file", Phrase, &[ATOM]),
+        form("set_permission", Phrase, &[ATOM, ATOM]),
+        with("create_stream", Phrase, &[ATOM], &[("create_stream.options", ATOM)]),
+        form("create_buffer", Phrase, &[ATOM]),
+        with("to_bytes", Phrase, &[ATOM], &[("encoding", ATOM)]),
+        with("to_text", Phrase, &[ATOM], &[("encoding", ATOM)]),
+        form("flush_buffer", Phrase, &[ATOM]),
+        form("end_of_file", Atom, &[]),
+        form("skip_bytes", Phrase, &[ATOM, ATOM]),
+        form("create_socket", Phrase, &[ATOM]),
+        form("listen_port", Phrase, &[ATOM]),
+        form("connect_remote", Phrase, &[ATOM]),
+        form("port_number", Phrase, &[ATOM]),
+        form("create_packet", Phrase, &[ATOM]),
+        form("handshake", Phrase, &[ATOM]),
+        form("process_create", Phrase, &[ATOM]),
+        form("memory_alloc", Phrase, &[ATOM]),
+        form("exit_program", Phrase, &[ATOM]),
+        form("send_signal", Phrase, &[ATOM, ATOM]),
+        form("property_access", Atom, &[ATOM, Name]),
+        form("thread", Atom, &[Body]),
+        form("lock", Atom, &[Body]),
+        form("emit", Phrase, &[ATOM]),
+        form("watch", Phrase, &[ATOM, ATOM]),
+        form("auth", Phrase, &[ATOM]),
+        form("test", Atom, &[Text, Body]),
+        form("mock", Phrase, &[ATOM]),
+        form("benchmark", Atom, &[Body]),
+        form("encrypt", Phrase, &[ATOM, ATOM]),
+        form("decrypt", Phrase, &[ATOM, ATOM]),
+        form("sign", Phrase, &[ATOM, ATOM]),
+        form("verify_signature", Phrase, &[ATOM, ATOM, ATOM]),
+        form("infinity", Atom, &[]),
+        form("delta", Phrase, &[Name, ATOM]),
+        form("length", Phrase, &[ATOM]),
+        form("length.piped", Phrase, &[]),
+        form("index", Atom, &[ATOM, SEQUENCE]),
+        form("array_push", Phrase, &[ATOM, ATOM]),
+        form("array_push.piped", Phrase, &[ATOM]),
+        form("add", Atom, &[PHRASE, PHRASE]),
+        form("subtract", Atom, &[PHRASE, PHRASE]),
+        form("multiply", Atom, &[PHRASE, PHRASE]),
+        form("divide", Atom, &[PHRASE, PHRASE]),
+        form("modulo", Atom, &[PHRASE, PHRASE]),
+        form("string_concat", Atom, &[PHRASE, PHRASE]),
+        form("char_at", Phrase, &[ATOM, ATOM]),
+        form("slice", Atom, &[ATOM, SEQUENCE, SEQUENCE]),
+        form("slice.open", Atom, &[ATOM, SEQUENCE]),
+        form("array_literal", Atom, &[Elements]),
+        form("object_literal", Atom, &[Pairs]),
+    ]
+};
+
+/// The form of a message
+pub(crate) fn form_of(key: &str) -> &'static Form {
+    FORMS.iter().find(|form| form.key == key).expect("every message has a form")
+}
+
+/// The value of a placeholder, borrowed from a node being explained or
+/// owned by one being read back from its explanation
+#[derive(Debug, Clone)]
+pub(crate) enum Value<'a> {
+    Node(Cow<'a, AstNode>),
+    Name(Cow<'a, str>),
+    Text(Cow<'a, str>),
+    Count(Option<i32>),
+    Operator(ComparisonOp),
+    Annotation(Option<TypeAnnotation>),
+    Bytes(Cow<'a, [u8]>),
+    Elements(Cow<'a, [AstNode]>),
+    Pairs(Cow<'a, [(String, AstNode)]>),
+}
+
+impl<'a> Value<'a> {
+    fn node(node: &'a AstNode) -> Self {
+        Value::Node(Cow::Borrowed(node))
+    }
+
+    fn name(name: &'a str) -> Self {
+        Value::Name(Cow::Borrowed(name))
+    }
+}
+
+/// The message that explains a node: its key, the values of its parts and
+/// the value of each of its form's extras, if present
+pub(crate) struct Message<'a> {
+    pub key: &'static str,
+    pub values: Vec<Value<'a>>,
+    pub extras: Vec<Option<Value<'a>>>,
+}
+
+/// The message for a node; sequences, variables and literals other than
+/// bytes are written as themselves
+pub(crate) fn message(node: &AstNode) -> Option<Message<'_>> {
+    let n = Value::node;
+    fn optional(node: &Option<Box<AstNode>>) -> Option<Value<'_>> {
+        node.as_deref().map(Value::node)
+    }
+    // Empty options and encodings are the defaults, so they go unsaid
+    fn given(node: &AstNode) -> Option<Value<'_>> {
+        Some(node).filter(|node| **node != AstNode::Empty).map(Value::node)
+    }
+    let piped = |node: &AstNode| matches!(node, AstNode::Variable(name) if name == PIPE_VARIABLE);
+    let (key, values, extras) = match node {
+        AstNode::Sequence(_) | AstNode::Variable(_) => return None,
+        AstNode::Literal(LiteralValue::Bytes(bytes)) => ("literal.bytes", vec![Value::Bytes(Cow::Borrowed(bytes))], vec![]),
+        AstNode::Literal(_) => return None,
+        AstNode::Function { name, body } => ("function", vec![Value::name(name), n(body)], vec![]),
+        AstNode::Output(value) => ("output", vec![n(value)], vec![]),
+        AstNode::Pipe { source, operation } => ("pipe", vec![n(source), n(operation)], vec![]),
+        AstNode::PipeInto { value, variable, annotation } => {
+            ("pipe_into", vec![n(value), Value::name(variable), Value::Annotation(*annotation)], vec![])
+        }
+        AstNode::Guard { condition, then_branch } => ("guard", vec![n(condition), n(then_branch)], vec![]),
+        AstNode::Halt(code) => ("halt", vec![n(code)], vec![]),
+        AstNode::Persist(value) => ("persist", vec![n(value)], vec![]),
+        AstNode::JsonParse(value) => ("json_parse", vec![n(value)], vec![]),
+        AstNode::IfThen { condition, then_branch, else_branch } => {
+            ("if_then", vec![n(condition), n(then_branch)], vec![optional(else_branch)])
+        }
+        AstNode::Loop { condition: Some(condition), body } => ("loop", vec![n(condition), n(body)], vec![]),
+        AstNode::Loop { condition: None, body } => ("loop.forever", vec![n(body)], vec![]),
+        AstNode::ForEach { variable, collection, body } => {
+            ("for_each", vec![Value::name(variable), n(collection), n(body)], vec![])
+        }
+        AstNode::Filter { predicate } => ("filter", vec![n(predicate)], vec![]),
+        AstNode::Reduce { operation, initial } => ("reduce", vec![n(operation), n(initial)], vec![]),
+        AstNode::TryRescue { try_body, rescue_body } => ("try_rescue", vec![n(try_body)], vec![optional(rescue_body)]),
+        AstNode::Retry { max_attempts, body } => ("retry", vec![Value::Count(*max_attempts), n(body)], vec![]),
+        AstNode::Import { module } => ("import", vec![Value::name(module)], vec![]),
+        AstNode::Split { target, delimiter: Some(delimiter) } => ("split.by", vec![n(target), n(delimiter)], vec![]),
+        AstNode::Split { target, delimiter: None } => ("split", vec![n(target)], vec![]),
+        AstNode::Join { elements, separator: Some(separator) } => ("join.with", vec![n(elements), n(separator)], vec![]),
+        AstNode::Join { elements, separator: None } => ("join", vec![n(elements)], vec![]),
+        AstNode::RegexMatch { pattern, target } => ("regex_match", vec![n(pattern), n(target)], vec![]),
+        AstNode::Comparison { left, operator, right } => {
+            ("comparison", vec![n(left), Value::Operator(operator.clone()), n(right)], vec![])
+        }
+        AstNode::Approx { left, right } if piped(left) => ("approx.piped", vec![n(right)], vec![]),
+        AstNode::Power { base, exponent } if piped(base) => ("power.piped", vec![n(exponent)], vec![]),
+        AstNode::Root { value } if piped(value) => ("root.piped", vec![], vec![]),
+        AstNode::Length { value } if piped(value) => ("length.piped", vec![], vec![]),
+        AstNode::ArrayPush { array, element } if piped(array) => ("array_push.piped", vec![n(element)], vec![]),
+        AstNode::Equal { left, right }
+        | AstNode::NotEqual { left, right }
+        | AstNode::And { left, right }
+        | AstNode::Or { left, right }
+        | AstNode::Approx { left, right }
+        | AstNode::Add { left, right }
+        | AstNode::Subtract { left, right }
+        | AstNode::Multiply { left, right }
+        | AstNode::Divide { left, right }
+        | AstNode::Modulo { left, right }
+        | AstNode::StringConcat { left, right }
+        | AstNode::Power { base: left, exponent: right }
+        | AstNode::WriteContent { target: left, content: right }
+        | AstNode::AppendContent { target: left, content: right }
+        | AstNode::SetPermission { target: left, permission: right }
+        | AstNode::SkipBytes { source: left, count: right }
+        | AstNode::SendSignal { signal: left, target: right }
+        | AstNode::Watch { event: left, handler: right }
+        | AstNode::Encrypt { data: left, key: right }
+        | AstNode::Decrypt { data: left, key: right }
+        | AstNode::Sign { data: left, key: right }
+        | AstNode::Index { target: left, index: right }
+        | AstNode::ArrayPush { array: left, element: right }
+        | AstNode::CharAt { target: left, index: right } => (node.kind(), vec![n(left), n(right)], vec![]),
+        AstNode::Not { operand: value }
+        | AstNode::Async { body: value }
+        | AstNode::Await { expression: value }
+        | AstNode::Thread { body: value }
+        | AstNode::Lock { body: value }
+        | AstNode::Benchmark { body: value }
+        | AstNode::Emit { event: value }
+        | AstNode::Auth { token: value }
+        | AstNode::Mock { target: value }
+        | AstNode::Log { message: value }
+        | AstNode::Assert { condition: value }
+        | AstNode::Hash { data: value }
+        | AstNode::Root { value }
+        | AstNode::Length { value }
+        | AstNode::ReadContent { source: value }
+        | AstNode::ShellExec { command: value }
+        | AstNode::EnvVar { name: value }
+        | AstNode::FileHandle { path: value }
+        | AstNode::Directory { path: value }
+        | AstNode::PathResolve { path: value }
+        | AstNode::DeleteFile { target: value }
+        | AstNode::CreateBuffer { size: value }
+        | AstNode::FlushBuffer { target: value }
+        | AstNode::CreateSocket { socket_type: value }
+        | AstNode::ListenPort { port: value }
+        | AstNode::ConnectRemote { address: value }
+        | AstNode::PortNumber { number: value }
+        | AstNode::CreatePacket { data: value }
+        | AstNode::Handshake { connection: value }
+        | AstNode::ProcessCreate { command: value }
+        | AstNode::MemoryAlloc { size: value }
+        | AstNode::ExitProgram { code: value } => (node.kind(), vec![n(value)], vec![]),
+        AstNode::Input | AstNode::Empty | AstNode::Random | AstNode::DateTime | AstNode::Debug | AstNode::Infinity
+        | AstNode::EndOfFile => (node.kind(), vec![], vec![]),
+        AstNode::HttpGet { url, headers }
+        | AstNode::HttpDelete { url, headers }
+        | AstNode::HttpHead { url, headers }
+        | AstNode::HttpOptions { url, headers } => (node.kind(), vec![n(url)], vec![optional(headers)]),
+        AstNode::HttpPost { url, body, headers }
+        | AstNode::HttpPut { url, body, headers }
+        | AstNode::HttpPatch { url, body, headers } => (node.kind(), vec![n(url)], vec![optional(body), optional(headers)]),
+        AstNode::Immutable { name, value } | AstNode::Delta { name, value } => {
+            (node.kind(), vec![Value::name(name), n(value)], vec![])
+        }
+        AstNode::CreateStream { source, options } => ("create_stream", vec![n(source)], vec![given(options)]),
+        AstNode::ToBytes { value, encoding } | AstNode::ToText { value, encoding } => {
+            (node.kind(), vec![n(value)], vec![given(encoding)])
+        }
+        AstNode::PropertyAccess { object, property } => ("property_access", vec![n(object), Value::name(property)], vec![]),
+        AstNode::Test { name, body } => ("test", vec![Value::Text(Cow::Borrowed(name)), n(body)], vec![]),
+        AstNode::VerifySignature { signature, data, key } => {
+            ("verify_signature", vec![n(signature), n(data), n(key)], vec![])
+        }
+        AstNode::Slice { target, start, end: Some(end) } => ("slice", vec![n(target), n(start), n(end)], vec![]),
+        AstNode::Slice { target, start, end: None } => ("slice.open", vec![n(target), n(start)], vec![]),
+        AstNode::ArrayLiteral { elements } => ("array_literal", vec![Value::Elements(Cow::Borrowed(elements))], vec![]),
+        AstNode::ObjectLiteral { pairs } => ("object_literal", vec![Value::Pairs(Cow::Borrowed(pairs))], vec![]),
+    };
+    Some(Message { key, values, extras })
+}
+
+/// How tightly a node's explanation holds together
+pub(crate) fn level_of(node: &AstNode) -> Level {
+    match node {
+        AstNode::Sequence(_) => Level::Sequence,
+        _ => message(node).map_or(Level::Atom, |message| form_of(message.key).level),
+    }
+}
+
+/// A piece of a message: text, or the index of a placeholder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Piece<'a> {
+    Text(&'a str),
+    Part(usize),
+}
 
-use crate::parser::{AstNode, LiteralValue};
+/// A message split into its text and `{n}` placeholders; a brace that does
+/// not start a placeholder is text
+pub(crate) fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let (mut start, mut search) = (0, 0);
+    while let Some(open) = template[search..].find('{').map(|i| i + search) {
+        let after = &template[open + 1..];
+        match after.find('}').and_then(|close| Some((after[..close].parse::<usize>().ok()?, close))) {
+            Some((index, close)) => {
+                if start < open {
+                    pieces.push(Piece::Text(&template[start..open]));
+                }
+                pieces.push(Piece::Part(index));
+                start = open + close + 2;
+                search = start;
+            }
+            None => search = open + 1,
+        }
+    }
+    if start < template.len() {
+        pieces.push(Piece::Text(&template[start..]));
+    }
+    pieces
+}
+
+/// Whether a word can be written bare where a name is expected
+pub(crate) fn is_name(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// A comparison operator as an explanation writes it
+pub(crate) fn operator(op: &ComparisonOp) -> &'static str {
+    match op {
+        ComparisonOp::GreaterThan => ">",
+        ComparisonOp::LessThan => "<",
+        ComparisonOp::GreaterEqual => ">=",
+        ComparisonOp::LessEqual => "<=",
+    }
+}
+
+/// Append to an explanation; what follows an indented block goes on a new
+/// line, since the block's last line belongs to the block
+fn push(output: &mut String, text: &str) {
+    let in_block = output.rsplit_once('\n').is_some_and(|(_, line)| line.starts_with(' '));
+    if in_block && !text.is_empty() && !text.starts_with('\n') {
+        output.push('\n');
+        output.push_str(text.trim_start_matches(' '));
+    } else {
+        output.push_str(text);
+    }
+}
+
+/// Every line of a block indented one level
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("  {}", line)).collect::<Vec<_>>().join("\n")
+}
 
 /// Explains an Aether AST in human-readable format
 pub struct Explainer {
-    indent_level: usize,
+    lang: Lang,
 }
 
 impl Explainer {
     pub fn new() -> Self {
-        Self { indent_level: 0 }
+        Self { lang: Lang::En }
+    }
+
+    /// Explain in `lang` instead of English
+    pub fn set_lang(&mut self, lang: Lang) {
+        self.lang = lang;
     }
 
     /// Convert an AST to human-readable explanation
     pub fn explain(&mut self, ast: &[AstNode]) -> String {
         let mut output = String::new();
-        
+
         for node in ast {
+            // A blank line after a block, or the next statement would read
+            // as a continuation of the one the block belongs to
+            if output.trim_end_matches('\n').rsplit('\n').next().is_some_and(|line| line.starts_with(' ')) {
+                output.push('\n');
+            }
             output.push_str(&self.explain_node(node));
             output.push('\n');
         }
-        
+
         output
     }
 
-    fn explain_node(&mut self, node: &AstNode) -> String {
-        match node {
-            AstNode::Function { name, body } => {
-                format!(
-                    "{}function {}:\n{}",
-                    self.indent(),
-                    name,
-                    self.with_indent(|e| e.explain_node(body))
-                )
-            }
-            
-            AstNode::Sequence(nodes) => {
-                let mut output = String::new();
-                for (i, n) in nodes.iter().enumerate() {
-                    if i > 0 {
-                        output.push_str(&format!("\n{}then\n", self.indent()));
-                    }
-                    output.push_str(&self.explain_node(n));
-                }
-                output
-            }
-            
-            AstNode::Input => format!("{}read input", self.indent()),
-            
-            AstNode::Output(value) => {
-                format!("{}output {}", self.indent(), self.explain_node(value))
-            }
-            
-            AstNode::Pipe { source, operation } => {
-                let source_str = self.explain_node(source);
-                let operation_str = self.explain_node(operation);
-                
-                // If source is the special _pipe variable, simplify output
-                if source_str == "_pipe" {
-                    operation_str
-                } else {
-                    format!("{} | {}", source_str, operation_str)
-                }
-            }
-            
-            AstNode::PipeInto { value, variable } => {
-                format!(
-                    "{} -> store in {}",
-                    self.explain_node(value),
-                    variable
-                )
-            }
-            
-            AstNode::Guard { condition, then_branch } => {
-                format!(
-                    "{}guard (if {} is null or invalid):\n{}",
-                    self.indent(),
-                    self.explain_node(condition),
-                    self.with_indent(|e| e.explain_node(then_branch))
-                )
-            }
-            
-            AstNode::Halt(code) => {
-                format!("{}halt with error {}", self.indent(), self.explain_node(code))
+    /// Explain a source as Markdown: each statement's source in an `aether`
+    /// code block, followed by its explanation as a nested list
+    pub fn explain_markdown(&mut self, source: &str) -> Result<String> {
+        let mut output = String::new();
+        for statement in statements(source)? {
+            let longest = statement.text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat((longest + 1).max(3));
+            output.push_str(&format!("{}aether\n{}\n{}\n\n", fence, statement.text, fence));
+            for line in self.explain_node(&statement.node).lines().filter(|line| !line.trim().is_empty()) {
+                let text = line.trim_start();
+                output.push_str(&format!("{}- {}\n", &line[..line.len() - text.len()], escape_markdown(text)));
             }
-            
-            AstNode::Persist(value) => {
-                format!("{}save to database: {}", self.indent(), self.explain_node(value))
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    /// Explain a source as JSON: the source, grapheme span, explanation and
+    /// AST of each statement
+    pub fn explain_json(&mut self, source: &str) -> Result<Json> {
+        let statements: Vec<Json> = statements(source)?
+            .into_iter()
+            .map(|statement| {
+                json!({
+                    "source": statement.text,
+                    "start": statement.span.0,
+                    "end": statement.span.1,
+                    "explanation": self.explain_node(&statement.node),
+                    "ast": statement.node.to_json(),
+                })
+            })
+            .collect();
+        Ok(json!({ "lang": self.lang.code(), "statements": statements }))
+    }
+
+    /// The catalog message for `key` with its `{n}` placeholders filled in
+    fn say(&self, key: &'static str, parts: &[&str]) -> String {
+        let mut output = String::new();
+        for piece in pieces(self.lang.template(key)) {
+            match piece {
+                Piece::Text(text) => push(&mut output, text),
+                Piece::Part(index) => push(&mut output, parts.get(index).copied().unwrap_or_default()),
             }
-            
-            AstNode::JsonParse(value) => {
-                format!("parse JSON({})", self.explain_node(value))
+        }
+        output
+    }
+
+    fn explain_node(&self, node: &AstNode) -> String {
+        match node {
+            AstNode::Sequence(steps) => {
+                let then = format!("\n{}\n", self.say("sequence.then", &[]));
+                steps.iter().map(|step| self.explain_at(step, Level::Chain)).collect::<Vec<_>>().join(&then)
             }
-            
             AstNode::Variable(name) => name.clone(),
-            
-            AstNode::Literal(lit) => self.explain_literal(lit),
-            
-            AstNode::Empty => "null".to_string(),
-            
-            AstNode::IfThen { condition, then_branch, else_branch } => {
-                let mut output = format!(
-                    "{}if {}:\n{}",
-                    self.indent(),
-                    self.explain_node(condition),
-                    self.with_indent(|e| e.explain_node(then_branch))
-                );
-                
-                if let Some(else_node) = else_branch {
-                    output.push_str(&format!(
-                        "\n{}else:\n{}",
-                        self.indent(),
-                        self.with_indent(|e| e.explain_node(else_node))
-                    ));
-                }
-                output
-            }
-            
-            AstNode::Loop { condition, body } => {
-                if let Some(cond) = condition {
-                    format!(
-                        "{}while {}:\n{}",
-                        self.indent(),
-                        self.explain_node(cond),
-                        self.with_indent(|e| e.explain_node(body))
-                    )
-                } else {
-                    format!(
-                        "{}loop forever:\n{}",
-                        self.indent(),
-                        self.with_indent(|e| e.explain_node(body))
-                    )
-                }
-            }
-            
-            AstNode::ForEach { variable, collection, body } => {
-                format!(
-                    "{}for each {} in {}:\n{}",
-                    self.indent(),
-                    variable,
-                    self.explain_node(collection),
-                    self.with_indent(|e| e.explain_node(body))
-                )
-            }
-            
-            AstNode::Filter { predicate } => {
-                format!("filter where {}", self.explain_node(predicate))
+            AstNode::Literal(lit) if !matches!(lit, LiteralValue::Bytes(_)) => explain_literal(lit),
+            _ => message(node).map(|message| self.render(&message)).unwrap_or_default(),
+        }
+    }
+
+    /// A node's explanation, in parentheses if it binds looser than `level`
+    fn explain_at(&self, node: &AstNode, level: Level) -> String {
+        let text = self.explain_node(node);
+        if level_of(node) >= level {
+            return text;
+        }
+        let mut wrapped = format!("({}", text);
+        push(&mut wrapped, ")");
+        wrapped
+    }
+
+    /// A message with its parts explained, followed by its extras
+    fn render(&self, message: &Message) -> String {
+        let form = form_of(message.key);
+        let mut output = self.fill(message.key, form.parts, &message.values);
+        for ((key, part), value) in form.extras.iter().zip(&message.extras) {
+            if let Some(value) = value {
+                push(&mut output, &self.fill(key, std::slice::from_ref(part), std::slice::from_ref(value)));
             }
-            
-            AstNode::Reduce { operation, initial } => {
-                format!(
-                    "reduce with {} starting from {}",
-                    self.explain_node(operation),
-                    self.explain_node(initial)
-                )
+        }
+        output
+    }
+
+    fn fill(&self, key: &'static str, parts: &[Part], values: &[Value]) -> String {
+        let texts: Vec<String> = parts.iter().zip(values).map(|(part, value)| self.part(*part, value)).collect();
+        self.say(key, &texts.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    fn part(&self, part: Part, value: &Value) -> String {
+        match (part, value) {
+            (Part::Node(level), Value::Node(node)) => self.explain_at(node, level),
+            (Part::Body, Value::Node(node)) => indent(&self.explain_node(node)),
+            (Part::Name, Value::Name(name)) => name.to_string(),
+            (Part::Text, Value::Text(text)) => quote(text),
+            (Part::Count, Value::Count(Some(count))) => count.to_string(),
+            (Part::Count, Value::Count(None)) => self.say("retry.unlimited", &[]),
+            (Part::Operator, Value::Operator(op)) => operator(op).to_string(),
+            (Part::Annotation, Value::Annotation(Some(TypeAnnotation::String))) => self.say("pipe_into.string", &[]),
+            (Part::Annotation, Value::Annotation(Some(TypeAnnotation::Number))) => self.say("pipe_into.number", &[]),
+            (Part::Bytes, Value::Bytes(bytes)) => {
+                format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
             }
-            
-            AstNode::TryRescue { try_body, rescue_body } => {
-                let mut output = format!(
-                    "{}try:\n{}",
-                    self.indent(),
-                    self.with_indent(|e| e.explain_node(try_body))
-                );
-                
-                if let Some(rescue) = rescue_body {
-                    output.push_str(&format!(
-                        "\n{}on error:\n{}",
-                        self.indent(),
-                        self.with_indent(|e| e.explain_node(rescue))
-                    ));
+            (Part::Elements, Value::Elements(nodes)) => {
+                let mut output = String::new();
+                for (i, node) in nodes.iter().enumerate() {
+                    push(&mut output, if i > 0 { ", " } else { "" });
+                    push(&mut output, &self.explain_at(node, Level::Chain));
                 }
                 output
             }
-            
-            AstNode::Retry { max_attempts, body } => {
-                let attempts_str = if let Some(attempts) = max_attempts {
-                    attempts.to_string()
-                } else {
-                    "unlimited".to_string()
-                };
-                format!(
-                    "{}retry up to {} times:\n{}",
-                    self.indent(),
-                    attempts_str,
-                    self.with_indent(|e| e.explain_node(body))
-                )
-            }
-            
-            AstNode::Async { body } => {
-                format!(
-                    "{}execute asynchronously:\n{}",
-                    self.indent(),
-                    self.with_indent(|e| e.explain_node(body))
-                )
-            }
-            
-            AstNode::Await { expression } => {
-                format!("{}wait for {}", self.indent(), self.explain_node(expression))
-            }
-            
-            AstNode::Import { module } => {
-                format!("{}import module {}", self.indent(), module)
-            }
-            
-            AstNode::Split { target, delimiter } => {
-                if let Some(delim) = delimiter {
-                    format!(
-                        "split {} by {}",
-                        self.explain_node(target),
-                        self.explain_node(delim)
-                    )
-                } else {
-                    format!("split {}", self.explain_node(target))
-                }
-            }
-            
-            AstNode::Join { elements, separator } => {
-                if let Some(sep) = separator {
-                    format!(
-                        "join {} with separator {}",
-                        self.explain_node(elements),
-                        self.explain_node(sep)
-                    )
-                } else {
-                    format!("join {}", self.explain_node(elements))
-                }
-            }
-            
-            AstNode::RegexMatch { pattern, target } => {
-                format!(
-                    "match pattern {} against {}",
-                    self.explain_node(pattern),
-                    self.explain_node(target)
-                )
-            }
-            
-            AstNode::Equal { left, right } => {
-                format!(
-                    "({} == {})",
-                    self.explain_node(left),
-                    self.explain_node(right)
-                )
-            }
-            
-            AstNode::NotEqual { left, right } => {
-                format!(
-                    "({} != {})",
-                    self.explain_node(left),
-                    self.explain_node(right)
-                )
-            }
-            
-            AstNode::And { left, right } => {
-                format!(
-                    "({} and {})",
-                    self.explain_node(left),
-                    self.explain_node(right)
-                )
-            }
-            
-            AstNode::Or { left, right } => {
-                format!(
-                    "({} or {})",
-                    self.explain_node(left),
-                    self.explain_node(right)
-                )
-            }
-            
-            AstNode::Not { operand } => {
-                format!(
-                    "(not {})",
-                    self.explain_node(operand)
-                )
-            }
-            
-            AstNode::Comparison { left, operator, right } => {
-                let op_str = match operator {
-                    crate::parser::ComparisonOp::GreaterThan => ">",
-                    crate::parser::ComparisonOp::LessThan => "<",
-                    crate::parser::ComparisonOp::GreaterEqual => ">=",
-                    crate::parser::ComparisonOp::LessEqual => "<=",
-                };
-                format!(
-                    "({} {} {})",
-                    self.explain_node(left),
-                    op_str,
-                    self.explain_node(right)
-                )
-            }
-            
-            AstNode::Approx { left, right } => {
-                let left_str = self.explain_node(left);
-                let right_str = self.explain_node(right);
-                
-                // If left is the special _pipe variable, simplify output
-                if left_str == "_pipe" {
-                    format!("(piped value) ≈ {}", right_str)
-                } else {
-                    format!("({} ≈ {})", left_str, right_str)
-                }
-            }
-            
-            AstNode::HttpGet { url, headers } => {
-                let mut result = format!("{}HTTP GET request to {}", self.indent(), self.explain_node(url));
-                if headers.is_some() {
-                    result.push_str(" with headers");
-                }
-                result
-            }
-            
-            AstNode::HttpPost { url, body, headers } => {
-                let mut result = format!("{}HTTP POST to {}", self.indent(), self.explain_node(url));
-                if let Some(b) = body {
-                    result.push_str(&format!(" with body {}", self.explain_node(b)));
-                }
-                if headers.is_some() {
-                    result.push_str(" with headers");
-                }
-                result
-            }
-            
-            AstNode::HttpPut { url, body, headers } => {
-                let mut result = format!("{}HTTP PUT to {}", self.indent(), self.explain_node(url));
-                if let Some(b) = body {
-                    result.push_str(&format!(" with body {}", self.explain_node(b)));
-                }
-                if headers.is_some() {
-                    result.push_str(" with headers");
-                }
-                result
-            }
-            
-            AstNode::HttpDelete { url, headers } => {
-                let mut result = format!("{}HTTP DELETE request to {}", self.indent(), self.explain_node(url));
-                if headers.is_some() {
-                    result.push_str(" with headers");
+            (Part::Pairs, Value::Pairs(pairs)) => {
+                let mut output = String::new();
+                for (i, (key, node)) in pairs.iter().enumerate() {
+                    push(&mut output, if i > 0 { ", " } else { "" });
+                    push(&mut output, &if is_name(key) { key.clone() } else { quote(key) });
+                    push(&mut output, ": ");
+                    push(&mut output, &self.explain_at(node, Level::Chain));
                 }
-                result
-            }
-            
-            AstNode::Random => "generate random number".to_string(),
-            
-            AstNode::DateTime => "get current date/time".to_string(),
-            
-            AstNode::Log { message } => {
-                format!("{}log message: {}", self.indent(), self.explain_node(message))
-            }
-            
-            AstNode::Assert { condition } => {
-                format!("{}assert {}", self.indent(), self.explain_node(condition))
-            }
-            
-            AstNode::Hash { data } => {
-                format!("hash {}", self.explain_node(data))
+                output
             }
-            
-            AstNode::Power { base, exponent } => {
-                let base_str = self.explain_node(base);
-                let exp_str = self.explain_node(exponent);
-                
-                // If base is the special _pipe variable, simplify output
-                if base_str == "_pipe" {
-                    format!("(piped value) raised to the power of {}", exp_str)
-                } else {
-                    format!("{} raised to the power of {}", base_str, exp_str)
+            _ => String::new(),
+        }
+    }
+
+    /// Glossary of the symbols a program uses, in the order they first
+    /// appear, with their names and descriptions from the symbol table
+    ///
+    /// The descriptions are the symbol table's, so they stay English in
+    /// every language.
+    pub fn glossary(tokens: &[Token]) -> String {
+        let mut seen: Vec<&SymbolInfo> = Vec::new();
+        for token in tokens {
+            if let TokenType::Symbol(symbol) = &token.token_type {
+                if let Some(info) = symbol.info().filter(|info| !seen.contains(info)) {
+                    seen.push(info);
                 }
             }
-            
-            AstNode::Root { value } => {
-                format!("square root of {}", self.explain_node(value))
-            }
-            
-            AstNode::ReadContent { source } => {
-                format!("{}read from {}", self.indent(), self.explain_node(source))
-            }
-            
-            AstNode::WriteContent { target, content } => {
-                format!(
-                    "{}write to {} content: {}",
-                    self.indent(),
-                    self.explain_node(target),
-                    self.explain_node(content)
-                )
-            }
-            
-            AstNode::ShellExec { command } => {
-                format!(
-                    "{}execute shell command: {}",
-                    self.indent(),
-                    self.explain_node(command)
-                )
-            }
-            
-            AstNode::EnvVar { name } => {
-                format!("get environment variable {}", self.explain_node(name))
-            }
-            
-            AstNode::Immutable { name, value } => {
-                format!(
-                    "{}define constant {} = {}",
-                    self.indent(),
-                    name,
-                    self.explain_node(value)
-                )
-            }
-            
-            AstNode::Debug => {
-                format!("{}enable debug mode", self.indent())
-            }
-            
-            // File System (v1.3) - Additional operations not yet fully explained
-            AstNode::FileHandle { .. } => format!("{}file handle", self.indent()),
-            AstNode::Directory { .. } => format!("{}directory", self.indent()),
-            AstNode::PathResolve { .. } => format!("{}path resolution", self.indent()),
-            AstNode::AppendContent { .. } => format!("{}append to file", self.indent()),
-            AstNode::DeleteFile { .. } => format!("{}delete file", self.indent()),
-            AstNode::SetPermission { .. } => format!("{}set file permissions", self.indent()),
-            
-            // Streams & Buffers (v1.3)
-            AstNode::CreateStream { .. } => format!("{}create stream", self.indent()),
-            AstNode::CreateBuffer { .. } => format!("{}create buffer", self.indent()),
-            AstNode::FlushBuffer { .. } => format!("{}flush buffer", self.indent()),
-            AstNode::EndOfFile => format!("{}end of file", self.indent()),
-            AstNode::SkipBytes { .. } => format!("{}skip bytes", self.indent()),
-            
-            // Networking (v1.3)
-            AstNode::CreateSocket { .. } => format!("{}create socket", self.indent()),
-            AstNode::ListenPort { .. } => format!("{}listen on port", self.indent()),
-            AstNode::ConnectRemote { .. } => format!("{}connect to remote", self.indent()),
-            AstNode::PortNumber { .. } => format!("{}port number", self.indent()),
-            AstNode::CreatePacket { .. } => format!("{}create packet", self.indent()),
-            AstNode::Handshake { .. } => format!("{}handshake", self.indent()),
-            
-            // Process & OS (v1.3)
-            AstNode::ProcessCreate { .. } => format!("{}create process", self.indent()),
-            AstNode::MemoryAlloc { .. } => format!("{}allocate memory", self.indent()),
-            AstNode::ExitProgram { .. } => format!("{}exit program", self.indent()),
-            AstNode::SendSignal { .. } => format!("{}send signal", self.indent()),
-            
-            // Other operations
-            AstNode::PropertyAccess { object, property } => {
-                format!("{}.{}", self.explain_node(object), property)
-            }
-            
-            // Additional operations
-            AstNode::Thread { .. } => format!("{}thread", self.indent()),
-            AstNode::Lock { .. } => format!("{}lock", self.indent()),
-            AstNode::Emit { .. } => format!("{}emit event", self.indent()),
-            AstNode::Watch { .. } => format!("{}watch event", self.indent()),
-            AstNode::Auth { .. } => format!("{}authenticate", self.indent()),
-            AstNode::Test { .. } => format!("{}test", self.indent()),
-            AstNode::Mock { .. } => format!("{}mock", self.indent()),
-            AstNode::Benchmark { .. } => format!("{}benchmark", self.indent()),
-            AstNode::Encrypt { .. } => format!("{}encrypt", self.indent()),
-            AstNode::Decrypt { .. } => format!("{}decrypt", self.indent()),
-            AstNode::Sign { .. } => format!("{}sign", self.indent()),
-            AstNode::VerifySignature { .. } => format!("{}verify signature", self.indent()),
-            AstNode::Infinity => "infinity".to_string(),
-            AstNode::Delta { .. } => format!("{}delta", self.indent()),
-            AstNode::HttpPatch { .. } => format!("{}HTTP PATCH", self.indent()),
-            AstNode::HttpHead { .. } => format!("{}HTTP HEAD", self.indent()),
-            AstNode::HttpOptions { .. } => format!("{}HTTP OPTIONS", self.indent()),
-            // Bootstrap operations (v1.4)
-            AstNode::Length { .. } => format!("{}length", self.indent()),
-            AstNode::Index { .. } => format!("{}index access", self.indent()),
-            AstNode::ArrayPush { .. } => format!("{}array push", self.indent()),
-            AstNode::Add { .. } => format!("{}add", self.indent()),
-            AstNode::Subtract { .. } => format!("{}subtract", self.indent()),
-            AstNode::Multiply { .. } => format!("{}multiply", self.indent()),
-            AstNode::Divide { .. } => format!("{}divide", self.indent()),
-            AstNode::Modulo { .. } => format!("{}modulo", self.indent()),
-            AstNode::StringConcat { .. } => format!("{}string concat", self.indent()),
-            AstNode::CharAt { .. } => format!("{}char at", self.indent()),
-            AstNode::Slice { .. } => format!("{}slice", self.indent()),
-            AstNode::ArrayLiteral { .. } => format!("{}array literal", self.indent()),
-            AstNode::ObjectLiteral { .. } => format!("{}object literal", self.indent()),
         }
+        seen.iter()
+            .map(|info| format!("{}  {} - {}\n", info.glyph, info.symbol.name(), info.description))
+            .collect()
     }
-    
-    fn explain_literal(&self, lit: &LiteralValue) -> String {
-        match lit {
-            LiteralValue::Number(n) => n.to_string(),
-            LiteralValue::String(s) => format!("\"{}\"", s),
+}
+
+/// A literal as Aether writes it; a whole decimal keeps a point so it reads
+/// back as a decimal
+fn explain_literal(lit: &LiteralValue) -> String {
+    match lit {
+        LiteralValue::Number(n) => format!("{:?}", n),
+        LiteralValue::Integer(n) => n.to_string(),
+        LiteralValue::Decimal(d) => {
+            let text = d.to_string();
+            if text.contains('.') || text.parse::<i64>().is_err() { text } else { format!("{}.0", text) }
         }
-    }
-    
-    fn indent(&self) -> String {
-        "  ".repeat(self.indent_level)
-    }
-    
-    fn with_indent<F>(&mut self, f: F) -> String
-    where
-        F: FnOnce(&mut Self) -> String,
-    {
-        self.indent_level += 1;
-        let result = f(self);
-        self.indent_level -= 1;
-        result
+        LiteralValue::String(s) => quote(s),
+        LiteralValue::Bytes(bytes) => format!("{:?}", bytes),
     }
 }
 
@@ -510,6 +971,39 @@ impl Default for Explainer {
     }
 }
 
+/// A top-level statement: its text, grapheme span and AST
+struct Statement {
+    text: String,
+    span: (usize, usize),
+    node: AstNode,
+}
+
+/// The top-level statements of a source
+fn statements(source: &str) -> Result<Vec<Statement>> {
+    normalize::check_legacy_verify(source)?;
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let mut parser = Parser::new(Lexer::new(source.to_string()).tokenize()?);
+    let ast = parser.parse()?;
+    Ok(parser
+        .statement_spans()
+        .iter()
+        .zip(ast)
+        .map(|(&(start, end), node)| Statement { text: graphemes[start..end].concat(), span: (start, end), node })
+        .collect())
+}
+
+/// Text with the characters Markdown would read as formatting escaped
+fn escape_markdown(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.chars() {
+        if "\\`*_[]<>#|".contains(c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -544,6 +1038,64 @@ mod tests {
         assert!(explanation.contains("store in x"));
     }
     
+    #[test]
+    fn test_glossary_lists_each_symbol_once() {
+        let tokens = Lexer::new("📥 ⇢ J ▷ u ⨠ 📤u ⨠ 📤\"done\"".to_string()).tokenize().unwrap();
+        assert_eq!(
+            Explainer::glossary(&tokens),
+            "📥  input - Input/Request context\n⇢  pipe - Pipe/flow data\nJ  json_parse - Parse JSON\n\
+             ▷  pipe_into - Pipe into variable\n⨠  sequence - Sequence operations\n📤  output - Output/Response\n"
+        );
+    }
+
+    #[test]
+    fn test_catalogs_have_the_same_messages() {
+        let placeholders = |message: &'static str| {
+            let mut found: Vec<&str> = message.split('{').skip(1).filter_map(|rest| rest.split('}').next()).collect();
+            found.sort();
+            found
+        };
+        assert_eq!(EN.len(), ZH.len());
+        for (key, message) in EN {
+            let (_, translated) = ZH.iter().find(|(k, _)| k == key).unwrap_or_else(|| panic!("no zh message for {}", key));
+            assert_eq!(placeholders(message), placeholders(translated), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_explain_in_chinese() {
+        let ast = Parser::new(Lexer::new("10 ▷ x ⨠ ◇(x > 5): 📤\"Large\"".to_string()).tokenize().unwrap()).parse().unwrap();
+        let mut explainer = Explainer::new();
+        explainer.set_lang(Lang::Zh);
+        assert_eq!(explainer.explain(&ast), "10 -> 存入 x\n然后\n如果 (x > 5)：\n  输出 \"Large\"\n");
+        assert_eq!(explainer.say("for_each", &["x", "xs", "{1}"]), "对 xs 中的每个 x：\n{1}");
+    }
+
+    #[test]
+    fn test_explain_markdown() {
+        let markdown = Explainer::new().explain_markdown("📤\"a*b\"\n◇(1 > 0): 📤1").unwrap();
+        assert_eq!(
+            markdown,
+            "```aether\n📤\"a*b\"\n```\n\n- output \"a\\*b\"\n\n```aether\n◇(1 > 0): 📤1\n```\n\n- if (1 \\> 0):\n  - output 1\n\n"
+        );
+        assert!(Explainer::new().explain_markdown("📤 ⨠").is_err());
+    }
+
+    #[test]
+    fn test_explain_json() {
+        let mut explainer = Explainer::new();
+        explainer.set_lang(Lang::Zh);
+        let json = explainer.explain_json("// hi\n📥 ▷ x:S").unwrap();
+        assert_eq!(json["lang"], "zh");
+        let statement = &json["statements"][0];
+        assert_eq!(statement["source"], "📥 ▷ x:S");
+        assert_eq!((statement["start"].as_u64(), statement["end"].as_u64()), (Some(6), Some(13)));
+        assert_eq!(statement["explanation"], "读取输入 -> 存入 x（字符串）");
+        assert_eq!(statement["ast"]["kind"], "pipe_into");
+        assert_eq!(statement["ast"]["annotation"], "string");
+        assert_eq!(statement["ast"]["value"]["kind"], "input");
+    }
+
     #[test]
     fn test_explain_conditional() {
         let source = "10 ▷ x ⨠ ◇(x > 5): 📤\"Large\"";
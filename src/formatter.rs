@@ -0,0 +1,411 @@
+//! Canonical formatting of Aether source (`aether fmt`)
+//!
+//! The formatter lays out the concrete syntax tree by a fixed set of rules,
+//! so a program comes out the same way however it was spaced, and
+//! formatting formatted source changes nothing:
+//!
+//! - one space on each side of `⨠`, `▷`, `⇢` and the other infix operators
+//!   (`↑` and `≈` are written before their operand, as in `2 ⇢ ↑3`) and
+//!   after `,` and `:`; none inside brackets, before `,` and `:` or
+//!   around `.`; anywhere else a run of spaces becomes one
+//! - two spaces of indentation inside each line's brackets that stay open,
+//!   and for the body of a `ƒ` definition that goes on past its first line
+//! - a line wider than `MAX_WIDTH` is broken after each of its `⨠` steps
+//! - no trailing spaces, no more than one blank line in a row and a single
+//!   line break at the end
+//! - each symbol written as its `Symbol::to_str` glyph, which drops or adds
+//!   emoji variation selectors to match
+//!
+//! Comments stay on their lines. The result must lex to the same tokens and
+//! comments as the source, or formatting fails rather than change the program.
+
+use crate::cst::{Cst, Kind, Leaf, Node};
+use crate::error::{AetherError, Result};
+use crate::lexer::{Lexer, TokenType};
+use crate::normalize;
+use crate::symbols::Symbol;
+use std::collections::VecDeque;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Widest a line may be, in graphemes, before its steps are split
+pub const MAX_WIDTH: usize = 100;
+
+/// Indentation of one level
+const INDENT: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Role {
+    Open,
+    Close,
+    Plain,
+}
+
+/// A leaf in line order, with its place among the brackets
+#[derive(Debug, Clone, Copy)]
+struct Item<'a> {
+    leaf: &'a Leaf,
+    role: Role,
+    /// Kind of the bracket the leaf is directly inside
+    group: Option<&'a Kind>,
+}
+
+/// A source line: its code and the comment that ends it
+#[derive(Debug, Clone, Default)]
+struct Line<'a> {
+    items: Vec<Item<'a>>,
+    comment: Option<&'a Leaf>,
+}
+
+/// Format a source file
+pub fn format(source: &str) -> Result<String> {
+    normalize::check_legacy_verify(source)?;
+    let cst = Cst::parse(source)?;
+    let mut items = Vec::new();
+    flatten(&cst.nodes, None, &mut items);
+
+    let mut lines = VecDeque::new();
+    let mut line = Line::default();
+    for item in items {
+        match &item.leaf.kind {
+            Kind::Token(TokenType::Newline) => lines.push_back(std::mem::take(&mut line)),
+            Kind::Token(TokenType::Comment(_)) => line.comment = Some(item.leaf),
+            _ => line.items.push(item),
+        }
+    }
+    lines.push_back(line);
+
+    let mut output = String::new();
+    // Indentation of the line that opened each bracket still open
+    let mut open: Vec<usize> = Vec::new();
+    // Whether the statement goes on to the next line
+    let mut continues = false;
+    // Whether the lines are the body of a `ƒ` begun on an earlier line
+    let mut body = false;
+    let mut blank = false;
+    while let Some(line) = lines.pop_front() {
+        let base = if body { INDENT } else { 0 };
+        let Some(first) = line.items.first() else {
+            match line.comment {
+                Some(comment) => {
+                    let indent = open.last().map_or(base, |indent| indent + INDENT);
+                    write_line(&mut output, &mut blank, indent, "", Some(comment));
+                }
+                None => blank = !output.is_empty(),
+            }
+            continue;
+        };
+
+        let starts_statement = open.is_empty() && !continues;
+        if starts_statement {
+            body = false;
+        }
+        let header = starts_statement && is_symbol(first, &Symbol::Function);
+        let indent = match first.role {
+            Role::Close => *open.last().expect("a closing bracket has an open one"),
+            _ => open.last().map_or(if body { INDENT } else { 0 }, |indent| indent + INDENT),
+        };
+        let text = render(&line.items);
+        let width = indent + text.graphemes(true).count() + line.comment.map_or(0, |c| 1 + c.text.trim_end().graphemes(true).count());
+        if width > MAX_WIDTH {
+            if let Some(pieces) = split(&line) {
+                for piece in pieces.into_iter().rev() {
+                    lines.push_front(piece);
+                }
+                continue;
+            }
+        }
+        write_line(&mut output, &mut blank, indent, &text, line.comment);
+
+        for item in &line.items {
+            match item.role {
+                Role::Open => open.push(indent),
+                Role::Close => {
+                    open.pop();
+                }
+                Role::Plain => {}
+            }
+        }
+        continues = !open.is_empty() || open_ended(&line.items);
+        if header {
+            body = continues;
+        }
+        if open.is_empty() && !continues {
+            body = false;
+        }
+    }
+
+    if significant(&output)? != significant(source)? {
+        return Err(AetherError::ParserError(
+            "Formatting would change the program; the source is left as it was".to_string(),
+        ));
+    }
+    Ok(output)
+}
+
+/// The leaves of a tree in order
+fn flatten<'a>(nodes: &'a [Node], group: Option<&'a Kind>, items: &mut Vec<Item<'a>>) {
+    for node in nodes {
+        match node {
+            Node::Leaf(leaf) => items.push(Item { leaf, role: Role::Plain, group }),
+            Node::Group { open, children, close } => {
+                items.push(Item { leaf: open, role: Role::Open, group });
+                flatten(children, Some(&open.kind), items);
+                items.push(Item { leaf: close, role: Role::Close, group });
+            }
+        }
+    }
+}
+
+fn write_line(output: &mut String, blank: &mut bool, indent: usize, text: &str, comment: Option<&Leaf>) {
+    if std::mem::take(blank) {
+        output.push('\n');
+    }
+    output.push_str(&" ".repeat(indent));
+    output.push_str(text);
+    if let Some(comment) = comment {
+        if !text.is_empty() {
+            output.push(' ');
+        }
+        output.push_str(comment.text.trim_end());
+    }
+    output.push('\n');
+}
+
+/// The code of a line with canonical spacing
+fn render(items: &[Item]) -> String {
+    let mut text = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 && spaced(items, i) {
+            text.push(' ');
+        }
+        text.push_str(&display(item.leaf));
+    }
+    text
+}
+
+/// How a leaf is written: symbols as their glyphs, anything else as it was
+fn display(leaf: &Leaf) -> String {
+    match &leaf.kind {
+        // Only the symbols with a glyph of their own have an ASCII spelling
+        Kind::Token(TokenType::Symbol(symbol)) if symbol.ascii().is_some() => symbol.to_str(),
+        _ => leaf.text.clone(),
+    }
+}
+
+fn token<'a>(item: &Item<'a>) -> Option<&'a TokenType> {
+    match &item.leaf.kind {
+        Kind::Token(kind) => Some(kind),
+        Kind::Interpolated => None,
+    }
+}
+
+fn is_symbol(item: &Item, symbol: &Symbol) -> bool {
+    matches!(token(item), Some(TokenType::Symbol(s)) if s == symbol)
+}
+
+/// Whether a space goes between `items[i - 1]` and `items[i]`
+fn spaced(items: &[Item], i: usize) -> bool {
+    let (a, b) = (&items[i - 1], &items[i]);
+    let (kind_a, kind_b) = (token(a), token(b));
+    if a.role == Role::Open || b.role == Role::Close || kind_b == Some(&TokenType::Comma) {
+        return false;
+    }
+    if kind_a == Some(&TokenType::Comma) {
+        return true;
+    }
+    if kind_a == Some(&TokenType::Dot) || kind_b == Some(&TokenType::Dot) || kind_b == Some(&TokenType::Colon) {
+        return false;
+    }
+    if kind_a == Some(&TokenType::Colon) {
+        // `s⊢1:2⊣` and `▷ name:S` stay tight
+        let slice = a.group == Some(&Kind::Token(TokenType::Symbol(Symbol::SliceStart)));
+        let annotation = i >= 3
+            && matches!(kind_b, Some(TokenType::Symbol(Symbol::StringType | Symbol::NumberType)))
+            && matches!(token(&items[i - 2]), Some(TokenType::Symbol(Symbol::Identifier(_))))
+            && is_symbol(&items[i - 3], &Symbol::PipeInto);
+        return !slice && !annotation;
+    }
+    if infix(items, i - 1) || infix(items, i) || is_symbol(b, &Symbol::ElseIf) || is_symbol(b, &Symbol::Else) {
+        return true;
+    }
+    // Keep words apart, as in `ƒ name`
+    let word = |c: char| c.is_alphanumeric() || c == '_';
+    if display(a.leaf).chars().last().is_some_and(word) && display(b.leaf).chars().next().is_some_and(word) {
+        return true;
+    }
+    !b.leaf.leading.is_empty()
+}
+
+/// Whether `items[i]` is an operator between two operands
+fn infix(items: &[Item], i: usize) -> bool {
+    let item = &items[i];
+    if item.role != Role::Plain {
+        return false;
+    }
+    match token(item) {
+        Some(TokenType::GreaterThan | TokenType::LessThan) => true,
+        // `-` is unary unless it follows an operand
+        Some(TokenType::Symbol(Symbol::Subtract)) => i > 0 && operand_end(&items[i - 1]),
+        Some(TokenType::Symbol(symbol)) => matches!(
+            symbol,
+            Symbol::Sequence
+                | Symbol::PipeInto
+                | Symbol::Pipe
+                | Symbol::MapArrow
+                | Symbol::Guard
+                | Symbol::Add
+                | Symbol::Multiply
+                | Symbol::Divide
+                | Symbol::Modulo
+                | Symbol::Concat
+                | Symbol::Equal
+                | Symbol::NotEqual
+                | Symbol::GreaterEqual
+                | Symbol::LessEqual
+                | Symbol::Or
+                | Symbol::And
+                | Symbol::Assign
+        ),
+        _ => false,
+    }
+}
+
+/// Whether an item can end an operand, so a `-` after it subtracts
+fn operand_end(item: &Item) -> bool {
+    if item.role == Role::Close {
+        return true;
+    }
+    match token(item) {
+        None => true,
+        Some(TokenType::Symbol(symbol)) => matches!(
+            symbol,
+            Symbol::Identifier(_)
+                | Symbol::StringLiteral(_)
+                | Symbol::NumberLiteral(_)
+                | Symbol::IntegerLiteral(_)
+                | Symbol::DecimalLiteral(_)
+                | Symbol::BytesLiteral(_)
+                | Symbol::Empty
+                | Symbol::Infinity
+                | Symbol::Eof
+                | Symbol::Input
+        ),
+        Some(_) => false,
+    }
+}
+
+/// Whether a line leaves its statement unfinished, ending in an operator,
+/// `:` or `,`
+fn open_ended(items: &[Item]) -> bool {
+    let last = items.len() - 1;
+    infix(items, last) || matches!(token(&items[last]), Some(TokenType::Colon | TokenType::Comma))
+}
+
+/// A line broken after each `⨠` outside the brackets it opens, or `None`
+/// if it has nowhere to break
+fn split<'a>(line: &Line<'a>) -> Option<Vec<Line<'a>>> {
+    let mut pieces = vec![Line::default()];
+    let mut depth: i32 = 0;
+    let mut lowest: i32 = 0;
+    for (i, item) in line.items.iter().enumerate() {
+        match item.role {
+            Role::Open => depth += 1,
+            Role::Close => depth -= 1,
+            Role::Plain => {}
+        }
+        lowest = lowest.min(depth);
+        pieces.last_mut().expect("there is a piece").items.push(*item);
+        if depth == lowest && is_symbol(item, &Symbol::Sequence) && i + 1 < line.items.len() {
+            pieces.push(Line::default());
+        }
+    }
+    if pieces.len() == 1 {
+        return None;
+    }
+    pieces.last_mut().expect("there is a piece").comment = line.comment;
+    Some(pieces)
+}
+
+/// The tokens and comments of a source, which formatting must not change
+fn significant(source: &str) -> Result<Vec<TokenType>> {
+    let tokens = Lexer::new(source.to_string()).tokenize_with_trivia()?;
+    Ok(tokens
+        .into_iter()
+        .filter(|token| token.token_type != TokenType::Newline)
+        .map(|token| match token.token_type {
+            TokenType::Comment(text) => TokenType::Comment(text.trim_end().to_string()),
+            other => other,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_spacing_is_canonical() {
+        assert_eq!(format("📥⇢J▷u ⁇🛑400⨠💾u   ⨠📤200").unwrap(), "📥 ⇢ J ▷ u ⁇ 🛑400 ⨠ 💾u ⨠ 📤200\n");
+        assert_eq!(
+            format("◇( x>5 ):📤\"big\"◆ :📤 \"small\"").unwrap(),
+            "◇(x > 5): 📤\"big\" ◆: 📤 \"small\"\n"
+        );
+        assert_eq!(format("[ 1,2 ,3 ] ▷ xs ⨠ xs⟦ 0 ⟧-1 ⨠ 3 - -2").unwrap(), "[1, 2, 3] ▷ xs ⨠ xs⟦0⟧ - 1 ⨠ 3 - -2\n");
+        assert_eq!(format("2⇢↑3 ⇢ ≈ 8").unwrap(), "2 ⇢ ↑3 ⇢ ≈ 8\n");
+        assert_eq!(format("📥 ▷ name:S ⨠ s⊢1:2⊣ ⨠ {a:u.b}").unwrap(), "📥 ▷ name:S ⨠ s⊢1:2⊣ ⨠ {a: u.b}\n");
+        assert_eq!(format("📤\"{ a+1 }\"").unwrap(), "📤\"{ a+1 }\"\n", "strings are left as written");
+        assert_eq!(format("").unwrap(), "");
+    }
+
+    #[test]
+    fn test_layout_keeps_comments_and_indents_blocks() {
+        let source = "\n\n// Rotate   \nƒ log:  📥msg ⨠   // header\n📄\"a\" ▷ f ⨠\n\n\n// check size\n◇(f.size > 1GB): (\n🐚\"mv\"\n) ⨠\nf ⇢ 🌬️\n1 ▷ done\n\n";
+        let expected = "// Rotate\nƒ log: 📥msg ⨠ // header\n  📄\"a\" ▷ f ⨠\n\n  // check size\n  ◇(f.size > 1GB): (\n    🐚\"mv\"\n  ) ⨠\n  f ⇢ 🌬️\n1 ▷ done\n";
+        assert_eq!(format(source).unwrap(), expected);
+        assert_eq!(format(expected).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_long_lines_break_after_steps() {
+        let steps: Vec<String> = (0..12).map(|i| format!("{} ▷ value_{}", i, i)).collect();
+        let source = format!("ƒ f: {} // end", steps.join(" ⨠ "));
+        let formatted = format(&source).unwrap();
+        let lines: Vec<&str> = formatted.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "ƒ f: 0 ▷ value_0 ⨠");
+        assert_eq!(lines[1], "  1 ▷ value_1 ⨠");
+        assert_eq!(lines[11], "  11 ▷ value_11 // end");
+        assert_eq!(format(&formatted).unwrap(), formatted);
+
+        // Steps inside brackets opened on the line stay together
+        let inner = format!("↻(x < 3): ({})", steps.join(" ⨠ "));
+        assert_eq!(format(&inner).unwrap(), format!("{}\n", inner));
+    }
+
+    #[test]
+    fn test_legacy_verify_is_refused() {
+        // Formatting would quietly rewrite the legacy verify as try
+        let err = format("🛡\u{FE0F}(📤1)").unwrap_err();
+        assert!(err.to_string().contains("aether migrate"), "{}", err);
+        assert_eq!(format("🛡(📤1)").unwrap(), "🛡(📤1)\n");
+    }
+
+    #[test]
+    fn test_examples_format_idempotently() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let mut formatted = 0;
+        for entry in std::fs::read_dir(root).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "ae") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            // A few examples don't lex yet
+            let Ok(once) = format(&source) else { continue };
+            assert_eq!(format(&once).unwrap(), once, "{} is not formatted idempotently", path.display());
+            formatted += 1;
+        }
+        assert!(formatted > 40, "only {} examples formatted", formatted);
+    }
+}
@@ -1,6 +1,9 @@
 //! Lexer for tokenizing Aether source code
 
+use crate::bytes;
 use crate::error::{AetherError, Result};
+use crate::normalize;
+use crate::numeric::Decimal;
 use crate::symbols::Symbol;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -21,6 +24,8 @@ pub enum TokenType {
     LeftBrace,
     RightBrace,
     Comma,
+    /// A `//` comment, without its line break
+    Comment(String),
     Eof,
 }
 
@@ -46,6 +51,12 @@ impl Token {
 pub struct Lexer {
     position: usize,
     graphemes: Vec<String>,
+    /// Tokens to hand out after the one just read
+    pending: Vec<Token>,
+    /// Whether ASCII spellings such as `fn` and `|>` stand for their symbols
+    ascii: bool,
+    /// Whether characters that look like a symbol, such as `→`, stand for it
+    look_alikes: bool,
 }
 
 impl Lexer {
@@ -55,20 +66,58 @@ impl Lexer {
         Lexer {
             position: 0,
             graphemes,
+            pending: Vec::new(),
+            ascii: false,
+            look_alikes: false,
         }
     }
 
+    /// Accept each symbol's ASCII spelling (`Symbol::ascii`) as well as its
+    /// glyph, and a backtick as an empty separator, so `fn`name` is `ƒname`.
+    /// The spellings become reserved words, so this is off by default; a
+    /// backtick that doesn't follow a word reads the next one as a name, so
+    /// `` `len `` is the variable `len` and ``fn``len`` is `ƒlen`.
+    pub fn set_ascii_aliases(&mut self, on: bool) {
+        self.ascii = on;
+    }
+
+    /// Read a character that only looks like a symbol (`normalize::look_alike`)
+    /// as that symbol rather than failing, as the lint and migration do
+    pub fn set_look_alikes(&mut self, on: bool) {
+        self.look_alikes = on;
+    }
+
+    /// 1-based (line, column) of a token position, counting columns in graphemes
+    pub fn location(&self, position: usize) -> (usize, usize) {
+        let before = &self.graphemes[..position.min(self.graphemes.len())];
+        let line_start = before.iter().rposition(|g| g.contains('\n')).map_or(0, |i| i + 1);
+        let line = 1 + before.iter().filter(|g| g.contains('\n')).count();
+        (line, position - line_start + 1)
+    }
+
+    /// Position reached so far, which after a failed `tokenize` is where it stopped
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
     /// Tokenize the entire input
     pub fn tokenize(&mut self) -> Result<Vec<Token>> {
+        let mut tokens = self.tokenize_with_trivia()?;
+        tokens.retain(|token| !matches!(token.token_type, TokenType::Newline | TokenType::Comment(_)));
+        Ok(tokens)
+    }
+
+    /// Tokenize the entire input, keeping line breaks and comments
+    pub fn tokenize_with_trivia(&mut self) -> Result<Vec<Token>> {
         let mut tokens = Vec::new();
 
         while self.position < self.graphemes.len() {
             let token = self.next_token()?;
-            // Skip whitespace and newline tokens
-            if matches!(token.token_type, TokenType::Whitespace | TokenType::Newline) {
+            if token.token_type == TokenType::Whitespace {
                 continue;
             }
             tokens.push(token);
+            tokens.append(&mut self.pending);
         }
 
         tokens.push(Token::new(TokenType::Eof, self.position, 0));
@@ -86,17 +135,37 @@ impl Lexer {
 
         // Handle comments (// to end of line)
         if current == "/" && self.position + 1 < self.graphemes.len() && self.graphemes[self.position + 1] == "/" {
-            // Skip to end of line
-            while self.position < self.graphemes.len() {
-                let ch = &self.graphemes[self.position];
+            let text = self.take_while(|c| c != '\n' && c != '\r');
+            return Ok(Token::new(TokenType::Comment(text), start_pos, self.position - start_pos));
+        }
+        
+        // ASCII spellings made of punctuation, longest first
+        if self.ascii {
+            if current == "`" {
                 self.position += 1;
-                if ch == "\n" || ch == "\r" {
-                    return Ok(Token::new(TokenType::Newline, start_pos, self.position - start_pos));
+                // A backtick right before a name keeps it a name, so `len
+                // is a variable even though len spells 📏; straight after a
+                // word it only separates, as in fn`len
+                let word = |g: Option<&String>| g.and_then(|g| g.chars().next()).is_some_and(|c| c.is_alphanumeric() || c == '_');
+                let after_word = start_pos > 0 && word(self.graphemes.get(start_pos - 1));
+                if !after_word && word(self.graphemes.get(self.position)) {
+                    return self.read_identifier(self.position, true);
+                }
+                return Ok(Token::new(TokenType::Whitespace, start_pos, 1));
+            }
+            for length in [2, 1] {
+                let Some(text) = self.graphemes.get(start_pos..start_pos + length).map(|g| g.concat()) else {
+                    continue;
+                };
+                if text.starts_with(|c: char| c.is_ascii_punctuation()) {
+                    if let Some(symbol) = Symbol::from_ascii(&text) {
+                        self.position += length;
+                        return Ok(Token::new(TokenType::Symbol(symbol), start_pos, length));
+                    }
                 }
             }
-            return Ok(Token::new(TokenType::Eof, self.position, 0));
         }
-        
+
         // Handle division operator (must be after comment check)
         if current == "/" {
             self.position += 1;
@@ -190,90 +259,335 @@ impl Lexer {
 
         // Handle string literals
         if current == "\"" {
-            return self.read_string_literal(start_pos);
+            return self.read_string_literal(start_pos, false);
         }
 
-        // Handle numbers (including negative numbers)
-        if current.chars().next().map_or(false, |c| c.is_numeric()) {
+        // Handle numbers; a leading `-` is the parser's unary minus
+        if current.chars().next().is_some_and(|c| c.is_numeric()) {
             return self.read_number(start_pos);
         }
 
-        // Try to parse as a known symbol
-        if let Some(symbol) = Symbol::from_str(current) {
+        // 🌐 and the method after it are one symbol, such as 🌐📥 for GET
+        if current == "🌐" {
+            let method = self.graphemes.get(self.position + 1).map(|next| format!("{}{}", current, next));
+            if let Some(symbol) = method.and_then(|method| Symbol::from_glyph(&method)) {
+                self.position += 2;
+                return Ok(Token::new(TokenType::Symbol(symbol), start_pos, 2));
+            }
+        }
+
+        // Try to parse as a known symbol, however its variation selectors
+        // are written
+        if let Some(symbol) = Symbol::from_glyph(current) {
             self.position += 1;
             return Ok(Token::new(TokenType::Symbol(symbol), start_pos, 1));
         }
 
         // Handle identifiers (alphanumeric sequences)
-        if current.chars().next().map_or(false, |c| c.is_alphanumeric() || c == '_') {
-            return self.read_identifier(start_pos);
+        if current.chars().next().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return self.read_identifier(start_pos, false);
         }
 
-        // Unknown character
+        // Unknown character, perhaps one that looks like a symbol
+        let hint = match normalize::look_alike(current) {
+            Some(glyph) if self.look_alikes => {
+                let symbol = Symbol::from_str(glyph).expect("look-alikes map to glyphs");
+                self.position += 1;
+                return Ok(Token::new(TokenType::Symbol(symbol), start_pos, 1));
+            }
+            Some(glyph) => format!(" (did you mean '{}'?)", glyph),
+            None => String::new(),
+        };
         Err(AetherError::LexerError(format!(
-            "Unknown character at position {}: '{}'",
-            start_pos, current
+            "Unknown character at position {}: '{}'{}",
+            start_pos, current, hint
         )))
     }
 
-    /// Read a string literal
-    fn read_string_literal(&mut self, start_pos: usize) -> Result<Token> {
-        self.position += 1; // Skip opening quote
+    /// Read a string literal starting at the opening quote
+    ///
+    /// `"""…"""` strings may span lines and contain quotes; a line break right
+    /// after the opening quotes is dropped. Raw strings (`r"…"`) take their
+    /// contents as written. Other strings understand escapes and interpolate
+    /// `{expression}`, which is lexed as `("text" ⧺ (expression) ⧺ "text")`
+    /// with the extra tokens zero-length, so the parser builds the usual
+    /// concatenation.
+    fn read_string_literal(&mut self, start_pos: usize, raw: bool) -> Result<Token> {
+        let triple = self.graphemes[self.position..].starts_with(&["\"".to_string(), "\"".to_string(), "\"".to_string()]);
+        let delimiter = if triple { 3 } else { 1 };
+        self.position += delimiter;
+        if triple && self.graphemes.get(self.position).is_some_and(|g| g.contains('\n')) {
+            self.position += 1;
+        }
+
+        let mut parts = Vec::new();
         let mut content = String::new();
+        let mut content_start = start_pos;
+        loop {
+            let Some(ch) = self.graphemes.get(self.position) else {
+                return Err(AetherError::LexerError(format!(
+                    "Unterminated string literal starting at position {}",
+                    start_pos
+                )));
+            };
+            if ch == "\"" && (!triple || self.graphemes[self.position..].iter().take(3).all(|g| g == "\"")) {
+                break;
+            }
+            if raw {
+                content.push_str(ch);
+                self.position += 1;
+            } else if ch == "\\" {
+                content.push(self.read_escape()?);
+            } else if ch == "{" {
+                let open = self.position;
+                let tokens = self.read_interpolation()?;
+                // Starting with the text, even when empty, makes the result a string
+                if parts.is_empty() || !content.is_empty() {
+                    if !parts.is_empty() {
+                        parts.push(Token::new(TokenType::Symbol(Symbol::Concat), content_start, 0));
+                    }
+                    parts.push(Token::new(
+                        TokenType::Symbol(Symbol::StringLiteral(content)),
+                        content_start,
+                        open - content_start,
+                    ));
+                }
+                parts.push(Token::new(TokenType::Symbol(Symbol::Concat), open, 0));
+                parts.push(Token::new(TokenType::LeftParen, open, 0));
+                parts.extend(tokens);
+                parts.push(Token::new(TokenType::RightParen, self.position - 1, 0));
+                content = String::new();
+                content_start = self.position;
+            } else {
+                content.push_str(ch);
+                self.position += 1;
+            }
+        }
+        self.position += delimiter;
+
+        if parts.is_empty() {
+            return Ok(Token::new(
+                TokenType::Symbol(Symbol::StringLiteral(content)),
+                start_pos,
+                self.position - start_pos,
+            ));
+        }
+        if !content.is_empty() {
+            parts.push(Token::new(TokenType::Symbol(Symbol::Concat), content_start, 0));
+            parts.push(Token::new(
+                TokenType::Symbol(Symbol::StringLiteral(content)),
+                content_start,
+                self.position - content_start,
+            ));
+        }
+        parts.push(Token::new(TokenType::RightParen, self.position, 0));
+        self.pending.extend(parts);
+        Ok(Token::new(TokenType::LeftParen, start_pos, 0))
+    }
 
-        while self.position < self.graphemes.len() {
-            let ch = &self.graphemes[self.position];
-            if ch == "\"" {
-                self.position += 1; // Skip closing quote
-                let length = self.position - start_pos;
-                return Ok(Token::new(
-                    TokenType::Symbol(Symbol::StringLiteral(content)),
-                    start_pos,
-                    length,
-                ));
+    /// Read an escape sequence starting at the backslash
+    fn read_escape(&mut self) -> Result<char> {
+        let start = self.position;
+        let code = self.graphemes.get(start + 1).map(String::as_str).unwrap_or_default();
+        self.position += 2;
+        let ch = match code {
+            "n" => '\n',
+            "t" => '\t',
+            "r" => '\r',
+            "0" => '\0',
+            "\\" => '\\',
+            "\"" => '"',
+            "{" => '{',
+            "}" => '}',
+            "u" => {
+                let digits: Vec<&String> = self.graphemes[self.position..].iter().take(8).take_while(|g| *g != "}").collect();
+                let hex: String = digits.iter().skip(1).map(|g| g.as_str()).collect();
+                let valid = self.graphemes.get(self.position).is_some_and(|g| g == "{")
+                    && self.graphemes.get(self.position + digits.len()).is_some_and(|g| g == "}");
+                let ch = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).filter(|_| valid);
+                self.position += digits.len() + 1;
+                match ch {
+                    Some(ch) => ch,
+                    None => {
+                        self.position = start;
+                        return Err(AetherError::LexerError(format!(
+                            "Invalid unicode escape at position {}; write \\u{{1F4E4}}",
+                            start
+                        )));
+                    }
+                }
             }
-            content.push_str(ch);
-            self.position += 1;
+            other => {
+                self.position = start;
+                return Err(AetherError::LexerError(format!(
+                    "Unknown escape '\\{}' at position {}",
+                    other, start
+                )));
+            }
+        };
+        Ok(ch)
+    }
+
+    /// Lex the expression of a `{…}` interpolation starting at the brace,
+    /// leaving the position after the closing brace
+    fn read_interpolation(&mut self) -> Result<Vec<Token>> {
+        let open = self.position;
+        let mut close = open + 1;
+        let mut depth = 0;
+        let mut in_string = false;
+        loop {
+            let Some(ch) = self.graphemes.get(close) else {
+                return Err(AetherError::LexerError(format!(
+                    "Unterminated interpolation at position {}",
+                    open
+                )));
+            };
+            match ch.as_str() {
+                "\\" if in_string => close += 1,
+                "\"" => in_string = !in_string,
+                "{" if !in_string => depth += 1,
+                "}" if !in_string && depth == 0 => break,
+                "}" if !in_string => depth -= 1,
+                _ => {}
+            }
+            close += 1;
         }
 
-        Err(AetherError::LexerError(
-            "Unterminated string literal".to_string(),
+        let mut inner = Lexer {
+            position: 0,
+            graphemes: self.graphemes[open + 1..close].to_vec(),
+            pending: Vec::new(),
+            ascii: self.ascii,
+            look_alikes: self.look_alikes,
+        };
+        let tokens = inner.tokenize().inspect_err(|_| self.position = open + 1 + inner.position)?;
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .filter(|token| token.token_type != TokenType::Eof)
+            .map(|token| Token::new(token.token_type, token.position + open + 1, token.length))
+            .collect();
+        if tokens.is_empty() {
+            return Err(AetherError::LexerError(format!("Empty interpolation at position {}", open)));
+        }
+        self.position = close + 1;
+        Ok(tokens)
+    }
+
+    /// Read the quoted part of a bytes literal written in `encoding`
+    fn read_bytes_literal(&mut self, start_pos: usize, encoding: &str) -> Result<Token> {
+        let token = self.read_string_literal(self.position, true)?;
+        let text = match token.token_type {
+            TokenType::Symbol(Symbol::StringLiteral(text)) => text,
+            _ => unreachable!("raw strings are never interpolated"),
+        };
+        let data = bytes::decode(&text, encoding)
+            .map_err(|e| AetherError::LexerError(format!("Invalid {} literal at position {}: {}", encoding, start_pos, e)))?;
+        Ok(Token::new(
+            TokenType::Symbol(Symbol::BytesLiteral(data)),
+            start_pos,
+            self.position - start_pos,
         ))
     }
 
-    /// Read a number
+    /// Read a number: decimal digits with an optional fraction and exponent
+    /// (`1.5e-9`), `0x` hex or `0b` binary digits, with `_` allowed between
+    /// digits, and for decimal numbers an optional duration or size suffix
     fn read_number(&mut self, start_pos: usize) -> Result<Token> {
-        let mut number_str = String::new();
-
-        while self.position < self.graphemes.len() {
-            let ch = &self.graphemes[self.position];
-            if ch.chars().next().map_or(false, |c| c.is_numeric() || c == '.') {
-                number_str.push_str(ch);
-                self.position += 1;
+        let radix = match self.graphemes.get(self.position + 1).map(String::as_str) {
+            Some("x" | "X") if self.graphemes[self.position] == "0" => Some(16),
+            Some("b" | "B") if self.graphemes[self.position] == "0" => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            self.position += 2;
+            let digits = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            let text = self.graphemes[start_pos..self.position].concat();
+            let value = separated(&digits).and_then(|digits| i64::from_str_radix(&digits, radix).ok());
+            return match value {
+                Some(n) => Ok(Token::new(TokenType::Symbol(Symbol::IntegerLiteral(n)), start_pos, self.position - start_pos)),
+                None => Err(AetherError::LexerError(format!("Invalid number at position {}: {}", start_pos, text))),
+            };
+        }
+
+        let mut number_str = self.take_while(|c| c.is_numeric() || c == '.' || c == '_');
+        // An exponent needs digits, so `2e` stays a number followed by a suffix
+        let exponent_digit = |offset: usize| {
+            self.graphemes.get(self.position + offset).is_some_and(|g| g.chars().all(|c| c.is_ascii_digit()))
+        };
+        let sign = self.graphemes.get(self.position + 1).is_some_and(|g| g == "-" || g == "+");
+        if self.graphemes.get(self.position).is_some_and(|g| g == "e" || g == "E")
+            && (exponent_digit(1) || (sign && exponent_digit(2)))
+        {
+            number_str.push_str(&self.graphemes[self.position..self.position + 1 + sign as usize].concat());
+            self.position += 1 + sign as usize;
+            number_str.push_str(&self.take_while(|c| c.is_ascii_digit() || c == '_'));
+        }
+        let suffix = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        let invalid = || AetherError::LexerError(format!("Invalid number at position {}: {}{}", start_pos, number_str, suffix));
+        let number = separated(&number_str).ok_or_else(invalid)?;
+
+        let symbol = if suffix.is_empty() {
+            // Whole numbers are integers and numbers with a point or exponent
+            // are decimals; a whole number beyond i64 is kept exactly as a decimal
+            if number.contains(['.', 'e', 'E']) {
+                Decimal::parse(&number).map(Symbol::DecimalLiteral)
             } else {
-                break;
+                number.parse::<i64>().ok().map(Symbol::IntegerLiteral)
+                    .or_else(|| Decimal::parse(&number).map(Symbol::DecimalLiteral))
             }
-        }
-
-        let number = number_str
-            .parse::<f64>()
-            .map_err(|e| AetherError::LexerError(format!("Invalid number: {}", e)))?;
+        } else {
+            // Durations are milliseconds and sizes bytes, as integers when whole
+            let scale: i64 = match suffix.as_str() {
+                "ms" | "B" => 1,
+                "s" => 1000,
+                "m" => 60_000,
+                "h" => 3_600_000,
+                "KB" => 1 << 10,
+                "MB" => 1 << 20,
+                "GB" => 1 << 30,
+                "TB" => 1 << 40,
+                _ => return Err(AetherError::LexerError(format!(
+                    "Unknown suffix '{}' on number at position {} (use ms, s, m, h, B, KB, MB, GB or TB)",
+                    suffix, start_pos
+                ))),
+            };
+            Decimal::parse(&number)
+                .and_then(|amount| amount.checked_mul(Decimal::from_i64(scale)))
+                .map(|value| match value.to_i64() {
+                    Some(n) => Symbol::IntegerLiteral(n),
+                    None => Symbol::DecimalLiteral(value),
+                })
+        }
+        .ok_or_else(invalid)?;
 
         let length = self.position - start_pos;
         Ok(Token::new(
-            TokenType::Symbol(Symbol::NumberLiteral(number)),
+            TokenType::Symbol(symbol),
             start_pos,
             length,
         ))
     }
 
-    /// Read an identifier
-    fn read_identifier(&mut self, start_pos: usize) -> Result<Token> {
+    /// Consume graphemes while their first character matches, returning them
+    fn take_while(&mut self, matches: impl Fn(char) -> bool) -> String {
+        let mut taken = String::new();
+        while let Some(grapheme) = self.graphemes.get(self.position) {
+            if !grapheme.chars().next().is_some_and(&matches) {
+                break;
+            }
+            taken.push_str(grapheme);
+            self.position += 1;
+        }
+        taken
+    }
+
+    /// Read an identifier; a `raw` one is never an ASCII spelling
+    fn read_identifier(&mut self, start_pos: usize, raw: bool) -> Result<Token> {
         let mut identifier = String::new();
 
         while self.position < self.graphemes.len() {
             let ch = &self.graphemes[self.position];
-            if ch.chars().next().map_or(false, |c| c.is_alphanumeric() || c == '_') {
+            if ch.chars().next().is_some_and(|c| c.is_alphanumeric() || c == '_') {
                 identifier.push_str(ch);
                 self.position += 1;
             } else {
@@ -281,14 +595,26 @@ impl Lexer {
             }
         }
 
-        // Check if identifier is a single-char symbol
-        if identifier.len() == 1 {
-            if let Some(symbol) = Symbol::from_str(&identifier) {
-                let length = self.position - start_pos;
-                return Ok(Token::new(TokenType::Symbol(symbol), start_pos, length));
+        // hex"…" and b64"…" are bytes literals, and r"…" a raw string
+        if self.graphemes.get(self.position).is_some_and(|g| g == "\"") {
+            match identifier.as_str() {
+                "hex" | "b64" => return self.read_bytes_literal(start_pos, &identifier),
+                "r" => return self.read_string_literal(start_pos, true),
+                _ => {}
             }
         }
 
+        // Check if identifier is a single-char symbol or an ASCII spelling
+        let symbol = match identifier.len() {
+            1 => Symbol::from_str(&identifier),
+            _ if self.ascii && !raw => Symbol::from_ascii(&identifier),
+            _ => None,
+        };
+        if let Some(symbol) = symbol {
+            let length = self.position - start_pos;
+            return Ok(Token::new(TokenType::Symbol(symbol), start_pos, length));
+        }
+
         let length = self.position - start_pos;
         Ok(Token::new(
             TokenType::Symbol(Symbol::Identifier(identifier)),
@@ -298,6 +624,44 @@ impl Lexer {
     }
 }
 
+/// Digits with their `_` separators removed, or `None` if a separator is
+/// not between two digits
+fn separated(text: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' {
+            let before = i.checked_sub(1).and_then(|i| chars.get(i));
+            let after = chars.get(i + 1);
+            if !before.is_some_and(|c| c.is_ascii_alphanumeric()) || !after.is_some_and(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+        }
+    }
+    Some(text.replace('_', ""))
+}
+
+/// The string literal that lexes back to `text`, with the characters that
+/// would end it, interpolate or break its line escaped
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '{' => quoted.push_str("\\{"),
+            '}' => quoted.push_str("\\}"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\0' => quoted.push_str("\\0"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,6 +689,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lexer_string_escapes() {
+        let tokens = Lexer::new(r#""a\"b\n\t\\ \{x\} \u{1F4E4}" r"c:\{d}\n" """
+one "two"
+three""" r"""x\
+y""""#.to_string()).tokenize().unwrap();
+        let strings: Vec<&str> = tokens.iter().filter_map(|t| match &t.token_type {
+            TokenType::Symbol(Symbol::StringLiteral(s)) => Some(s.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(strings, vec!["a\"b\n\t\\ {x} 📤", "c:\\{d}\\n", "one \"two\"\nthree", "x\\\ny"]);
+        assert_eq!(tokens[1].position, 29);
+
+        for bad in [r#""\q""#, r#""\u{110000}""#, r#""\u1F4E4""#, "\"abc", "\"{}\"", "\"{x\"", "\"{1 ~}\""] {
+            assert!(Lexer::new(bad.to_string()).tokenize().is_err(), "{}", bad);
+        }
+        let mut lexer = Lexer::new(r#"📤 "ok \q""#.to_string());
+        assert!(lexer.tokenize().is_err());
+        assert_eq!(lexer.position(), 6);
+    }
+
+    #[test]
+    fn test_lexer_interpolation() {
+        let tokens = Lexer::new(r#""Hi {name}, {n + 1}!""#.to_string()).tokenize().unwrap();
+        let kinds: Vec<String> = tokens.iter().map(|t| match &t.token_type {
+            TokenType::Symbol(Symbol::StringLiteral(s)) => format!("{:?}", s),
+            TokenType::Symbol(Symbol::Identifier(id)) => id.clone(),
+            TokenType::Symbol(symbol) => symbol.to_str(),
+            TokenType::LeftParen => "(".to_string(),
+            TokenType::RightParen => ")".to_string(),
+            other => format!("{:?}", other),
+        }).collect();
+        assert_eq!(kinds.join(" "), r#"( "Hi " ⧺ ( name ) ⧺ ", " ⧺ ( n + 1 ) ⧺ "!" ) Eof"#);
+        // Names keep their place in the source
+        assert_eq!((tokens[4].position, tokens[4].length), (5, 4));
+
+        // A lone interpolation still makes a string, and braces may nest
+        let tokens = Lexer::new(r#""{ {a: "}"}.a }""#.to_string()).tokenize().unwrap();
+        assert_eq!(tokens[1].token_type, TokenType::Symbol(Symbol::StringLiteral(String::new())));
+        assert!(tokens.iter().any(|t| t.token_type == TokenType::Symbol(Symbol::StringLiteral("}".to_string()))));
+    }
+
     #[test]
     fn test_lexer_number() {
         let mut lexer = Lexer::new("42.5".to_string());
@@ -332,9 +738,129 @@ mod tests {
 
         assert_eq!(tokens.len(), 2); // number + EOF
         match &tokens[0].token_type {
-            TokenType::Symbol(Symbol::NumberLiteral(n)) => assert_eq!(*n, 42.5),
-            _ => panic!("Expected number literal"),
+            TokenType::Symbol(Symbol::DecimalLiteral(d)) => assert_eq!(d.to_string(), "42.5"),
+            _ => panic!("Expected decimal literal"),
+        }
+    }
+
+    #[test]
+    fn test_lexer_integer_and_large_numbers() {
+        let mut lexer = Lexer::new("42 9007199254740993 99999999999999999999 19.90".to_string());
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Symbol(Symbol::IntegerLiteral(42)));
+        assert_eq!(tokens[1].token_type, TokenType::Symbol(Symbol::IntegerLiteral(9_007_199_254_740_993)));
+        match &tokens[2].token_type {
+            TokenType::Symbol(Symbol::DecimalLiteral(d)) => assert_eq!(d.to_string(), "99999999999999999999"),
+            other => panic!("Expected decimal literal, got {:?}", other),
         }
+        match &tokens[3].token_type {
+            TokenType::Symbol(Symbol::DecimalLiteral(d)) => assert_eq!(d.to_string(), "19.90"),
+            other => panic!("Expected decimal literal, got {:?}", other),
+        }
+        assert!(Lexer::new("1.2.3".to_string()).tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lexer_number_forms_and_suffixes() {
+        let source = "0xFF 0b1010 1_000_000 1e-9 2.5E3 30s 5ms 1.5m 2h 10MB 4KB 1GB 8B 0x7fff_ffff";
+        let tokens = Lexer::new(source.to_string()).tokenize().unwrap();
+        let values: Vec<String> = tokens.iter().filter_map(|t| match &t.token_type {
+            TokenType::Symbol(Symbol::IntegerLiteral(n)) => Some(n.to_string()),
+            TokenType::Symbol(Symbol::DecimalLiteral(d)) => Some(format!("{}d", d)),
+            _ => None,
+        }).collect();
+        assert_eq!(values, [
+            "255", "10", "1000000", "0.000000001d", "2500d", "30000", "5", "90000", "7200000",
+            "10485760", "4096", "1073741824", "8", "2147483647",
+        ]);
+        assert_eq!((tokens[2].position, tokens[2].length), (12, 9));
+
+        // `-` is always an operator token
+        let tokens = Lexer::new("-10".to_string()).tokenize().unwrap();
+        assert_eq!(tokens[0].token_type, TokenType::Symbol(Symbol::Subtract));
+        assert_eq!(tokens[1].token_type, TokenType::Symbol(Symbol::IntegerLiteral(10)));
+
+        for bad in ["1__0", "1_", "1_.5", "0x", "0xG", "0b12", "2e", "3x", "10kb"] {
+            assert!(Lexer::new(bad.to_string()).tokenize().is_err(), "{} should not lex", bad);
+        }
+        let err = Lexer::new("5 ▷ t ⨠ 3days".to_string()).tokenize().unwrap_err();
+        assert!(err.to_string().contains("Unknown suffix 'days'"), "{}", err);
+    }
+
+    #[test]
+    fn test_lexer_variation_selectors_and_http_methods() {
+        let mut lexer = Lexer::new("⏱ 🏷\u{FE0F} 🛡\u{FE0F} 🔏 🌐🔄\"u\" 🌐👁\u{FE0F} 🌐 📥".to_string());
+        let tokens: Vec<(TokenType, usize)> =
+            lexer.tokenize().unwrap().into_iter().map(|token| (token.token_type, token.length)).collect();
+        let symbol = |symbol, length| (TokenType::Symbol(symbol), length);
+        assert_eq!(
+            tokens,
+            vec![
+                symbol(Symbol::Benchmark, 1),
+                symbol(Symbol::HttpHeaders, 1),
+                symbol(Symbol::Try, 1),
+                symbol(Symbol::Verify, 1),
+                symbol(Symbol::HttpPut, 2),
+                symbol(Symbol::StringLiteral("u".to_string()), 3),
+                symbol(Symbol::HttpHead, 2),
+                symbol(Symbol::HttpRequest, 1),
+                symbol(Symbol::Input, 1),
+                (TokenType::Eof, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lexer_ascii_aliases() {
+        let source = "fn`f: x |> y >> retry`3: y[|0|] -> len ++ \"{a >= 1}\" >> fnx";
+        let mut lexer = Lexer::new(source.to_string());
+        lexer.set_ascii_aliases(true);
+        let kinds: Vec<TokenType> = lexer.tokenize().unwrap().into_iter().map(|t| t.token_type).collect();
+        let symbol = |s: Symbol| TokenType::Symbol(s);
+        let name = |n: &str| TokenType::Symbol(Symbol::Identifier(n.to_string()));
+        assert_eq!(kinds[..9], [
+            symbol(Symbol::Function), name("f"), TokenType::Colon, name("x"), symbol(Symbol::PipeInto),
+            name("y"), symbol(Symbol::Sequence), symbol(Symbol::Retry), symbol(Symbol::IntegerLiteral(3)),
+        ]);
+        assert!(kinds.contains(&symbol(Symbol::IndexStart)) && kinds.contains(&symbol(Symbol::Length)));
+        assert!(kinds.contains(&symbol(Symbol::GreaterEqual)), "aliases work inside interpolation");
+        assert_eq!(kinds[kinds.len() - 2], name("fnx"));
+
+        // A backtick before a spelling escapes it as a name
+        let mut lexer = Lexer::new("`len |> `input >> fn``len".to_string());
+        lexer.set_ascii_aliases(true);
+        let kinds: Vec<TokenType> = lexer.tokenize().unwrap().into_iter().map(|t| t.token_type).collect();
+        assert_eq!(kinds[..6], [
+            name("len"), symbol(Symbol::PipeInto), name("input"), symbol(Symbol::Sequence),
+            symbol(Symbol::Function), name("len"),
+        ]);
+        let mut lexer = Lexer::new("fn`len".to_string());
+        lexer.set_ascii_aliases(true);
+        assert_eq!(lexer.tokenize().unwrap()[1].token_type, symbol(Symbol::Length));
+
+        // Off by default: the spellings are ordinary names and backticks are errors
+        let tokens = Lexer::new("retry".to_string()).tokenize().unwrap();
+        assert_eq!(tokens[0].token_type, name("retry"));
+        assert!(Lexer::new("fn`f".to_string()).tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lexer_comments_and_line_breaks() {
+        let source = "1 // one\n// two";
+        let tokens = Lexer::new(source.to_string()).tokenize_with_trivia().unwrap();
+        let kinds: Vec<TokenType> = tokens.iter().map(|t| t.token_type.clone()).collect();
+        assert_eq!(kinds, [
+            TokenType::Symbol(Symbol::IntegerLiteral(1)),
+            TokenType::Comment("// one".to_string()),
+            TokenType::Newline,
+            TokenType::Comment("// two".to_string()),
+            TokenType::Eof,
+        ]);
+        assert_eq!((tokens[1].position, tokens[1].length), (2, 6));
+
+        let tokens = Lexer::new(source.to_string()).tokenize().unwrap();
+        assert_eq!(tokens.len(), 2);
     }
 
     #[test]
@@ -349,6 +875,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lexer_bytes_literals() {
+        let mut lexer = Lexer::new("hex\"00ff\" b64\"SGk=\" hex".to_string());
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Symbol(Symbol::BytesLiteral(vec![0x00, 0xff])));
+        assert_eq!(tokens[1].token_type, TokenType::Symbol(Symbol::BytesLiteral(b"Hi".to_vec())));
+        assert_eq!(tokens[2].token_type, TokenType::Symbol(Symbol::Identifier("hex".to_string())));
+
+        assert!(Lexer::new("hex\"0g\"".to_string()).tokenize().is_err());
+    }
+
     #[test]
     fn test_lexer_complex() {
         let mut lexer = Lexer::new("ƒ®: 📥⇢J".to_string());
@@ -33,6 +33,29 @@ pub mod compiler;
 pub mod vm;
 pub mod constants;
 pub mod explainer;
+pub mod net;
+pub mod stream;
+pub mod bytes;
+pub mod crypto;
+pub mod numeric;
+pub mod checker;
+pub mod verifier;
+pub mod optimizer;
+pub mod limits;
+pub mod debugger;
+pub mod dap;
+pub mod lsp;
+pub mod ascii;
+pub mod cst;
+pub mod formatter;
+pub mod normalize;
+pub mod tokenizer;
+pub mod stats;
+pub mod transpiler;
+pub mod projector;
+
+#[cfg(test)]
+mod conformance;
 
 pub use error::{AetherError, Result};
 pub use lexer::{Lexer, Token, TokenType};
@@ -43,6 +66,7 @@ pub use bytecode::{BytecodeProgram, Opcode};
 pub use compiler::Compiler;
 pub use vm::VM;
 pub use explainer::Explainer;
+pub use limits::{Limit, Limits};
 
 /// Version information for the Aether language
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,219 @@
+//! Execution budgets for Aether
+//!
+//! `Limits` caps how much work one execution of a program may do: the number
+//! of steps, the wall-clock time, the stack depth and the size of the values
+//! it builds. Both the tree-walking `Runtime` and the bytecode `VM` enforce
+//! them through a `Meter`, and report the first budget a program runs out of
+//! as `AetherError::LimitExceeded`, so hosts running untrusted programs can
+//! give each tenant its own quota.
+
+use crate::error::{AetherError, Result};
+use crate::runtime::Value;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Steps between checks of the wall clock
+const CLOCK_INTERVAL: u64 = 64;
+
+/// A budget a program can run out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Steps,
+    Time,
+    StackDepth,
+    CollectionSize,
+    StringLength,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Limit::Steps => "step",
+            Limit::Time => "time",
+            Limit::StackDepth => "stack depth",
+            Limit::CollectionSize => "collection size",
+            Limit::StringLength => "string length",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Budgets for one execution, where `None` means unlimited
+///
+/// A step is one instruction in the VM and one evaluated expression in the
+/// runtime. Stack depth counts values on the VM stack and nested expressions
+/// in the runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub max_stack_depth: Option<usize>,
+    pub max_collection_size: Option<usize>,
+    pub max_string_length: Option<usize>,
+}
+
+impl Limits {
+    /// No budgets at all
+    pub fn unlimited() -> Self {
+        Limits {
+            max_steps: None,
+            timeout: None,
+            max_stack_depth: None,
+            max_collection_size: None,
+            max_string_length: None,
+        }
+    }
+}
+
+/// Generous enough for real programs while still stopping runaway loops
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_steps: Some(10_000_000),
+            max_stack_depth: Some(1_000_000),
+            ..Limits::unlimited()
+        }
+    }
+}
+
+/// Tracks one execution against its `Limits`
+#[derive(Debug, Clone)]
+pub struct Meter {
+    limits: Limits,
+    steps: u64,
+    deadline: Option<Instant>,
+}
+
+impl Meter {
+    /// Start metering now
+    pub fn new(limits: Limits) -> Self {
+        let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+        Meter { limits, steps: 0, deadline }
+    }
+
+    /// Steps taken so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Count one step, checking the step budget and the deadline
+    pub fn step(&mut self) -> Result<()> {
+        self.steps += 1;
+        if let Some(max) = self.limits.max_steps {
+            if self.steps > max {
+                return Err(exceeded(Limit::Steps, format!("more than {} steps", max)));
+            }
+        }
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.limits.timeout) {
+            if self.steps % CLOCK_INTERVAL == 1 && Instant::now() >= deadline {
+                return Err(exceeded(Limit::Time, format!("ran longer than {:?}", timeout)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the current stack depth
+    pub fn check_depth(&self, depth: usize) -> Result<()> {
+        match self.limits.max_stack_depth {
+            Some(max) if depth > max => Err(exceeded(
+                Limit::StackDepth,
+                format!("stack depth {} is over the limit of {}", depth, max),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the size of a value the program produced
+    pub fn check_value(&self, value: &Value) -> Result<()> {
+        match value {
+            Value::Array(items) => self.check_length(Limit::CollectionSize, "array", items.len()),
+            Value::Object(fields) => self.check_length(Limit::CollectionSize, "object", fields.len()),
+            Value::String(text) => self.check_length(Limit::StringLength, "string", text.len()),
+            Value::Bytes(bytes) => self.check_length(Limit::StringLength, "byte string", bytes.len()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the length of a value before building it, so builtins whose
+    /// result size the program chooses refuse instead of allocating it
+    pub fn check_length(&self, limit: Limit, kind: &str, len: usize) -> Result<()> {
+        let max = match limit {
+            Limit::CollectionSize => self.limits.max_collection_size,
+            Limit::StringLength => self.limits.max_string_length,
+            _ => None,
+        };
+        match max {
+            Some(max) if len > max => Err(exceeded(
+                limit,
+                format!("{} of length {} is over the limit of {}", kind, len, max),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn exceeded(limit: Limit, detail: String) -> AetherError {
+    AetherError::LimitExceeded(limit, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_enforces_each_budget() {
+        let mut meter = Meter::new(Limits {
+            max_steps: Some(2),
+            max_stack_depth: Some(3),
+            max_collection_size: Some(1),
+            max_string_length: Some(4),
+            ..Limits::unlimited()
+        });
+
+        assert!(meter.step().is_ok());
+        assert!(meter.step().is_ok());
+        let err = meter.step().unwrap_err();
+        assert_eq!(err, AetherError::LimitExceeded(Limit::Steps, "more than 2 steps".to_string()));
+        assert_eq!(err.to_string(), "step limit exceeded: more than 2 steps");
+
+        assert!(meter.check_depth(3).is_ok());
+        assert!(matches!(meter.check_depth(4), Err(AetherError::LimitExceeded(Limit::StackDepth, _))));
+
+        assert!(meter.check_value(&Value::Array(vec![Value::Null])).is_ok());
+        assert!(matches!(
+            meter.check_value(&Value::Array(vec![Value::Null, Value::Null])),
+            Err(AetherError::LimitExceeded(Limit::CollectionSize, _))
+        ));
+        assert!(meter.check_value(&Value::String("abcd".to_string())).is_ok());
+        assert!(matches!(
+            meter.check_value(&Value::Bytes(vec![0; 5])),
+            Err(AetherError::LimitExceeded(Limit::StringLength, _))
+        ));
+    }
+
+    #[test]
+    fn test_lengths_are_checked_before_building() {
+        let meter = Meter::new(Limits { max_string_length: Some(10), ..Limits::unlimited() });
+        assert!(meter.check_length(Limit::StringLength, "string", 10).is_ok());
+        assert!(matches!(
+            meter.check_length(Limit::StringLength, "byte string", 100_000_000_000),
+            Err(AetherError::LimitExceeded(Limit::StringLength, _))
+        ));
+        assert!(meter.check_length(Limit::CollectionSize, "array", 100_000_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_meter_stops_at_deadline() {
+        let mut meter = Meter::new(Limits {
+            timeout: Some(Duration::from_millis(0)),
+            ..Limits::unlimited()
+        });
+        assert!(matches!(meter.step(), Err(AetherError::LimitExceeded(Limit::Time, _))));
+
+        let mut meter = Meter::new(Limits::unlimited());
+        for _ in 0..1000 {
+            assert!(meter.step().is_ok());
+        }
+        assert_eq!(meter.steps(), 1000);
+    }
+}
@@ -0,0 +1,595 @@
+//! Language Server Protocol server for Aether
+//!
+//! `aether lsp` speaks LSP over stdio. Each open document is lexed, parsed and
+//! type checked on every change, which gives the editor:
+//!
+//! - diagnostics for lexer, parser and type errors, and warnings for glyphs
+//!   not written in their canonical form (`normalize::lint`)
+//! - hover with the description of a symbol and the `Explainer` projection of
+//!   the statement around it
+//! - completion of symbols by English name, so typing `retry` inserts `♻`
+//! - go-to-definition for variables and functions
+//! - semantic tokens for highlighting
+//!
+//! Positions are converted between the lexer's grapheme offsets and LSP's
+//! lines and UTF-16 columns.
+
+use crate::checker;
+use crate::dap::{read_body, write_message};
+use crate::error::Result;
+use crate::explainer::Explainer;
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::normalize;
+use crate::parser::{AstNode, Parser};
+use crate::symbols::Symbol;
+use serde_json::{json, Value as Json};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// JSON-RPC error code for requests the server doesn't know
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for requests after `shutdown`
+const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code for a message that isn't valid JSON
+const PARSE_ERROR: i64 = -32700;
+
+/// Semantic token types, indexed by `TokenKind`
+const TOKEN_TYPES: &[&str] = &["keyword", "operator", "function", "variable", "string", "number", "type"];
+
+/// Semantic token kinds, in the order of `TOKEN_TYPES`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenKind {
+    Keyword,
+    Operator,
+    Function,
+    Variable,
+    String,
+    Number,
+    Type,
+}
+
+/// What a name is bound to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Binding {
+    Variable,
+    Function,
+}
+
+/// A name bound by `▷`, `ƒ`, `∀` or `🧊`
+struct Definition {
+    name: String,
+    binding: Binding,
+    /// Index of the identifier token
+    token: usize,
+}
+
+/// An open document and what was learned from it
+struct Document {
+    /// LSP (line, UTF-16 column) of each grapheme, then of the end of the text
+    positions: Vec<(u32, u32)>,
+    /// Tokens without `Eof`; empty when lexing failed
+    tokens: Vec<Token>,
+    /// Statements with their source ranges, each `⨠` step on its own; empty
+    /// when parsing failed
+    statements: Vec<(AstNode, (usize, usize))>,
+    definitions: Vec<Definition>,
+    diagnostics: Vec<Json>,
+}
+
+impl Document {
+    fn new(text: &str) -> Self {
+        let mut document = Document {
+            positions: positions(text),
+            tokens: Vec::new(),
+            statements: Vec::new(),
+            definitions: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let mut lexer = Lexer::new(text.to_string());
+        let tokens = match lexer.tokenize() {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                let start = lexer.position();
+                document.diagnostics.push(document.diagnostic(start, start + 1, &err.to_string()));
+                return document;
+            }
+        };
+        document.tokens = tokens.iter().filter(|t| t.token_type != TokenType::Eof).cloned().collect();
+        document.definitions = definitions(&document.tokens);
+        for warning in normalize::lint(text).unwrap_or_default() {
+            let range = document.range(warning.position, warning.position + warning.length);
+            document.diagnostics.push(json!({ "range": range, "severity": 2, "source": "aether", "message": warning.message }));
+        }
+
+        let mut parser = Parser::new(tokens);
+        let nodes = match parser.parse() {
+            Ok(nodes) => nodes,
+            Err(err) => {
+                let start = parser.start_position();
+                let end = document.tokens.iter()
+                    .find(|t| t.position == start)
+                    .map_or(start + 1, |t| start + t.length.max(1));
+                document.diagnostics.push(document.diagnostic(start, end, &err.to_string()));
+                return document;
+            }
+        };
+        for (node, steps) in nodes.into_iter().zip(parser.step_spans()) {
+            match node {
+                AstNode::Sequence(operations) if operations.len() == steps.len() && steps.len() > 1 => {
+                    document.statements.extend(operations.into_iter().zip(steps.iter().copied()));
+                }
+                node => {
+                    let span = (steps.first().map_or(0, |s| s.0), steps.last().map_or(0, |s| s.1));
+                    document.statements.push((node, span));
+                }
+            }
+        }
+
+        let program: Vec<AstNode> = document.statements.iter().map(|(node, _)| node.clone()).collect();
+        for (index, diagnostic) in checker::check_each(&program) {
+            let (start, end) = document.statements[index].1;
+            document.diagnostics.push(document.diagnostic(start, end, &diagnostic.message));
+        }
+        document
+    }
+
+    fn diagnostic(&self, start: usize, end: usize, message: &str) -> Json {
+        json!({ "range": self.range(start, end), "severity": 1, "source": "aether", "message": message })
+    }
+
+    fn position(&self, index: usize) -> Json {
+        let (line, character) = self.positions[index.min(self.positions.len() - 1)];
+        json!({ "line": line, "character": character })
+    }
+
+    fn range(&self, start: usize, end: usize) -> Json {
+        json!({ "start": self.position(start), "end": self.position(end) })
+    }
+
+    /// Grapheme offset of the character at an LSP position
+    fn offset(&self, position: &Json) -> usize {
+        let target = (
+            position["line"].as_u64().unwrap_or(0) as u32,
+            position["character"].as_u64().unwrap_or(0) as u32,
+        );
+        self.positions.partition_point(|p| *p <= target).saturating_sub(1)
+    }
+
+    /// Index of the token covering a grapheme offset
+    fn token_at(&self, offset: usize) -> Option<usize> {
+        self.tokens.iter().position(|t| t.position <= offset && offset < t.position + t.length)
+    }
+
+    fn hover(&self, position: &Json) -> Json {
+        let offset = self.offset(position);
+        let mut sections = Vec::new();
+        let token = self.token_at(offset);
+
+        if let Some(index) = token {
+            if let TokenType::Symbol(symbol) = &self.tokens[index].token_type {
+                sections.push(match symbol {
+                    Symbol::Identifier(name) => match self.binding(name) {
+                        Some(Binding::Function) => format!("`{}`: function", name),
+                        _ => format!("`{}`: variable", name),
+                    },
+                    symbol if is_operation(symbol) => {
+                        format!("`{}` **{}**: {}", symbol.to_str(), symbol.name(), symbol.description())
+                    }
+                    symbol => symbol.description().to_string(),
+                });
+            }
+        }
+        if let Some((node, _)) = self.statements.iter().find(|(_, (start, end))| *start <= offset && offset < *end) {
+            let explanation = Explainer::new().explain(std::slice::from_ref(node));
+            sections.push(format!("```\n{}\n```", explanation.trim_end()));
+        }
+
+        if sections.is_empty() {
+            return Json::Null;
+        }
+        let mut hover = json!({ "contents": { "kind": "markdown", "value": sections.join("\n\n") } });
+        if let Some(index) = token {
+            let token = &self.tokens[index];
+            hover["range"] = self.range(token.position, token.position + token.length);
+        }
+        hover
+    }
+
+    fn completion(&self) -> Json {
+        let mut items: Vec<Json> = Symbol::all().iter().map(|symbol| json!({
+            "label": symbol.name(),
+            "kind": 24,
+            "detail": format!("{} {}", symbol.to_str(), symbol.description()),
+            "insertText": symbol.to_str(),
+        })).collect();
+
+        let mut seen = Vec::new();
+        for definition in &self.definitions {
+            if seen.contains(&definition.name) {
+                continue;
+            }
+            seen.push(definition.name.clone());
+            let kind = match definition.binding {
+                Binding::Variable => 6,
+                Binding::Function => 3,
+            };
+            items.push(json!({ "label": definition.name, "kind": kind }));
+        }
+        json!({ "isIncomplete": false, "items": items })
+    }
+
+    /// Where the name at a position was last bound before it, or first bound
+    /// if it is only bound later
+    fn definition(&self, uri: &Json, position: &Json) -> Json {
+        let Some(index) = self.token_at(self.offset(position)) else {
+            return Json::Null;
+        };
+        let TokenType::Symbol(Symbol::Identifier(name)) = &self.tokens[index].token_type else {
+            return Json::Null;
+        };
+        let candidates: Vec<&Definition> = self.definitions.iter().filter(|d| &d.name == name).collect();
+        let target = candidates.iter().rev().find(|d| d.token <= index).or(candidates.first());
+        match target {
+            Some(definition) => {
+                let token = &self.tokens[definition.token];
+                json!({ "uri": uri, "range": self.range(token.position, token.position + token.length) })
+            }
+            None => Json::Null,
+        }
+    }
+
+    fn semantic_tokens(&self) -> Json {
+        let mut data = Vec::new();
+        let (mut last_line, mut last_column) = (0, 0);
+        // Zero-length tokens are the ones added for string interpolation
+        for token in self.tokens.iter().filter(|t| t.length > 0) {
+            let Some(kind) = self.kind(token) else { continue };
+            let (line, column) = self.positions[token.position];
+            let (end_line, end_column) = self.positions[(token.position + token.length).min(self.positions.len() - 1)];
+            // Tokens spanning lines, such as multi-line strings, are left plain
+            if end_line != line {
+                continue;
+            }
+            let delta = if line == last_line { column - last_column } else { column };
+            data.extend([line - last_line, delta, end_column - column, kind as u32, 0]);
+            (last_line, last_column) = (line, column);
+        }
+        json!({ "data": data })
+    }
+
+    fn kind(&self, token: &Token) -> Option<TokenKind> {
+        let TokenType::Symbol(symbol) = &token.token_type else {
+            return None;
+        };
+        Some(match symbol {
+            Symbol::Identifier(name) => match self.binding(name) {
+                Some(Binding::Function) => TokenKind::Function,
+                _ => TokenKind::Variable,
+            },
+            Symbol::StringLiteral(_) | Symbol::BytesLiteral(_) => TokenKind::String,
+            Symbol::NumberLiteral(_) | Symbol::IntegerLiteral(_) | Symbol::DecimalLiteral(_) => TokenKind::Number,
+            Symbol::StringType | Symbol::NumberType => TokenKind::Type,
+            Symbol::Function | Symbol::Lambda | Symbol::If | Symbol::ElseIf | Symbol::Else | Symbol::Loop
+            | Symbol::ForEach | Symbol::Try | Symbol::Retry | Symbol::Guard | Symbol::Halt | Symbol::Async
+            | Symbol::Await | Symbol::Test | Symbol::Import => TokenKind::Keyword,
+            _ => TokenKind::Operator,
+        })
+    }
+
+    fn binding(&self, name: &str) -> Option<Binding> {
+        self.definitions.iter().find(|d| d.name == name).map(|d| d.binding)
+    }
+}
+
+/// Serves LSP requests read from `input`, writing responses and notifications to `output`
+pub struct LspServer<R, W> {
+    input: R,
+    output: W,
+    documents: HashMap<String, Document>,
+    shut_down: bool,
+}
+
+impl<R: BufRead, W: Write> LspServer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        LspServer {
+            input,
+            output,
+            documents: HashMap::new(),
+            shut_down: false,
+        }
+    }
+
+    /// Serve messages until `exit` or the end of the input
+    pub fn run(&mut self) -> Result<()> {
+        while let Some(body) = read_body(&mut self.input)? {
+            // A message that can't be parsed has no id to answer, so the
+            // error goes to null and the server carries on
+            let message: Json = match serde_json::from_slice(&body) {
+                Ok(message) => message,
+                Err(err) => {
+                    let error = json!({ "code": PARSE_ERROR, "message": format!("Parse error: {}", err) });
+                    write_message(&mut self.output, &json!({ "jsonrpc": "2.0", "id": null, "error": error }))?;
+                    continue;
+                }
+            };
+            let method = message["method"].as_str().unwrap_or_default().to_string();
+            let params = &message["params"];
+
+            let Some(id) = message.get("id") else {
+                if method == "exit" {
+                    return Ok(());
+                }
+                self.notification(&method, params)?;
+                continue;
+            };
+            let result = if self.shut_down {
+                Err((INVALID_REQUEST, "The server has shut down".to_string()))
+            } else {
+                self.request(&method, params)
+            };
+            let response = match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, message)) => {
+                    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+                }
+            };
+            write_message(&mut self.output, &response)?;
+        }
+        Ok(())
+    }
+
+    fn request(&mut self, method: &str, params: &Json) -> std::result::Result<Json, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let document = self.documents.get(uri);
+        let position = &params["position"];
+        Ok(match (method, document) {
+            ("initialize", _) => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": [] },
+                    "definitionProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": { "tokenTypes": TOKEN_TYPES, "tokenModifiers": [] },
+                        "full": true,
+                    },
+                },
+                "serverInfo": { "name": "aether", "version": crate::VERSION },
+            }),
+            ("shutdown", _) => {
+                self.shut_down = true;
+                Json::Null
+            }
+            ("textDocument/hover", Some(document)) => document.hover(position),
+            ("textDocument/completion", Some(document)) => document.completion(),
+            ("textDocument/definition", Some(document)) => document.definition(&params["textDocument"]["uri"], position),
+            ("textDocument/semanticTokens/full", Some(document)) => document.semantic_tokens(),
+            ("textDocument/hover" | "textDocument/definition", None) => Json::Null,
+            ("textDocument/completion", None) => Document::new("").completion(),
+            ("textDocument/semanticTokens/full", None) => json!({ "data": [] }),
+            (method, _) => return Err((METHOD_NOT_FOUND, format!("Unsupported request '{}'", method))),
+        })
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Result<()> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // Full sync: the last change holds the whole text
+            "textDocument/didChange" => params["contentChanges"].as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return self.publish(&uri, Vec::new());
+            }
+            _ => return Ok(()),
+        };
+        let Some(text) = text else {
+            return Ok(());
+        };
+        let document = Document::new(text);
+        let diagnostics = document.diagnostics.clone();
+        self.documents.insert(uri.clone(), document);
+        self.publish(&uri, diagnostics)
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Vec<Json>) -> Result<()> {
+        write_message(&mut self.output, &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }))
+    }
+}
+
+/// LSP position of each grapheme of `text`, then of its end
+fn positions(text: &str) -> Vec<(u32, u32)> {
+    let mut positions = Vec::new();
+    let (mut line, mut column) = (0, 0);
+    for grapheme in text.graphemes(true) {
+        positions.push((line, column));
+        if grapheme.contains('\n') {
+            line += 1;
+            column = 0;
+        } else {
+            column += grapheme.encode_utf16().count() as u32;
+        }
+    }
+    positions.push((line, column));
+    positions
+}
+
+/// Names bound in a token stream, in order
+fn definitions(tokens: &[Token]) -> Vec<Definition> {
+    let mut found = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        let TokenType::Symbol(symbol) = &token.token_type else { continue };
+        let binding = match symbol {
+            Symbol::PipeInto | Symbol::ForEach | Symbol::Immutable => Binding::Variable,
+            Symbol::Function => Binding::Function,
+            _ => continue,
+        };
+        // `∀(item)` as well as `∀item`
+        let mut next = index + 1;
+        if *symbol == Symbol::ForEach && tokens.get(next).is_some_and(|t| t.token_type == TokenType::LeftParen) {
+            next += 1;
+        }
+        if let Some(TokenType::Symbol(Symbol::Identifier(name))) = tokens.get(next).map(|t| &t.token_type) {
+            found.push(Definition { name: name.clone(), binding, token: next });
+        }
+    }
+    found
+}
+
+/// Whether a symbol is an operation with a glyph, rather than a name or literal
+fn is_operation(symbol: &Symbol) -> bool {
+    !matches!(
+        symbol,
+        Symbol::Identifier(_)
+            | Symbol::StringLiteral(_)
+            | Symbol::NumberLiteral(_)
+            | Symbol::IntegerLiteral(_)
+            | Symbol::DecimalLiteral(_)
+            | Symbol::BytesLiteral(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dap::read_message;
+    use std::io::Cursor;
+
+    const URI: &str = "file:///tmp/order.ae";
+
+    /// Frame messages as an editor would, giving requests an id
+    fn session(messages: &[Json]) -> Vec<Json> {
+        let mut input = Vec::new();
+        for (id, message) in messages.iter().enumerate() {
+            let mut message = message.clone();
+            message["jsonrpc"] = json!("2.0");
+            let method = message["method"].as_str().unwrap();
+            if !(method.starts_with("textDocument/did") || method == "exit") {
+                message["id"] = json!(id);
+            }
+            write_message(&mut input, &message).unwrap();
+        }
+        serve(input)
+    }
+
+    /// Run the server over framed input and collect what it writes back
+    fn serve(input: Vec<u8>) -> Vec<Json> {
+        let mut output = Vec::new();
+        LspServer::new(Cursor::new(input), &mut output).run().unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut replies = Vec::new();
+        while let Some(reply) = read_message(&mut reader).unwrap() {
+            replies.push(reply);
+        }
+        replies
+    }
+
+    fn open(text: &str) -> Json {
+        json!({ "method": "textDocument/didOpen", "params": { "textDocument": { "uri": URI, "languageId": "aether", "version": 1, "text": text } } })
+    }
+
+    fn at(method: &str, line: u32, character: u32) -> Json {
+        json!({ "method": method, "params": { "textDocument": { "uri": URI }, "position": { "line": line, "character": character } } })
+    }
+
+    #[test]
+    fn test_diagnostics_follow_changes() {
+        let replies = session(&[
+            open("5 ▷ x ⨠\n\"a\" ▷ s ⨠\ns * 2"),
+            json!({ "method": "textDocument/didChange", "params": { "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": "5 ▷ x ⨠ x +" }] } }),
+            json!({ "method": "textDocument/didChange", "params": { "textDocument": { "uri": URI, "version": 3 }, "contentChanges": [{ "text": "📤 \"ok\" ⨠ 🎃" }] } }),
+            json!({ "method": "textDocument/didClose", "params": { "textDocument": { "uri": URI } } }),
+        ]);
+        let diagnostics: Vec<&Json> = replies.iter().map(|r| &r["params"]["diagnostics"]).collect();
+        assert_eq!(diagnostics.len(), 4);
+
+        // The type error covers the `⨠` step it is in
+        assert_eq!(diagnostics[0][0]["message"], "* expects number, found string");
+        assert_eq!(diagnostics[0][0]["range"], json!({ "start": { "line": 2, "character": 0 }, "end": { "line": 2, "character": 5 } }));
+        assert!(diagnostics[1][0]["message"].as_str().unwrap().starts_with("Parser error"));
+        // 📤 is two UTF-16 code units
+        assert!(diagnostics[2][0]["message"].as_str().unwrap().starts_with("Lexer error"));
+        assert_eq!(diagnostics[2][0]["range"]["start"], json!({ "line": 0, "character": 10 }));
+        assert_eq!(diagnostics[3], &json!([]));
+    }
+
+    #[test]
+    fn test_look_alikes_are_warnings() {
+        let replies = session(&[open("5 ▷ Јx ⨠\n📤5")]);
+        let diagnostics = &replies[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 2);
+        assert_eq!(diagnostics[0]["range"], json!({ "start": { "line": 0, "character": 4 }, "end": { "line": 0, "character": 6 } }));
+    }
+
+    #[test]
+    fn test_hover_completion_and_definition() {
+        let replies = session(&[
+            json!({ "method": "initialize", "params": { "capabilities": {} } }),
+            open("[1, 2] ▷ items ⨠\n♻3: (📏items ▷ n) ⨠\n📤n"),
+            at("textDocument/hover", 1, 0),
+            at("textDocument/hover", 2, 2),
+            at("textDocument/completion", 2, 2),
+            at("textDocument/definition", 2, 2),
+            at("textDocument/definition", 1, 8),
+            at("textDocument/definition", 1, 1),
+            json!({ "method": "textDocument/formatting", "params": { "textDocument": { "uri": URI } } }),
+            json!({ "method": "shutdown" }),
+            json!({ "method": "exit" }),
+        ]);
+        let result = |id: usize| &replies.iter().find(|r| r["id"] == id).unwrap()["result"];
+
+        assert_eq!(result(0)["capabilities"]["hoverProvider"], true);
+        let hover = result(2)["contents"]["value"].as_str().unwrap();
+        assert!(hover.starts_with("`♻` **retry**: Retry on failure\n\n```\nretry up to 3 times:"), "{}", hover);
+        assert_eq!(result(2)["range"]["end"], json!({ "line": 1, "character": 1 }));
+        assert!(result(3)["contents"]["value"].as_str().unwrap().starts_with("`n`: variable"));
+
+        let items = result(4)["items"].as_array().unwrap();
+        let retry = items.iter().find(|item| item["label"] == "retry").unwrap();
+        assert_eq!(retry["insertText"], "♻");
+        assert!(items.iter().any(|item| item["label"] == "items" && item["kind"] == 6));
+
+        assert_eq!(result(5)["range"]["start"], json!({ "line": 1, "character": 15 }));
+        assert_eq!(result(6)["range"]["start"], json!({ "line": 0, "character": 9 }));
+        assert_eq!(*result(7), Json::Null);
+        let unsupported = replies.iter().find(|r| r["id"] == 8).unwrap();
+        assert_eq!(unsupported["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(*result(9), Json::Null);
+    }
+
+    #[test]
+    fn test_bad_json_gets_a_parse_error_and_serving_continues() {
+        let mut input = b"Content-Length: 5\r\n\r\n{1:2}".to_vec();
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" })).unwrap();
+        let replies = serve(input);
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["id"], Json::Null);
+        assert_eq!(replies[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(replies[1], json!({ "jsonrpc": "2.0", "id": 1, "result": null }));
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let document = Document::new("ƒ total: 📥 ▷ n:N ⨠\n\"a\" ▷ s");
+        let tokens = document.semantic_tokens();
+        let data: Vec<u64> = tokens["data"].as_array().unwrap().iter().map(|n| n.as_u64().unwrap()).collect();
+        let kinds: Vec<u64> = data.chunks(5).map(|t| t[3]).collect();
+        use TokenKind::*;
+        let expected = [Keyword, Function, Operator, Operator, Variable, Type, Operator, String, Operator, Variable];
+        assert_eq!(kinds, expected.iter().map(|k| *k as u64).collect::<Vec<_>>());
+        // `📥` on line 0 starts 9 UTF-16 units in and is two units long
+        assert_eq!(&data[10..13], &[0, 7, 2]);
+        // `"a"` starts the next line
+        assert_eq!(&data[35..38], &[1, 0, 3]);
+    }
+}
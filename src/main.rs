@@ -1,10 +1,16 @@
 //! Aether CLI - Command line interface for the Aether programming language
 
-use aether::{Lexer, Parser, Runtime, Compiler, VM, BytecodeProgram, Explainer, LANGUAGE_NAME, VERSION};
-use glob;
+use aether::optimizer::OptLevel;
+use aether::projector::{self, Projector};
+use aether::explainer::{Format, Lang};
+use aether::stats::{self, Size};
+use aether::tokenizer::{Bpe, ByteEstimate, Tokenizer};
+use aether::transpiler::{self, Target};
+use aether::{ascii, bytes, checker, crypto, formatter, normalize, Limits, Lexer, Parser, Runtime, Compiler, VM, BytecodeProgram, Explainer, LANGUAGE_NAME, VERSION};
 use std::env;
 use std::fs;
 use std::io::{BufReader, BufWriter};
+use std::path::Path;
 use std::process;
 
 fn main() {
@@ -25,15 +31,19 @@ fn main() {
             print_help();
         }
         "run" => {
+            let (args, limits) = split_limits(&args);
+            let (args, ascii) = split_flag(&args, "--ascii");
             if args.len() < 3 {
                 eprintln!("Error: No input file specified");
                 print_usage();
                 process::exit(1);
             }
             let filename = &args[2];
-            run_file(filename);
+            run_file(filename, limits, ascii);
         }
         "compile" => {
+            let (args, opt_level) = split_opt_level(&args);
+            let (args, sign_key) = split_option(&args, "--sign");
             if args.len() < 3 {
                 eprintln!("Error: No input file specified");
                 print_usage();
@@ -46,18 +56,90 @@ fn main() {
                 // Replace .ae extension with .aeb
                 input_file.replace(".ae", ".aeb")
             };
-            compile_file(input_file, &output_file);
+            compile_file(input_file, &output_file, opt_level, sign_key.as_deref());
         }
         "exec" => {
+            let (args, limits) = split_limits(&args);
+            let (args, verify_key) = split_option(&args, "--verify");
             if args.len() < 3 {
                 eprintln!("Error: No bytecode file specified");
                 print_usage();
                 process::exit(1);
             }
             let filename = &args[2];
-            exec_bytecode(filename);
+            exec_bytecode(filename, verify_key.as_deref(), limits);
+        }
+        "keygen" => {
+            let (args, force) = split_flag(&args, "--force");
+            if args.len() < 3 {
+                eprintln!("Error: No key name specified");
+                print_usage();
+                process::exit(1);
+            }
+            generate_keys(&args[2], force);
+        }
+        "disasm" => {
+            let (args, opt_level) = split_opt_level(&args);
+            if args.len() < 3 {
+                eprintln!("Error: No input file specified");
+                print_usage();
+                process::exit(1);
+            }
+            disassemble_file(&args[2], opt_level);
+        }
+        "debug" => {
+            let (args, limits) = split_limits(&args);
+            if args.len() < 3 {
+                eprintln!("Error: No input file specified");
+                print_usage();
+                process::exit(1);
+            }
+            debug_file(&args[2], limits);
+        }
+        "dap" => {
+            let (_, limits) = split_limits(&args);
+            let stdin = std::io::stdin();
+            let mut server = aether::dap::DapServer::new(stdin.lock(), std::io::stdout());
+            server.set_limits(limits);
+            if let Err(err) = server.run() {
+                eprintln!("DAP error: {}", err);
+                process::exit(1);
+            }
+        }
+        "lsp" => {
+            let stdin = std::io::stdin();
+            let mut server = aether::lsp::LspServer::new(stdin.lock(), std::io::stdout());
+            if let Err(err) = server.run() {
+                eprintln!("LSP error: {}", err);
+                process::exit(1);
+            }
+        }
+        "asm" => {
+            if args.len() < 3 {
+                eprintln!("Error: No input file specified");
+                print_usage();
+                process::exit(1);
+            }
+            let input_file = &args[2];
+            let output_file = if args.len() >= 4 {
+                args[3].clone()
+            } else {
+                Path::new(input_file).with_extension("aeb").to_string_lossy().to_string()
+            };
+            assemble_file(input_file, &output_file);
         }
         "explain" => {
+            let (args, format) = split_option(&args, "--format");
+            let (args, lang) = split_option(&args, "--lang");
+            let format = match format.as_deref().map(Format::from_name) {
+                None => Format::Text,
+                Some(Some(format)) => format,
+                Some(None) => {
+                    eprintln!("Error: Unknown format '{}' (expected text, md or json)", format.unwrap_or_default());
+                    process::exit(1);
+                }
+            };
+            let lang = lang_option(lang);
             if args.len() < 3 {
                 eprintln!("Error: No input file(s) specified");
                 print_usage();
@@ -65,10 +147,98 @@ fn main() {
             }
             // Collect all file patterns from arguments
             let patterns: Vec<String> = args[2..].to_vec();
-            explain_files(&patterns);
+            explain_files(&patterns, format, lang);
+        }
+        "unexplain" => {
+            let (args, lang) = split_option(&args, "--lang");
+            let lang = lang_option(lang);
+            if args.len() != 3 {
+                eprintln!("Error: Expected one input file, or - for stdin");
+                print_usage();
+                process::exit(1);
+            }
+            unexplain_file(&args[2], lang);
+        }
+        "check" => {
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            let patterns: Vec<String> = args[2..].to_vec();
+            check_files(&patterns);
+        }
+        "fmt" => {
+            let (args, check) = split_flag(&args, "--check");
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            format_files(&args[2..], check);
+        }
+        "asciify" | "glyphify" => {
+            let (args, write) = split_flag(&args, "--write");
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            convert_files(&args[2..], command == "asciify", write);
+        }
+        "migrate" => {
+            let (args, write) = split_flag(&args, "--write");
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            migrate_files(&args[2..], write);
+        }
+        "stats" => {
+            let (args, tokenizer) = split_option(&args, "--tokenizer");
+            let tokenizer: Box<dyn Tokenizer> = match tokenizer.as_deref() {
+                None | Some("bpe") => Box::new(Bpe::bundled()),
+                Some("bytes") => Box::new(ByteEstimate),
+                Some(other) => {
+                    eprintln!("Error: Unknown tokenizer '{}' (expected bpe or bytes)", other);
+                    process::exit(1);
+                }
+            };
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            stats_files(&args[2..], tokenizer.as_ref());
+        }
+        "transpile" => {
+            let (args, to) = split_option(&args, "--to");
+            let (args, write) = split_flag(&args, "--write");
+            let target = match to.as_deref().map(Target::from_name) {
+                None => Target::Python,
+                Some(Some(target)) => target,
+                Some(None) => {
+                    eprintln!("Error: Unknown target '{}' (expected py or js)", to.unwrap_or_default());
+                    process::exit(1);
+                }
+            };
+            if args.len() < 3 {
+                eprintln!("Error: No input file(s) specified");
+                print_usage();
+                process::exit(1);
+            }
+            transpile_files(&args[2..], target, write);
         }
         "symbols" => {
-            print_symbols();
+            match args.get(2).map(String::as_str) {
+                format @ (None | Some("--json") | Some("--markdown")) => print_symbols(format),
+                Some(other) => {
+                    eprintln!("Error: Unknown option '{}'", other);
+                    print_usage();
+                    process::exit(1);
+                }
+            }
         }
         _ => {
             eprintln!("Error: Unknown command '{}'", command);
@@ -83,10 +253,46 @@ fn print_usage() {
     println!();
     println!("Commands:");
     println!("  run <file>              Run an Aether source file (.ae)");
+    println!("      [--max-steps <n>]   Stop after n steps (default 10000000, or none)");
+    println!("      [--timeout <time>]  Stop after a wall-clock time such as 500ms or 2s");
+    println!("      [--max-stack <n>]   Limit stack depth (default 1000000, or none)");
+    println!("      [--max-collection <n>] Limit array and object sizes");
+    println!("      [--max-string <n>]  Limit string and byte string lengths");
+    println!("      [--ascii]           Accept ASCII spellings such as fn and |> for symbols");
     println!("  compile <file> [out]    Compile .ae source to .aeb bytecode");
+    println!("          [-O0|-O1|-O2]   Optimization level (default -O0)");
+    println!("          [--sign <key>]  Sign the bytecode with a secret key file");
     println!("  exec <file>             Execute .aeb bytecode file");
+    println!("       [--verify <pub>]   Refuse bytecode not signed by a public key file");
+    println!("       [limits]           Same execution limits as run");
+    println!("  keygen <name>           Create a signing key pair <name>.key and <name>.pub");
+    println!("         [--force]        Overwrite an existing key pair");
+    println!("  disasm <file> [-O1|-O2] Disassemble .aeb bytecode (or .ae source, with line info)");
+    println!("  debug <file>            Step through a .ae or .aeb program with breakpoints");
+    println!("  dap                     Serve the Debug Adapter Protocol on stdin/stdout");
+    println!("  lsp                     Serve the Language Server Protocol on stdin/stdout");
+    println!("  asm <file> [out]        Assemble .aes bytecode assembly to .aeb");
     println!("  explain <file(s)>       Explain .ae file(s) in human-readable format");
+    println!("          [--format <f>]  Write text (default), md with the source, or json with the AST");
+    println!("          [--lang <l>]    Explain in en (default) or zh");
+    println!("  unexplain <file|->     Turn an explanation back into Aether code");
+    println!("            [--lang <l>]  Read an explanation in en (default) or zh");
+    println!("  check <file(s)>         Type-check .ae file(s) without running them");
+    println!("  fmt <file(s)>           Format .ae file(s) in place");
+    println!("      [--check]           List files that need formatting instead, failing if any do");
+    println!("  asciify <file(s)>       Rewrite symbols with their ASCII spellings");
+    println!("  glyphify <file(s)>      Rewrite ASCII spellings as symbols");
+    println!("           [--write]      Convert the files in place instead of printing them");
+    println!("  migrate <file(s)>       Rewrite glyphs in their canonical form, 🛡️ (verify) as 🔏");
+    println!("          [--write]       Migrate the files in place instead of printing them");
+    println!("  stats <file(s)>         Count graphemes, code points, bytes and estimated tokens");
+    println!("        [--tokenizer <t>] Estimate tokens with bpe (default) or bytes (four a token)");
+    println!("  transpile <file(s)>     Transpile .ae file(s) to runnable Python or JavaScript");
+    println!("            [--to <t>]    Write py (default) or js");
+    println!("            [--write]     Write <file>.py or <file>.js next to each file instead of printing");
     println!("  symbols                 Display symbol reference");
+    println!("          [--json]        Print the symbol table as JSON");
+    println!("          [--markdown]    Print the symbol table as Markdown");
     println!("  version                 Display version information");
     println!("  help                    Display this help message");
 }
@@ -102,154 +308,59 @@ fn print_help() {
     println!("Examples:");
     println!("  aether run program.ae              # Run an Aether program");
     println!("  aether compile program.ae          # Compile to program.aeb");
+    println!("  aether compile program.ae -O2      # Compile with all optimizations");
     println!("  aether exec program.aeb            # Execute bytecode");
+    println!("  aether run untrusted.ae --max-steps 100000 --timeout 2s");
+    println!("  aether keygen deploy               # Create deploy.key and deploy.pub");
+    println!("  aether compile app.ae --sign deploy.key");
+    println!("  aether exec app.aeb --verify deploy.pub");
+    println!("  aether disasm program.aeb          # Show bytecode as assembly");
+    println!("  aether debug program.ae            # Debug interactively");
+    println!("  aether dap                         # Debug from an editor");
+    println!("  aether lsp                         # Edit with diagnostics, hover and completion");
+    println!("  aether asm test.aes                # Assemble to test.aeb");
     println!("  aether explain program.ae          # Explain program in readable form");
     println!("  aether explain examples/*.ae       # Explain multiple programs");
+    println!("  aether explain --format md --lang zh program.ae");
+    println!("  aether explain program.ae | aether unexplain - # Back to glyphs");
+    println!("  aether check examples/*.ae         # Type-check programs");
+    println!("  aether fmt examples/*.ae           # Format programs");
+    println!("  aether fmt --check examples/*.ae   # Fail if any program needs formatting");
+    println!("  aether asciify program.ae          # Print program.ae in ASCII");
+    println!("  aether glyphify --write program.ae # Turn ASCII spellings back into symbols");
+    println!("  aether stats examples/*.ae         # Compare token counts with the explanations");
+    println!("  aether transpile --to js program.ae # Print program.ae as JavaScript");
     println!("  aether symbols                     # View symbol reference");
 }
 
-fn print_symbols() {
-    use aether::Symbol;
-
-    println!("{} - Symbol Reference", LANGUAGE_NAME);
-    println!("{}", "=".repeat(60));
-    println!();
-
-    let symbols = vec![
-        // Core symbols
-        (Symbol::Function, "ƒ"),
-        (Symbol::Lambda, "λ"),
-        (Symbol::Input, "📥"),
-        (Symbol::Output, "📤"),
-        (Symbol::Persist, "💾"),
-        (Symbol::Query, "🔍"),
-        (Symbol::Pipe, "⇢"),
-        (Symbol::PipeInto, "▷"),
-        (Symbol::JsonParse, "J"),
-        (Symbol::Guard, "⁇"),
-        (Symbol::Halt, "🛑"),
-        (Symbol::Success, "✓"),
-        (Symbol::Sequence, "⨠"),
-        (Symbol::If, "◇"),
-        (Symbol::Or, "⊕"),
-        (Symbol::And, "⊗"),
-        (Symbol::Not, "¬"),
-        (Symbol::Array, "🗂"),
-        (Symbol::Map, "🗄"),
-        (Symbol::Empty, "∅"),
-        (Symbol::HttpRequest, "🌐"),
-        (Symbol::Register, "®"),
-    ];
-
-    for (symbol, glyph) in symbols {
-        println!("  {}  -  {}", glyph, symbol.description());
-    }
+/// List the symbol table, as text or, with `format`, as JSON or Markdown
+fn print_symbols(format: Option<&str>) {
+    use aether::symbols::{self, SYMBOLS};
 
-    println!();
-    println!("--- v1.1 Symbols ---");
-    println!();
-    
-    let v11_symbols = vec![
-        // Control Flow & Iteration
-        (Symbol::Loop, "↻"),
-        (Symbol::ForEach, "∀"),
-        (Symbol::Filter, "∃"),
-        (Symbol::Reduce, "∑"),
-        (Symbol::Try, "🛡"),
-        (Symbol::Retry, "♻"),
-        // Concurrency & Async
-        (Symbol::Async, "⚡"),
-        (Symbol::Await, "⏳"),
-        (Symbol::Thread, "🧵"),
-        (Symbol::Lock, "🔒"),
-        (Symbol::Emit, "📡"),
-        (Symbol::Watch, "👁"),
-        // Data Manipulation
-        (Symbol::Split, "✂"),
-        (Symbol::Join, "🔗"),
-        (Symbol::Regex, "✱"),
-        (Symbol::Equal, "≡"),
-        (Symbol::NotEqual, "≠"),
-        (Symbol::Immutable, "🧊"),
-        // System & Environment
-        (Symbol::Import, "🧩"),
-        (Symbol::Auth, "🔑"),
-        (Symbol::DateTime, "📅"),
-        (Symbol::Random, "🎲"),
-        (Symbol::Log, "🪵"),
-    ];
-
-    for (symbol, glyph) in v11_symbols {
-        println!("  {}  -  {}", glyph, symbol.description());
+    match format {
+        Some("--json") => {
+            println!("{}", serde_json::to_string_pretty(&symbols::reference_json()).unwrap_or_default());
+            return;
+        }
+        Some("--markdown") => {
+            println!("{}\n\n{}\n{}", symbols::GENERATED_START, symbols::reference_markdown(), symbols::GENERATED_END);
+            return;
+        }
+        _ => {}
     }
 
-    println!();
-    println!("--- v1.2 Symbols ---");
-    println!();
-    
-    let v12_symbols = vec![
-        // Testing & Debugging
-        (Symbol::Test, "🧪"),
-        (Symbol::Assert, "⚖️"),
-        (Symbol::Mock, "🎭"),
-        (Symbol::Benchmark, "⏱️"),
-        (Symbol::Debug, "🐛"),
-        // Security & Crypto
-        (Symbol::Encrypt, "🔐"),
-        (Symbol::Decrypt, "🔓"),
-        (Symbol::Hash, "#️⃣"),
-        (Symbol::Sign, "✍️"),
-        (Symbol::Verify, "🛡️"),
-        // Math & Science
-        (Symbol::Power, "↑"),
-        (Symbol::Root, "√"),
-        (Symbol::Approx, "≈"),
-        (Symbol::Infinity, "∞"),
-        (Symbol::Delta, "∆"),
-    ];
-
-    for (symbol, glyph) in v12_symbols {
-        println!("  {}  -  {}", glyph, symbol.description());
-    }
+    println!("{} - Symbol Reference", LANGUAGE_NAME);
+    println!("{}", "=".repeat(60));
 
-    println!();
-    println!("--- v1.3 Symbols ---");
-    println!();
-    
-    let v13_symbols = vec![
-        // File System
-        (Symbol::File, "📄"),
-        (Symbol::Dir, "📂"),
-        (Symbol::Path, "📍"),
-        (Symbol::Read, "📖"),
-        (Symbol::Write, "🖊️"),
-        (Symbol::Append, "🖇️"),
-        (Symbol::Delete, "🗑️"),
-        (Symbol::Perm, "🛂"),
-        // Streams & Buffers
-        (Symbol::Stream, "🌊"),
-        (Symbol::Buffer, "🧱"),
-        (Symbol::Flush, "🌬️"),
-        (Symbol::Eof, "🔚"),
-        (Symbol::Skip, "⏭️"),
-        // Networking
-        (Symbol::Socket, "🔌"),
-        (Symbol::Listen, "👂"),
-        (Symbol::Connect, "📞"),
-        (Symbol::Port, "🚪"),
-        (Symbol::Packet, "📦"),
-        (Symbol::Handshake, "🤝"),
-        // Process & OS
-        (Symbol::Process, "⚙️"),
-        (Symbol::Shell, "🐚"),
-        (Symbol::Env, "🌍"),
-        (Symbol::Memory, "🐏"),
-        (Symbol::Exit, "👋"),
-        (Symbol::Signal, "📶"),
-    ];
-
-    for (symbol, glyph) in v13_symbols {
-        println!("  {}  -  {}", glyph, symbol.description());
+    let mut category = None;
+    for info in SYMBOLS {
+        if category != Some(info.category) {
+            category = Some(info.category);
+            println!();
+            println!("--- {} ---", info.category.title());
+            println!();
+        }
+        println!("  {:<4} {:<14} {}", info.glyph, info.ascii, info.description);
     }
 
     println!();
@@ -268,13 +379,13 @@ fn print_symbols() {
     println!("  ƒ log: 📥msg ⨠ 📄📍\"/var/log/app.log\" ▷ f ⨠ ◇(f.size > 1GB): 🐚\"mv /var/log/app.log /var/log/app.old\" ⨠ msg ⇢ 🖇️f");
     println!();
     println!("TCP Echo Server (v1.3):");
-    println!("  🔌TCP ⨠ 👂8080 ⨠ ↻: (⏳👂 ▷ conn ⨠ ⚡(🛡(conn ⇢ 🌊 ▷ s ⨠ ↻(s ≠ 🔚): (s ⇢ 📖 ▷ data ⨠ data ⇢ 🖊️s ⨠ s ⇢ 🌬️)) ⨠ conn ⇢ 👋))");
+    println!("  🔌TCP ⨠ 👂8080 ▷ server ⨠ ↻(server): (⏳👂 ▷ conn ⨠ 🛡(conn ⇢ 🌊 ▷ s ⨠ ↻(s ≠ 🔚): (s ⇢ 📖 ▷ data ⨠ data ⇢ 🖊️s ⨠ s ⇢ 🌬️)) ⨠ 🗑️conn)");
     println!();
     println!("Environment Variable & Shell (v1.3):");
     println!("  🌍\"PATH\" ▷ path ⨠ 🐚\"ls -la\" ▷ output ⨠ 📤output");
 }
 
-fn run_file(filename: &str) {
+fn run_file(filename: &str, limits: Limits, ascii: bool) {
     let source = match fs::read_to_string(filename) {
         Ok(content) => content,
         Err(err) => {
@@ -282,12 +393,14 @@ fn run_file(filename: &str) {
             process::exit(1);
         }
     };
+    refuse_legacy_verify(&source);
 
     println!("Running Aether program: {}", filename);
     println!("{}", "-".repeat(60));
 
     // Lexer
     let mut lexer = Lexer::new(source.clone());
+    lexer.set_ascii_aliases(ascii);
     let tokens = match lexer.tokenize() {
         Ok(t) => t,
         Err(err) => {
@@ -312,6 +425,7 @@ fn run_file(filename: &str) {
 
     // Runtime
     let mut runtime = Runtime::new();
+    runtime.set_limits(limits);
     match runtime.execute(ast) {
         Ok(result) => {
             println!("{}", "-".repeat(60));
@@ -326,7 +440,7 @@ fn run_file(filename: &str) {
     }
 }
 
-fn compile_file(input_file: &str, output_file: &str) {
+fn compile_file(input_file: &str, output_file: &str, opt_level: OptLevel, sign_key: Option<&str>) {
     println!("Compiling {} to {}...", input_file, output_file);
     println!("{}", "-".repeat(60));
 
@@ -338,9 +452,10 @@ fn compile_file(input_file: &str, output_file: &str) {
             process::exit(1);
         }
     };
+    refuse_legacy_verify(&source);
 
     // Lexer
-    let mut lexer = Lexer::new(source);
+    let mut lexer = Lexer::new(source.clone());
     let tokens = match lexer.tokenize() {
         Ok(t) => t,
         Err(err) => {
@@ -361,9 +476,10 @@ fn compile_file(input_file: &str, output_file: &str) {
     };
     println!("✓ Parser: {} AST nodes generated", ast.len());
 
-    // Compiler
+    // Compiler, keeping source spans and the source hash as debug info
     let mut compiler = Compiler::new();
-    let bytecode = match compiler.compile(ast) {
+    compiler.set_opt_level(opt_level);
+    let bytecode = match compiler.compile_source(&source) {
         Ok(b) => b,
         Err(err) => {
             eprintln!("Compiler error: {}", err);
@@ -371,6 +487,9 @@ fn compile_file(input_file: &str, output_file: &str) {
         }
     };
     println!("✓ Compiler: {} bytes of bytecode generated", bytecode.code.len());
+    if opt_level != OptLevel::O0 {
+        println!("  - Optimization: {:?}", opt_level);
+    }
     println!("  - Constants: {}", bytecode.constants.len());
 
     // Write bytecode to file
@@ -383,7 +502,15 @@ fn compile_file(input_file: &str, output_file: &str) {
     };
 
     let mut writer = BufWriter::new(file);
-    match bytecode.serialize(&mut writer) {
+    let written = match sign_key {
+        Some(path) => {
+            let secret_key = read_key(path, "secret");
+            println!("✓ Signed with key {}", bytes::hex(&crypto::public_key(&secret_key)));
+            bytecode.serialize_signed(&mut writer, &secret_key)
+        }
+        None => bytecode.serialize(&mut writer),
+    };
+    match written {
         Ok(_) => {
             println!("{}", "-".repeat(60));
             println!("✓ Compilation successful!");
@@ -396,7 +523,7 @@ fn compile_file(input_file: &str, output_file: &str) {
     }
 }
 
-fn exec_bytecode(filename: &str) {
+fn exec_bytecode(filename: &str, verify_key: Option<&str>, limits: Limits) {
     println!("Executing bytecode: {}", filename);
     println!("{}", "-".repeat(60));
 
@@ -421,10 +548,28 @@ fn exec_bytecode(filename: &str) {
     println!("✓ Bytecode loaded:");
     println!("  - Code size: {} bytes", bytecode.code.len());
     println!("  - Constants: {}", bytecode.constants.len());
+    if let Some(version) = &bytecode.compiler_version {
+        println!("  - Compiled by: Aether {}", version);
+    }
+    if let Some(path) = verify_key {
+        let trusted = read_key(path, "public");
+        match bytecode.signed_by {
+            Some(signer) if signer == trusted => println!("  - Signature: verified ({})", path),
+            Some(signer) => {
+                eprintln!("Error: '{}' is signed by an untrusted key {}", filename, bytes::hex(&signer));
+                process::exit(1);
+            }
+            None => {
+                eprintln!("Error: '{}' is not signed", filename);
+                process::exit(1);
+            }
+        }
+    }
     println!("{}", "-".repeat(60));
 
     // Execute with VM
     let mut vm = VM::new(bytecode);
+    vm.set_limits(limits);
     match vm.execute() {
         Ok(result) => {
             println!("{}", "-".repeat(60));
@@ -439,10 +584,176 @@ fn exec_bytecode(filename: &str) {
     }
 }
 
-fn explain_files(patterns: &[String]) {
+/// Write a new secret key to `<name>.key` and its public key to `<name>.pub`
+///
+/// An existing pair is kept unless `force` is set, since the secret key may
+/// be the only copy.
+fn generate_keys(name: &str, force: bool) {
+    let secret_key = crypto::generate_secret_key();
+    let secret_file = format!("{}.key", name);
+    let public_file = format!("{}.pub", name);
+
+    if !force {
+        if let Some(existing) = [&secret_file, &public_file].into_iter().find(|path| Path::new(path).exists()) {
+            eprintln!("Error: Key file '{}' already exists; pass --force to replace it", existing);
+            process::exit(1);
+        }
+    }
+
+    if let Err(err) = write_secret(&secret_file, &bytes::hex(&secret_key)) {
+        eprintln!("Error writing key file '{}': {}", secret_file, err);
+        process::exit(1);
+    }
+    if let Err(err) = fs::write(&public_file, bytes::hex(&crypto::public_key(&secret_key)) + "\n") {
+        eprintln!("Error writing key file '{}': {}", public_file, err);
+        process::exit(1);
+    }
+
+    println!("✓ Secret key: {} (keep this private, use with compile --sign)", secret_file);
+    println!("✓ Public key: {} (use with exec --verify)", public_file);
+}
+
+/// Create a file only its owner can read
+fn write_secret(path: &str, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    writeln!(options.open(path)?, "{}", contents)
+}
+
+/// Read a 32-byte key stored as hex, as written by `aether keygen`
+fn read_key(path: &str, kind: &str) -> [u8; 32] {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("Error reading {} key file '{}': {}", kind, path, err);
+            process::exit(1);
+        }
+    };
+    match aether::bytes::decode(text.trim(), "hex").ok().and_then(|key| key.try_into().ok()) {
+        Some(key) => key,
+        None => {
+            eprintln!("Error: '{}' is not a {} key (expected 64 hex digits)", path, kind);
+            process::exit(1);
+        }
+    }
+}
+
+/// Remove `-O0`, `-O1` or `-O2` from the arguments, returning the rest and the level
+fn split_opt_level(args: &[String]) -> (Vec<String>, OptLevel) {
+    let mut level = OptLevel::O0;
+    let mut rest = Vec::new();
+    for arg in args {
+        if arg.starts_with("-O") {
+            level = OptLevel::from_flag(arg).unwrap_or_else(|| {
+                eprintln!("Error: Unknown optimization level '{}' (use -O0, -O1 or -O2)", arg);
+                process::exit(1);
+            });
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (rest, level)
+}
+
+/// Remove `flag <value>` from the arguments, returning the rest and the value
+fn split_option(args: &[String], flag: &str) -> (Vec<String>, Option<String>) {
+    let mut rest = Vec::new();
+    let mut value = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            match iter.next() {
+                Some(v) => value = Some(v.clone()),
+                None => {
+                    eprintln!("Error: {} needs a value", flag);
+                    process::exit(1);
+                }
+            }
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (rest, value)
+}
+
+/// Remove `flag` from the arguments, returning the rest and whether it was there
+fn split_flag(args: &[String], flag: &str) -> (Vec<String>, bool) {
+    let rest: Vec<String> = args.iter().filter(|arg| *arg != flag).cloned().collect();
+    let found = rest.len() < args.len();
+    (rest, found)
+}
+
+/// Remove the execution limit options from the arguments, returning the rest
+/// and the limits, starting from the defaults
+fn split_limits(args: &[String]) -> (Vec<String>, Limits) {
+    let mut limits = Limits::default();
+    let (args, steps) = split_option(args, "--max-steps");
+    let (args, timeout) = split_option(&args, "--timeout");
+    let (args, stack) = split_option(&args, "--max-stack");
+    let (args, collection) = split_option(&args, "--max-collection");
+    let (args, string) = split_option(&args, "--max-string");
+
+    if let Some(steps) = steps {
+        limits.max_steps = parse_limit("--max-steps", &steps);
+    }
+    if let Some(timeout) = timeout {
+        limits.timeout = Some(parse_duration(&timeout).unwrap_or_else(|| {
+            eprintln!("Error: Invalid --timeout '{}' (use e.g. 500ms, 2s or 1m)", timeout);
+            process::exit(1);
+        }));
+    }
+    if let Some(stack) = stack {
+        limits.max_stack_depth = parse_limit("--max-stack", &stack);
+    }
+    if let Some(collection) = collection {
+        limits.max_collection_size = parse_limit("--max-collection", &collection);
+    }
+    if let Some(string) = string {
+        limits.max_string_length = parse_limit("--max-string", &string);
+    }
+    (args, limits)
+}
+
+/// Parse a limit count, where `none` removes the limit
+fn parse_limit<T: std::str::FromStr>(flag: &str, value: &str) -> Option<T> {
+    if value == "none" {
+        return None;
+    }
+    match value.parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            eprintln!("Error: {} needs a number or 'none', got '{}'", flag, value);
+            process::exit(1);
+        }
+    }
+}
+
+/// Parse a duration such as `250ms`, `2s`, `1.5s` or `1m`
+fn parse_duration(text: &str) -> Option<std::time::Duration> {
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "s" | "" => number,
+        "m" => number * 60.0,
+        _ => return None,
+    };
+    std::time::Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Expand file arguments and glob patterns into the list of .ae files they name
+fn expand_patterns(patterns: &[String]) -> Vec<String> {
     use std::path::Path;
     
-    let mut files_to_explain = Vec::new();
+    let mut files = Vec::new();
     
     // Process each pattern
     for pattern in patterns {
@@ -455,7 +766,7 @@ fn explain_files(patterns: &[String]) {
                         match entry {
                             Ok(path) => {
                                 if path.extension().and_then(|s| s.to_str()) == Some("ae") {
-                                    files_to_explain.push(path.to_string_lossy().to_string());
+                                    files.push(path.to_string_lossy().to_string());
                                 }
                             }
                             Err(e) => eprintln!("Warning: Error reading path: {}", e),
@@ -471,7 +782,7 @@ fn explain_files(patterns: &[String]) {
             let path = Path::new(pattern);
             if path.exists() {
                 if path.extension().and_then(|s| s.to_str()) == Some("ae") {
-                    files_to_explain.push(pattern.clone());
+                    files.push(pattern.clone());
                 } else {
                     eprintln!("Warning: '{}' is not an .ae file, skipping", pattern);
                 }
@@ -481,22 +792,386 @@ fn explain_files(patterns: &[String]) {
         }
     }
     
+    files
+}
+
+fn disassemble_file(filename: &str, opt_level: OptLevel) {
+    let (bytecode, source) = load_program(filename, opt_level);
+    print!("{}", bytecode.disassemble(source.as_deref()));
+}
+
+/// Stop before running or compiling a source that still writes verify as
+/// `🛡️`, which now means try
+fn refuse_legacy_verify(source: &str) {
+    if let Err(err) = normalize::check_legacy_verify(source) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+/// Read a `.aeb` file, or compile a `.ae` file with line info, returning the
+/// program and the source when there is one
+fn load_program(filename: &str, opt_level: OptLevel) -> (BytecodeProgram, Option<String>) {
+    if filename.ends_with(".ae") {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                process::exit(1);
+            }
+        };
+        refuse_legacy_verify(&source);
+        let mut compiler = Compiler::new();
+        compiler.set_opt_level(opt_level);
+        match compiler.compile_source(&source) {
+            Ok(b) => (b, Some(source)),
+            Err(err) => {
+                eprintln!("Compile error: {}", err);
+                process::exit(1);
+            }
+        }
+    } else {
+        let file = match fs::File::open(filename) {
+            Ok(f) => f,
+            Err(err) => {
+                eprintln!("Error opening file '{}': {}", filename, err);
+                process::exit(1);
+            }
+        };
+        match BytecodeProgram::deserialize(&mut BufReader::new(file)) {
+            Ok(b) => (b, None),
+            Err(err) => {
+                eprintln!("Error reading bytecode: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Interactive debugger session over the VM, reading commands from stdin
+fn debug_file(filename: &str, limits: Limits) {
+    use aether::debugger::{Debugger, Stop};
+    use std::io::{BufRead, Write};
+
+    let (bytecode, source) = load_program(filename, OptLevel::O0);
+    if bytecode.lines.is_empty() {
+        println!("Note: '{}' has no line table; each step is one instruction", filename);
+    }
+    let source_lines: Vec<String> = source
+        .map(|s| s.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let mut vm = VM::new(bytecode);
+    vm.set_limits(limits);
+    let mut debugger = match Debugger::new(vm) {
+        Ok(debugger) => debugger,
+        Err(err) => {
+            eprintln!("VM error: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let show_location = |debugger: &Debugger| {
+        let pc = debugger.vm().pc();
+        match debugger.location() {
+            Some(span) => {
+                let text = source_lines.get((span.line as usize).wrapping_sub(1));
+                match text {
+                    Some(text) => println!("{:>4} | {}", span.line, span.excerpt(text)),
+                    None => println!("line {} (position {})", span.line, pc),
+                }
+            }
+            None => println!("position {}", pc),
+        }
+    };
+
+    println!("Debugging {} (type 'help' for commands)", filename);
+    show_location(&debugger);
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(debug) ");
+        std::io::stdout().flush().ok();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, rest) = match words.split_first() {
+            Some((command, rest)) => (*command, rest),
+            None => continue,
+        };
+
+        let stepping = matches!(command, "continue" | "c" | "step" | "s" | "next" | "n" | "finish" | "out" | "o");
+        if stepping && debugger.has_ended() {
+            println!("The program has ended; 'quit' to leave");
+            continue;
+        }
+
+        let stop = match command {
+            "continue" | "c" => Some(debugger.resume()),
+            "step" | "s" => Some(debugger.step_into()),
+            "next" | "n" => Some(debugger.step_over()),
+            "finish" | "out" | "o" => Some(debugger.step_out()),
+            "break" | "b" => {
+                match rest.first().and_then(|n| n.parse::<u32>().ok()) {
+                    Some(line) if debugger.add_breakpoint(line) => println!("Breakpoint set on line {}", line),
+                    Some(line) => println!("Breakpoint set on line {} (no statement starts there)", line),
+                    None => {
+                        let lines: Vec<String> = debugger.breakpoints().map(|l| l.to_string()).collect();
+                        println!("Breakpoints: {}", if lines.is_empty() { "none".to_string() } else { lines.join(", ") });
+                    }
+                }
+                None
+            }
+            "delete" | "d" => {
+                match rest.first().and_then(|n| n.parse::<u32>().ok()) {
+                    Some(line) if debugger.remove_breakpoint(line) => println!("Removed breakpoint on line {}", line),
+                    Some(line) => println!("No breakpoint on line {}", line),
+                    None => println!("Usage: delete <line>"),
+                }
+                None
+            }
+            "print" | "p" => {
+                for name in rest {
+                    match debugger.vm().get_variable(name) {
+                        Some(value) => println!("{} = {:?}", name, value),
+                        None => println!("{} is not set", name),
+                    }
+                }
+                None
+            }
+            "vars" | "v" => {
+                let mut names: Vec<&String> = debugger.vm().variables().keys().collect();
+                names.sort();
+                for name in names {
+                    println!("{} = {:?}", name, debugger.vm().variables()[name]);
+                }
+                None
+            }
+            "set" => {
+                match rest.split_first() {
+                    Some((name, value)) if !value.is_empty() => {
+                        let text = value.join(" ");
+                        let value = debugger.evaluate(&text);
+                        match value.and_then(|v| debugger.vm_mut().set_variable(name.to_string(), v)) {
+                            Ok(()) => println!("{} = {:?}", name, debugger.vm().variables()[*name]),
+                            Err(err) => println!("Error: {}", err),
+                        }
+                    }
+                    _ => println!("Usage: set <name> <value>"),
+                }
+                None
+            }
+            "stack" => {
+                let stack = debugger.vm().stack();
+                if stack.is_empty() {
+                    println!("(empty)");
+                }
+                for (depth, value) in stack.iter().rev().enumerate() {
+                    println!("#{} {:?}", depth, value);
+                }
+                None
+            }
+            "frames" | "bt" => {
+                println!("#0 position {} (current)", debugger.vm().pc());
+                for (depth, address) in debugger.vm().call_frames().iter().rev().enumerate() {
+                    println!("#{} returns to position {}", depth + 1, address);
+                }
+                None
+            }
+            "where" | "w" => {
+                show_location(&debugger);
+                None
+            }
+            "help" | "h" => {
+                print_debug_help();
+                None
+            }
+            "quit" | "q" => break,
+            other => {
+                println!("Unknown command '{}' (type 'help' for commands)", other);
+                None
+            }
+        };
+
+        match stop {
+            Some(Ok(Stop::Finished(value))) => {
+                println!("Program finished: {:?}", value);
+            }
+            Some(Ok(stop)) => {
+                match stop {
+                    Stop::Breakpoint(line) => println!("Breakpoint on line {}", line),
+                    Stop::DebugSymbol => println!("Stopped at 🐛"),
+                    _ => {}
+                }
+                show_location(&debugger);
+            }
+            Some(Err(err)) => {
+                println!("VM error: {}", err);
+                println!("The program has stopped; variables and the stack can still be inspected");
+            }
+            None => {}
+        }
+    }
+}
+
+fn print_debug_help() {
+    println!("Commands:");
+    println!("  break <line>, b      Stop before statements on a line (no line lists breakpoints)");
+    println!("  delete <line>, d     Remove a breakpoint");
+    println!("  continue, c          Run to the next breakpoint or 🐛");
+    println!("  step, s              Step into the next statement");
+    println!("  next, n              Step over to the next statement");
+    println!("  finish, o            Step out of the current call");
+    println!("  print <name>, p      Show variables");
+    println!("  vars, v              Show all variables");
+    println!("  set <name> <value>   Change a variable, e.g. set total 19.99");
+    println!("  stack                Show the VM stack, top first");
+    println!("  frames, bt           Show call frames");
+    println!("  where, w             Show the statement about to run");
+    println!("  quit, q              Leave the debugger");
+}
+
+fn assemble_file(input_file: &str, output_file: &str) {
+    // Writing the bytecode over its own assembly would lose the source
+    let same = |a: &str, b: &str| match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => Path::new(a) == Path::new(b),
+    };
+    if same(input_file, output_file) {
+        eprintln!("Error: Output file '{}' is the input file; name a different output", output_file);
+        process::exit(1);
+    }
+
+    let text = match fs::read_to_string(input_file) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Error reading file '{}': {}", input_file, err);
+            process::exit(1);
+        }
+    };
+    
+    let bytecode = match BytecodeProgram::assemble(&text) {
+        Ok(b) => b,
+        Err(err) => {
+            eprintln!("Assembler error: {}", err);
+            process::exit(1);
+        }
+    };
+    
+    let file = match fs::File::create(output_file) {
+        Ok(f) => f,
+        Err(err) => {
+            eprintln!("Error creating output file '{}': {}", output_file, err);
+            process::exit(1);
+        }
+    };
+    
+    if let Err(err) = bytecode.serialize(&mut BufWriter::new(file)) {
+        eprintln!("Error writing bytecode: {}", err);
+        process::exit(1);
+    }
+    println!("✓ Assembled {} bytes of bytecode to {}", bytecode.code.len(), output_file);
+}
+
+fn explain_files(patterns: &[String], format: Format, lang: Lang) {
+    let files_to_explain = expand_patterns(patterns);
+    
     if files_to_explain.is_empty() {
         eprintln!("Error: No valid .ae files found");
         process::exit(1);
     }
     
-    // Explain each file
-    for (i, filename) in files_to_explain.iter().enumerate() {
-        if i > 0 {
-            println!("\n{}", "=".repeat(80));
-            println!();
+    match format {
+        Format::Text => {
+            // Explain each file
+            for (i, filename) in files_to_explain.iter().enumerate() {
+                if i > 0 {
+                    println!("\n{}", "=".repeat(80));
+                    println!();
+                }
+                explain_file(filename, lang);
+            }
+        }
+        Format::Markdown => {
+            for filename in &files_to_explain {
+                let mut explainer = Explainer::new();
+                explainer.set_lang(lang);
+                match fs::read_to_string(filename).map_err(Into::into).and_then(|source| explainer.explain_markdown(&source)) {
+                    Ok(markdown) => print!("## {}\n\n{}", filename, markdown),
+                    Err(err) => eprintln!("{}: {}", filename, err),
+                }
+            }
+        }
+        Format::Json => {
+            let mut files = Vec::new();
+            for filename in &files_to_explain {
+                let mut explainer = Explainer::new();
+                explainer.set_lang(lang);
+                match fs::read_to_string(filename).map_err(Into::into).and_then(|source| explainer.explain_json(&source)) {
+                    Ok(mut explanation) => {
+                        explanation["file"] = filename.as_str().into();
+                        files.push(explanation);
+                    }
+                    Err(err) => eprintln!("{}: {}", filename, err),
+                }
+            }
+            println!("{}", serde_json::to_string_pretty(&files).unwrap_or_default());
+        }
+    }
+}
+
+/// The language of `--lang`, English if it is not given
+fn lang_option(lang: Option<String>) -> Lang {
+    match lang.as_deref().map(Lang::from_code) {
+        None => Lang::En,
+        Some(Some(lang)) => lang,
+        Some(None) => {
+            eprintln!("Error: Unknown language '{}' (expected en or zh)", lang.unwrap_or_default());
+            process::exit(1);
+        }
+    }
+}
+
+/// Project an explanation back into formatted glyph code. The output of
+/// `aether explain` can be given whole; only its explanation is read.
+fn unexplain_file(filename: &str, lang: Lang) {
+    let read = if filename == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        fs::read_to_string(filename)
+    };
+    let text = match read {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Error reading file '{}': {}", filename, err);
+            process::exit(1);
+        }
+    };
+
+    let heading = format!("📖 {}", lang.template("heading.explanation"));
+    let explanation = match text.split_once(&heading) {
+        Some((_, rest)) => {
+            let section = rest.split(&format!("🔤 {}", lang.template("heading.symbols"))).next().unwrap_or_default();
+            section.lines().filter(|line| !line.starts_with("----")).collect::<Vec<_>>().join("\n")
+        }
+        None => text,
+    };
+
+    let mut projector = Projector::new();
+    projector.set_lang(lang);
+    match projector.project(&explanation).and_then(|ast| projector::glyphs(&ast)) {
+        Ok(code) => print!("{}", code),
+        Err(err) => {
+            eprintln!("{}: {}", filename, err);
+            process::exit(1);
         }
-        explain_file(filename);
     }
 }
 
-fn explain_file(filename: &str) {
+fn explain_file(filename: &str, lang: Lang) {
     let source = match fs::read_to_string(filename) {
         Ok(content) => content,
         Err(err) => {
@@ -504,12 +1179,16 @@ fn explain_file(filename: &str) {
             return;
         }
     };
+    if let Err(err) = normalize::check_legacy_verify(&source) {
+        eprintln!("{}: {}", filename, err);
+        return;
+    }
     
-    println!("📄 File: {}", filename);
+    println!("📄 {} {}", lang.template("heading.file"), filename);
     println!("{}", "-".repeat(80));
     
     // Show original code
-    println!("\n🔣 Aether Code:");
+    println!("\n🔣 {}", lang.template("heading.code"));
     println!("{}", "-".repeat(80));
     for line in source.lines() {
         let trimmed = line.trim();
@@ -528,6 +1207,8 @@ fn explain_file(filename: &str) {
         }
     };
     
+    let glossary = Explainer::glossary(&tokens);
+
     // Parser
     let mut parser = Parser::new(tokens);
     let ast = match parser.parse() {
@@ -540,9 +1221,342 @@ fn explain_file(filename: &str) {
     
     // Explain
     let mut explainer = Explainer::new();
+    explainer.set_lang(lang);
     let explanation = explainer.explain(&ast);
     
-    println!("\n📖 Human-Readable Explanation:");
+    println!("\n📖 {}", lang.template("heading.explanation"));
     println!("{}", "-".repeat(80));
     println!("{}", explanation);
+
+    println!("🔤 {}", lang.template("heading.symbols"));
+    println!("{}", "-".repeat(80));
+    print!("{}", glossary);
+}
+
+fn check_files(patterns: &[String]) {
+    let files = expand_patterns(patterns);
+    
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+    
+    let mut failed = 0;
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+        
+        // Look-alike glyphs are reported but don't fail the check
+        for warning in normalize::lint(&source).unwrap_or_default() {
+            eprintln!("{}: {}", filename, warning);
+        }
+
+        match checker::check_source(&source) {
+            Ok(diagnostics) if diagnostics.is_empty() => {
+                println!("✓ {}", filename);
+            }
+            Ok(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    eprintln!("{}: {}", filename, diagnostic);
+                }
+                failed += 1;
+            }
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+            }
+        }
+    }
+    
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) failed type checking", failed, files.len());
+        process::exit(1);
+    }
+}
+
+/// Convert files between glyph and ASCII spellings, printing the result or
+/// writing it back
+fn convert_files(patterns: &[String], to_ascii: bool, write: bool) {
+    let files = expand_patterns(patterns);
+
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+
+    let mut failed = 0;
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let converted = if to_ascii { ascii::asciify(&source) } else { ascii::glyphify(&source) };
+        match converted {
+            Ok(text) if write => {
+                if text != source {
+                    if let Err(err) = fs::write(filename, text) {
+                        eprintln!("Error writing file '{}': {}", filename, err);
+                        failed += 1;
+                        continue;
+                    }
+                }
+                println!("✓ {}", filename);
+            }
+            Ok(text) => print!("{}", text),
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) could not be converted", failed, files.len());
+        process::exit(1);
+    }
+}
+
+/// Transpile files to Python or JavaScript, printing the result or writing
+/// it next to each file
+fn transpile_files(patterns: &[String], target: Target, write: bool) {
+    let files = expand_patterns(patterns);
+
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+
+    let mut failed = 0;
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match transpiler::transpile(&source, target) {
+            Ok(code) if write => {
+                let output = Path::new(filename).with_extension(target.extension());
+                match fs::write(&output, code) {
+                    Ok(()) => println!("Transpiled: {} -> {}", filename, output.display()),
+                    Err(err) => {
+                        eprintln!("Error writing file '{}': {}", output.display(), err);
+                        failed += 1;
+                    }
+                }
+            }
+            Ok(code) => print!("{}", code),
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) could not be transpiled", failed, files.len());
+        process::exit(1);
+    }
+}
+
+/// Rewrite files with canonical glyphs, printing the result or writing it back
+fn migrate_files(patterns: &[String], write: bool) {
+    let files = expand_patterns(patterns);
+
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+
+    let mut failed = 0;
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match normalize::migrate(&source) {
+            Ok(text) if write && text == source => {}
+            Ok(text) if write => match fs::write(filename, text) {
+                Ok(()) => println!("Migrated: {}", filename),
+                Err(err) => {
+                    eprintln!("Error writing file '{}': {}", filename, err);
+                    failed += 1;
+                }
+            },
+            Ok(text) => print!("{}", text),
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) could not be migrated", failed, files.len());
+        process::exit(1);
+    }
+}
+
+/// Report the size of each file as written, as code and as explained, and
+/// the glyphs of several code points that cost more tokens than their ASCII
+/// spellings
+fn stats_files(patterns: &[String], tokenizer: &dyn Tokenizer) {
+    let files = expand_patterns(patterns);
+
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+
+    let mut failed = 0;
+    let (mut source_total, mut code_total) = (Size::default(), Size::default());
+    let (mut explained_code, mut explanation_total) = (Size::default(), Size::default());
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let stats = match stats::analyze(&source, tokenizer) {
+            Ok(stats) => stats,
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        println!("📄 {}", filename);
+        print_sizes(tokenizer, &stats.source, &stats.code, stats.explanation.as_ref());
+        let expensive: Vec<_> = stats.expensive_glyphs().collect();
+        if !expensive.is_empty() {
+            println!("  Glyphs of several code points that cost more than their ASCII spellings:");
+            for cost in expensive {
+                let ascii = cost.symbol.ascii().unwrap_or_default();
+                println!(
+                    "    {}  {} code points, {} bytes, {} tokens ({} is {}) × {}",
+                    cost.glyph, cost.code_points, cost.bytes, cost.tokens, ascii, cost.ascii_tokens, cost.uses
+                );
+            }
+        }
+        println!();
+
+        source_total += stats.source;
+        code_total += stats.code;
+        if let Some(explanation) = stats.explanation {
+            explained_code += stats.code;
+            explanation_total += explanation;
+        }
+    }
+
+    if files.len() > 1 {
+        println!("Total ({} files)", files.len());
+        let explanation = (explained_code.tokens > 0).then_some(&explanation_total);
+        print_sizes(tokenizer, &source_total, &code_total, explanation);
+        if explained_code != code_total {
+            println!("  (the explanation covers only the files that parse)");
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) could not be measured", failed, files.len());
+        process::exit(1);
+    }
+}
+
+fn print_sizes(tokenizer: &dyn Tokenizer, source: &Size, code: &Size, explanation: Option<&Size>) {
+    println!("  {:<12} {:>10} {:>12} {:>8} {:>8}", "", "graphemes", "code points", "bytes", "tokens");
+    let row = |name: &str, size: &Size| {
+        println!("  {:<12} {:>10} {:>12} {:>8} {:>8}", name, size.graphemes, size.code_points, size.bytes, size.tokens);
+    };
+    row("source", source);
+    row("code", code);
+    match explanation {
+        Some(explanation) => {
+            row("explanation", explanation);
+            if code.tokens > 0 {
+                println!(
+                    "  The explanation takes {:.1}× the {} tokens of the code",
+                    explanation.tokens as f64 / code.tokens as f64,
+                    tokenizer.name()
+                );
+            }
+        }
+        None => println!("  {:<12} (does not parse)", "explanation"),
+    }
+}
+
+/// Format files in place, or with `check` only report the ones that would change
+fn format_files(patterns: &[String], check: bool) {
+    let files = expand_patterns(patterns);
+
+    if files.is_empty() {
+        eprintln!("Error: No valid .ae files found");
+        process::exit(1);
+    }
+
+    let mut failed = 0;
+    let mut unformatted = 0;
+    for filename in &files {
+        let source = match fs::read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Error reading file '{}': {}", filename, err);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match formatter::format(&source) {
+            Ok(text) if text == source => {}
+            Ok(_) if check => {
+                println!("Would reformat: {}", filename);
+                unformatted += 1;
+            }
+            Ok(text) => match fs::write(filename, text) {
+                Ok(()) => println!("Formatted: {}", filename),
+                Err(err) => {
+                    eprintln!("Error writing file '{}': {}", filename, err);
+                    failed += 1;
+                }
+            },
+            Err(err) => {
+                eprintln!("{}: {}", filename, err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("\n{} of {} file(s) could not be formatted", failed, files.len());
+    }
+    if unformatted > 0 {
+        eprintln!("\n{} of {} file(s) need formatting", unformatted, files.len());
+    }
+    if failed > 0 || unformatted > 0 {
+        process::exit(1);
+    }
 }
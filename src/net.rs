@@ -0,0 +1,475 @@
+//! Networking resources backing the socket symbols (🔌 👂 📞 📦 🤝)
+//!
+//! Sockets, listeners and connections are exposed to Aether programs as
+//! `Value::Object`s with a `"type"` key and an opaque `"handle"` key. The
+//! handle refers to a live OS resource owned by a [`NetRegistry`], which is
+//! shared by the tree-walking runtime and the bytecode VM.
+
+use crate::bytes;
+use crate::error::{AetherError, Result};
+use crate::numeric;
+use crate::runtime::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+
+/// Maximum number of bytes returned by a single read from a connection
+pub const READ_CHUNK_SIZE: usize = 8192;
+
+/// Address prefix selecting UDP for 👂 and 📞
+const UDP_SCHEME: &str = "udp://";
+
+/// Address prefix selecting TCP for 👂 and 📞 (the default)
+const TCP_SCHEME: &str = "tcp://";
+
+/// A live network resource
+enum NetResource {
+    Listener(TcpListener),
+    Connection(TcpStream),
+    Udp(UdpSocket),
+}
+
+/// Owner of all open sockets, keyed by handle
+#[derive(Default)]
+pub struct NetRegistry {
+    resources: HashMap<String, NetResource>,
+    counter: usize,
+    last_listener: Option<String>,
+}
+
+impl NetRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a socket for the given protocol (🔌)
+    ///
+    /// TCP sockets are unbound descriptors; use 👂 or 📞 to open them.
+    /// UDP sockets are bound to an ephemeral local port straight away so
+    /// they can send and receive datagrams.
+    pub fn socket(&mut self, protocol: &Value) -> Result<Value> {
+        let protocol = protocol.as_string()
+            .ok_or_else(|| AetherError::RuntimeError("Socket protocol must be TCP or UDP".to_string()))?
+            .to_uppercase();
+
+        match protocol.as_str() {
+            "TCP" => {
+                let mut obj = HashMap::new();
+                obj.insert("type".to_string(), Value::String("socket".to_string()));
+                obj.insert("protocol".to_string(), Value::String("TCP".to_string()));
+                obj.insert("connected".to_string(), Value::Boolean(false));
+                Ok(Value::Object(obj))
+            }
+            "UDP" => {
+                let socket = UdpSocket::bind("0.0.0.0:0")
+                    .map_err(|e| AetherError::RuntimeError(format!("Failed to bind UDP socket: {}", e)))?;
+                self.register_udp(socket, false)
+            }
+            other => Err(AetherError::RuntimeError(format!("Unsupported socket protocol: {}", other))),
+        }
+    }
+
+    /// Bind a listener (👂port)
+    ///
+    /// Numbers bind every interface on that port; strings are taken as a
+    /// full `host:port` address. A `udp://` prefix binds a UDP socket instead
+    /// of a TCP listener.
+    pub fn listen(&mut self, target: &Value) -> Result<Value> {
+        let address = match target {
+            n if n.is_numeric() => format!("0.0.0.0:{}", port_number(n)?),
+            Value::String(s) => s.clone(),
+            _ => return Err(AetherError::RuntimeError("Listen requires port number or address".to_string())),
+        };
+
+        if let Some(udp_addr) = address.strip_prefix(UDP_SCHEME) {
+            let socket = UdpSocket::bind(udp_addr)
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to bind UDP socket on {}: {}", udp_addr, e)))?;
+            return self.register_udp(socket, false);
+        }
+
+        let tcp_addr = address.strip_prefix(TCP_SCHEME).unwrap_or(&address);
+        let listener = TcpListener::bind(tcp_addr)
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to listen on {}: {}", tcp_addr, e)))?;
+        let port = listener.local_addr()?.port();
+
+        let handle = self.insert(NetResource::Listener(listener));
+        self.last_listener = Some(handle.clone());
+
+        let mut obj = HashMap::new();
+        obj.insert("type".to_string(), Value::String("listener".to_string()));
+        obj.insert("port".to_string(), Value::Integer(port as i64));
+        obj.insert("active".to_string(), Value::Boolean(true));
+        obj.insert("handle".to_string(), Value::String(handle));
+        Ok(Value::Object(obj))
+    }
+
+    /// Block until a client connects to a listener
+    ///
+    /// Without an explicit listener the most recently opened one is used,
+    /// which is what a bare `⏳👂` refers to.
+    pub fn accept(&mut self, listener: Option<&Value>) -> Result<Value> {
+        let handle = match listener {
+            Some(value) => handle_of(value)
+                .ok_or_else(|| AetherError::RuntimeError("Accept requires a listener".to_string()))?,
+            None => self.last_listener.clone()
+                .ok_or_else(|| AetherError::RuntimeError("No active listener to accept connections from".to_string()))?,
+        };
+
+        let (stream, peer) = match self.resources.get(&handle) {
+            Some(NetResource::Listener(listener)) => listener.accept()
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to accept connection: {}", e)))?,
+            Some(_) => return Err(AetherError::RuntimeError("Accept requires a listener".to_string())),
+            None => return Err(closed_error(&handle)),
+        };
+
+        Ok(self.register_connection(stream, peer))
+    }
+
+    /// Open a client connection (📞address)
+    ///
+    /// A `udp://` prefix yields a UDP socket whose default peer is the given
+    /// address, so plain writes and 📦 packets without `to` reach it.
+    pub fn connect(&mut self, address: &Value) -> Result<Value> {
+        let address = address.as_string()
+            .ok_or_else(|| AetherError::RuntimeError("Connect address must be string".to_string()))?;
+
+        if let Some(udp_addr) = address.strip_prefix(UDP_SCHEME) {
+            let peer = resolve(udp_addr)?;
+            let local = if peer.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+            let socket = UdpSocket::bind(local)
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to bind UDP socket: {}", e)))?;
+            socket.connect(peer)
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to connect to {}: {}", udp_addr, e)))?;
+            return self.register_udp(socket, true);
+        }
+
+        let tcp_addr = address.strip_prefix(TCP_SCHEME).unwrap_or(address);
+        let stream = TcpStream::connect(tcp_addr)
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to connect to {}: {}", tcp_addr, e)))?;
+        let peer = stream.peer_addr()?;
+        Ok(self.register_connection(stream, peer))
+    }
+
+    /// Read from a connection or UDP socket (📖)
+    ///
+    /// Connections have stream semantics: each read returns the next chunk
+    /// of data that has arrived (at most [`READ_CHUNK_SIZE`] bytes), and
+    /// `Null` once the peer has closed its side. UDP sockets return the next
+    /// datagram as a packet object with `data` and `from` keys. Data is a
+    /// string when it is valid UTF-8 and bytes otherwise.
+    pub fn read(&mut self, resource: &Value) -> Result<Value> {
+        let handle = self.require_handle(resource)?;
+        match self.resources.get_mut(&handle) {
+            Some(NetResource::Connection(stream)) => {
+                let mut buf = vec![0u8; READ_CHUNK_SIZE];
+                let n = stream.read(&mut buf)
+                    .map_err(|e| AetherError::RuntimeError(format!("Failed to read from connection: {}", e)))?;
+                if n == 0 {
+                    return Ok(Value::Null);
+                }
+                buf.truncate(n);
+                Ok(bytes::text_or_bytes(buf))
+            }
+            Some(NetResource::Udp(socket)) => {
+                let mut buf = vec![0u8; 65535];
+                let (n, from) = socket.recv_from(&mut buf)
+                    .map_err(|e| AetherError::RuntimeError(format!("Failed to receive datagram: {}", e)))?;
+                let mut packet = HashMap::new();
+                packet.insert("type".to_string(), Value::String("packet".to_string()));
+                buf.truncate(n);
+                packet.insert("data".to_string(), bytes::text_or_bytes(buf));
+                packet.insert("from".to_string(), Value::String(from.to_string()));
+                Ok(Value::Object(packet))
+            }
+            Some(NetResource::Listener(_)) => Err(AetherError::RuntimeError(
+                "Cannot read from a listener; accept a connection first".to_string()
+            )),
+            None => Err(closed_error(&handle)),
+        }
+    }
+
+    /// Write to a connection or UDP socket (🖊️)
+    ///
+    /// Returns the number of bytes written. Writing a 📦 packet to a UDP
+    /// socket sends it as one datagram to the packet's `to` address, or to
+    /// the socket's connected peer.
+    pub fn write(&mut self, resource: &Value, data: &Value) -> Result<Value> {
+        let handle = self.require_handle(resource)?;
+        match self.resources.get_mut(&handle) {
+            Some(NetResource::Connection(stream)) => {
+                let bytes = payload(data)?;
+                stream.write_all(&bytes)
+                    .and_then(|_| stream.flush())
+                    .map_err(|e| AetherError::RuntimeError(format!("Failed to write to connection: {}", e)))?;
+                Ok(Value::Integer(bytes.len() as i64))
+            }
+            Some(NetResource::Udp(socket)) => {
+                let destination = match data {
+                    Value::Object(obj) => obj.get("to").and_then(|v| v.as_string()),
+                    _ => None,
+                };
+                let bytes = payload(data)?;
+                let sent = match destination {
+                    Some(addr) => socket.send_to(&bytes, resolve(addr)?),
+                    None => socket.send(&bytes),
+                }
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to send datagram: {}", e)))?;
+                Ok(Value::Integer(sent as i64))
+            }
+            Some(NetResource::Listener(_)) => Err(AetherError::RuntimeError(
+                "Cannot write to a listener; accept a connection first".to_string()
+            )),
+            None => Err(closed_error(&handle)),
+        }
+    }
+
+    /// Check that the peer of a connection is still reachable (🤝)
+    pub fn handshake(&mut self, resource: &Value) -> Result<Value> {
+        let handle = self.require_handle(resource)?;
+        let alive = match self.resources.get(&handle) {
+            Some(NetResource::Connection(stream)) => stream.peer_addr().is_ok(),
+            Some(NetResource::Udp(socket)) => socket.peer_addr().is_ok(),
+            Some(NetResource::Listener(listener)) => listener.local_addr().is_ok(),
+            None => false,
+        };
+        Ok(Value::Boolean(alive))
+    }
+
+    /// Close a resource and release its handle (🗑️)
+    pub fn close(&mut self, resource: &Value) -> Result<Value> {
+        let handle = self.require_handle(resource)?;
+        match self.resources.remove(&handle) {
+            Some(NetResource::Connection(stream)) => {
+                // The peer may already be gone; closing is still successful
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+            Some(_) => {}
+            None => return Err(closed_error(&handle)),
+        }
+        if self.last_listener.as_deref() == Some(handle.as_str()) {
+            self.last_listener = None;
+        }
+        Ok(Value::Boolean(true))
+    }
+
+    /// Duplicate the OS handle of a connection so a 🌊 stream can share it
+    pub fn clone_connection(&self, resource: &Value) -> Result<TcpStream> {
+        let handle = self.require_handle(resource)?;
+        match self.resources.get(&handle) {
+            Some(NetResource::Connection(stream)) => stream.try_clone()
+                .map_err(|e| AetherError::RuntimeError(format!("Failed to open stream on connection: {}", e))),
+            Some(_) => Err(AetherError::RuntimeError("Streams require a connection".to_string())),
+            None => Err(closed_error(&handle)),
+        }
+    }
+
+    /// Whether a value refers to a resource owned by this registry
+    pub fn owns(&self, value: &Value) -> bool {
+        handle_of(value).is_some_and(|h| self.resources.contains_key(&h))
+    }
+
+    fn require_handle(&self, resource: &Value) -> Result<String> {
+        handle_of(resource)
+            .ok_or_else(|| AetherError::RuntimeError("Expected a socket, listener or connection".to_string()))
+    }
+
+    fn insert(&mut self, resource: NetResource) -> String {
+        self.counter += 1;
+        let handle = format!("net_{}", self.counter);
+        self.resources.insert(handle.clone(), resource);
+        handle
+    }
+
+    fn register_connection(&mut self, stream: TcpStream, peer: SocketAddr) -> Value {
+        let handle = self.insert(NetResource::Connection(stream));
+        let mut obj = HashMap::new();
+        obj.insert("type".to_string(), Value::String("connection".to_string()));
+        obj.insert("address".to_string(), Value::String(peer.to_string()));
+        obj.insert("connected".to_string(), Value::Boolean(true));
+        obj.insert("handle".to_string(), Value::String(handle));
+        Value::Object(obj)
+    }
+
+    fn register_udp(&mut self, socket: UdpSocket, connected: bool) -> Result<Value> {
+        let port = socket.local_addr()?.port();
+        let handle = self.insert(NetResource::Udp(socket));
+        let mut obj = HashMap::new();
+        obj.insert("type".to_string(), Value::String("socket".to_string()));
+        obj.insert("protocol".to_string(), Value::String("UDP".to_string()));
+        obj.insert("port".to_string(), Value::Integer(port as i64));
+        obj.insert("connected".to_string(), Value::Boolean(connected));
+        obj.insert("handle".to_string(), Value::String(handle));
+        Ok(Value::Object(obj))
+    }
+}
+
+/// Build a packet object from 📦's argument
+///
+/// Strings become the packet payload. Objects are taken as a packet
+/// description and may carry a `to` address alongside `data`.
+pub fn packet(data: Value) -> Value {
+    let mut obj = match data {
+        Value::Object(fields) if fields.contains_key("data") => fields,
+        other => {
+            let mut fields = HashMap::new();
+            fields.insert("data".to_string(), other);
+            fields
+        }
+    };
+    obj.insert("type".to_string(), Value::String("packet".to_string()));
+    Value::Object(obj)
+}
+
+/// Extract the resource handle from a socket or stream object
+pub(crate) fn handle_of(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(obj) => obj.get("handle").and_then(|h| h.as_string()).map(|h| h.to_string()),
+        _ => None,
+    }
+}
+
+/// Bytes to put on the wire for a value
+pub(crate) fn payload(data: &Value) -> Result<Vec<u8>> {
+    match data {
+        Value::Object(obj) if obj.get("type") == Some(&Value::String("packet".to_string())) => {
+            payload(obj.get("data").unwrap_or(&Value::Null))
+        }
+        _ => bytes::raw(data)
+            .ok_or_else(|| AetherError::RuntimeError("Network data must be bytes, string, number or packet".to_string())),
+    }
+}
+
+fn port_number(n: &Value) -> Result<u16> {
+    numeric::as_integer(n)
+        .and_then(|n| u16::try_from(n).ok())
+        .ok_or_else(|| AetherError::RuntimeError(format!(
+            "Invalid port number: {}",
+            numeric::display(n).unwrap_or_default()
+        )))
+}
+
+fn resolve(address: &str) -> Result<SocketAddr> {
+    address.to_socket_addrs()
+        .map_err(|e| AetherError::RuntimeError(format!("Invalid address '{}': {}", address, e)))?
+        .next()
+        .ok_or_else(|| AetherError::RuntimeError(format!("Address '{}' did not resolve", address)))
+}
+
+fn closed_error(handle: &str) -> AetherError {
+    AetherError::RuntimeError(format!("Network resource {} is closed", handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn port_of(value: &Value) -> u16 {
+        match value {
+            Value::Object(obj) => obj.get("port").and_then(|p| p.as_number()).unwrap() as u16,
+            _ => panic!("Expected object with port"),
+        }
+    }
+
+    #[test]
+    fn test_tcp_echo_over_loopback() {
+        let mut net = NetRegistry::new();
+        let listener = net.listen(&Value::String("127.0.0.1:0".to_string())).unwrap();
+        let port = port_of(&listener);
+        assert_ne!(port, 0);
+
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream.write_all(b"ping").unwrap();
+            let mut reply = String::new();
+            stream.read_to_string(&mut reply).unwrap();
+            reply
+        });
+
+        let conn = net.accept(None).unwrap();
+        assert_eq!(net.read(&conn).unwrap(), Value::String("ping".to_string()));
+        assert_eq!(net.write(&conn, &Value::String("pong".to_string())).unwrap(), Value::Number(4.0));
+        assert_eq!(net.handshake(&conn).unwrap(), Value::Boolean(true));
+        net.close(&conn).unwrap();
+
+        assert_eq!(client.join().unwrap(), "pong");
+        assert!(!net.owns(&conn));
+    }
+
+    #[test]
+    fn test_tcp_read_returns_null_at_eof() {
+        let mut net = NetRegistry::new();
+        let listener = net.listen(&Value::String("127.0.0.1:0".to_string())).unwrap();
+        let port = port_of(&listener);
+
+        let client = thread::spawn(move || {
+            let mut net = NetRegistry::new();
+            let conn = net.connect(&Value::String(format!("127.0.0.1:{}", port))).unwrap();
+            net.write(&conn, &Value::String("bye".to_string())).unwrap();
+            net.close(&conn).unwrap();
+        });
+
+        let conn = net.accept(Some(&listener)).unwrap();
+        client.join().unwrap();
+        assert_eq!(net.read(&conn).unwrap(), Value::String("bye".to_string()));
+        assert_eq!(net.read(&conn).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_udp_packet_roundtrip() {
+        let mut net = NetRegistry::new();
+        let receiver = net.listen(&Value::String("udp://127.0.0.1:0".to_string())).unwrap();
+        let port = port_of(&receiver);
+
+        let sender = net.socket(&Value::String("UDP".to_string())).unwrap();
+        let mut desc = HashMap::new();
+        desc.insert("to".to_string(), Value::String(format!("127.0.0.1:{}", port)));
+        desc.insert("data".to_string(), Value::String("hello".to_string()));
+        let pkt = packet(Value::Object(desc));
+        assert_eq!(net.write(&sender, &pkt).unwrap(), Value::Number(5.0));
+
+        match net.read(&receiver).unwrap() {
+            Value::Object(obj) => {
+                assert_eq!(obj.get("type"), Some(&Value::String("packet".to_string())));
+                assert_eq!(obj.get("data"), Some(&Value::String("hello".to_string())));
+                assert!(obj.contains_key("from"));
+            }
+            other => panic!("Expected packet, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_connected_udp_socket_uses_default_peer() {
+        let mut net = NetRegistry::new();
+        let receiver = net.listen(&Value::String("udp://127.0.0.1:0".to_string())).unwrap();
+        let port = port_of(&receiver);
+
+        let sender = net.connect(&Value::String(format!("udp://127.0.0.1:{}", port))).unwrap();
+        net.write(&sender, &packet(Value::String("hi".to_string()))).unwrap();
+
+        match net.read(&receiver).unwrap() {
+            Value::Object(obj) => assert_eq!(obj.get("data"), Some(&Value::String("hi".to_string()))),
+            other => panic!("Expected packet, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_accept_without_listener_fails() {
+        let mut net = NetRegistry::new();
+        assert!(net.accept(None).is_err());
+    }
+
+    #[test]
+    fn test_tcp_socket_is_unbound() {
+        let mut net = NetRegistry::new();
+        match net.socket(&Value::String("tcp".to_string())).unwrap() {
+            Value::Object(obj) => {
+                assert_eq!(obj.get("protocol"), Some(&Value::String("TCP".to_string())));
+                assert_eq!(obj.get("connected"), Some(&Value::Boolean(false)));
+            }
+            _ => panic!("Expected socket object"),
+        }
+        assert!(net.socket(&Value::String("SCTP".to_string())).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Canonical spellings of glyphs
+//!
+//! Each symbol has one canonical glyph, the one `Symbol::to_str` gives. The
+//! lexer reads a glyph however its variation selectors are written, since
+//! editors and models add and drop them unseen, so `🏷` and `🏷️` are both
+//! headers. `lint` warns about what the lexer reads that way, about
+//! characters that only look like a symbol, such as `→` for `⇢`, and about
+//! names with letters that look like Latin ones or like a symbol. `migrate`
+//! rewrites a file with the canonical glyphs.
+
+use crate::error::{AetherError, Result};
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::symbols::{without_variation_selectors, Symbol};
+use std::fmt;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Characters the lexer doesn't read, and the glyphs they are mistaken for
+const LOOK_ALIKES: &[(&str, &str)] = &[
+    ("→", "⇢"), ("➔", "⇢"), ("➜", "⇢"), ("⇨", "⇢"), ("⟶", "⇢"), ("⟹", "⇒"), ("▶", "▷"), ("►", "▷"),
+    ("▸", "▷"), ("▹", "▷"), ("⊳", "▷"), ("»", "⨠"), ("≫", "⨠"), ("⪢", "⨠"), ("✔", "✓"), ("✅", "✓"),
+    ("☑", "✓"), ("◊", "◇"), ("⋄", "◇"), ("♢", "◇"), ("♦", "◆"), ("⬥", "◆"), ("⌀", "∅"), ("♾", "∞"),
+    ("↺", "↻"), ("⟳", "↻"), ("🔁", "↻"), ("🔄", "↻"), ("−", "-"), ("–", "-"), ("—", "-"), ("×", "*"),
+    ("✕", "*"), ("∗", "*"), ("÷", "/"), ("∕", "/"), ("⩵", "≡"), ("⟪", "⟦"), ("⟫", "⟧"), ("〈", "⟨"),
+    ("〉", "⟩"), ("⩾", "≥"), ("≧", "≥"), ("⩽", "≤"), ("≦", "≤"), ("⧻", "⧺"), ("≃", "≈"),
+];
+
+/// Letters from other scripts that read as names but look like a Latin
+/// letter or a symbol
+const LOOK_ALIKE_LETTERS: &[(char, &str)] = &[
+    ('А', "A"), ('В', "B"), ('Е', "E"), ('І', "I"), ('Ј', "J"), ('К', "K"), ('М', "M"), ('Н', "H"),
+    ('О', "O"), ('Р', "P"), ('С', "C"), ('Ѕ', "S"), ('Т', "T"), ('Х', "X"), ('а', "a"), ('е', "e"),
+    ('і', "i"), ('ј', "j"), ('о', "o"), ('р', "p"), ('с', "c"), ('ѕ', "s"), ('у', "y"), ('х', "x"),
+    ('Α', "A"), ('Β', "B"), ('Ε', "E"), ('Ζ', "Z"), ('Η', "H"), ('Ι', "I"), ('Κ', "K"), ('Μ', "M"),
+    ('Ν', "N"), ('Ο', "O"), ('Ρ', "P"), ('Τ', "T"), ('Υ', "Y"), ('Χ', "X"), ('ο', "o"), ('ν', "v"),
+    ('Σ', "∑"), ('Δ', "∆"), ('Ø', "∅"), ('ø', "∅"), ('Ǝ', "∃"),
+];
+
+/// The glyph a character the lexer doesn't read looks like, if any
+pub fn look_alike(grapheme: &str) -> Option<&'static str> {
+    let bare = without_variation_selectors(grapheme);
+    LOOK_ALIKES.iter().find(|(look_alike, _)| *look_alike == bare).map(|(_, glyph)| *glyph)
+}
+
+/// A spelling the lexer accepts or guesses at but that isn't canonical
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Grapheme position in the source
+    pub position: usize,
+    /// Length in graphemes
+    pub length: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "warning at position {}: {}", self.position, self.message)
+    }
+}
+
+/// Warnings for glyphs not in their canonical form, characters that look
+/// like a symbol and names with look-alike letters
+pub fn lint(source: &str) -> Result<Vec<Warning>> {
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let mut warnings = Vec::new();
+    for token in lex(source)? {
+        let TokenType::Symbol(symbol) = &token.token_type else { continue };
+        let written = graphemes[token.position..token.position + token.length].concat();
+        let message = match symbol {
+            Symbol::Identifier(name) => match name.chars().find_map(|c| letter_look_alike(c).map(|like| (c, like))) {
+                Some((c, like)) if name.chars().count() == 1 => {
+                    format!("'{}' (U+{:04X}) is read as a name; '{}' is the symbol", c, c as u32, like)
+                }
+                Some((c, like)) => {
+                    format!("'{}' contains '{}' (U+{:04X}), which looks like '{}'", name, c, c as u32, like)
+                }
+                None => continue,
+            },
+            _ if symbol.ascii().is_none() || written == symbol.to_str() => continue,
+            _ if is_legacy_verify(&written) => {
+                "'🛡️' reads as '🛡' (try); verify is now '🔏'".to_string()
+            }
+            _ if look_alike(&written).is_some() => {
+                format!("'{}' is not a symbol; did you mean '{}' ({})?", written, symbol.to_str(), symbol.name())
+            }
+            _ => format!("'{}' differs from '{}' ({}) only in variation selectors", written, symbol.to_str(), symbol.name()),
+        };
+        warnings.push(Warning { position: token.position, length: token.length, message });
+    }
+    Ok(warnings)
+}
+
+/// Refuse a source that writes verify as `🛡️`, which the lexer now reads
+/// as try, so running or compiling it can't quietly change what it does
+///
+/// Sources the lexer can't read pass; lexing them reports the error.
+pub fn check_legacy_verify(source: &str) -> Result<()> {
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let Ok(tokens) = lex(source) else { return Ok(()) };
+    let legacy = tokens.iter().find(|token| {
+        matches!(token.token_type, TokenType::Symbol(_))
+            && is_legacy_verify(&graphemes[token.position..token.position + token.length].concat())
+    });
+    match legacy {
+        Some(token) => Err(AetherError::LexerError(format!(
+            "'🛡️' at position {} meant verify but now reads as '🛡' (try); run `aether migrate` to rewrite it as '🔏'",
+            token.position
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Rewrite a source with each glyph in its canonical form
+///
+/// `🛡️` becomes `🔏`, since it meant verify before verify had a glyph of its
+/// own, and characters that look like a symbol become the symbol. Names are
+/// left alone: whether `Ј` is meant to be `J` is for the author to say.
+pub fn migrate(source: &str) -> Result<String> {
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let tokens = lex(source)?;
+
+    let mut output = String::new();
+    let mut position = 0;
+    let mut expected = Vec::new();
+    for token in &tokens {
+        let mut kind = token.token_type.clone();
+        if token.length > 0 {
+            output.push_str(&graphemes[position..token.position].concat());
+            let written = graphemes[token.position..token.position + token.length].concat();
+            let text = match &kind {
+                TokenType::Symbol(_) if is_legacy_verify(&written) => {
+                    kind = TokenType::Symbol(Symbol::Verify);
+                    Symbol::Verify.to_str()
+                }
+                TokenType::Symbol(symbol) if symbol.ascii().is_some() => symbol.to_str(),
+                _ => written,
+            };
+            output.push_str(&text);
+            position = token.position + token.length;
+        }
+        expected.push(kind);
+    }
+    output.push_str(&graphemes[position..].concat());
+
+    // The rewritten source must lex as written, with 🛡️ read as verify
+    let kinds: Vec<TokenType> = Lexer::new(output.clone()).tokenize()?.into_iter().map(|token| token.token_type).collect();
+    if kinds != expected {
+        return Err(AetherError::LexerError("Migrating would change the meaning of the program".to_string()));
+    }
+    Ok(output)
+}
+
+/// Tokens of a source, reading characters that look like a symbol as it
+fn lex(source: &str) -> Result<Vec<Token>> {
+    let mut lexer = Lexer::new(source.to_string());
+    lexer.set_look_alikes(true);
+    lexer.tokenize()
+}
+
+/// Whether a symbol is written as the old verify, 🛡 with a selector after it
+fn is_legacy_verify(written: &str) -> bool {
+    written != "🛡" && without_variation_selectors(written) == "🛡"
+}
+
+fn letter_look_alike(c: char) -> Option<&'static str> {
+    LOOK_ALIKE_LETTERS.iter().find(|(letter, _)| *letter == c).map(|(_, like)| *like)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_finds_non_canonical_spellings() {
+        let source = "🏷\"h\" → x ⨠ 🛡\u{FE0F}(s, d, k) ⨠ Јson ⨠ Σ ⨠ 🌐👁\u{FE0F}\"u\" ⨠ ◇(x ≡ 1): ✓";
+        let messages: Vec<(usize, String)> =
+            lint(source).unwrap().into_iter().map(|warning| (warning.position, warning.message)).collect();
+        assert_eq!(messages.len(), 6, "{:?}", messages);
+        assert!(messages[0].1.contains("'🏷️' (http_headers)"), "{}", messages[0].1);
+        assert_eq!(messages[1], (5, "'→' is not a symbol; did you mean '⇢' (pipe)?".to_string()));
+        assert_eq!(messages[2].1, "'🛡️' reads as '🛡' (try); verify is now '🔏'");
+        assert!(messages[3].1.contains("'Јson' contains 'Ј' (U+0408), which looks like 'J'"));
+        assert!(messages[4].1.contains("is read as a name; '∑' is the symbol"));
+        assert!(messages[5].1.contains("'🌐👁' (http_head)"));
+
+        assert!(lint("📥 ▷ x ⨠ 🔏(s, d, k) ⨠ 📤\"→ Σ\" // 🛡️ →").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_legacy_verify_is_refused() {
+        let err = check_legacy_verify("📥 ⨠ 🛡\u{FE0F} (📤 1)").unwrap_err();
+        assert!(err.to_string().contains("position 4"), "{}", err);
+        assert!(err.to_string().contains("aether migrate"), "{}", err);
+        assert!(check_legacy_verify("🛡 (📤 1) ⨠ 🔏(s, d, k) ⨠ 📤\"🛡\u{FE0F}\"").is_ok());
+    }
+
+    #[test]
+    fn test_migrate_rewrites_to_canonical_glyphs() {
+        let source = "🛡\u{FE0F}(s, d, k) → ok ⨠ 🏷{} ⨠ 🛡 ↻ ⨠ 📤\"🛡\u{FE0F} →\" // 🛡\u{FE0F}\n🌐👁\u{FE0F}\"u\" ▷ Јx";
+        let migrated = migrate(source).unwrap();
+        assert_eq!(migrated, "🔏(s, d, k) ⇢ ok ⨠ 🏷️{} ⨠ 🛡 ↻ ⨠ 📤\"🛡\u{FE0F} →\" // 🛡\u{FE0F}\n🌐👁\"u\" ▷ Јx");
+        assert_eq!(migrate(&migrated).unwrap(), migrated);
+        assert_eq!(lint(&migrated).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_look_alikes_are_hinted_in_lexer_errors() {
+        let err = Lexer::new("1 ➜ x".to_string()).tokenize().unwrap_err();
+        assert!(err.to_string().contains("Unknown character at position 2: '➜' (did you mean '⇢'?)"), "{}", err);
+        for (look_alike, glyph) in LOOK_ALIKES {
+            assert!(Symbol::from_glyph(look_alike).is_none(), "{} is a symbol", look_alike);
+            assert!(Symbol::from_str(glyph).is_some(), "{} is not a symbol", glyph);
+            assert!(!look_alike.chars().next().unwrap().is_alphanumeric());
+        }
+    }
+}
@@ -0,0 +1,578 @@
+//! Integer and decimal arithmetic behind Aether's numeric tower
+//!
+//! Numbers come in three kinds: `Value::Integer` (i64, overflow checked),
+//! `Value::Decimal` (exact base-10, for money and IDs) and `Value::Number`
+//! (f64). Literals pick the kind from how they are written: `42` is an
+//! integer and `19.99` a decimal. Mixed arithmetic widens integer → decimal →
+//! float, so exact operands always give exact results and only a float
+//! operand makes the result a float. The runtime and VM share these helpers.
+
+use crate::error::{AetherError, Result};
+use crate::runtime::Value;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Most digits a decimal keeps after the point
+pub const MAX_SCALE: u32 = 28;
+
+/// Digits kept after the point when a decimal division does not terminate
+pub const DIVISION_SCALE: u32 = 18;
+
+/// An exact base-10 number: `mantissa × 10^-scale`
+///
+/// `19.90` is mantissa 1990 with scale 2. The scale is kept as written, so
+/// amounts print with their cents, but equality and ordering compare values.
+#[derive(Clone, Copy)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Decimal { mantissa, scale }
+    }
+
+    pub fn from_i64(n: i64) -> Self {
+        Decimal::new(n as i128, 0)
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Parse decimal text such as `-12.50`, `3` or `1.5e3`
+    ///
+    /// Returns `None` for malformed text and for values that do not fit in
+    /// 38 significant digits or [`MAX_SCALE`] places.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (body, exponent) = match text.find(['e', 'E']) {
+            Some(i) => (&text[..i], text[i + 1..].parse::<i64>().ok()?),
+            None => (text, 0),
+        };
+        let (negative, digits) = match body.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, body.strip_prefix('+').unwrap_or(body)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return None;
+        }
+
+        let mut mantissa: i128 = 0;
+        for c in whole.chars().chain(fraction.chars()) {
+            let digit = c.to_digit(10)?;
+            mantissa = mantissa.checked_mul(10)?.checked_add(digit as i128)?;
+        }
+        let mut scale = fraction.len() as i64 - exponent;
+        while scale < 0 {
+            mantissa = mantissa.checked_mul(10)?;
+            scale += 1;
+        }
+        if scale > MAX_SCALE as i64 {
+            return None;
+        }
+        Some(Decimal::new(if negative { -mantissa } else { mantissa }, scale as u32))
+    }
+
+    /// The decimal closest to a float's shortest representation (0.1 → 0.1)
+    pub fn from_f64(n: f64) -> Option<Self> {
+        if n.is_finite() {
+            Decimal::parse(&n.to_string())
+        } else {
+            None
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// The value as an i64 when it has no fractional part
+    pub fn to_i64(&self) -> Option<i64> {
+        let whole = self.trimmed(0);
+        if whole.scale == 0 {
+            i64::try_from(whole.mantissa).ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Drop trailing zeros after the point, keeping at least `min_scale` places
+    fn trimmed(&self, min_scale: u32) -> Self {
+        let mut d = *self;
+        while d.scale > min_scale && d.mantissa % 10 == 0 {
+            d.mantissa /= 10;
+            d.scale -= 1;
+        }
+        d
+    }
+
+    /// The same value written with more places
+    fn rescaled(&self, scale: u32) -> Option<Self> {
+        let factor = 10i128.checked_pow(scale.checked_sub(self.scale)?)?;
+        Some(Decimal::new(self.mantissa.checked_mul(factor)?, scale))
+    }
+
+    /// Round to `scale` places, ties to even
+    fn rounded(&self, scale: u32) -> Self {
+        if self.scale <= scale {
+            return *self;
+        }
+        // A divisor of ten or more can't overflow the quotient
+        match 10i128.checked_pow(self.scale - scale).and_then(|factor| round_quotient(self.mantissa, factor)) {
+            Some(mantissa) => Decimal::new(mantissa, scale),
+            None => Decimal::new(0, scale),
+        }
+    }
+
+    fn aligned(self, other: Decimal) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        Some((self.rescaled(scale)?.mantissa, other.rescaled(scale)?.mantissa, scale))
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Self> {
+        let (a, b, scale) = self.aligned(other)?;
+        Some(Decimal::new(a.checked_add(b)?, scale))
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Self> {
+        let (a, b, scale) = self.aligned(other)?;
+        Some(Decimal::new(a.checked_sub(b)?, scale))
+    }
+
+    pub fn checked_mul(self, other: Decimal) -> Option<Self> {
+        let product = Decimal::new(self.mantissa.checked_mul(other.mantissa)?, self.scale + other.scale);
+        Some(product.rounded(MAX_SCALE))
+    }
+
+    /// Divide, rounding to [`DIVISION_SCALE`] places when the result does not terminate
+    ///
+    /// Exact results keep the larger scale of the operands: 10.00 / 4 = 2.50.
+    pub fn checked_div(self, other: Decimal) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let floor = self.scale.max(other.scale);
+        let mut scale = DIVISION_SCALE.max(floor);
+        // Shift the dividend so the quotient has `scale` places, giving up
+        // places when the shifted dividend would not fit
+        loop {
+            let shift = (scale + other.scale).checked_sub(self.scale);
+            let numerator = shift
+                .and_then(|s| 10i128.checked_pow(s))
+                .and_then(|factor| self.mantissa.checked_mul(factor));
+            if let Some(quotient) = numerator.and_then(|n| round_quotient(n, other.mantissa)) {
+                return Some(Decimal::new(quotient, scale).trimmed(floor));
+            }
+            if scale == floor {
+                return None;
+            }
+            scale -= 1;
+        }
+    }
+
+    pub fn checked_rem(self, other: Decimal) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let (a, b, scale) = self.aligned(other)?;
+        Some(Decimal::new(a.checked_rem(b)?, scale))
+    }
+
+    /// Raise to a power by squaring, so any `u32` exponent takes at most
+    /// 32 squarings and the result overflows instead of looping
+    ///
+    /// The result keeps the places repeated multiplication would give
+    /// (1.10 ↑ 2 = 1.2100) where they fit.
+    pub fn checked_pow(self, exponent: u32) -> Option<Self> {
+        let mut result = Decimal::from_i64(1);
+        let mut base = self.trimmed(0);
+        let mut rest = exponent;
+        // Trailing zeros would double with every squaring, so they are
+        // dropped along the way and the places restored at the end
+        while rest > 0 {
+            if rest & 1 == 1 {
+                result = result.checked_mul(base)?.trimmed(0);
+            }
+            rest >>= 1;
+            if rest > 0 {
+                base = base.checked_mul(base)?.trimmed(0);
+            }
+        }
+        let scale = self.scale.saturating_mul(exponent).min(MAX_SCALE);
+        Some(result.rescaled(scale).unwrap_or(result))
+    }
+}
+
+/// `numerator / denominator` rounded to the nearest integer, ties to even,
+/// or `None` when it doesn't fit (`i128::MIN / -1`)
+fn round_quotient(numerator: i128, denominator: i128) -> Option<i128> {
+    let quotient = numerator.checked_div(denominator)?;
+    let remainder = numerator.checked_rem(denominator)?.unsigned_abs();
+    let rest = denominator.unsigned_abs() - remainder;
+    let away = match remainder.cmp(&rest) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => quotient % 2 != 0,
+    };
+    if !away {
+        Some(quotient)
+    } else if (numerator < 0) != (denominator < 0) {
+        quotient.checked_sub(1)
+    } else {
+        quotient.checked_add(1)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.aligned(*other) {
+            Some((a, b, _)) => Some(a.cmp(&b)),
+            None => self.to_f64().partial_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl fmt::Debug for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Binary arithmetic operators over numbers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arith {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Arith {
+    fn name(&self) -> &'static str {
+        match self {
+            Arith::Add => "Addition",
+            Arith::Sub => "Subtraction",
+            Arith::Mul => "Multiplication",
+            Arith::Div => "Division",
+            Arith::Rem => "Modulo",
+        }
+    }
+}
+
+/// A numeric value at its place in the tower
+#[derive(Clone, Copy)]
+enum Num {
+    Int(i64),
+    Dec(Decimal),
+    Float(f64),
+}
+
+fn num(value: &Value) -> Option<Num> {
+    match value {
+        Value::Integer(n) => Some(Num::Int(*n)),
+        Value::Decimal(d) => Some(Num::Dec(*d)),
+        Value::Number(n) => Some(Num::Float(*n)),
+        _ => None,
+    }
+}
+
+impl Num {
+    fn to_f64(self) -> f64 {
+        match self {
+            Num::Int(n) => n as f64,
+            Num::Dec(d) => d.to_f64(),
+            Num::Float(n) => n,
+        }
+    }
+
+    fn to_decimal(self) -> Option<Decimal> {
+        match self {
+            Num::Int(n) => Some(Decimal::from_i64(n)),
+            Num::Dec(d) => Some(d),
+            Num::Float(n) => Decimal::from_f64(n),
+        }
+    }
+}
+
+/// Apply `op`, widening the operands to the narrower kind that holds both
+///
+/// Integer results that overflow i64 and decimal results that overflow 38
+/// digits are errors rather than silently rounded floats. Integer division
+/// stays an integer when exact and becomes a decimal otherwise (7 / 2 = 3.5).
+pub fn arithmetic(op: Arith, left: &Value, right: &Value) -> Result<Value> {
+    let (l, r) = match (num(left), num(right)) {
+        (Some(l), Some(r)) => (l, r),
+        _ => return Err(AetherError::RuntimeError(format!("{} requires numeric values", op.name()))),
+    };
+
+    let zero = match r {
+        Num::Int(n) => n == 0,
+        Num::Dec(d) => d.is_zero(),
+        Num::Float(n) => n == 0.0,
+    };
+    if zero && op == Arith::Div {
+        return Err(AetherError::RuntimeError("Division by zero".to_string()));
+    }
+    if zero && op == Arith::Rem {
+        return Err(AetherError::RuntimeError("Modulo by zero".to_string()));
+    }
+
+    match (l, r) {
+        (Num::Float(_), _) | (_, Num::Float(_)) => {
+            let (a, b) = (l.to_f64(), r.to_f64());
+            Ok(Value::Number(match op {
+                Arith::Add => a + b,
+                Arith::Sub => a - b,
+                Arith::Mul => a * b,
+                Arith::Div => a / b,
+                Arith::Rem => a % b,
+            }))
+        }
+        (Num::Int(a), Num::Int(b)) => {
+            let result = match op {
+                Arith::Add => a.checked_add(b),
+                Arith::Sub => a.checked_sub(b),
+                Arith::Mul => a.checked_mul(b),
+                Arith::Rem => a.checked_rem(b),
+                Arith::Div if a.checked_rem(b) == Some(0) => a.checked_div(b),
+                Arith::Div => return decimal_arithmetic(op, Decimal::from_i64(a), Decimal::from_i64(b)),
+            };
+            result
+                .map(Value::Integer)
+                .ok_or_else(|| AetherError::RuntimeError(format!("Integer overflow in {}", op.name().to_lowercase())))
+        }
+        _ => match (l.to_decimal(), r.to_decimal()) {
+            (Some(a), Some(b)) => decimal_arithmetic(op, a, b),
+            _ => Err(AetherError::RuntimeError(format!("{} requires numeric values", op.name()))),
+        },
+    }
+}
+
+fn decimal_arithmetic(op: Arith, a: Decimal, b: Decimal) -> Result<Value> {
+    let result = match op {
+        Arith::Add => a.checked_add(b),
+        Arith::Sub => a.checked_sub(b),
+        Arith::Mul => a.checked_mul(b),
+        Arith::Div => a.checked_div(b),
+        Arith::Rem => a.checked_rem(b),
+    };
+    result
+        .map(Value::Decimal)
+        .ok_or_else(|| AetherError::RuntimeError(format!("Decimal overflow in {}", op.name().to_lowercase())))
+}
+
+/// Raise `base` to `exponent` (↑)
+///
+/// Integer and decimal bases with a non-negative integer exponent stay
+/// exact; everything else is computed as a float.
+pub fn power(base: &Value, exponent: &Value) -> Result<Value> {
+    let b = num(base).ok_or_else(|| AetherError::RuntimeError("Power requires number base".to_string()))?;
+    let e = num(exponent).ok_or_else(|| AetherError::RuntimeError("Power requires number exponent".to_string()))?;
+
+    match (b, e) {
+        (Num::Int(b), Num::Int(e)) if e >= 0 => u32::try_from(e).ok()
+            .and_then(|e| b.checked_pow(e))
+            .map(Value::Integer)
+            .ok_or_else(|| AetherError::RuntimeError("Integer overflow in power".to_string())),
+        (Num::Dec(b), Num::Int(e)) if e >= 0 => u32::try_from(e).ok()
+            .and_then(|e| b.checked_pow(e))
+            .map(Value::Decimal)
+            .ok_or_else(|| AetherError::RuntimeError("Decimal overflow in power".to_string())),
+        _ => Ok(Value::Number(b.to_f64().powf(e.to_f64()))),
+    }
+}
+
+/// Order two numbers of any kind by value, or `None` when either is not a number
+///
+/// Integers and decimals compare exactly, so IDs beyond 2^53 stay distinct.
+pub fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    let (l, r) = (num(left)?, num(right)?);
+    match (l, r) {
+        (Num::Int(a), Num::Int(b)) => Some(a.cmp(&b)),
+        (Num::Float(a), Num::Float(b)) => a.partial_cmp(&b),
+        _ => match (l.to_decimal(), r.to_decimal()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => l.to_f64().partial_cmp(&r.to_f64()),
+        },
+    }
+}
+
+/// An integer-valued number as i64 (for counts, sizes, ports and indices)
+pub fn as_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(n) => Some(*n),
+        Value::Decimal(d) => d.to_i64(),
+        Value::Number(n) if n.fract() == 0.0 && n.abs() < 9.2e18 => Some(*n as i64),
+        _ => None,
+    }
+}
+
+/// Display text for a number, matching how it was written
+pub fn display(value: &Value) -> Option<String> {
+    match value {
+        Value::Integer(n) => Some(n.to_string()),
+        Value::Decimal(d) => Some(d.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// A number as JSON, keeping integers and decimals digit for digit
+pub fn to_json(value: &Value) -> Option<serde_json::Value> {
+    match value {
+        Value::Integer(n) => Some(serde_json::Value::Number((*n).into())),
+        Value::Decimal(d) => serde_json::from_str::<serde_json::Number>(&d.to_string())
+            .ok()
+            .map(serde_json::Value::Number),
+        Value::Number(n) => Some(serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null)),
+        _ => None,
+    }
+}
+
+/// A JSON number as the narrowest kind that holds it exactly
+///
+/// Whole numbers within i64 become integers, other numbers written with up
+/// to 38 digits become decimals, and anything larger falls back to a float.
+pub fn from_json(n: &serde_json::Number) -> Value {
+    if let Some(i) = n.as_i64() {
+        return Value::Integer(i);
+    }
+    let text = n.to_string();
+    match Decimal::parse(&text) {
+        Some(d) => Value::Decimal(d),
+        None => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(text: &str) -> Value {
+        Value::Decimal(Decimal::parse(text).unwrap())
+    }
+
+    #[test]
+    fn test_decimal_parse_and_display() {
+        assert_eq!(Decimal::parse("19.90").unwrap().to_string(), "19.90");
+        assert_eq!(Decimal::parse("-0.05").unwrap().to_string(), "-0.05");
+        assert_eq!(Decimal::parse("1.5e3").unwrap().to_string(), "1500");
+        assert_eq!(Decimal::parse("25e-4").unwrap().to_string(), "0.0025");
+        assert!(Decimal::parse("1.2.3").is_none());
+        assert!(Decimal::parse("abc").is_none());
+        assert!(Decimal::parse("1".repeat(40).as_str()).is_none());
+        assert_eq!(Decimal::parse("1.50"), Decimal::parse("1.5"));
+    }
+
+    #[test]
+    fn test_money_is_exact() {
+        let total = arithmetic(Arith::Add, &dec("0.1"), &dec("0.2")).unwrap();
+        assert_eq!(total, dec("0.3"));
+        let price = arithmetic(Arith::Mul, &dec("19.99"), &Value::Integer(3)).unwrap();
+        assert!(matches!(&price, Value::Decimal(d) if d.to_string() == "59.97"));
+        let split = arithmetic(Arith::Div, &dec("10.00"), &Value::Integer(4)).unwrap();
+        assert!(matches!(&split, Value::Decimal(d) if d.to_string() == "2.50"));
+        let third = arithmetic(Arith::Div, &dec("1"), &dec("3")).unwrap();
+        assert!(matches!(&third, Value::Decimal(d) if d.to_string() == "0.333333333333333333"));
+        let even = arithmetic(Arith::Div, &dec("0.5"), &dec("4")).unwrap();
+        assert_eq!(even, dec("0.125"));
+    }
+
+    #[test]
+    fn test_integers_are_checked() {
+        let id = Value::Integer(9_007_199_254_740_993);
+        assert_eq!(arithmetic(Arith::Add, &id, &Value::Integer(1)).unwrap(), Value::Integer(9_007_199_254_740_994));
+        assert!(arithmetic(Arith::Add, &Value::Integer(i64::MAX), &Value::Integer(1)).is_err());
+        assert!(arithmetic(Arith::Rem, &Value::Integer(i64::MIN), &Value::Integer(-1)).is_err());
+        assert_eq!(arithmetic(Arith::Div, &Value::Integer(8), &Value::Integer(2)).unwrap(), Value::Integer(4));
+        assert!(matches!(arithmetic(Arith::Div, &Value::Integer(7), &Value::Integer(2)).unwrap(), Value::Decimal(_)));
+        assert!(arithmetic(Arith::Div, &Value::Integer(1), &Value::Integer(0)).is_err());
+        assert_eq!(power(&Value::Integer(2), &Value::Integer(62)).unwrap(), Value::Integer(1 << 62));
+        assert!(power(&Value::Integer(2), &Value::Integer(64)).is_err());
+    }
+
+    #[test]
+    fn test_large_exponents_are_fast() {
+        assert_eq!(power(&dec("1.0"), &Value::Integer(4_000_000_000)).unwrap(), dec("1"));
+        assert!(power(&dec("1.5"), &Value::Integer(4_000_000_000)).is_err());
+        assert_eq!(power(&dec("-0.5"), &Value::Integer(3)).unwrap(), dec("-0.125"));
+        assert_eq!(power(&dec("1.1"), &Value::Integer(10)).unwrap(), dec("2.5937424601"));
+        assert!(matches!(power(&dec("1.10"), &Value::Integer(2)).unwrap(), Value::Decimal(d) if d.to_string() == "1.2100"));
+    }
+
+    #[test]
+    fn test_most_negative_decimal_over_minus_one_overflows() {
+        let min = Value::Decimal(Decimal::new(i128::MIN, 0));
+        let minus_one = dec("-1");
+        for op in [Arith::Div, Arith::Rem] {
+            let err = arithmetic(op, &min, &minus_one).unwrap_err();
+            assert!(err.to_string().contains("Decimal overflow"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_float_operands_widen_to_float() {
+        let mixed = arithmetic(Arith::Add, &Value::Integer(1), &Value::Number(0.5)).unwrap();
+        assert!(matches!(mixed, Value::Number(n) if n == 1.5));
+        assert!(matches!(power(&Value::Integer(2), &Value::Integer(-1)).unwrap(), Value::Number(n) if n == 0.5));
+        assert!(arithmetic(Arith::Add, &Value::Integer(1), &Value::String("x".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_compare_across_kinds() {
+        let big = Value::Integer(9_007_199_254_740_993);
+        let near = Value::Integer(9_007_199_254_740_992);
+        assert_eq!(compare(&big, &near), Some(Ordering::Greater));
+        assert_eq!(compare(&Value::Integer(2), &Value::Number(2.0)), Some(Ordering::Equal));
+        assert_eq!(compare(&dec("0.1"), &Value::Number(0.1)), Some(Ordering::Equal));
+        assert_eq!(compare(&dec("2.50"), &Value::Integer(3)), Some(Ordering::Less));
+        assert_eq!(compare(&Value::Null, &Value::Integer(3)), None);
+    }
+
+    #[test]
+    fn test_json_round_trip_is_faithful() {
+        let json: serde_json::Value = serde_json::from_str(r#"[9007199254740993, 19.90, 18446744073709551616, 0.5]"#).unwrap();
+        let values: Vec<Value> = json.as_array().unwrap().iter()
+            .map(|n| from_json(n.as_number().unwrap()))
+            .collect();
+        assert_eq!(values[0], Value::Integer(9_007_199_254_740_993));
+        assert!(matches!(&values[1], Value::Decimal(d) if d.to_string() == "19.90"));
+        assert!(matches!(&values[2], Value::Decimal(d) if d.to_string() == "18446744073709551616"));
+        let back: Vec<String> = values.iter().map(|v| to_json(v).unwrap().to_string()).collect();
+        assert_eq!(back, vec!["9007199254740993", "19.90", "18446744073709551616", "0.5"]);
+    }
+}
@@ -0,0 +1,683 @@
+//! Optimization passes for the bytecode compiler
+//!
+//! At `-O1` constant expressions and constant `◇` conditions are folded in
+//! the AST before code generation, and a peephole pass tidies the emitted
+//! bytecode. `-O2` also drops stores to variables that are never read and
+//! code that no path reaches, such as statements after `🛑`.
+//!
+//! No pass changes what a program does, including the errors it raises:
+//! expressions that would fail, like a division by zero, are left for the
+//! VM to report.
+
+use crate::bytecode::{BytecodeProgram, Constant, Instruction, Opcode, Operand};
+use crate::constants::PIPE_VARIABLE;
+use crate::numeric::{self, Arith};
+use crate::parser::{AstNode, ComparisonOp, LiteralValue};
+use crate::runtime::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// How much the compiler optimizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+    /// Translate the AST as written
+    #[default]
+    O0,
+    /// Constant folding, constant branches and peephole rewrites
+    O1,
+    /// `O1` plus unused store and unreachable code elimination
+    O2,
+}
+
+impl OptLevel {
+    /// Parse a command line flag: `-O0`, `-O1` or `-O2`
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "-O0" => Some(OptLevel::O0),
+            "-O1" => Some(OptLevel::O1),
+            "-O2" => Some(OptLevel::O2),
+            _ => None,
+        }
+    }
+}
+
+/// Run the AST passes for a level over a program's top-level statements
+///
+/// Statements are rewritten in place and never added or removed, so they
+/// keep lining up with the parser's statement spans. A statement that folds
+/// away entirely becomes an empty sequence.
+pub fn optimize_ast(nodes: &mut [AstNode], level: OptLevel) {
+    if level == OptLevel::O0 {
+        return;
+    }
+    for node in nodes.iter_mut() {
+        fold(node);
+    }
+    if level >= OptLevel::O2 {
+        let mut reads = HashSet::new();
+        for node in nodes.iter() {
+            collect_reads(node, &mut reads);
+        }
+        for node in nodes.iter_mut() {
+            remove_unused_stores(node, &reads);
+        }
+    }
+}
+
+/// Fold constant arithmetic bottom up and replace `◇` on a constant
+/// condition with the branch it always takes
+fn fold(node: &mut AstNode) {
+    for child in node.children_mut() {
+        fold(child);
+    }
+
+    match node {
+        AstNode::Add { .. }
+        | AstNode::Subtract { .. }
+        | AstNode::Multiply { .. }
+        | AstNode::Divide { .. }
+        | AstNode::Modulo { .. }
+        | AstNode::Power { .. } => {
+            if let Some(literal) = constant(node).and_then(literal) {
+                *node = AstNode::Literal(literal);
+            }
+        }
+        AstNode::IfThen { condition, then_branch, else_branch } => {
+            if let Some(value) = constant(condition) {
+                let taken = if value.is_truthy() {
+                    Some(std::mem::replace(then_branch.as_mut(), AstNode::Empty))
+                } else {
+                    else_branch.take().map(|branch| *branch)
+                };
+                // A false condition without an else leaves nothing on the stack
+                *node = taken.unwrap_or(AstNode::Sequence(Vec::new()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The value of an expression built only from literals, if evaluating it
+/// in the VM would succeed
+fn constant(node: &AstNode) -> Option<Value> {
+    let (opcode, left, right) = match node {
+        AstNode::Literal(literal) => {
+            return Some(match literal {
+                LiteralValue::String(s) => Value::String(s.clone()),
+                LiteralValue::Number(n) => Value::Number(*n),
+                LiteralValue::Integer(n) => Value::Integer(*n),
+                LiteralValue::Decimal(d) => Value::Decimal(*d),
+                LiteralValue::Bytes(b) => Value::Bytes(b.clone()),
+            });
+        }
+        AstNode::Not { operand } => return Some(Value::Boolean(!constant(operand)?.is_truthy())),
+        AstNode::Add { left, right } => (Opcode::Add, left, right),
+        AstNode::Subtract { left, right } => (Opcode::Sub, left, right),
+        AstNode::Multiply { left, right } => (Opcode::Mul, left, right),
+        AstNode::Divide { left, right } => (Opcode::Div, left, right),
+        AstNode::Modulo { left, right } => (Opcode::Mod, left, right),
+        AstNode::Power { base, exponent } => (Opcode::Power, base, exponent),
+        AstNode::Equal { left, right } => (Opcode::Equal, left, right),
+        AstNode::NotEqual { left, right } => (Opcode::NotEqual, left, right),
+        AstNode::And { left, right } => (Opcode::And, left, right),
+        AstNode::Or { left, right } => (Opcode::Or, left, right),
+        AstNode::Comparison { left, operator, right } => {
+            let opcode = match operator {
+                ComparisonOp::GreaterThan => Opcode::GreaterThan,
+                ComparisonOp::LessThan => Opcode::LessThan,
+                ComparisonOp::GreaterEqual => Opcode::GreaterEqual,
+                ComparisonOp::LessEqual => Opcode::LessEqual,
+            };
+            (opcode, left, right)
+        }
+        _ => return None,
+    };
+    binary(&opcode, &constant(left)?, &constant(right)?)
+}
+
+/// What a binary opcode leaves on the stack for two values, when the VM
+/// would run it without error
+fn binary(opcode: &Opcode, left: &Value, right: &Value) -> Option<Value> {
+    let ordered = |test: fn(Ordering) -> bool| {
+        // The VM rejects comparisons of anything but numbers
+        if !left.is_numeric() || !right.is_numeric() {
+            return None;
+        }
+        Some(Value::Boolean(numeric::compare(left, right).is_some_and(test)))
+    };
+
+    match opcode {
+        Opcode::Add => numeric::arithmetic(Arith::Add, left, right).ok(),
+        Opcode::Sub => numeric::arithmetic(Arith::Sub, left, right).ok(),
+        Opcode::Mul => numeric::arithmetic(Arith::Mul, left, right).ok(),
+        Opcode::Div => numeric::arithmetic(Arith::Div, left, right).ok(),
+        Opcode::Mod => numeric::arithmetic(Arith::Rem, left, right).ok(),
+        Opcode::Power => numeric::power(left, right).ok(),
+        Opcode::Equal => Some(Value::Boolean(left == right)),
+        Opcode::NotEqual => Some(Value::Boolean(left != right)),
+        Opcode::And => Some(Value::Boolean(left.is_truthy() && right.is_truthy())),
+        Opcode::Or => Some(Value::Boolean(left.is_truthy() || right.is_truthy())),
+        Opcode::GreaterThan => ordered(Ordering::is_gt),
+        Opcode::LessThan => ordered(Ordering::is_lt),
+        Opcode::GreaterEqual => ordered(Ordering::is_ge),
+        Opcode::LessEqual => ordered(Ordering::is_le),
+        _ => None,
+    }
+}
+
+/// A folded number as a literal; other values have no literal form
+fn literal(value: Value) -> Option<LiteralValue> {
+    match value {
+        Value::Integer(n) => Some(LiteralValue::Integer(n)),
+        Value::Decimal(d) => Some(LiteralValue::Decimal(d)),
+        Value::Number(n) => Some(LiteralValue::Number(n)),
+        _ => None,
+    }
+}
+
+/// Names of every variable the program reads
+fn collect_reads(node: &AstNode, reads: &mut HashSet<String>) {
+    match node {
+        AstNode::Variable(name) | AstNode::Delta { name, .. } => {
+            reads.insert(name.clone());
+        }
+        _ => {}
+    }
+    for child in node.children() {
+        collect_reads(child, reads);
+    }
+}
+
+/// Replace `value ▷ name` with just `value` when `name` is never read
+fn remove_unused_stores(node: &mut AstNode, reads: &HashSet<String>) {
+    for child in node.children_mut() {
+        remove_unused_stores(child, reads);
+    }
+
+    if let AstNode::PipeInto { value, variable, .. } = node {
+        if variable != PIPE_VARIABLE && !reads.contains(variable.as_str()) {
+            let value = std::mem::replace(value.as_mut(), AstNode::Empty);
+            *node = value;
+        }
+    }
+}
+
+/// Run the bytecode passes for a level over a compiled program
+///
+/// Jump targets, function entries and line info are moved along with the
+/// code, and constants no instruction uses any more are dropped. Programs
+/// that do not decode cleanly are left untouched.
+pub fn optimize_bytecode(program: &mut BytecodeProgram, level: OptLevel) {
+    if level == OptLevel::O0 {
+        return;
+    }
+    let Some(mut code) = decode_all(program) else { return };
+    let end = program.code.len();
+
+    loop {
+        resolve_targets(&mut code, end);
+        let entries: Vec<usize> = program.functions.iter().map(|(_, entry)| resolve(&code, *entry as usize, end)).collect();
+        let mut changed = peephole(program, &mut code, &entries, end);
+        if level >= OptLevel::O2 {
+            changed |= remove_unreachable(&mut code, &entries, end);
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    rebuild(program, &code);
+}
+
+fn decode_all(program: &BytecodeProgram) -> Option<Vec<Instruction>> {
+    let mut code = Vec::new();
+    let mut offset = 0;
+    while offset < program.code.len() {
+        let instruction = program.decode(offset).ok()?;
+        offset += instruction.size;
+        code.push(instruction);
+    }
+    Some(code)
+}
+
+/// Where control lands for an original code position: the first remaining
+/// instruction at or after it, or `end`
+///
+/// Instructions keep the offset they were decoded at, or one from the
+/// instructions they replaced, so offsets stay in order while passes run.
+fn resolve(code: &[Instruction], position: usize, end: usize) -> usize {
+    let index = code.partition_point(|instruction| instruction.offset < position);
+    code.get(index).map_or(end, |instruction| instruction.offset)
+}
+
+/// Point every target at an instruction that is still present
+fn resolve_targets(code: &mut [Instruction], end: usize) {
+    let resolved: Vec<Option<usize>> = code.iter().map(|instruction| target(instruction).map(|t| resolve(code, t, end))).collect();
+    for (instruction, resolved) in code.iter_mut().zip(resolved) {
+        if let Some(resolved) = resolved {
+            set_target(instruction, resolved);
+        }
+    }
+}
+
+fn target(instruction: &Instruction) -> Option<usize> {
+    instruction.operands.iter().find_map(|operand| match operand {
+        Operand::Target(target) => Some(*target as usize),
+        _ => None,
+    })
+}
+
+fn set_target(instruction: &mut Instruction, position: usize) {
+    for operand in &mut instruction.operands {
+        if let Operand::Target(target) = operand {
+            *target = position as u32;
+        }
+    }
+}
+
+fn simple(offset: usize, opcode: Opcode) -> Instruction {
+    Instruction { offset, opcode, operands: Vec::new(), size: 1 }
+}
+
+/// The value pushed by an instruction that cannot fail and has no other effect
+fn pushed(program: &BytecodeProgram, instruction: &Instruction) -> Option<Value> {
+    match (&instruction.opcode, instruction.operands.first()) {
+        (Opcode::PushNull, _) => Some(Value::Null),
+        (Opcode::PushBool, Some(Operand::Bool(b))) => Some(Value::Boolean(*b)),
+        (Opcode::PushNumber, Some(Operand::Number(n))) => Some(Value::Number(*n)),
+        (Opcode::PushInteger, Some(Operand::Integer(n))) => Some(Value::Integer(*n)),
+        (Opcode::PushDecimal, Some(Operand::Decimal(d))) => Some(Value::Decimal(*d)),
+        (Opcode::PushString, Some(Operand::Constant(index))) => {
+            program.string_constant(*index as usize).map(|s| Value::String(s.to_string()))
+        }
+        (Opcode::PushConstant, Some(Operand::Constant(index))) => match program.constants.get(*index as usize)? {
+            Constant::String(s) => Some(Value::String(s.clone())),
+            Constant::Integer(n) => Some(Value::Integer(*n)),
+            Constant::Decimal(d) => Some(Value::Decimal(*d)),
+            Constant::Number(n) => Some(Value::Number(*n)),
+            Constant::Bytes(b) => Some(Value::Bytes(b.clone())),
+        },
+        _ => None,
+    }
+}
+
+/// An instruction that pushes a folded value
+fn push(program: &mut BytecodeProgram, offset: usize, value: Value) -> Option<Instruction> {
+    let (opcode, operand) = match value {
+        Value::Boolean(b) => (Opcode::PushBool, Operand::Bool(b)),
+        Value::Integer(n) => (Opcode::PushConstant, Operand::Constant(program.add_constant(Constant::Integer(n)))),
+        Value::Decimal(d) => (Opcode::PushConstant, Operand::Constant(program.add_constant(Constant::Decimal(d)))),
+        Value::Number(n) => (Opcode::PushConstant, Operand::Constant(program.add_constant(Constant::Number(n)))),
+        _ => return None,
+    };
+    let size = 1 + opcode.operand_kinds()[0].size();
+    Some(Instruction { offset, opcode, operands: vec![operand], size })
+}
+
+/// Rewrite short instruction sequences into cheaper equivalents
+///
+/// A sequence is only rewritten when nothing jumps into its middle; its first
+/// instruction may be a target, since control arriving there still runs the
+/// replacement.
+fn peephole(program: &mut BytecodeProgram, code: &mut Vec<Instruction>, entries: &[usize], end: usize) -> bool {
+    let labels: HashSet<usize> = code.iter().filter_map(target).chain(entries.iter().copied()).collect();
+    let index: HashMap<usize, usize> = code.iter().enumerate().map(|(i, instruction)| (instruction.offset, i)).collect();
+    let mut out = Vec::with_capacity(code.len());
+    let mut changed = false;
+    let mut i = 0;
+
+    while i < code.len() {
+        let rest = &code[i..];
+        let unlabelled = |n: usize| rest.len() >= n && rest[1..n].iter().all(|instruction| !labels.contains(&instruction.offset));
+        let next = rest.get(1).map_or(end, |instruction| instruction.offset);
+        let first = &rest[0];
+        let value = pushed(program, first);
+        let folded_binary = || {
+            let (second, op) = (rest.get(1)?, rest.get(2)?);
+            let result = binary(&op.opcode, value.as_ref()?, &pushed(program, second)?)?;
+            unlabelled(3).then_some((3, result))
+        };
+        let folded_not = || {
+            let operand = value.as_ref()?;
+            (unlabelled(2) && rest[1].opcode == Opcode::Not).then(|| (2, Value::Boolean(!operand.is_truthy())))
+        };
+        let folded = folded_binary().or_else(folded_not);
+
+        // Push a; Push b; Add  →  Push (a + b), and likewise for other operators
+        if let Some((n, instruction)) = folded.and_then(|(n, result)| Some((n, push(program, first.offset, result)?))) {
+            out.push(instruction);
+            i += n;
+        }
+        // A constant condition either always jumps or never does
+        else if unlabelled(2) && value.is_some() && rest[1].opcode == Opcode::JumpIfFalse {
+            if !value.is_some_and(|v| v.is_truthy()) {
+                out.push(Instruction { offset: first.offset, opcode: Opcode::Jump, ..rest[1].clone() });
+            }
+            i += 2;
+        }
+        // StoreVar x; LoadVar x  →  Dup; StoreVar x
+        else if unlabelled(2)
+            && first.opcode == Opcode::StoreVar
+            && rest[1].opcode == Opcode::LoadVar
+            && first.operands == rest[1].operands
+        {
+            out.push(simple(first.offset, Opcode::Dup));
+            out.push(Instruction { offset: rest[1].offset, ..first.clone() });
+            i += 2;
+        }
+        // Dup; StoreVar x; Pop  →  StoreVar x
+        else if unlabelled(3)
+            && first.opcode == Opcode::Dup
+            && rest[1].opcode == Opcode::StoreVar
+            && rest[2].opcode == Opcode::Pop
+        {
+            out.push(Instruction { offset: first.offset, ..rest[1].clone() });
+            i += 3;
+        }
+        // A value pushed only to be popped
+        else if unlabelled(2) && value.is_some() && rest[1].opcode == Opcode::Pop {
+            i += 2;
+        }
+        // Jumps to the next instruction: the condition is still popped
+        else if target(first) == Some(next) && matches!(first.opcode, Opcode::Jump | Opcode::JumpIfNull | Opcode::JumpIfFalse) {
+            if first.opcode == Opcode::JumpIfFalse {
+                out.push(simple(first.offset, Opcode::Pop));
+            }
+            i += 1;
+        }
+        // Jumps to an unconditional jump go straight to its target
+        else if let Some(through) = matches!(first.opcode, Opcode::Jump | Opcode::JumpIfNull | Opcode::JumpIfFalse)
+            .then(|| target(first))
+            .flatten()
+            .and_then(|t| index.get(&t))
+            .map(|&t| &code[t])
+            .filter(|landing| landing.opcode == Opcode::Jump)
+            .and_then(target)
+            .filter(|&through| Some(through) != target(first))
+        {
+            let mut jump = first.clone();
+            set_target(&mut jump, through);
+            out.push(jump);
+            i += 1;
+        } else {
+            out.push(first.clone());
+            i += 1;
+            continue;
+        }
+        changed = true;
+    }
+
+    *code = out;
+    changed
+}
+
+/// Drop instructions that no path from the entry point or a function entry reaches
+fn remove_unreachable(code: &mut Vec<Instruction>, entries: &[usize], end: usize) -> bool {
+    let index: HashMap<usize, usize> = code.iter().enumerate().map(|(i, instruction)| (instruction.offset, i)).collect();
+    let mut reachable = vec![false; code.len()];
+    let mut pending: Vec<usize> = entries.iter().copied().chain(code.first().map(|i| i.offset)).collect();
+
+    while let Some(offset) = pending.pop() {
+        let Some(&i) = index.get(&offset) else { continue };
+        if reachable[i] {
+            continue;
+        }
+        reachable[i] = true;
+        let instruction = &code[i];
+        let next = code.get(i + 1).map_or(end, |next| next.offset);
+
+        match instruction.opcode {
+            Opcode::End | Opcode::Halt | Opcode::Return | Opcode::ExitProgram => {}
+            Opcode::Jump | Opcode::LoopEnd => pending.extend(target(instruction)),
+            Opcode::ForEach => {
+                // A null collection skips past the matching LoopEnd
+                pending.extend(loop_exit(code, i, end));
+                pending.push(next);
+            }
+            _ => {
+                // Conditional jumps, and the exits recorded by LoopStart and TryStart
+                pending.extend(target(instruction));
+                pending.push(next);
+            }
+        }
+    }
+
+    let before = code.len();
+    let mut keep = reachable.into_iter();
+    code.retain(|_| keep.next().unwrap_or(true));
+    code.len() != before
+}
+
+/// Position after the LoopEnd that closes the ForEach at index `start`
+fn loop_exit(code: &[Instruction], start: usize, end: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, instruction) in code.iter().enumerate().skip(start) {
+        match instruction.opcode {
+            Opcode::ForEach | Opcode::LoopStart => depth += 1,
+            Opcode::LoopEnd => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(code.get(i + 1).map_or(end, |next| next.offset));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Encode the optimized instructions back into the program
+fn rebuild(program: &mut BytecodeProgram, code: &[Instruction]) {
+    let end = program.code.len();
+
+    // New position of each remaining instruction, then of the end
+    let mut positions = Vec::with_capacity(code.len() + 1);
+    let mut position = 0;
+    for instruction in code {
+        positions.push(position);
+        position += 1 + instruction.opcode.operand_kinds().iter().map(|kind| kind.size()).sum::<usize>();
+    }
+    positions.push(position);
+    let relocate = |old: usize| {
+        let index = if old >= end {
+            code.len()
+        } else {
+            code.partition_point(|instruction| instruction.offset < old)
+        };
+        positions[index] as u32
+    };
+
+    // Keep only the constants still in use, in their original order
+    let mut used: Vec<u32> = code
+        .iter()
+        .flat_map(|instruction| instruction.operands.iter())
+        .filter_map(|operand| match operand {
+            Operand::Constant(index) => Some(*index),
+            _ => None,
+        })
+        .collect();
+    used.sort_unstable();
+    used.dedup();
+    let compact = used.iter().all(|&index| (index as usize) < program.constants.len());
+    let constants: Vec<Constant> = if compact {
+        used.iter().map(|&index| program.constants[index as usize].clone()).collect()
+    } else {
+        program.constants.clone()
+    };
+    let constant = |index: u32| if compact { used.binary_search(&index).unwrap_or(0) as u32 } else { index };
+
+    let mut optimized = BytecodeProgram::new();
+    for instruction in code {
+        optimized.emit_opcode(instruction.opcode.clone());
+        for operand in &instruction.operands {
+            optimized.emit_operand(&match operand {
+                Operand::Target(target) => Operand::Target(relocate(*target as usize)),
+                Operand::Constant(index) => Operand::Constant(constant(*index)),
+                other => other.clone(),
+            });
+        }
+    }
+
+    let mut lines: Vec<(u32, _)> = Vec::with_capacity(program.lines.len());
+    for (position, span) in &program.lines {
+        let position = relocate(*position as usize);
+        match lines.last_mut() {
+            // Code for the earlier statement was optimized away
+            Some((last, last_span)) if *last == position => *last_span = *span,
+            Some((_, last_span)) if last_span == span => {}
+            _ => lines.push((position, *span)),
+        }
+    }
+
+    for (_, entry) in &mut program.functions {
+        *entry = relocate(*entry as usize);
+    }
+    program.code = optimized.code;
+    program.constants = constants;
+    program.lines = lines;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+    use crate::vm::VM;
+
+    fn compile(source: &str, level: OptLevel) -> BytecodeProgram {
+        let mut compiler = Compiler::new();
+        compiler.set_opt_level(level);
+        compiler.compile_source(source).unwrap()
+    }
+
+    fn opcodes(program: &BytecodeProgram) -> Vec<Opcode> {
+        decode_all(program).unwrap().into_iter().map(|i| i.opcode).collect()
+    }
+
+    fn run(program: BytecodeProgram) -> String {
+        match VM::new(program).execute() {
+            Ok(value) => format!("{:?}", value),
+            Err(err) => format!("error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        let program = compile("📤((2 + 3) * 4)", OptLevel::O1);
+        assert_eq!(opcodes(&program), vec![Opcode::PushConstant, Opcode::Output, Opcode::End]);
+        assert_eq!(program.constants, vec![Constant::Integer(20)]);
+
+        // Decimal results stay exact and failing expressions are left for the VM
+        assert_eq!(compile("7 / 2", OptLevel::O1).constants, vec![Constant::Decimal(crate::numeric::Decimal::new(35, 1))]);
+        let unfolded = compile("1 / 0", OptLevel::O1);
+        assert!(opcodes(&unfolded).contains(&Opcode::Div));
+        assert_eq!(run(unfolded), run(compile("1 / 0", OptLevel::O0)));
+    }
+
+    #[test]
+    fn test_constant_conditions_keep_one_branch() {
+        let program = compile("◇(2 > 1): 📤\"yes\" ◆: 📤\"no\"", OptLevel::O1);
+        assert_eq!(opcodes(&program), vec![Opcode::PushString, Opcode::Output, Opcode::End]);
+        assert_eq!(program.constants, vec![Constant::from("yes")]);
+
+        let program = compile("◇(1 ≡ 2): 📤\"never\"", OptLevel::O1);
+        assert_eq!(opcodes(&program), vec![Opcode::End]);
+        assert_eq!(program.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_unused_stores_removed_at_o2() {
+        let source = "5 ▷ unused ⨠ 6 ▷ used ⨠ 📤used";
+        let o1 = compile(source, OptLevel::O1);
+        assert_eq!(o1.constants.iter().filter(|c| c.as_str() == Some("unused")).count(), 1);
+        let o2 = compile(source, OptLevel::O2);
+        assert!(o2.constants.iter().all(|c| c.as_str() != Some("unused")));
+        assert!(o2.constants.contains(&Constant::from("used")));
+    }
+
+    #[test]
+    fn test_peephole_reuses_stored_value() {
+        let mut program = BytecodeProgram::assemble("
+            PushConstant integer(1)
+            StoreVar \"x\"
+            LoadVar \"x\"
+            Output
+            PushNull
+            Pop
+            Jump L0
+        L0:
+            End
+        ").unwrap();
+        optimize_bytecode(&mut program, OptLevel::O1);
+        assert_eq!(
+            opcodes(&program),
+            vec![Opcode::PushConstant, Opcode::Dup, Opcode::StoreVar, Opcode::Output, Opcode::End]
+        );
+    }
+
+    #[test]
+    fn test_peephole_folds_piped_and_boolean_constants() {
+        let program = compile("2 ⇢ ↑10 ▷ p ⨠ 📤p", OptLevel::O1);
+        assert!(program.constants.contains(&Constant::Integer(1024)));
+        assert!(!program.constants.contains(&Constant::Integer(2)));
+        assert_eq!(opcodes(&program)[..2], [Opcode::PushConstant, Opcode::Dup]);
+
+        let mut program = BytecodeProgram::assemble("
+            PushConstant integer(10)
+            PushConstant integer(5)
+            GreaterThan
+            Not
+            JumpIfFalse L0
+            PushString \"skipped\"
+        L0:
+            End
+        ").unwrap();
+        optimize_bytecode(&mut program, OptLevel::O2);
+        assert_eq!(opcodes(&program), vec![Opcode::End]);
+        assert!(program.constants.is_empty());
+    }
+
+    #[test]
+    fn test_peephole_respects_jump_targets() {
+        // The LoadVar is a jump target, so the pair must stay as written
+        let source = "
+            LoadVar \"c\"
+            JumpIfFalse L0
+            PushConstant integer(1)
+            StoreVar \"x\"
+        L0:
+            LoadVar \"x\"
+            End
+        ";
+        let mut program = BytecodeProgram::assemble(source).unwrap();
+        optimize_bytecode(&mut program, OptLevel::O2);
+        assert_eq!(program.code, BytecodeProgram::assemble(source).unwrap().code);
+    }
+
+    #[test]
+    fn test_unreachable_code_after_halt_removed_at_o2() {
+        let source = "🛑400 ⨠ 📤\"unreachable\"";
+        let o2 = compile(source, OptLevel::O2);
+        assert_eq!(opcodes(&o2), vec![Opcode::PushConstant, Opcode::Halt]);
+        assert_eq!(run(o2), run(compile(source, OptLevel::O0)));
+        assert!(opcodes(&compile(source, OptLevel::O1)).contains(&Opcode::Output));
+    }
+
+    #[test]
+    fn test_optimized_programs_behave_the_same() {
+        let sources = [
+            "5 ▷ x ⨠ ◇(x > 1): 📤\"big\" ⨠ [1, 2] ⇢ ∀i: 📤i ⨠ ↻(x < 10): (x + 1 ▷ x)",
+            "ƒ double: 📤(2 * 21) ⨠ 2 ⇢ ↑10 ▷ p ⨠ 📤p",
+            "◇(0): 📤\"no\" ◆: 📤\"yes\" ⨠ 1.5 + 2 ▷ y ⨠ 📤y ⨠ 🛑(10 % 4)",
+            // Interpolation reads `name`, so its store must stay
+            "\"Ada\" ▷ name ⨠ 📤\"hi {name}\"",
+        ];
+        for source in sources {
+            let expected = run(compile(source, OptLevel::O0));
+            for level in [OptLevel::O1, OptLevel::O2] {
+                let program = compile(source, level);
+                assert!(crate::verifier::verify(&program).is_ok(), "{} at {:?}", source, level);
+                assert_eq!(run(program), expected, "{} at {:?}", source, level);
+            }
+        }
+    }
+}
@@ -3,7 +3,9 @@
 use crate::constants::PIPE_VARIABLE;
 use crate::error::{AetherError, Result};
 use crate::lexer::{Token, TokenType};
+use crate::numeric::Decimal;
 use crate::symbols::Symbol;
+use serde_json::{json, Value as Json};
 
 /// AST Node representing Aether code structure
 #[derive(Debug, Clone, PartialEq)]
@@ -29,10 +31,11 @@ pub enum AstNode {
         operation: Box<AstNode>,
     },
 
-    /// PipeInto: value -> variable name
+    /// PipeInto: value -> variable name, with an optional type (`▷ u:S`)
     PipeInto {
         value: Box<AstNode>,
         variable: String,
+        annotation: Option<TypeAnnotation>,
     },
 
     /// Guard (null check) with alternative
@@ -381,13 +384,29 @@ pub enum AstNode {
     /// Create stream
     CreateStream {
         source: Box<AstNode>,
+        /// Read mode: "lines", a chunk size or a buffer (Empty for default chunks)
+        options: Box<AstNode>,
     },
     
-    /// Buffer data
+    /// Buffer data: zeroed bytes of a size, or a value copied into bytes
     CreateBuffer {
         size: Box<AstNode>,
     },
     
+    /// Convert to bytes, decoding strings with an encoding (value ⇢ 🧱"hex")
+    ToBytes {
+        value: Box<AstNode>,
+        /// Encoding name (Empty for utf8)
+        encoding: Box<AstNode>,
+    },
+    
+    /// Convert to a string, encoding bytes with an encoding (value ⇢ S"hex")
+    ToText {
+        value: Box<AstNode>,
+        /// Encoding name (Empty for utf8)
+        encoding: Box<AstNode>,
+    },
+    
     /// Flush buffer
     FlushBuffer {
         target: Box<AstNode>,
@@ -556,6 +575,527 @@ pub enum AstNode {
     },
 }
 
+impl AstNode {
+    /// The node's direct sub-expressions, in source order
+    pub fn children(&self) -> Vec<&AstNode> {
+        match self {
+            AstNode::Sequence(nodes) => nodes.iter().collect(),
+            AstNode::ArrayLiteral { elements } => elements.iter().collect(),
+            AstNode::ObjectLiteral { pairs } => pairs.iter().map(|(_, value)| value).collect(),
+            AstNode::Function { body, .. }
+            | AstNode::Retry { body, .. }
+            | AstNode::Async { body }
+            | AstNode::Thread { body }
+            | AstNode::Lock { body }
+            | AstNode::Test { body, .. }
+            | AstNode::Benchmark { body } => vec![body.as_ref()],
+            AstNode::Output(node)
+            | AstNode::Halt(node)
+            | AstNode::Persist(node)
+            | AstNode::JsonParse(node) => vec![node.as_ref()],
+            AstNode::Pipe { source, operation } => vec![source.as_ref(), operation.as_ref()],
+            AstNode::PipeInto { value, .. }
+            | AstNode::Immutable { value, .. }
+            | AstNode::Root { value }
+            | AstNode::Delta { value, .. }
+            | AstNode::Length { value } => vec![value.as_ref()],
+            AstNode::Guard { condition, then_branch } => vec![condition.as_ref(), then_branch.as_ref()],
+            AstNode::IfThen { condition, then_branch, else_branch } => {
+                [Some(condition.as_ref()), Some(then_branch.as_ref()), else_branch.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::Loop { condition, body } => {
+                [condition.as_deref(), Some(body.as_ref())].into_iter().flatten().collect()
+            }
+            AstNode::ForEach { collection, body, .. } => vec![collection.as_ref(), body.as_ref()],
+            AstNode::Filter { predicate } => vec![predicate.as_ref()],
+            AstNode::Reduce { operation, initial } => vec![operation.as_ref(), initial.as_ref()],
+            AstNode::TryRescue { try_body, rescue_body } => {
+                [Some(try_body.as_ref()), rescue_body.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::Await { expression } => vec![expression.as_ref()],
+            AstNode::Emit { event } => vec![event.as_ref()],
+            AstNode::Watch { event, handler } => vec![event.as_ref(), handler.as_ref()],
+            AstNode::Split { target, delimiter } => {
+                [Some(target.as_ref()), delimiter.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::Join { elements, separator } => {
+                [Some(elements.as_ref()), separator.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::RegexMatch { pattern, target } => vec![pattern.as_ref(), target.as_ref()],
+            AstNode::Equal { left, right }
+            | AstNode::NotEqual { left, right }
+            | AstNode::And { left, right }
+            | AstNode::Or { left, right }
+            | AstNode::Approx { left, right }
+            | AstNode::Comparison { left, right, .. }
+            | AstNode::Add { left, right }
+            | AstNode::Subtract { left, right }
+            | AstNode::StringConcat { left, right }
+            | AstNode::Multiply { left, right }
+            | AstNode::Divide { left, right }
+            | AstNode::Modulo { left, right } => vec![left.as_ref(), right.as_ref()],
+            AstNode::Not { operand } => vec![operand.as_ref()],
+            AstNode::Auth { token } => vec![token.as_ref()],
+            AstNode::Log { message } => vec![message.as_ref()],
+            AstNode::HttpGet { url, headers }
+            | AstNode::HttpDelete { url, headers }
+            | AstNode::HttpHead { url, headers }
+            | AstNode::HttpOptions { url, headers } => {
+                [Some(url.as_ref()), headers.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::HttpPost { url, body, headers }
+            | AstNode::HttpPut { url, body, headers }
+            | AstNode::HttpPatch { url, body, headers } => {
+                [Some(url.as_ref()), body.as_deref(), headers.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::Assert { condition } => vec![condition.as_ref()],
+            AstNode::Mock { target }
+            | AstNode::DeleteFile { target }
+            | AstNode::FlushBuffer { target } => vec![target.as_ref()],
+            AstNode::Encrypt { data, key }
+            | AstNode::Decrypt { data, key }
+            | AstNode::Sign { data, key } => vec![data.as_ref(), key.as_ref()],
+            AstNode::Hash { data }
+            | AstNode::CreatePacket { data } => vec![data.as_ref()],
+            AstNode::VerifySignature { signature, data, key } => vec![signature.as_ref(), data.as_ref(), key.as_ref()],
+            AstNode::Power { base, exponent } => vec![base.as_ref(), exponent.as_ref()],
+            AstNode::FileHandle { path }
+            | AstNode::Directory { path }
+            | AstNode::PathResolve { path } => vec![path.as_ref()],
+            AstNode::ReadContent { source } => vec![source.as_ref()],
+            AstNode::WriteContent { target, content }
+            | AstNode::AppendContent { target, content } => vec![target.as_ref(), content.as_ref()],
+            AstNode::SetPermission { target, permission } => vec![target.as_ref(), permission.as_ref()],
+            AstNode::CreateStream { source, options } => vec![source.as_ref(), options.as_ref()],
+            AstNode::CreateBuffer { size }
+            | AstNode::MemoryAlloc { size } => vec![size.as_ref()],
+            AstNode::ToBytes { value, encoding }
+            | AstNode::ToText { value, encoding } => vec![value.as_ref(), encoding.as_ref()],
+            AstNode::SkipBytes { source, count } => vec![source.as_ref(), count.as_ref()],
+            AstNode::CreateSocket { socket_type } => vec![socket_type.as_ref()],
+            AstNode::ListenPort { port } => vec![port.as_ref()],
+            AstNode::ConnectRemote { address } => vec![address.as_ref()],
+            AstNode::PortNumber { number } => vec![number.as_ref()],
+            AstNode::Handshake { connection } => vec![connection.as_ref()],
+            AstNode::ProcessCreate { command }
+            | AstNode::ShellExec { command } => vec![command.as_ref()],
+            AstNode::EnvVar { name } => vec![name.as_ref()],
+            AstNode::ExitProgram { code } => vec![code.as_ref()],
+            AstNode::SendSignal { signal, target } => vec![signal.as_ref(), target.as_ref()],
+            AstNode::PropertyAccess { object, .. } => vec![object.as_ref()],
+            AstNode::Index { target, index }
+            | AstNode::CharAt { target, index } => vec![target.as_ref(), index.as_ref()],
+            AstNode::ArrayPush { array, element } => vec![array.as_ref(), element.as_ref()],
+            AstNode::Slice { target, start, end } => {
+                [Some(target.as_ref()), Some(start.as_ref()), end.as_deref()].into_iter().flatten().collect()
+            }
+            AstNode::Input
+            | AstNode::Variable(_)
+            | AstNode::Literal(_)
+            | AstNode::Empty
+            | AstNode::Import { .. }
+            | AstNode::DateTime
+            | AstNode::Random
+            | AstNode::Debug
+            | AstNode::Infinity
+            | AstNode::EndOfFile => Vec::new(),
+        }
+    }
+
+    /// Mutable access to the node's direct sub-expressions, in the order of [`children`](Self::children)
+    pub fn children_mut(&mut self) -> Vec<&mut AstNode> {
+        match self {
+            AstNode::Sequence(nodes) => nodes.iter_mut().collect(),
+            AstNode::ArrayLiteral { elements } => elements.iter_mut().collect(),
+            AstNode::ObjectLiteral { pairs } => pairs.iter_mut().map(|(_, value)| value).collect(),
+            AstNode::Function { body, .. }
+            | AstNode::Retry { body, .. }
+            | AstNode::Async { body }
+            | AstNode::Thread { body }
+            | AstNode::Lock { body }
+            | AstNode::Test { body, .. }
+            | AstNode::Benchmark { body } => vec![body.as_mut()],
+            AstNode::Output(node)
+            | AstNode::Halt(node)
+            | AstNode::Persist(node)
+            | AstNode::JsonParse(node) => vec![node.as_mut()],
+            AstNode::Pipe { source, operation } => vec![source.as_mut(), operation.as_mut()],
+            AstNode::PipeInto { value, .. }
+            | AstNode::Immutable { value, .. }
+            | AstNode::Root { value }
+            | AstNode::Delta { value, .. }
+            | AstNode::Length { value } => vec![value.as_mut()],
+            AstNode::Guard { condition, then_branch } => vec![condition.as_mut(), then_branch.as_mut()],
+            AstNode::IfThen { condition, then_branch, else_branch } => {
+                [Some(condition.as_mut()), Some(then_branch.as_mut()), else_branch.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::Loop { condition, body } => {
+                [condition.as_deref_mut(), Some(body.as_mut())].into_iter().flatten().collect()
+            }
+            AstNode::ForEach { collection, body, .. } => vec![collection.as_mut(), body.as_mut()],
+            AstNode::Filter { predicate } => vec![predicate.as_mut()],
+            AstNode::Reduce { operation, initial } => vec![operation.as_mut(), initial.as_mut()],
+            AstNode::TryRescue { try_body, rescue_body } => {
+                [Some(try_body.as_mut()), rescue_body.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::Await { expression } => vec![expression.as_mut()],
+            AstNode::Emit { event } => vec![event.as_mut()],
+            AstNode::Watch { event, handler } => vec![event.as_mut(), handler.as_mut()],
+            AstNode::Split { target, delimiter } => {
+                [Some(target.as_mut()), delimiter.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::Join { elements, separator } => {
+                [Some(elements.as_mut()), separator.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::RegexMatch { pattern, target } => vec![pattern.as_mut(), target.as_mut()],
+            AstNode::Equal { left, right }
+            | AstNode::NotEqual { left, right }
+            | AstNode::And { left, right }
+            | AstNode::Or { left, right }
+            | AstNode::Approx { left, right }
+            | AstNode::Comparison { left, right, .. }
+            | AstNode::Add { left, right }
+            | AstNode::Subtract { left, right }
+            | AstNode::StringConcat { left, right }
+            | AstNode::Multiply { left, right }
+            | AstNode::Divide { left, right }
+            | AstNode::Modulo { left, right } => vec![left.as_mut(), right.as_mut()],
+            AstNode::Not { operand } => vec![operand.as_mut()],
+            AstNode::Auth { token } => vec![token.as_mut()],
+            AstNode::Log { message } => vec![message.as_mut()],
+            AstNode::HttpGet { url, headers }
+            | AstNode::HttpDelete { url, headers }
+            | AstNode::HttpHead { url, headers }
+            | AstNode::HttpOptions { url, headers } => {
+                [Some(url.as_mut()), headers.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::HttpPost { url, body, headers }
+            | AstNode::HttpPut { url, body, headers }
+            | AstNode::HttpPatch { url, body, headers } => {
+                [Some(url.as_mut()), body.as_deref_mut(), headers.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::Assert { condition } => vec![condition.as_mut()],
+            AstNode::Mock { target }
+            | AstNode::DeleteFile { target }
+            | AstNode::FlushBuffer { target } => vec![target.as_mut()],
+            AstNode::Encrypt { data, key }
+            | AstNode::Decrypt { data, key }
+            | AstNode::Sign { data, key } => vec![data.as_mut(), key.as_mut()],
+            AstNode::Hash { data }
+            | AstNode::CreatePacket { data } => vec![data.as_mut()],
+            AstNode::VerifySignature { signature, data, key } => vec![signature.as_mut(), data.as_mut(), key.as_mut()],
+            AstNode::Power { base, exponent } => vec![base.as_mut(), exponent.as_mut()],
+            AstNode::FileHandle { path }
+            | AstNode::Directory { path }
+            | AstNode::PathResolve { path } => vec![path.as_mut()],
+            AstNode::ReadContent { source } => vec![source.as_mut()],
+            AstNode::WriteContent { target, content }
+            | AstNode::AppendContent { target, content } => vec![target.as_mut(), content.as_mut()],
+            AstNode::SetPermission { target, permission } => vec![target.as_mut(), permission.as_mut()],
+            AstNode::CreateStream { source, options } => vec![source.as_mut(), options.as_mut()],
+            AstNode::CreateBuffer { size }
+            | AstNode::MemoryAlloc { size } => vec![size.as_mut()],
+            AstNode::ToBytes { value, encoding }
+            | AstNode::ToText { value, encoding } => vec![value.as_mut(), encoding.as_mut()],
+            AstNode::SkipBytes { source, count } => vec![source.as_mut(), count.as_mut()],
+            AstNode::CreateSocket { socket_type } => vec![socket_type.as_mut()],
+            AstNode::ListenPort { port } => vec![port.as_mut()],
+            AstNode::ConnectRemote { address } => vec![address.as_mut()],
+            AstNode::PortNumber { number } => vec![number.as_mut()],
+            AstNode::Handshake { connection } => vec![connection.as_mut()],
+            AstNode::ProcessCreate { command }
+            | AstNode::ShellExec { command } => vec![command.as_mut()],
+            AstNode::EnvVar { name } => vec![name.as_mut()],
+            AstNode::ExitProgram { code } => vec![code.as_mut()],
+            AstNode::SendSignal { signal, target } => vec![signal.as_mut(), target.as_mut()],
+            AstNode::PropertyAccess { object, .. } => vec![object.as_mut()],
+            AstNode::Index { target, index }
+            | AstNode::CharAt { target, index } => vec![target.as_mut(), index.as_mut()],
+            AstNode::ArrayPush { array, element } => vec![array.as_mut(), element.as_mut()],
+            AstNode::Slice { target, start, end } => {
+                [Some(target.as_mut()), Some(start.as_mut()), end.as_deref_mut()].into_iter().flatten().collect()
+            }
+            AstNode::Input
+            | AstNode::Variable(_)
+            | AstNode::Literal(_)
+            | AstNode::Empty
+            | AstNode::Import { .. }
+            | AstNode::DateTime
+            | AstNode::Random
+            | AstNode::Debug
+            | AstNode::Infinity
+            | AstNode::EndOfFile => Vec::new(),
+        }
+    }
+
+    /// Name of the node's variant in snake case, such as `http_get`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AstNode::Function { .. } => "function",
+            AstNode::Sequence(_) => "sequence",
+            AstNode::Input => "input",
+            AstNode::Output(_) => "output",
+            AstNode::Pipe { .. } => "pipe",
+            AstNode::PipeInto { .. } => "pipe_into",
+            AstNode::Guard { .. } => "guard",
+            AstNode::Halt(_) => "halt",
+            AstNode::Persist(_) => "persist",
+            AstNode::JsonParse(_) => "json_parse",
+            AstNode::Variable(_) => "variable",
+            AstNode::Literal(_) => "literal",
+            AstNode::Empty => "empty",
+            AstNode::IfThen { .. } => "if_then",
+            AstNode::Loop { .. } => "loop",
+            AstNode::ForEach { .. } => "for_each",
+            AstNode::Filter { .. } => "filter",
+            AstNode::Reduce { .. } => "reduce",
+            AstNode::TryRescue { .. } => "try_rescue",
+            AstNode::Retry { .. } => "retry",
+            AstNode::Async { .. } => "async",
+            AstNode::Await { .. } => "await",
+            AstNode::Thread { .. } => "thread",
+            AstNode::Lock { .. } => "lock",
+            AstNode::Emit { .. } => "emit",
+            AstNode::Watch { .. } => "watch",
+            AstNode::Split { .. } => "split",
+            AstNode::Join { .. } => "join",
+            AstNode::RegexMatch { .. } => "regex_match",
+            AstNode::Equal { .. } => "equal",
+            AstNode::NotEqual { .. } => "not_equal",
+            AstNode::And { .. } => "and",
+            AstNode::Or { .. } => "or",
+            AstNode::Not { .. } => "not",
+            AstNode::Immutable { .. } => "immutable",
+            AstNode::Import { .. } => "import",
+            AstNode::Auth { .. } => "auth",
+            AstNode::DateTime => "date_time",
+            AstNode::Random => "random",
+            AstNode::Log { .. } => "log",
+            AstNode::HttpGet { .. } => "http_get",
+            AstNode::HttpPost { .. } => "http_post",
+            AstNode::HttpPut { .. } => "http_put",
+            AstNode::HttpDelete { .. } => "http_delete",
+            AstNode::HttpPatch { .. } => "http_patch",
+            AstNode::HttpHead { .. } => "http_head",
+            AstNode::HttpOptions { .. } => "http_options",
+            AstNode::Test { .. } => "test",
+            AstNode::Assert { .. } => "assert",
+            AstNode::Mock { .. } => "mock",
+            AstNode::Benchmark { .. } => "benchmark",
+            AstNode::Debug => "debug",
+            AstNode::Encrypt { .. } => "encrypt",
+            AstNode::Decrypt { .. } => "decrypt",
+            AstNode::Hash { .. } => "hash",
+            AstNode::Sign { .. } => "sign",
+            AstNode::VerifySignature { .. } => "verify_signature",
+            AstNode::Power { .. } => "power",
+            AstNode::Root { .. } => "root",
+            AstNode::Approx { .. } => "approx",
+            AstNode::Infinity => "infinity",
+            AstNode::Delta { .. } => "delta",
+            AstNode::FileHandle { .. } => "file_handle",
+            AstNode::Directory { .. } => "directory",
+            AstNode::PathResolve { .. } => "path_resolve",
+            AstNode::ReadContent { .. } => "read_content",
+            AstNode::WriteContent { .. } => "write_content",
+            AstNode::AppendContent { .. } => "append_content",
+            AstNode::DeleteFile { .. } => "delete_file",
+            AstNode::SetPermission { .. } => "set_permission",
+            AstNode::CreateStream { .. } => "create_stream",
+            AstNode::CreateBuffer { .. } => "create_buffer",
+            AstNode::ToBytes { .. } => "to_bytes",
+            AstNode::ToText { .. } => "to_text",
+            AstNode::FlushBuffer { .. } => "flush_buffer",
+            AstNode::EndOfFile => "end_of_file",
+            AstNode::SkipBytes { .. } => "skip_bytes",
+            AstNode::CreateSocket { .. } => "create_socket",
+            AstNode::ListenPort { .. } => "listen_port",
+            AstNode::ConnectRemote { .. } => "connect_remote",
+            AstNode::PortNumber { .. } => "port_number",
+            AstNode::CreatePacket { .. } => "create_packet",
+            AstNode::Handshake { .. } => "handshake",
+            AstNode::ProcessCreate { .. } => "process_create",
+            AstNode::ShellExec { .. } => "shell_exec",
+            AstNode::EnvVar { .. } => "env_var",
+            AstNode::MemoryAlloc { .. } => "memory_alloc",
+            AstNode::ExitProgram { .. } => "exit_program",
+            AstNode::SendSignal { .. } => "send_signal",
+            AstNode::PropertyAccess { .. } => "property_access",
+            AstNode::Comparison { .. } => "comparison",
+            AstNode::Length { .. } => "length",
+            AstNode::Index { .. } => "index",
+            AstNode::ArrayPush { .. } => "array_push",
+            AstNode::Add { .. } => "add",
+            AstNode::Subtract { .. } => "subtract",
+            AstNode::StringConcat { .. } => "string_concat",
+            AstNode::Multiply { .. } => "multiply",
+            AstNode::Divide { .. } => "divide",
+            AstNode::Modulo { .. } => "modulo",
+            AstNode::CharAt { .. } => "char_at",
+            AstNode::Slice { .. } => "slice",
+            AstNode::ArrayLiteral { .. } => "array_literal",
+            AstNode::ObjectLiteral { .. } => "object_literal",
+        }
+    }
+
+    /// The node as JSON: its `kind`, and its fields by name with
+    /// sub-expressions as nodes and missing optional ones as `null`
+    pub fn to_json(&self) -> Json {
+        let node = |node: &AstNode| node.to_json();
+        let optional = |node: &Option<Box<AstNode>>| node.as_deref().map_or(Json::Null, AstNode::to_json);
+        let mut fields = match self {
+            AstNode::Function { name, body } => json!({ "name": name, "body": node(body) }),
+            AstNode::Sequence(nodes) => json!({ "steps": nodes.iter().map(node).collect::<Vec<_>>() }),
+            AstNode::Output(value)
+            | AstNode::Halt(value)
+            | AstNode::Persist(value)
+            | AstNode::JsonParse(value) => json!({ "value": node(value) }),
+            AstNode::Pipe { source, operation } => json!({ "source": node(source), "operation": node(operation) }),
+            AstNode::PipeInto { value, variable, annotation } => json!({
+                "value": node(value),
+                "variable": variable,
+                "annotation": annotation.map(|annotation| match annotation {
+                    TypeAnnotation::String => "string",
+                    TypeAnnotation::Number => "number",
+                }),
+            }),
+            AstNode::Guard { condition, then_branch } => {
+                json!({ "condition": node(condition), "then_branch": node(then_branch) })
+            }
+            AstNode::Variable(name) => json!({ "name": name }),
+            AstNode::Literal(literal) => match literal {
+                LiteralValue::String(s) => json!({ "type": "string", "value": s }),
+                LiteralValue::Number(n) => json!({ "type": "number", "value": n }),
+                LiteralValue::Integer(i) => json!({ "type": "integer", "value": i }),
+                LiteralValue::Decimal(d) => json!({ "type": "decimal", "value": d.to_string() }),
+                LiteralValue::Bytes(b) => json!({ "type": "bytes", "value": b }),
+            },
+            AstNode::IfThen { condition, then_branch, else_branch } => json!({
+                "condition": node(condition),
+                "then_branch": node(then_branch),
+                "else_branch": optional(else_branch),
+            }),
+            AstNode::Loop { condition, body } => json!({ "condition": optional(condition), "body": node(body) }),
+            AstNode::ForEach { variable, collection, body } => {
+                json!({ "variable": variable, "collection": node(collection), "body": node(body) })
+            }
+            AstNode::Filter { predicate } => json!({ "predicate": node(predicate) }),
+            AstNode::Reduce { operation, initial } => json!({ "operation": node(operation), "initial": node(initial) }),
+            AstNode::TryRescue { try_body, rescue_body } => {
+                json!({ "try_body": node(try_body), "rescue_body": optional(rescue_body) })
+            }
+            AstNode::Retry { max_attempts, body } => json!({ "max_attempts": max_attempts, "body": node(body) }),
+            AstNode::Async { body }
+            | AstNode::Thread { body }
+            | AstNode::Lock { body }
+            | AstNode::Benchmark { body } => json!({ "body": node(body) }),
+            AstNode::Test { name, body } => json!({ "name": name, "body": node(body) }),
+            AstNode::Await { expression } => json!({ "expression": node(expression) }),
+            AstNode::Emit { event } => json!({ "event": node(event) }),
+            AstNode::Watch { event, handler } => json!({ "event": node(event), "handler": node(handler) }),
+            AstNode::Split { target, delimiter } => json!({ "target": node(target), "delimiter": optional(delimiter) }),
+            AstNode::Join { elements, separator } => json!({ "elements": node(elements), "separator": optional(separator) }),
+            AstNode::RegexMatch { pattern, target } => json!({ "pattern": node(pattern), "target": node(target) }),
+            AstNode::Equal { left, right }
+            | AstNode::NotEqual { left, right }
+            | AstNode::And { left, right }
+            | AstNode::Or { left, right }
+            | AstNode::Approx { left, right }
+            | AstNode::Add { left, right }
+            | AstNode::Subtract { left, right }
+            | AstNode::StringConcat { left, right }
+            | AstNode::Multiply { left, right }
+            | AstNode::Divide { left, right }
+            | AstNode::Modulo { left, right } => json!({ "left": node(left), "right": node(right) }),
+            AstNode::Comparison { left, operator, right } => json!({
+                "left": node(left),
+                "operator": match operator {
+                    ComparisonOp::GreaterThan => ">",
+                    ComparisonOp::LessThan => "<",
+                    ComparisonOp::GreaterEqual => ">=",
+                    ComparisonOp::LessEqual => "<=",
+                },
+                "right": node(right),
+            }),
+            AstNode::Not { operand } => json!({ "operand": node(operand) }),
+            AstNode::Immutable { name, value }
+            | AstNode::Delta { name, value } => json!({ "name": name, "value": node(value) }),
+            AstNode::Import { module } => json!({ "module": module }),
+            AstNode::Auth { token } => json!({ "token": node(token) }),
+            AstNode::Log { message } => json!({ "message": node(message) }),
+            AstNode::HttpGet { url, headers }
+            | AstNode::HttpDelete { url, headers }
+            | AstNode::HttpHead { url, headers }
+            | AstNode::HttpOptions { url, headers } => json!({ "url": node(url), "headers": optional(headers) }),
+            AstNode::HttpPost { url, body, headers }
+            | AstNode::HttpPut { url, body, headers }
+            | AstNode::HttpPatch { url, body, headers } => {
+                json!({ "url": node(url), "body": optional(body), "headers": optional(headers) })
+            }
+            AstNode::Assert { condition } => json!({ "condition": node(condition) }),
+            AstNode::Mock { target }
+            | AstNode::DeleteFile { target }
+            | AstNode::FlushBuffer { target } => json!({ "target": node(target) }),
+            AstNode::Encrypt { data, key }
+            | AstNode::Decrypt { data, key }
+            | AstNode::Sign { data, key } => json!({ "data": node(data), "key": node(key) }),
+            AstNode::Hash { data }
+            | AstNode::CreatePacket { data } => json!({ "data": node(data) }),
+            AstNode::VerifySignature { signature, data, key } => {
+                json!({ "signature": node(signature), "data": node(data), "key": node(key) })
+            }
+            AstNode::Power { base, exponent } => json!({ "base": node(base), "exponent": node(exponent) }),
+            AstNode::Root { value }
+            | AstNode::Length { value } => json!({ "value": node(value) }),
+            AstNode::FileHandle { path }
+            | AstNode::Directory { path }
+            | AstNode::PathResolve { path } => json!({ "path": node(path) }),
+            AstNode::ReadContent { source } => json!({ "source": node(source) }),
+            AstNode::WriteContent { target, content }
+            | AstNode::AppendContent { target, content } => json!({ "target": node(target), "content": node(content) }),
+            AstNode::SetPermission { target, permission } => {
+                json!({ "target": node(target), "permission": node(permission) })
+            }
+            AstNode::CreateStream { source, options } => json!({ "source": node(source), "options": node(options) }),
+            AstNode::CreateBuffer { size }
+            | AstNode::MemoryAlloc { size } => json!({ "size": node(size) }),
+            AstNode::ToBytes { value, encoding }
+            | AstNode::ToText { value, encoding } => json!({ "value": node(value), "encoding": node(encoding) }),
+            AstNode::SkipBytes { source, count } => json!({ "source": node(source), "count": node(count) }),
+            AstNode::CreateSocket { socket_type } => json!({ "socket_type": node(socket_type) }),
+            AstNode::ListenPort { port } => json!({ "port": node(port) }),
+            AstNode::ConnectRemote { address } => json!({ "address": node(address) }),
+            AstNode::PortNumber { number } => json!({ "number": node(number) }),
+            AstNode::Handshake { connection } => json!({ "connection": node(connection) }),
+            AstNode::ProcessCreate { command }
+            | AstNode::ShellExec { command } => json!({ "command": node(command) }),
+            AstNode::EnvVar { name } => json!({ "name": node(name) }),
+            AstNode::ExitProgram { code } => json!({ "code": node(code) }),
+            AstNode::SendSignal { signal, target } => json!({ "signal": node(signal), "target": node(target) }),
+            AstNode::PropertyAccess { object, property } => json!({ "object": node(object), "property": property }),
+            AstNode::Index { target, index }
+            | AstNode::CharAt { target, index } => json!({ "target": node(target), "index": node(index) }),
+            AstNode::ArrayPush { array, element } => json!({ "array": node(array), "element": node(element) }),
+            AstNode::Slice { target, start, end } => {
+                json!({ "target": node(target), "start": node(start), "end": optional(end) })
+            }
+            AstNode::ArrayLiteral { elements } => json!({ "elements": elements.iter().map(node).collect::<Vec<_>>() }),
+            AstNode::ObjectLiteral { pairs } => json!({
+                "pairs": pairs.iter().map(|(key, value)| json!({ "key": key, "value": node(value) })).collect::<Vec<_>>(),
+            }),
+            AstNode::Input
+            | AstNode::Empty
+            | AstNode::DateTime
+            | AstNode::Random
+            | AstNode::Debug
+            | AstNode::Infinity
+            | AstNode::EndOfFile => json!({}),
+        };
+        fields["kind"] = json!(self.kind());
+        fields
+    }
+}
+
 /// Comparison operators
 #[derive(Debug, Clone, PartialEq)]
 pub enum ComparisonOp {
@@ -570,12 +1110,30 @@ pub enum ComparisonOp {
 pub enum LiteralValue {
     String(String),
     Number(f64),
+    Integer(i64),
+    Decimal(Decimal),
+    Bytes(Vec<u8>),
+}
+
+/// A type written after a bound variable (`▷ name:S`, `▷ total:N`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeAnnotation {
+    /// S - string
+    String,
+    /// N - number of any kind
+    Number,
 }
 
 /// Parser for Aether tokens
 pub struct Parser {
     tokens: Vec<Token>,
     position: usize,
+    /// Source ranges (start, end) of the top-level statements parsed so far
+    statement_spans: Vec<(usize, usize)>,
+    /// Source ranges of the `⨠` steps of each top-level statement
+    step_spans: Vec<Vec<(usize, usize)>>,
+    /// Nesting of `parse_sequence` calls, 0 at the top level
+    sequence_depth: usize,
 }
 
 impl Parser {
@@ -584,15 +1142,49 @@ impl Parser {
         Parser {
             tokens,
             position: 0,
+            statement_spans: Vec::new(),
+            step_spans: Vec::new(),
+            sequence_depth: 0,
         }
     }
 
+    /// Source range (start, end) of each top-level statement returned by `parse`
+    pub fn statement_spans(&self) -> &[(usize, usize)] {
+        &self.statement_spans
+    }
+
+    /// Source position of the next token, which after a failed `parse` is
+    /// where parsing stopped
+    pub fn start_position(&self) -> usize {
+        self.peek().map_or(0, |token| token.position)
+    }
+
+    /// Source position just past the last token consumed, or `start` if none was
+    fn end_position(&self, start: usize) -> usize {
+        self.tokens[..self.position]
+            .last()
+            .map_or(start, |token| token.position + token.length)
+    }
+
+    /// Source ranges of the steps of each top-level statement: one per
+    /// operation of a `⨠` sequence, or the whole statement otherwise
+    pub fn step_spans(&self) -> &[Vec<(usize, usize)>] {
+        &self.step_spans
+    }
+
     /// Parse tokens into an AST
     pub fn parse(&mut self) -> Result<Vec<AstNode>> {
         let mut nodes = Vec::new();
 
         while !self.is_at_end() {
+            let start = self.start_position();
+            self.step_spans.push(Vec::new());
             let node = self.parse_statement()?;
+            let end = self.end_position(start);
+            self.statement_spans.push((start, end));
+            if !matches!(node, AstNode::Sequence(_)) {
+                *self.step_spans.last_mut().unwrap() = vec![(start, end)];
+            }
             nodes.push(node);
         }
 
@@ -648,10 +1240,29 @@ impl Parser {
 
     /// Parse a sequence of operations (separated by ⨠)
     fn parse_sequence(&mut self) -> Result<AstNode> {
-        let mut operations = vec![self.parse_pipe()?];
+        let top_level = self.sequence_depth == 0;
+        self.sequence_depth += 1;
+        let mut operations = Vec::new();
+        let mut spans = Vec::new();
+
+        loop {
+            let start = self.start_position();
+            let operation = self.parse_pipe();
+            if operation.is_err() {
+                self.sequence_depth -= 1;
+            }
+            operations.push(operation?);
+            spans.push((start, self.end_position(start)));
+            if !self.match_symbol(&Symbol::Sequence) {
+                break;
+            }
+        }
 
-        while self.match_symbol(&Symbol::Sequence) {
-            operations.push(self.parse_pipe()?);
+        self.sequence_depth -= 1;
+        if top_level {
+            if let Some(steps) = self.step_spans.last_mut() {
+                *steps = spans;
+            }
         }
 
         if operations.len() == 1 {
@@ -839,6 +1450,31 @@ impl Parser {
                         };
                         matched = true;
                     }
+                    // Slice: expr⊢start⊣ or expr⊢start:end⊣
+                    TokenType::Symbol(Symbol::SliceStart) => {
+                        self.advance();
+                        let start = if self.check_token_type(&TokenType::Colon) {
+                            AstNode::Literal(LiteralValue::Integer(0))
+                        } else {
+                            self.parse_expression()?
+                        };
+                        let end = if self.match_token_type(&TokenType::Colon) {
+                            Some(Box::new(self.parse_expression()?))
+                        } else {
+                            None
+                        };
+                        if !self.match_symbol(&Symbol::SliceEnd) {
+                            return Err(AetherError::ParserError(
+                                "Expected closing ⊣ for slice".to_string()
+                            ));
+                        }
+                        expr = AstNode::Slice {
+                            target: Box::new(expr),
+                            start: Box::new(start),
+                            end,
+                        };
+                        matched = true;
+                    }
                     _ => {}
                 }
             }
@@ -889,9 +1525,11 @@ impl Parser {
                     ));
                 };
 
+                let annotation = self.parse_type_annotation();
                 expr = AstNode::PipeInto {
                     value: Box::new(expr),
                     variable: var_name,
+                    annotation,
                 };
             } else {
                 // Regular pipe
@@ -906,6 +1544,21 @@ impl Parser {
         Ok(expr)
     }
 
+    /// Parse an optional `:S` or `:N` after a bound variable
+    fn parse_type_annotation(&mut self) -> Option<TypeAnnotation> {
+        let annotation = match self.tokens.get(self.position + 1).map(|t| &t.token_type) {
+            Some(TokenType::Symbol(Symbol::StringType)) => TypeAnnotation::String,
+            Some(TokenType::Symbol(Symbol::NumberType)) => TypeAnnotation::Number,
+            _ => return None,
+        };
+        if !self.check_token_type(&TokenType::Colon) {
+            return None;
+        }
+        self.advance();
+        self.advance();
+        Some(annotation)
+    }
+
     /// Parse primary expressions
     fn parse_primary(&mut self) -> Result<AstNode> {
         if let Some(token) = self.peek() {
@@ -943,6 +1596,25 @@ impl Parser {
                         operand: Box::new(operand),
                     })
                 }
+                // Unary minus: negative literals stay literals, and anything
+                // else is subtracted from zero
+                TokenType::Symbol(Symbol::Subtract) => {
+                    self.advance();
+                    let operand = self.parse_primary()?;
+                    Ok(match operand {
+                        AstNode::Literal(LiteralValue::Integer(n)) if n != i64::MIN => {
+                            AstNode::Literal(LiteralValue::Integer(-n))
+                        }
+                        AstNode::Literal(LiteralValue::Decimal(d)) => {
+                            AstNode::Literal(LiteralValue::Decimal(Decimal::new(-d.mantissa(), d.scale())))
+                        }
+                        AstNode::Literal(LiteralValue::Number(n)) => AstNode::Literal(LiteralValue::Number(-n)),
+                        operand => AstNode::Subtract {
+                            left: Box::new(AstNode::Literal(LiteralValue::Integer(0))),
+                            right: Box::new(operand),
+                        },
+                    })
+                }
                 TokenType::Symbol(Symbol::Persist) => {
                     self.advance();
                     let value = if !self.is_at_end() && !self.check_symbol(&Symbol::Sequence) {
@@ -967,6 +1639,21 @@ impl Parser {
                     self.advance();
                     Ok(AstNode::Literal(LiteralValue::Number(n)))
                 }
+                TokenType::Symbol(Symbol::IntegerLiteral(n)) => {
+                    let n = *n;
+                    self.advance();
+                    Ok(AstNode::Literal(LiteralValue::Integer(n)))
+                }
+                TokenType::Symbol(Symbol::DecimalLiteral(d)) => {
+                    let d = *d;
+                    self.advance();
+                    Ok(AstNode::Literal(LiteralValue::Decimal(d)))
+                }
+                TokenType::Symbol(Symbol::BytesLiteral(b)) => {
+                    let b = b.clone();
+                    self.advance();
+                    Ok(AstNode::Literal(LiteralValue::Bytes(b)))
+                }
                 // Handle parentheses for grouping
                 TokenType::LeftParen => {
                     self.advance();
@@ -1203,7 +1890,7 @@ impl Parser {
                     let operation = self.parse_primary()?;
                     Ok(AstNode::Reduce {
                         operation: Box::new(operation),
-                        initial: Box::new(AstNode::Literal(LiteralValue::Number(0.0))),
+                        initial: Box::new(AstNode::Literal(LiteralValue::Integer(0))),
                     })
                 }
                 TokenType::Symbol(Symbol::Try) => {
@@ -1219,7 +1906,7 @@ impl Parser {
                     // Check if next token is a number (retry count)
                     let max_attempts = if let Some(token) = self.peek() {
                         match &token.token_type {
-                            TokenType::Symbol(Symbol::NumberLiteral(n)) => {
+                            TokenType::Symbol(Symbol::IntegerLiteral(n)) => {
                                 let count = *n as i32;
                                 self.advance();
                                 Some(count)
@@ -1762,15 +2449,64 @@ impl Parser {
                 // Streams & Buffers (v1.3)
                 TokenType::Symbol(Symbol::Stream) => {
                     self.advance();
+                    // Optional read mode: 🌊"lines", 🌊4096 or 🌊🧱4096
+                    let options = match self.peek().map(|t| &t.token_type) {
+                        Some(TokenType::Symbol(Symbol::StringLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::NumberLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::IntegerLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::DecimalLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::Buffer)) => self.parse_primary()?,
+                        _ => AstNode::Empty,
+                    };
                     Ok(AstNode::CreateStream {
                         source: Box::new(AstNode::Empty), // Will be set by pipe
+                        options: Box::new(options),
                     })
                 }
                 TokenType::Symbol(Symbol::Buffer) => {
                     self.advance();
-                    let size = self.parse_primary()?;
-                    Ok(AstNode::CreateBuffer {
-                        size: Box::new(size),
+                    // 🧱"hex" names the encoding of the piped value, a bare 🧱
+                    // converts it as utf8, and anything else is a size or data
+                    match self.peek().map(|t| &t.token_type) {
+                        Some(TokenType::Symbol(Symbol::StringLiteral(_))) => {
+                            let encoding = self.parse_primary()?;
+                            Ok(AstNode::ToBytes {
+                                value: Box::new(AstNode::Empty),
+                                encoding: Box::new(encoding),
+                            })
+                        }
+                        Some(TokenType::Symbol(Symbol::NumberLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::IntegerLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::DecimalLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::BytesLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::Identifier(_)))
+                        | Some(TokenType::LeftParen)
+                        | Some(TokenType::LeftBracket) => {
+                            let size = self.parse_primary()?;
+                            Ok(AstNode::CreateBuffer {
+                                size: Box::new(size),
+                            })
+                        }
+                        _ => Ok(AstNode::ToBytes {
+                            value: Box::new(AstNode::Empty),
+                            encoding: Box::new(AstNode::Empty),
+                        }),
+                    }
+                }
+                TokenType::Symbol(Symbol::StringType) => {
+                    self.advance();
+                    // S"base64" encodes the piped bytes as text; a bare S uses utf8
+                    let encoding = if matches!(
+                        self.peek().map(|t| &t.token_type),
+                        Some(TokenType::Symbol(Symbol::StringLiteral(_)))
+                    ) {
+                        self.parse_primary()?
+                    } else {
+                        AstNode::Empty
+                    };
+                    Ok(AstNode::ToText {
+                        value: Box::new(AstNode::Empty),
+                        encoding: Box::new(encoding),
                     })
                 }
                 TokenType::Symbol(Symbol::Flush) => {
@@ -1795,15 +2531,35 @@ impl Parser {
                 // Networking (v1.3)
                 TokenType::Symbol(Symbol::Socket) => {
                     self.advance();
-                    let socket_type = self.parse_primary()?;
+                    // Bare protocol names (🔌TCP, 🔌UDP) are literals, not variables
+                    let socket_type = match self.peek().map(|t| &t.token_type) {
+                        Some(TokenType::Symbol(Symbol::Identifier(id)))
+                            if id.eq_ignore_ascii_case("TCP") || id.eq_ignore_ascii_case("UDP") =>
+                        {
+                            let protocol = id.to_uppercase();
+                            self.advance();
+                            AstNode::Literal(LiteralValue::String(protocol))
+                        }
+                        _ => self.parse_primary()?,
+                    };
                     Ok(AstNode::CreateSocket {
                         socket_type: Box::new(socket_type),
                     })
                 }
                 TokenType::Symbol(Symbol::Listen) => {
                     self.advance();
+                    // 👂8080 binds a port; a bare 👂 accepts on the piped or latest listener
+                    let port = match self.peek().map(|t| &t.token_type) {
+                        Some(TokenType::Symbol(Symbol::NumberLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::IntegerLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::DecimalLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::StringLiteral(_)))
+                        | Some(TokenType::Symbol(Symbol::Identifier(_)))
+                        | Some(TokenType::LeftParen) => self.parse_primary()?,
+                        _ => AstNode::Empty, // Will be set by pipe
+                    };
                     Ok(AstNode::ListenPort {
-                        port: Box::new(AstNode::Empty), // Will be set by pipe or next token
+                        port: Box::new(port),
                     })
                 }
                 TokenType::Symbol(Symbol::Connect) => {
@@ -2402,4 +3158,139 @@ mod tests {
             _ => panic!("Expected Not node"),
         }
     }
+
+    #[test]
+    fn test_parse_unary_minus() {
+        let tokens = Lexer::new("-10 ⨠ -1.5 ⨠ 3 - -2 ⨠ -x".to_string()).tokenize().unwrap();
+        let ast = Parser::new(tokens).parse().unwrap();
+
+        let AstNode::Sequence(steps) = &ast[0] else { panic!("Expected Sequence node, got {:?}", ast[0]) };
+        assert_eq!(steps[0], AstNode::Literal(LiteralValue::Integer(-10)));
+        assert_eq!(steps[1], AstNode::Literal(LiteralValue::Decimal(Decimal::parse("-1.5").unwrap())));
+        match &steps[2] {
+            AstNode::Subtract { right, .. } => assert_eq!(**right, AstNode::Literal(LiteralValue::Integer(-2))),
+            other => panic!("Expected Subtract node, got {:?}", other),
+        }
+        match &steps[3] {
+            AstNode::Subtract { left, right } => {
+                assert_eq!(**left, AstNode::Literal(LiteralValue::Integer(0)));
+                assert_eq!(**right, AstNode::Variable("x".to_string()));
+            }
+            other => panic!("Expected Subtract node, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_socket_and_listen() {
+        let mut lexer = Lexer::new("🔌TCP ⨠ 👂8080".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+        
+        assert_eq!(ast.len(), 1);
+        match &ast[0] {
+            AstNode::Sequence(nodes) => {
+                assert!(matches!(
+                    &nodes[0],
+                    AstNode::CreateSocket { socket_type } if matches!(socket_type.as_ref(), AstNode::Literal(LiteralValue::String(p)) if p == "TCP")
+                ));
+                assert!(matches!(
+                    &nodes[1],
+                    AstNode::ListenPort { port } if matches!(port.as_ref(), AstNode::Literal(LiteralValue::Integer(8080)))
+                ));
+            }
+            _ => panic!("Expected Sequence node"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_stream_options() {
+        let mut lexer = Lexer::new("f ⇢ 🌊\"lines\" ▷ s".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+        
+        assert_eq!(ast.len(), 1);
+        match &ast[0] {
+            AstNode::PipeInto { value, .. } => match value.as_ref() {
+                AstNode::Pipe { operation, .. } => assert!(matches!(
+                    operation.as_ref(),
+                    AstNode::CreateStream { options, .. } if matches!(options.as_ref(), AstNode::Literal(LiteralValue::String(m)) if m == "lines")
+                )),
+                _ => panic!("Expected Pipe node"),
+            },
+            _ => panic!("Expected PipeInto node"),
+        }
+    }
+    
+    #[test]
+    fn test_parse_bytes_conversions_and_slice() {
+        let mut lexer = Lexer::new("d ⇢ 🧱\"hex\" ⨠ 🧱16 ⨠ d⊢1:3⊣ ⨠ d ⇢ S".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+        
+        let nodes = match &ast[0] {
+            AstNode::Sequence(nodes) => nodes,
+            other => panic!("Expected Sequence node, got {:?}", other),
+        };
+        assert_eq!(nodes.len(), 4);
+        
+        assert!(matches!(
+            &nodes[0],
+            AstNode::Pipe { operation, .. } if matches!(operation.as_ref(), AstNode::ToBytes { encoding, .. }
+                if matches!(encoding.as_ref(), AstNode::Literal(LiteralValue::String(e)) if e == "hex"))
+        ));
+        assert!(matches!(&nodes[1], AstNode::CreateBuffer { .. }));
+        assert!(matches!(&nodes[2], AstNode::Slice { end: Some(_), .. }));
+        assert!(matches!(
+            &nodes[3],
+            AstNode::Pipe { operation, .. } if matches!(operation.as_ref(), AstNode::ToText { encoding, .. }
+                if matches!(encoding.as_ref(), AstNode::Empty))
+        ));
+    }
+    
+    #[test]
+    fn test_parse_bare_listen_accepts() {
+        let mut lexer = Lexer::new("⏳👂 ▷ conn".to_string());
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens);
+        let ast = parser.parse().unwrap();
+        
+        assert_eq!(ast.len(), 1);
+        match &ast[0] {
+            AstNode::PipeInto { value, variable, .. } => {
+                assert_eq!(variable, "conn");
+                match value.as_ref() {
+                    AstNode::Await { expression } => assert!(matches!(
+                        expression.as_ref(),
+                        AstNode::ListenPort { port } if matches!(port.as_ref(), AstNode::Empty)
+                    )),
+                    _ => panic!("Expected Await node"),
+                }
+            }
+            _ => panic!("Expected PipeInto node"),
+        }
+    }
+
+    #[test]
+    fn test_ast_to_json() {
+        let mut parser = Parser::new(Lexer::new("🌐📥\"u\" ⨠ ◇(x ≥ 2): 📤{a: 1.5}".to_string()).tokenize().unwrap());
+        let ast = parser.parse().unwrap();
+        let json = ast[0].to_json();
+        assert_eq!(json["kind"], "sequence");
+        let steps = json["steps"].as_array().unwrap();
+        assert_eq!(steps[0], serde_json::json!({
+            "kind": "http_get",
+            "url": { "kind": "literal", "type": "string", "value": "u" },
+            "headers": null,
+        }));
+        assert_eq!(steps[1]["kind"], "if_then");
+        assert_eq!(steps[1]["condition"]["operator"], ">=");
+        assert_eq!(steps[1]["else_branch"], serde_json::Value::Null);
+        assert_eq!(steps[1]["then_branch"]["value"]["pairs"][0]["key"], "a");
+        for node in &ast {
+            assert_eq!(node.to_json()["kind"], node.kind());
+        }
+    }
 }
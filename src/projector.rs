@@ -0,0 +1,1235 @@
+//! Projector for Aether - reads explanations back into glyph code
+//!
+//! The reverse of the `Explainer` (`aether unexplain`): `Projector::project`
+//! parses the pseudo-code an explanation is written in back into the AST it
+//! explains, and `glyphs` writes an AST as formatted Aether source. An
+//! explanation edited in English or Chinese can so become code again.
+//!
+//! The projector reads with the explainer's own tables: each message in the
+//! catalog is matched by its template, and its placeholders are read as the
+//! parts `FORMS` gives them, at the levels that decided where the explainer
+//! put parentheses. Where messages begin alike, the reader backtracks; what
+//! it has read at each position is remembered, so this stays linear.
+
+use crate::constants::PIPE_VARIABLE;
+use crate::error::{AetherError, Result};
+use crate::explainer::{is_name, operator, pieces, Form, Lang, Level, Part, Piece, Value, FORMS};
+use crate::formatter;
+use crate::lexer::{quote, Lexer, TokenType};
+use crate::numeric::Decimal;
+use crate::parser::{AstNode, ComparisonOp, LiteralValue, TypeAnnotation};
+use crate::symbols::Symbol;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Reads explanations back into the AST they explain
+pub struct Projector {
+    lang: Lang,
+}
+
+impl Projector {
+    pub fn new() -> Self {
+        Self { lang: Lang::En }
+    }
+
+    /// Read explanations written in `lang` instead of English
+    pub fn set_lang(&mut self, lang: Lang) {
+        self.lang = lang;
+    }
+
+    /// The statements an explanation describes, one per unindented line
+    /// and its continuations
+    pub fn project(&self, explanation: &str) -> Result<Vec<AstNode>> {
+        let grammar = Grammar::new(self.lang);
+        let (tokens, lines) = tokenize(explanation)?;
+        let mut reader = Reader { grammar: &grammar, tokens, position: 0, furthest: 0, memo: HashMap::new() };
+        let mut ast = Vec::new();
+        reader.blank();
+        while reader.peek().is_some() {
+            match reader.sequence() {
+                Some(node) if reader.end_of_line() => ast.push(node),
+                _ => return Err(reader.error(&lines)),
+            }
+            reader.blank();
+        }
+        Ok(ast)
+    }
+}
+
+impl Default for Projector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A token of an explanation
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    /// A run of letters, digits and `_`, or a number
+    Word(String),
+    /// A string, unescaped
+    Str(String),
+    /// Any other character
+    Mark(char),
+    /// The end of a line
+    Newline,
+    /// A line indented deeper than the one before
+    Indent,
+    /// The end of an indented block
+    Dedent,
+}
+
+/// The tokens of an explanation and the line each is on. A blank line is a
+/// `Newline` after the indentation of the line that follows it, so a blank
+/// line ends a statement but not a block.
+fn tokenize(text: &str) -> Result<(Vec<Tok>, Vec<usize>)> {
+    let (mut tokens, mut lines) = (Vec::new(), Vec::new());
+    let mut depths = vec![0];
+    let mut blank = 0;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if line.trim().is_empty() {
+            blank += 1;
+            continue;
+        }
+        let depth = line.len() - line.trim_start().len();
+        let mut found = Vec::new();
+        if depth > *depths.last().unwrap() {
+            depths.push(depth);
+            found.push(Tok::Indent);
+        }
+        while depth < *depths.last().unwrap() {
+            depths.pop();
+            found.push(Tok::Dedent);
+        }
+        if depth != *depths.last().unwrap() {
+            return Err(AetherError::ParserError(format!(
+                "Line {} of the explanation is indented unlike the lines before it",
+                number
+            )));
+        }
+        found.extend(std::iter::repeat_n(Tok::Newline, blank));
+        blank = 0;
+        found.extend(words(line.trim_start()).ok_or_else(|| {
+            AetherError::ParserError(format!("Unterminated string on line {} of the explanation", number))
+        })?);
+        found.push(Tok::Newline);
+        lines.extend(std::iter::repeat_n(number, found.len()));
+        tokens.extend(found);
+    }
+    let last = lines.last().copied().unwrap_or(1);
+    for _ in 1..depths.len() {
+        tokens.push(Tok::Dedent);
+        lines.push(last);
+    }
+    Ok((tokens, lines))
+}
+
+/// The tokens of one line, or `None` if a string is not closed
+fn words(line: &str) -> Option<Vec<Tok>> {
+    let chars: Vec<char> = line.chars().collect();
+    let word = |c: char| c.is_alphanumeric() || c == '_';
+    let digit = |i: usize| chars.get(i).is_some_and(|c| c.is_ascii_digit());
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        // A minus starts a number unless it follows a word, as in `a-1`
+        let number = digit(i) || (c == '-' && digit(i + 1) && !(i > 0 && word(chars[i - 1])));
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' {
+            let (text, end) = string(&chars, i + 1)?;
+            tokens.push(Tok::Str(text));
+            i = end;
+        } else if number || word(c) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (word(chars[i]) || (number && chars[i] == '.' && digit(i + 1))) {
+                i += 1;
+            }
+            tokens.push(Tok::Word(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Tok::Mark(c));
+            i += 1;
+        }
+    }
+    Some(tokens)
+}
+
+/// A string's text from after its opening quote, with its escapes read as
+/// the lexer reads them, and the position after its closing quote
+fn string(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut i = start;
+    loop {
+        match *chars.get(i)? {
+            '"' => return Some((text, i + 1)),
+            '\\' => {
+                i += 1;
+                match *chars.get(i)? {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    'r' => text.push('\r'),
+                    '0' => text.push('\0'),
+                    'u' if chars.get(i + 1) == Some(&'{') => {
+                        let close = (i + 2..chars.len()).find(|&j| chars[j] == '}')?;
+                        let hex: String = chars[i + 2..close].iter().collect();
+                        text.push(u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)?);
+                        i = close;
+                    }
+                    c => text.push(c),
+                }
+            }
+            c => text.push(c),
+        }
+        i += 1;
+    }
+}
+
+/// A piece of a template: the tokens of its text, or a placeholder
+enum Slot {
+    Words(Vec<Tok>),
+    Part(usize),
+}
+
+/// The templates of a language, tokenized
+struct Grammar {
+    templates: HashMap<&'static str, Vec<Slot>>,
+}
+
+impl Grammar {
+    fn new(lang: Lang) -> Self {
+        let fixed = ["sequence.then", "retry.unlimited", "pipe_into.string", "pipe_into.number"];
+        let keys = FORMS.iter().flat_map(|form| std::iter::once(form.key).chain(form.extras.iter().map(|(key, _)| *key)));
+        let templates = keys
+            .chain(fixed)
+            .map(|key| {
+                let slots = pieces(lang.template(key))
+                    .into_iter()
+                    .filter_map(|piece| match piece {
+                        Piece::Text(text) => {
+                            let tokens = words(&text.replace('\n', " ")).unwrap_or_default();
+                            (!tokens.is_empty()).then_some(Slot::Words(tokens))
+                        }
+                        Piece::Part(index) => Some(Slot::Part(index)),
+                    })
+                    .collect();
+                (key, slots)
+            })
+            .collect();
+        Self { templates }
+    }
+
+    /// The placeholder a message begins with, if it begins with one
+    fn lead(&self, key: &str) -> Option<usize> {
+        match self.templates.get(key)?.first()? {
+            Slot::Part(index) => Some(*index),
+            Slot::Words(_) => None,
+        }
+    }
+}
+
+type Read = Option<(AstNode, Level)>;
+
+/// The values of a message's placeholders, or of its extras
+type Filled = Vec<Option<Value<'static>>>;
+
+/// A position in an explanation's tokens, and what was read where
+struct Reader<'g> {
+    grammar: &'g Grammar,
+    tokens: Vec<Tok>,
+    position: usize,
+    /// The first token that has not been read, for errors
+    furthest: usize,
+    /// What was read at a position and level, and where it ended
+    memo: HashMap<(usize, Level), (Read, usize)>,
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) {
+        self.position += 1;
+        self.furthest = self.furthest.max(self.position);
+    }
+
+    fn eat(&mut self, token: &Tok) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    /// The next word, if it passes `test`
+    fn word(&mut self, test: impl Fn(&str) -> bool) -> Option<String> {
+        match self.peek() {
+            Some(Tok::Word(word)) if test(word) => {
+                let word = word.clone();
+                self.advance();
+                Some(word)
+            }
+            _ => None,
+        }
+    }
+
+    /// Read a message that has no parts
+    fn phrase(&mut self, key: &str) -> bool {
+        let start = self.position;
+        let grammar = self.grammar;
+        let read = grammar.templates[key].iter().all(|slot| match slot {
+            Slot::Words(tokens) => tokens.iter().all(|token| self.eat(token)),
+            Slot::Part(_) => false,
+        });
+        if !read {
+            self.position = start;
+        }
+        read
+    }
+
+    fn blank(&mut self) {
+        while self.eat(&Tok::Newline) {}
+    }
+
+    /// The end of a line, which a block has already read when it ended
+    fn end_of_line(&mut self) -> bool {
+        self.eat(&Tok::Newline) || (self.position > 0 && self.tokens[self.position - 1] == Tok::Dedent)
+    }
+
+    /// Steps joined by `then` lines
+    fn sequence(&mut self) -> Option<AstNode> {
+        let mut steps = vec![self.level(Level::Chain)?];
+        loop {
+            let start = self.position;
+            let then = self.end_of_line() && {
+                self.blank();
+                self.phrase("sequence.then")
+            } && self.eat(&Tok::Newline);
+            match then.then(|| self.level(Level::Chain)).flatten() {
+                Some(step) => steps.push(step),
+                None => {
+                    self.position = start;
+                    break;
+                }
+            }
+        }
+        Some(if steps.len() == 1 { steps.pop().unwrap() } else { AstNode::Sequence(steps) })
+    }
+
+    fn level(&mut self, level: Level) -> Option<AstNode> {
+        self.read(level).map(|(node, _)| node)
+    }
+
+    /// A node explained at `level` or tighter, and the level it was at
+    fn read(&mut self, level: Level) -> Read {
+        let start = self.position;
+        if let Some((read, end)) = self.memo.get(&(start, level)) {
+            self.position = *end;
+            return read.clone();
+        }
+        let read = match level {
+            Level::Sequence => self.sequence().map(|node| (node, Level::Sequence)),
+            _ => self.grow(level),
+        };
+        if read.is_none() {
+            self.position = start;
+        }
+        self.memo.insert((start, level), (read.clone(), self.position));
+        read
+    }
+
+    /// A message or primary, then the messages that begin with a node for
+    /// as long as they follow
+    fn grow(&mut self, level: Level) -> Read {
+        let grammar = self.grammar;
+        let start = self.position;
+        let mut first = None;
+        for form in FORMS.iter().filter(|form| form.level >= level && grammar.lead(form.key).is_none()) {
+            if let Some((values, extras)) = self.values(form, None) {
+                first = Some((build(form.key, values, extras)?, form.level));
+                break;
+            }
+            self.position = start;
+        }
+        let (mut node, mut reached) = match first {
+            Some(first) => first,
+            None => (self.primary()?, Level::Atom),
+        };
+        'grow: loop {
+            for form in FORMS.iter().filter(|form| form.level >= level) {
+                let Some(lead) = grammar.lead(form.key) else { continue };
+                if !matches!(form.parts.get(lead), Some(Part::Node(needed)) if reached >= *needed) {
+                    continue;
+                }
+                let before = self.position;
+                if let Some((mut values, extras)) = self.values(form, Some(lead)) {
+                    values[lead] = Some(Value::Node(Cow::Owned(node)));
+                    node = build(form.key, values, extras)?;
+                    reached = form.level;
+                    continue 'grow;
+                }
+                self.position = before;
+            }
+            return Some((node, reached));
+        }
+    }
+
+    /// A string, number, name, or a sequence in parentheses
+    fn primary(&mut self) -> Option<AstNode> {
+        let node = match self.peek()?.clone() {
+            Tok::Str(text) => AstNode::Literal(LiteralValue::String(text)),
+            Tok::Word(word) => match number(&word) {
+                Some(number) => number,
+                None if is_name(&word) => AstNode::Variable(word),
+                None => return None,
+            },
+            Tok::Mark('(') => {
+                self.advance();
+                let node = self.sequence()?;
+                return self.eat(&Tok::Mark(')')).then_some(node);
+            }
+            _ => return None,
+        };
+        self.advance();
+        Some(node)
+    }
+
+    /// The values of a form's message and extras, leaving out the value of
+    /// the placeholder it leads with, if it has been read already
+    fn values(&mut self, form: &Form, lead: Option<usize>) -> Option<(Filled, Filled)> {
+        let grammar = self.grammar;
+        let mut values = vec![None; form.parts.len()];
+        for (i, slot) in grammar.templates[form.key].iter().enumerate() {
+            match slot {
+                Slot::Words(tokens) => {
+                    if !tokens.iter().all(|token| self.eat(token)) {
+                        return None;
+                    }
+                }
+                Slot::Part(_) if i == 0 && lead.is_some() => {}
+                Slot::Part(index) => *values.get_mut(*index)? = Some(self.part(*form.parts.get(*index)?)?),
+            }
+        }
+        if values.iter().enumerate().any(|(i, value)| value.is_none() && Some(i) != lead) {
+            return None;
+        }
+        let mut extras = Vec::new();
+        for (key, part) in form.extras {
+            let start = self.position;
+            let value = self.extra(key, *part);
+            if value.is_none() {
+                self.position = start;
+            }
+            extras.push(value);
+        }
+        Some((values, extras))
+    }
+
+    fn extra(&mut self, key: &str, part: Part) -> Option<Value<'static>> {
+        let grammar = self.grammar;
+        let mut value = None;
+        for slot in &grammar.templates[key] {
+            match slot {
+                Slot::Words(tokens) => {
+                    if !tokens.iter().all(|token| self.eat(token)) {
+                        return None;
+                    }
+                }
+                Slot::Part(_) => value = Some(self.part(part)?),
+            }
+        }
+        value
+    }
+
+    fn part(&mut self, part: Part) -> Option<Value<'static>> {
+        Some(match part {
+            Part::Node(level) => Value::Node(Cow::Owned(self.level(level)?)),
+            Part::Body => {
+                if !(self.eat(&Tok::Newline) && self.eat(&Tok::Indent)) {
+                    return None;
+                }
+                self.blank();
+                let body = self.sequence()?;
+                if !(self.end_of_line() && self.eat(&Tok::Dedent)) {
+                    return None;
+                }
+                Value::Node(Cow::Owned(body))
+            }
+            Part::Name => Value::Name(Cow::Owned(self.word(is_name)?)),
+            Part::Text => match self.peek()?.clone() {
+                Tok::Str(text) => {
+                    self.advance();
+                    Value::Text(Cow::Owned(text))
+                }
+                _ => return None,
+            },
+            Part::Count if self.phrase("retry.unlimited") => Value::Count(None),
+            Part::Count => Value::Count(Some(self.word(|word| word.parse::<i32>().is_ok())?.parse().ok()?)),
+            Part::Operator => {
+                let start = self.position;
+                let ops = [ComparisonOp::GreaterEqual, ComparisonOp::LessEqual, ComparisonOp::GreaterThan, ComparisonOp::LessThan];
+                for op in ops {
+                    if operator(&op).chars().all(|c| self.eat(&Tok::Mark(c))) {
+                        return Some(Value::Operator(op));
+                    }
+                    self.position = start;
+                }
+                return None;
+            }
+            Part::Annotation if self.phrase("pipe_into.string") => Value::Annotation(Some(TypeAnnotation::String)),
+            Part::Annotation if self.phrase("pipe_into.number") => Value::Annotation(Some(TypeAnnotation::Number)),
+            Part::Annotation => Value::Annotation(None),
+            Part::Bytes => {
+                let word = self.word(|word| word.strip_prefix("0x").is_some_and(|hex| hex.len() % 2 == 0))?;
+                let hex = &word[2..];
+                let bytes = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect::<Option<Vec<u8>>>()?;
+                Value::Bytes(Cow::Owned(bytes))
+            }
+            Part::Elements => {
+                let mut elements = Vec::new();
+                if let Some(first) = self.level(Level::Chain) {
+                    elements.push(first);
+                    while self.eat(&Tok::Mark(',')) {
+                        elements.push(self.level(Level::Chain)?);
+                    }
+                }
+                Value::Elements(Cow::Owned(elements))
+            }
+            Part::Pairs => {
+                let mut pairs = Vec::new();
+                loop {
+                    let key = match self.peek() {
+                        Some(Tok::Str(key)) => key.clone(),
+                        Some(Tok::Word(key)) if is_name(key) => key.clone(),
+                        _ if pairs.is_empty() => break,
+                        _ => return None,
+                    };
+                    self.advance();
+                    if !self.eat(&Tok::Mark(':')) {
+                        return None;
+                    }
+                    pairs.push((key, self.level(Level::Chain)?));
+                    if !self.eat(&Tok::Mark(',')) {
+                        break;
+                    }
+                }
+                Value::Pairs(Cow::Owned(pairs))
+            }
+        })
+    }
+
+    fn error(&self, lines: &[usize]) -> AetherError {
+        let line = lines.get(self.furthest).or(lines.last()).copied().unwrap_or(1);
+        let near = match self.tokens.get(self.furthest) {
+            Some(Tok::Word(word)) => format!("'{}'", word),
+            Some(Tok::Str(text)) => quote(text),
+            Some(Tok::Mark(c)) => format!("'{}'", c),
+            Some(Tok::Newline) => "the end of the line".to_string(),
+            Some(Tok::Indent) => "an indented line".to_string(),
+            Some(Tok::Dedent) => "the end of a block".to_string(),
+            None => "the end".to_string(),
+        };
+        AetherError::ParserError(format!("Cannot read line {} of the explanation, near {}", line, near))
+    }
+}
+
+/// A number as the lexer reads it: whole numbers are integers unless too
+/// large, and numbers with a point are decimals
+fn number(word: &str) -> Option<AstNode> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    if !digits.starts_with(|c: char| c.is_ascii_digit()) || !digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let literal = match word.parse::<i64>() {
+        Ok(n) if !word.contains('.') => LiteralValue::Integer(n),
+        _ => LiteralValue::Decimal(Decimal::parse(word)?),
+    };
+    Some(AstNode::Literal(literal))
+}
+
+/// The values of a message, taken in order
+struct Values(std::vec::IntoIter<Option<Value<'static>>>);
+
+impl Values {
+    fn node(&mut self) -> Option<Box<AstNode>> {
+        match self.0.next()?? {
+            Value::Node(node) => Some(Box::new(node.into_owned())),
+            _ => None,
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        match self.0.next()?? {
+            Value::Name(name) | Value::Text(name) => Some(name.into_owned()),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<Value<'static>> {
+        self.0.next()?
+    }
+}
+
+/// The node a message with these values explains
+fn build(key: &str, values: Filled, extras: Filled) -> Option<AstNode> {
+    let mut v = Values(values.into_iter());
+    let mut extra = Values(extras.into_iter());
+    let piped = || Box::new(AstNode::Variable(PIPE_VARIABLE.to_string()));
+    let empty = || Box::new(AstNode::Empty);
+    Some(match key {
+        "function" => AstNode::Function { name: v.name()?, body: v.node()? },
+        "input" => AstNode::Input,
+        "output" => AstNode::Output(v.node()?),
+        "pipe" => AstNode::Pipe { source: v.node()?, operation: v.node()? },
+        "pipe_into" => {
+            let (value, variable) = (v.node()?, v.name()?);
+            let Value::Annotation(annotation) = v.next()? else { return None };
+            AstNode::PipeInto { value, variable, annotation }
+        }
+        "guard" => AstNode::Guard { condition: v.node()?, then_branch: v.node()? },
+        "halt" => AstNode::Halt(v.node()?),
+        "persist" => AstNode::Persist(v.node()?),
+        "json_parse" => AstNode::JsonParse(v.node()?),
+        "literal.bytes" => match v.next()? {
+            Value::Bytes(bytes) => AstNode::Literal(LiteralValue::Bytes(bytes.into_owned())),
+            _ => return None,
+        },
+        "empty" => AstNode::Empty,
+        "if_then" => AstNode::IfThen { condition: v.node()?, then_branch: v.node()?, else_branch: extra.node() },
+        "loop" => AstNode::Loop { condition: Some(v.node()?), body: v.node()? },
+        "loop.forever" => AstNode::Loop { condition: None, body: v.node()? },
+        "for_each" => AstNode::ForEach { variable: v.name()?, collection: v.node()?, body: v.node()? },
+        "filter" => AstNode::Filter { predicate: v.node()? },
+        "reduce" => AstNode::Reduce { operation: v.node()?, initial: v.node()? },
+        "try_rescue" => AstNode::TryRescue { try_body: v.node()?, rescue_body: extra.node() },
+        "retry" => {
+            let Value::Count(max_attempts) = v.next()? else { return None };
+            AstNode::Retry { max_attempts, body: v.node()? }
+        }
+        "async" => AstNode::Async { body: v.node()? },
+        "await" => AstNode::Await { expression: v.node()? },
+        "import" => AstNode::Import { module: v.name()? },
+        "split.by" => AstNode::Split { target: v.node()?, delimiter: Some(v.node()?) },
+        "split" => AstNode::Split { target: v.node()?, delimiter: None },
+        "join.with" => AstNode::Join { elements: v.node()?, separator: Some(v.node()?) },
+        "join" => AstNode::Join { elements: v.node()?, separator: None },
+        "regex_match" => AstNode::RegexMatch { pattern: v.node()?, target: v.node()? },
+        "equal" => AstNode::Equal { left: v.node()?, right: v.node()? },
+        "not_equal" => AstNode::NotEqual { left: v.node()?, right: v.node()? },
+        "and" => AstNode::And { left: v.node()?, right: v.node()? },
+        "or" => AstNode::Or { left: v.node()?, right: v.node()? },
+        "not" => AstNode::Not { operand: v.node()? },
+        "comparison" => {
+            let left = v.node()?;
+            let Value::Operator(operator) = v.next()? else { return None };
+            AstNode::Comparison { left, operator, right: v.node()? }
+        }
+        "approx" => AstNode::Approx { left: v.node()?, right: v.node()? },
+        "approx.piped" => AstNode::Approx { left: piped(), right: v.node()? },
+        "http_get" => AstNode::HttpGet { url: v.node()?, headers: extra.node() },
+        "http_post" => AstNode::HttpPost { url: v.node()?, body: extra.node(), headers: extra.node() },
+        "http_put" => AstNode::HttpPut { url: v.node()?, body: extra.node(), headers: extra.node() },
+        "http_delete" => AstNode::HttpDelete { url: v.node()?, headers: extra.node() },
+        "http_patch" => AstNode::HttpPatch { url: v.node()?, body: extra.node(), headers: extra.node() },
+        "http_head" => AstNode::HttpHead { url: v.node()?, headers: extra.node() },
+        "http_options" => AstNode::HttpOptions { url: v.node()?, headers: extra.node() },
+        "random" => AstNode::Random,
+        "date_time" => AstNode::DateTime,
+        "log" => AstNode::Log { message: v.node()? },
+        "assert" => AstNode::Assert { condition: v.node()? },
+        "hash" => AstNode::Hash { data: v.node()? },
+        "power" => AstNode::Power { base: v.node()?, exponent: v.node()? },
+        "power.piped" => AstNode::Power { base: piped(), exponent: v.node()? },
+        "root" => AstNode::Root { value: v.node()? },
+        "root.piped" => AstNode::Root { value: piped() },
+        "read_content" => AstNode::ReadContent { source: v.node()? },
+        "write_content" => AstNode::WriteContent { target: v.node()?, content: v.node()? },
+        "shell_exec" => AstNode::ShellExec { command: v.node()? },
+        "env_var" => AstNode::EnvVar { name: v.node()? },
+        "immutable" => AstNode::Immutable { name: v.name()?, value: v.node()? },
+        "debug" => AstNode::Debug,
+        "file_handle" => AstNode::FileHandle { path: v.node()? },
+        "directory" => AstNode::Directory { path: v.node()? },
+        "path_resolve" => AstNode::PathResolve { path: v.node()? },
+        "append_content" => AstNode::AppendContent { target: v.node()?, content: v.node()? },
+        "delete_file" => AstNode::DeleteFile { target: v.node()? },
+        "set_permission" => AstNode::SetPermission { target: v.node()?, permission: v.node()? },
+        "create_stream" => AstNode::CreateStream { source: v.node()?, options: extra.node().unwrap_or_else(empty) },
+        "create_buffer" => AstNode::CreateBuffer { size: v.node()? },
+        "to_bytes" => AstNode::ToBytes { value: v.node()?, encoding: extra.node().unwrap_or_else(empty) },
+        "to_text" => AstNode::ToText { value: v.node()?, encoding: extra.node().unwrap_or_else(empty) },
+        "flush_buffer" => AstNode::FlushBuffer { target: v.node()? },
+        "end_of_file" => AstNode::EndOfFile,
+        "skip_bytes" => AstNode::SkipBytes { source: v.node()?, count: v.node()? },
+        "create_socket" => AstNode::CreateSocket { socket_type: v.node()? },
+        "listen_port" => AstNode::ListenPort { port: v.node()? },
+        "connect_remote" => AstNode::ConnectRemote { address: v.node()? },
+        "port_number" => AstNode::PortNumber { number: v.node()? },
+        "create_packet" => AstNode::CreatePacket { data: v.node()? },
+        "handshake" => AstNode::Handshake { connection: v.node()? },
+        "process_create" => AstNode::ProcessCreate { command: v.node()? },
+        "memory_alloc" => AstNode::MemoryAlloc { size: v.node()? },
+        "exit_program" => AstNode::ExitProgram { code: v.node()? },
+        "send_signal" => AstNode::SendSignal { signal: v.node()?, target: v.node()? },
+        "property_access" => AstNode::PropertyAccess { object: v.node()?, property: v.name()? },
+        "thread" => AstNode::Thread { body: v.node()? },
+        "lock" => AstNode::Lock { body: v.node()? },
+        "emit" => AstNode::Emit { event: v.node()? },
+        "watch" => AstNode::Watch { event: v.node()?, handler: v.node()? },
+        "auth" => AstNode::Auth { token: v.node()? },
+        "test" => AstNode::Test { name: v.name()?, body: v.node()? },
+        "mock" => AstNode::Mock { target: v.node()? },
+        "benchmark" => AstNode::Benchmark { body: v.node()? },
+        "encrypt" => AstNode::Encrypt { data: v.node()?, key: v.node()? },
+        "decrypt" => AstNode::Decrypt { data: v.node()?, key: v.node()? },
+        "sign" => AstNode::Sign { data: v.node()?, key: v.node()? },
+        "verify_signature" => AstNode::VerifySignature { signature: v.node()?, data: v.node()?, key: v.node()? },
+        "infinity" => AstNode::Infinity,
+        "delta" => AstNode::Delta { name: v.name()?, value: v.node()? },
+        "length" => AstNode::Length { value: v.node()? },
+        "length.piped" => AstNode::Length { value: piped() },
+        "index" => AstNode::Index { target: v.node()?, index: v.node()? },
+        "array_push" => AstNode::ArrayPush { array: v.node()?, element: v.node()? },
+        "array_push.piped" => AstNode::ArrayPush { array: piped(), element: v.node()? },
+        "add" => AstNode::Add { left: v.node()?, right: v.node()? },
+        "subtract" => AstNode::Subtract { left: v.node()?, right: v.node()? },
+        "multiply" => AstNode::Multiply { left: v.node()?, right: v.node()? },
+        "divide" => AstNode::Divide { left: v.node()?, right: v.node()? },
+        "modulo" => AstNode::Modulo { left: v.node()?, right: v.node()? },
+        "string_concat" => AstNode::StringConcat { left: v.node()?, right: v.node()? },
+        "char_at" => AstNode::CharAt { target: v.node()?, index: v.node()? },
+        "slice" => AstNode::Slice { target: v.node()?, start: v.node()?, end: Some(v.node()?) },
+        "slice.open" => AstNode::Slice { target: v.node()?, start: v.node()?, end: None },
+        "array_literal" => match v.next()? {
+            Value::Elements(elements) => AstNode::ArrayLiteral { elements: elements.into_owned() },
+            _ => return None,
+        },
+        "object_literal" => match v.next()? {
+            Value::Pairs(pairs) => AstNode::ObjectLiteral { pairs: pairs.into_owned() },
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// How loosely a piece of glyph code holds together, from `⨠` steps to a
+/// primary that is an operand anywhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Sequence,
+    /// `⇢` and `▷`
+    Chain,
+    /// Infix operators, `⟦⟧` and `⊢⊣`, left to right
+    Binary,
+    /// `.property`
+    Postfix,
+    Primary,
+}
+
+/// What follows a piece of glyph code, which decides whether a glyph whose
+/// operand is optional can be written without one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Next {
+    /// `⨠` or the end of the program
+    Stop,
+    /// `⇢`
+    Pipe,
+    /// `▷`
+    Into,
+    /// `🏷️`
+    Headers,
+    /// `◈` or `◆`
+    Else,
+    /// A closing bracket, `,` or `:`
+    Close,
+    /// Anything else, even the next statement
+    Other,
+}
+
+/// Aether source for an AST, formatted, one statement per line. Every AST
+/// the parser produces comes back from the source it is written as; fields
+/// the parser only ever leaves empty or piped are written as a pipe.
+pub fn glyphs(ast: &[AstNode]) -> Result<String> {
+    let mut lines = Vec::new();
+    for (i, node) in ast.iter().enumerate() {
+        let mut line = print(node, Rank::Sequence, if i + 1 < ast.len() { Next::Other } else { Next::Stop });
+        // The parser reads on past line breaks, so `-` and `[` would
+        // continue the statement before
+        if i > 0 && line.starts_with(['-', '[']) {
+            line = format!("({})", line);
+        }
+        lines.push(line);
+    }
+    formatter::format(&lines.join("\n"))
+}
+
+fn rank(node: &AstNode) -> Rank {
+    match node {
+        AstNode::Sequence(_) => Rank::Sequence,
+        AstNode::Pipe { .. } | AstNode::PipeInto { .. } => Rank::Chain,
+        AstNode::Comparison { .. }
+        | AstNode::Equal { .. }
+        | AstNode::NotEqual { .. }
+        | AstNode::And { .. }
+        | AstNode::Or { .. }
+        | AstNode::Add { .. }
+        | AstNode::Subtract { .. }
+        | AstNode::StringConcat { .. }
+        | AstNode::Multiply { .. }
+        | AstNode::Divide { .. }
+        | AstNode::Modulo { .. }
+        | AstNode::Index { .. }
+        | AstNode::CharAt { .. }
+        | AstNode::Slice { .. } => Rank::Binary,
+        AstNode::PropertyAccess { .. } => Rank::Postfix,
+        _ => Rank::Primary,
+    }
+}
+
+/// Whether a node can be written without parentheses before `next`
+fn bare(node: &AstNode, next: Next) -> bool {
+    // Nothing that can follow these starts an operand
+    let closed = next != Next::Other;
+    match node {
+        AstNode::IfThen { .. } => next != Next::Else || !dangling(node),
+        AstNode::HttpGet { headers: None, .. }
+        | AstNode::HttpDelete { headers: None, .. }
+        | AstNode::HttpHead { headers: None, .. }
+        | AstNode::HttpOptions { headers: None, .. } => next != Next::Headers,
+        AstNode::HttpPost { body, headers: None, .. }
+        | AstNode::HttpPut { body, headers: None, .. }
+        | AstNode::HttpPatch { body, headers: None, .. } => next != Next::Headers && (body.is_some() || next != Next::Pipe),
+        AstNode::CreateStream { source, options } => closed || !(empty(source) && empty(options)),
+        AstNode::ToBytes { value, encoding } | AstNode::ToText { value, encoding } => {
+            closed || !(empty(value) && empty(encoding))
+        }
+        AstNode::ListenPort { port } => closed || !empty(port),
+        _ => true,
+    }
+}
+
+/// Whether an `◇` chain ends without an `◆`, so an `◈` or `◆` after it
+/// would join it
+fn dangling(node: &AstNode) -> bool {
+    match node {
+        AstNode::IfThen { else_branch: None, .. } => true,
+        AstNode::IfThen { else_branch: Some(branch), .. } => matches!(**branch, AstNode::IfThen { .. }) && dangling(branch),
+        _ => false,
+    }
+}
+
+fn empty(node: &AstNode) -> bool {
+    *node == AstNode::Empty
+}
+
+fn is_piped(node: &AstNode) -> bool {
+    matches!(node, AstNode::Variable(name) if name == PIPE_VARIABLE)
+}
+
+/// A node written at `rank` or tighter, before `next`
+fn print(node: &AstNode, rank: Rank, next: Next) -> String {
+    if self::rank(node) < rank || !bare(node, next) {
+        return format!("({})", write(node, Next::Close));
+    }
+    write(node, next)
+}
+
+/// An operand of a prefix glyph, before `next`
+fn operand(node: &AstNode, next: Next) -> String {
+    print(node, Rank::Primary, next)
+}
+
+/// A glyph's code with the value it works on piped in, where the parser
+/// only leaves that value empty or piped
+fn through(source: &AstNode, code: String) -> String {
+    if empty(source) || is_piped(source) {
+        code
+    } else {
+        format!("({} ⇢ {})", print(source, Rank::Chain, Next::Pipe), code)
+    }
+}
+
+fn binary(left: &AstNode, op: &str, right: &AstNode, next: Next) -> String {
+    format!("{} {} {}", print(left, Rank::Binary, Next::Other), op, operand(right, next))
+}
+
+/// Whether a name lexes back as an identifier
+fn identifier(name: &str) -> bool {
+    matches!(
+        Lexer::new(name.to_string()).tokenize().as_deref(),
+        Ok([token, _]) if token.token_type == TokenType::Symbol(Symbol::Identifier(name.to_string()))
+    )
+}
+
+fn literal(lit: &LiteralValue) -> String {
+    match lit {
+        LiteralValue::String(s) => quote(s),
+        LiteralValue::Integer(n) => n.to_string(),
+        LiteralValue::Decimal(d) => {
+            let text = d.to_string();
+            if text.contains('.') || text.parse::<i64>().is_err() { text } else { format!("{}.0", text) }
+        }
+        LiteralValue::Number(n) => format!("{:?}", n),
+        LiteralValue::Bytes(bytes) => format!("hex\"{}\"", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+    }
+}
+
+fn write(node: &AstNode, next: Next) -> String {
+    let o = |node: &AstNode| operand(node, Next::Other);
+    let p = |node: &AstNode| operand(node, next);
+    match node {
+        AstNode::Sequence(steps) => steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| print(step, Rank::Chain, if i + 1 < steps.len() { Next::Stop } else { next }))
+            .collect::<Vec<_>>()
+            .join(" ⨠ "),
+        AstNode::Function { name, body } => {
+            let name = match name.as_str() {
+                "register" => "®",
+                "anonymous" => "",
+                name => name,
+            };
+            format!("ƒ{}: {}", name, print(body, Rank::Sequence, next))
+        }
+        AstNode::Input => "📥".to_string(),
+        AstNode::Output(value) => format!("📤{}", p(value)),
+        AstNode::Pipe { source, operation } => {
+            format!("{} ⇢ {}", print(source, Rank::Chain, Next::Pipe), p(operation))
+        }
+        AstNode::PipeInto { value, variable, annotation } => {
+            let annotation = match annotation {
+                Some(TypeAnnotation::String) => ":S",
+                Some(TypeAnnotation::Number) => ":N",
+                None => "",
+            };
+            format!("{} ▷ {}{}", print(value, Rank::Chain, Next::Into), variable, annotation)
+        }
+        AstNode::Guard { condition, then_branch } => through(condition, format!("⁇{}", p(then_branch))),
+        AstNode::Halt(code) => format!("🛑{}", p(code)),
+        AstNode::Persist(value) if empty(value) && next == Next::Stop => "💾".to_string(),
+        AstNode::Persist(value) => format!("💾{}", p(value)),
+        AstNode::JsonParse(value) if **value == AstNode::Input => "J".to_string(),
+        AstNode::JsonParse(value) => through(value, "J".to_string()),
+        AstNode::Variable(name) => name.clone(),
+        AstNode::Literal(lit) => literal(lit),
+        AstNode::Empty => "∅".to_string(),
+        AstNode::IfThen { condition, then_branch, else_branch } => {
+            let mut code = String::new();
+            let mut branch = Some((condition, then_branch, else_branch));
+            let mut glyph = "◇";
+            while let Some((condition, then_branch, else_branch)) = branch.take() {
+                let then_next = if else_branch.is_some() { Next::Else } else { next };
+                code.push_str(&format!(
+                    "{}({}): {}",
+                    glyph,
+                    print(condition, Rank::Sequence, Next::Close),
+                    operand(then_branch, then_next)
+                ));
+                match else_branch.as_deref() {
+                    Some(AstNode::IfThen { condition, then_branch, else_branch }) => {
+                        branch = Some((condition, then_branch, else_branch));
+                        glyph = " ◈";
+                    }
+                    Some(otherwise) => code.push_str(&format!(" ◆: {}", p(otherwise))),
+                    None => {}
+                }
+            }
+            code
+        }
+        AstNode::Loop { condition: Some(condition), body } => {
+            format!("↻({}): {}", print(condition, Rank::Sequence, Next::Close), p(body))
+        }
+        AstNode::Loop { condition: None, body } => format!("↻{}", p(body)),
+        AstNode::ForEach { variable, collection, body } => through(collection, format!("∀{}: {}", variable, p(body))),
+        AstNode::Filter { predicate } => format!("∃{}", p(predicate)),
+        AstNode::Reduce { operation, .. } => format!("∑{}", p(operation)),
+        AstNode::TryRescue { try_body, .. } => format!("🛡{}", p(try_body)),
+        AstNode::Retry { max_attempts, body } => {
+            format!("♻{}: {}", max_attempts.map(|n| n.to_string()).unwrap_or_default(), p(body))
+        }
+        AstNode::Async { body } => format!("⚡{}", p(body)),
+        AstNode::Await { expression } => format!("⏳{}", p(expression)),
+        AstNode::Thread { body } => format!("🧵{}", p(body)),
+        AstNode::Lock { body } => format!("🔒{}", p(body)),
+        AstNode::Emit { event } => format!("📡{}", p(event)),
+        AstNode::Watch { event, handler } if empty(handler) && next == Next::Stop => format!("👁{}", p(event)),
+        AstNode::Watch { event, handler } => format!("👁{} {}", o(event), p(handler)),
+        AstNode::Split { target, delimiter } => {
+            through(target, format!("✂{}", delimiter.as_deref().map(p).unwrap_or_default()))
+        }
+        AstNode::Join { elements, separator } => {
+            through(elements, format!("🔗{}", separator.as_deref().map(p).unwrap_or_default()))
+        }
+        AstNode::RegexMatch { pattern, target } => through(target, format!("✱{}", p(pattern))),
+        AstNode::Equal { left, right } => binary(left, "≡", right, next),
+        AstNode::NotEqual { left, right } => binary(left, "≠", right, next),
+        AstNode::And { left, right } => binary(left, "⊗", right, next),
+        AstNode::Or { left, right } => binary(left, "⊕", right, next),
+        AstNode::Comparison { left, operator, right } => {
+            let op = match operator {
+                ComparisonOp::GreaterThan => ">",
+                ComparisonOp::LessThan => "<",
+                ComparisonOp::GreaterEqual => "≥",
+                ComparisonOp::LessEqual => "≤",
+            };
+            binary(left, op, right, next)
+        }
+        AstNode::Add { left, right } => binary(left, "+", right, next),
+        AstNode::Subtract { left, right } => binary(left, "-", right, next),
+        AstNode::StringConcat { left, right } => binary(left, "⧺", right, next),
+        AstNode::Multiply { left, right } => binary(left, "*", right, next),
+        AstNode::Divide { left, right } => binary(left, "/", right, next),
+        AstNode::Modulo { left, right } => binary(left, "%", right, next),
+        AstNode::Not { operand } => format!("¬{}", p(operand)),
+        AstNode::Immutable { name, value } => format!("🧊{} {}", name, p(value)),
+        AstNode::Import { module } if module == "http" => "🧩🌐".to_string(),
+        AstNode::Import { module } => format!("🧩{}", module),
+        AstNode::Auth { token } => format!("🔑{}", p(token)),
+        AstNode::DateTime => "📅".to_string(),
+        AstNode::Random => "🎲".to_string(),
+        AstNode::Log { message } => format!("🪵{}", p(message)),
+        AstNode::HttpGet { url, headers } => http("🌐📥", url, None, headers, next),
+        AstNode::HttpPost { url, body, headers } => http("🌐📤", url, Some(body), headers, next),
+        AstNode::HttpPut { url, body, headers } => http("🌐🔄", url, Some(body), headers, next),
+        AstNode::HttpDelete { url, headers } => http("🌐🗑️", url, None, headers, next),
+        AstNode::HttpPatch { url, body, headers } => http("🌐🔧", url, Some(body), headers, next),
+        AstNode::HttpHead { url, headers } => http("🌐👁", url, None, headers, next),
+        AstNode::HttpOptions { url, headers } => http("🌐⚙️", url, None, headers, next),
+        AstNode::Test { name, body } => format!("🧪{}: {}", quote(name), p(body)),
+        AstNode::Assert { condition } => format!("⚖️{}", p(condition)),
+        AstNode::Mock { target } => format!("🎭{}", p(target)),
+        AstNode::Benchmark { body } => format!("⏱️{}", p(body)),
+        AstNode::Debug => "🐛".to_string(),
+        AstNode::Encrypt { data, key } => format!("🔐{} {}", o(data), p(key)),
+        AstNode::Decrypt { data, key } => format!("🔓{} {}", o(data), p(key)),
+        AstNode::Hash { data } => format!("#️⃣{}", p(data)),
+        AstNode::Sign { data, key } => format!("✍️{} {}", o(data), p(key)),
+        AstNode::VerifySignature { signature, data, key } => format!("🔏{} {} {}", o(signature), o(data), p(key)),
+        AstNode::Power { base, exponent } => through(base, format!("↑{}", p(exponent))),
+        AstNode::Root { value } if is_piped(value) && matches!(next, Next::Stop | Next::Into) => "√".to_string(),
+        AstNode::Root { value } => format!("√{}", p(value)),
+        AstNode::Approx { left, right } => through(left, format!("≈{}", p(right))),
+        AstNode::Infinity => "∞".to_string(),
+        AstNode::Delta { name, value } => format!("∆{} {}", name, p(value)),
+        AstNode::FileHandle { path } => format!("📄{}", p(path)),
+        AstNode::Directory { path } => format!("📂{}", p(path)),
+        AstNode::PathResolve { path } => format!("📍{}", p(path)),
+        AstNode::ReadContent { source } => through(source, "📖".to_string()),
+        AstNode::WriteContent { target, content } => through(content, format!("🖊️{}", p(target))),
+        AstNode::AppendContent { target, content } => through(content, format!("🖇️{}", p(target))),
+        AstNode::DeleteFile { target } => format!("🗑️{}", p(target)),
+        AstNode::SetPermission { target, permission } => format!("🛂{} {}", o(target), p(permission)),
+        AstNode::CreateStream { source, options } if empty(options) => through(source, "🌊".to_string()),
+        AstNode::CreateStream { source, options } => through(source, format!("🌊{}", p(options))),
+        AstNode::CreateBuffer { size } => {
+            // 🧱 reads a size or data only from what starts like one
+            let sized = match &**size {
+                AstNode::Literal(LiteralValue::String(_)) => false,
+                AstNode::Literal(LiteralValue::Bytes(_)) | AstNode::ArrayLiteral { .. } => true,
+                AstNode::Literal(lit) => !literal(lit).starts_with('-'),
+                AstNode::Variable(name) => identifier(name),
+                _ => false,
+            };
+            if sized {
+                format!("🧱{}", p(size))
+            } else {
+                format!("🧱({})", write(size, Next::Close))
+            }
+        }
+        AstNode::ToBytes { value, encoding } if empty(encoding) => through(value, "🧱".to_string()),
+        AstNode::ToBytes { value, encoding } => through(value, format!("🧱{}", p(encoding))),
+        AstNode::ToText { value, encoding } if empty(encoding) => through(value, "S".to_string()),
+        AstNode::ToText { value, encoding } => through(value, format!("S{}", p(encoding))),
+        AstNode::FlushBuffer { target } => through(target, "🌬️".to_string()),
+        AstNode::EndOfFile => "🔚".to_string(),
+        AstNode::SkipBytes { source, count } => through(source, format!("⏭️{}", p(count))),
+        AstNode::CreateSocket { socket_type } => match &**socket_type {
+            // A bare TCP or UDP is the protocol's name, not a variable
+            AstNode::Variable(name) if name.eq_ignore_ascii_case("tcp") || name.eq_ignore_ascii_case("udp") => {
+                format!("🔌({})", name)
+            }
+            socket_type => format!("🔌{}", p(socket_type)),
+        },
+        AstNode::ListenPort { port } if empty(port) => "👂".to_string(),
+        AstNode::ListenPort { port } => match &**port {
+            AstNode::Literal(lit) if !matches!(lit, LiteralValue::Bytes(_)) && !literal(lit).starts_with('-') => {
+                format!("👂{}", p(port))
+            }
+            AstNode::Variable(name) if identifier(name) => format!("👂{}", p(port)),
+            port => format!("👂({})", write(port, Next::Close)),
+        },
+        AstNode::ConnectRemote { address } => format!("📞{}", p(address)),
+        AstNode::PortNumber { number } => format!("🚪{}", p(number)),
+        AstNode::CreatePacket { data } => format!("📦{}", p(data)),
+        AstNode::Handshake { connection } => format!("🤝{}", p(connection)),
+        AstNode::ProcessCreate { command } => format!("⚙️{}", p(command)),
+        AstNode::ShellExec { command } => format!("🐚{}", p(command)),
+        AstNode::EnvVar { name } => format!("🌍{}", p(name)),
+        AstNode::MemoryAlloc { size } => format!("🐏{}", p(size)),
+        AstNode::ExitProgram { code } => format!("👋{}", p(code)),
+        AstNode::SendSignal { signal, target } => format!("📶{} {}", o(signal), p(target)),
+        AstNode::PropertyAccess { object, property } => {
+            // `5.x` would lex as a number
+            let object = match &**object {
+                AstNode::Literal(LiteralValue::String(_) | LiteralValue::Bytes(_)) => print(object, Rank::Postfix, Next::Other),
+                AstNode::Literal(lit) => format!("({})", literal(lit)),
+                object => print(object, Rank::Postfix, Next::Other),
+            };
+            format!("{}.{}", object, property)
+        }
+        AstNode::Length { value } if is_piped(value) && matches!(next, Next::Stop | Next::Pipe | Next::Into) => {
+            "📏".to_string()
+        }
+        AstNode::Length { value } => format!("📏{}", p(value)),
+        AstNode::Index { target, index } | AstNode::CharAt { target, index } => format!(
+            "{}⟦{}⟧",
+            print(target, Rank::Binary, Next::Other),
+            print(index, Rank::Sequence, Next::Close)
+        ),
+        AstNode::ArrayPush { array, element } => through(array, format!("⊞{}", p(element))),
+        AstNode::Slice { target, start, end } => format!(
+            "{}⊢{}{}⊣",
+            print(target, Rank::Binary, Next::Other),
+            print(start, Rank::Sequence, Next::Close),
+            end.as_deref().map(|end| format!(":{}", print(end, Rank::Sequence, Next::Close))).unwrap_or_default()
+        ),
+        AstNode::ArrayLiteral { elements } => format!(
+            "[{}]",
+            elements.iter().map(|element| print(element, Rank::Sequence, Next::Close)).collect::<Vec<_>>().join(", ")
+        ),
+        AstNode::ObjectLiteral { pairs } => format!(
+            "{{{}}}",
+            pairs
+                .iter()
+                .map(|(key, value)| {
+                    let key = if identifier(key) { key.clone() } else { quote(key) };
+                    format!("{}: {}", key, print(value, Rank::Sequence, Next::Close))
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// An HTTP glyph with its url, the body methods that send one take after
+/// `⇢`, and its `🏷️` headers
+fn http(glyph: &str, url: &AstNode, body: Option<&Option<Box<AstNode>>>, headers: &Option<Box<AstNode>>, next: Next) -> String {
+    let body = body.and_then(|body| body.as_deref());
+    let after_body = if headers.is_some() { Next::Headers } else { next };
+    let mut code = format!("{}{}", glyph, operand(url, if body.is_some() { Next::Pipe } else { after_body }));
+    if let Some(body) = body {
+        code.push_str(&format!(" ⇢ {}", operand(body, after_body)));
+    }
+    if let Some(headers) = headers {
+        code.push_str(&format!(" 🏷️ {}", operand(headers, next)));
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explainer::Explainer;
+    use crate::{Lexer, Parser};
+    use std::path::Path;
+
+    fn parse(source: &str) -> Vec<AstNode> {
+        let tokens = Lexer::new(source.to_string()).tokenize().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    fn explain(ast: &[AstNode], lang: Lang) -> String {
+        let mut explainer = Explainer::new();
+        explainer.set_lang(lang);
+        explainer.explain(ast)
+    }
+
+    fn project(explanation: &str, lang: Lang) -> Result<Vec<AstNode>> {
+        let mut projector = Projector::new();
+        projector.set_lang(lang);
+        projector.project(explanation)
+    }
+
+    /// Explaining and projecting back, and writing as glyphs and parsing
+    /// back, both give the same AST
+    fn assert_round_trips(source: &str) {
+        let ast = parse(source);
+        for lang in [Lang::En, Lang::Zh] {
+            let explanation = explain(&ast, lang);
+            let projected = project(&explanation, lang)
+                .unwrap_or_else(|err| panic!("{}\n{}\n{}", source, explanation, err));
+            assert_eq!(projected, ast, "{}\n{}", source, explanation);
+        }
+        let code = glyphs(&ast).unwrap_or_else(|err| panic!("{}\n{}", source, err));
+        assert_eq!(parse(&code), ast, "{}\n{}", source, code);
+    }
+
+    #[test]
+    fn test_unexplain_crawler() {
+        let source = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/crawler.ae")).unwrap();
+        let ast = project(&explain(&parse(&source), Lang::En), Lang::En).unwrap();
+        assert_eq!(glyphs(&ast).unwrap(), "🧩🌐 ⨠ ♻3: 🌐📥\"https://example.com\" ▷ res ⨠ 💾res\n");
+    }
+
+    #[test]
+    fn test_project_written_explanation() {
+        let ast = project("read input | parse JSON(read input) -> store in u\n\noutput u.name", Lang::En).unwrap();
+        assert_eq!(ast, parse("📥 ⇢ J ▷ u\n📤(u.name)"));
+        let ast = project("如果 (x > 1)：\n  输出 \"大\"\n否则：\n  输出 \"小\"", Lang::Zh).unwrap();
+        assert_eq!(ast, parse("◇(x > 1): 📤\"大\" ◆: 📤\"小\""));
+    }
+
+    #[test]
+    fn test_examples_round_trip() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let mut projected = 0;
+        for entry in std::fs::read_dir(root).unwrap() {
+            let path = entry.unwrap().path();
+            // api_demo.ae doesn't lex yet
+            if path.extension().is_none_or(|ext| ext != "ae") || path.ends_with("api_demo.ae") {
+                continue;
+            }
+            assert_round_trips(&std::fs::read_to_string(&path).unwrap());
+            projected += 1;
+        }
+        assert!(projected > 40, "only {} examples projected", projected);
+    }
+
+    #[test]
+    fn test_constructs_round_trip() {
+        let sources = [
+            "◇(a): 📤1 ◈(b): 📤2",
+            "◇(a): (◇(b): 📤1) ◆: 📤2",
+            "◇(a): 📤1 ◈(b): 📤2 ◆: 📤3 ⨠ 📤4",
+            "💾 ⨠ 💾∅ ⇢ 📤_pipe",
+            "📥 ⇢ 📏 ▷ n ⨠ 📏xs ⨠ 9 ⇢ √ ▷ r ⨠ √16",
+            "📤1\n(-2 ⇢ 📤_pipe)\n([1, 2] ⇢ 📤_pipe)",
+            "📤\"tab\\t \\\"quoted\\\" {braces} \\\\ \\n\"",
+            "↻(i < 3): (📤i ⨠ ∆i 1) ⨠ 📤\"done\"",
+            "∀x: 📤x ⨠ ♻: 📤1 ⨠ 🧪\"adds\": ⚖️(1 + 1 ≡ 2)",
+            "🛡(🌐📤\"u\" ⇢ {a: 1, \"b c\": [1, 2.5]} 🏷️ {k: \"v\"}) ⨠ 🌐🔧\"u\" ⨠ 🌐👁\"u\" 🏷️ h",
+            "👁\"e\" h ⨠ 👁\"e\"",
+            "🌊 ⨠ 🌊\"utf8\" ⨠ 🧱 ⨠ 🧱\"utf8\" ⨠ 🧱1024 ⨠ 🧱hex\"00ff\" ⨠ S ⨠ S\"utf8\" ⨠ 👂 ⨠ 👂8080",
+            "🔌TCP ⨠ 🔌(x + 1) ⨠ 🔐d k ⨠ 🔏s d k ⨠ 🛂\"f\" 420 ⨠ 📶9 pid",
+            "xs⟦0⟧ ⨠ s⊢1:3⊣ ⨠ s⊢:2⊣ ⨠ ≡1 ⨠ ≠x ⨠ ¬(a ⊗ b ⊕ c)",
+            "ƒ add: x + y\nƒ®: 📤1\nƒ: 📤2",
+            "🧊 limit 10 ⨠ ✂\",\" ⨠ 🔗 ⨠ ✱\"a+\" ⨠ ⁇\"missing\" ⨠ ∑(a + b)",
+            "↑2 ⨠ ≈0.5 ⨠ ⊞4 ⨠ 📖 ⨠ 🖊️\"f\" ⨠ 🖇️\"f\" ⨠ 🌬️ ⨠ ⏭️4 ⨠ 🔚 ⨠ ∞",
+            "↻📤1 ⨠ (🌐📤\"u\") ⇢ 📤_pipe ⨠ 🧱(-1) ⨠ 🧱(x ⇢ 📏xs) ⨠ 👂(p + 1) ⨠ [📏_pipe, √_pipe]",
+            "📥 ▷ body:S ⨠ 📥 ▷ n:N ⨠ (a ⇢ 📤_pipe) ▷ b ⨠ 1.0 ⨠ 2.50 ⨠ -3",
+        ];
+        for source in sources {
+            assert_round_trips(source);
+        }
+    }
+
+    #[test]
+    fn test_unreadable_explanation_is_an_error() {
+        let err = project("output 1\noutput with nothing", Lang::En).unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+    }
+}
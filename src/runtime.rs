@@ -1,22 +1,20 @@
 //! Runtime for executing Aether AST
 
+use crate::bytes;
+use crate::crypto;
+use crate::debugger::DebugHook;
 use crate::error::{AetherError, Result};
+use crate::limits::{Limit, Limits, Meter};
+use crate::net::{self, NetRegistry};
+use crate::numeric::{self, Arith, Decimal};
 use crate::parser::{AstNode, LiteralValue};
+use crate::stream::{self, StreamRegistry};
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 use chrono::Utc;
 use rand::Rng;
 use regex::Regex;
 
-// Crypto imports for v1.2
-use sha2::{Sha256, Digest};
-use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
-};
-use ed25519_dalek::{Signer, Verifier, SigningKey, Signature};
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-
 // HTTP client import
 use reqwest;
 use serde_json;
@@ -26,14 +24,21 @@ use tokio::runtime::Runtime as TokioRuntime;
 use std::sync::{Arc, Mutex};
 
 /// Runtime value
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Value {
     String(String),
+    /// Floating-point number
     Number(f64),
+    /// Whole number, checked for overflow
+    Integer(i64),
+    /// Exact base-10 number
+    Decimal(Decimal),
     Boolean(bool),
     Null,
     Object(HashMap<String, Value>),
     Array(Vec<Value>),
+    /// Raw binary data
+    Bytes(Vec<u8>),
     /// AsyncTask represents a task handle (ID for tracking)
     AsyncTask(String),
 }
@@ -46,13 +51,27 @@ impl Value {
         }
     }
 
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Any kind of number as a float
     pub fn as_number(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Integer(n) => Some(*n as f64),
+            Value::Decimal(d) => Some(d.to_f64()),
             _ => None,
         }
     }
 
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Value::Number(_) | Value::Integer(_) | Value::Decimal(_))
+    }
+
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null)
     }
@@ -62,9 +81,12 @@ impl Value {
             Value::Null => false,
             Value::Boolean(b) => *b,
             Value::Number(n) => *n != 0.0,
+            Value::Integer(n) => *n != 0,
+            Value::Decimal(d) => !d.is_zero(),
             Value::String(s) => !s.is_empty(),
             Value::Array(a) => !a.is_empty(),
             Value::Object(o) => !o.is_empty(),
+            Value::Bytes(b) => !b.is_empty(),
             Value::AsyncTask(_) => true, // Task handles are truthy
         }
     }
@@ -77,19 +99,48 @@ impl Value {
     }
 }
 
+/// Numbers are equal by value whatever their kind, so `2 == 2.0`
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::AsyncTask(a), Value::AsyncTask(b)) => a == b,
+            _ => numeric::compare(self, other) == Some(std::cmp::Ordering::Equal),
+        }
+    }
+}
+
+/// Shared table of async task results, keyed by task ID
+type TaskTable = Arc<Mutex<HashMap<String, Arc<Mutex<Option<Value>>>>>>;
+
 /// Runtime environment for executing Aether programs
 pub struct Runtime {
     variables: HashMap<String, Value>,
     immutable_vars: HashSet<String>,
-    max_loop_iterations: usize,
+    // Execution budgets
+    limits: Limits,
+    meter: Meter,
+    depth: usize,
     // v1.2 Testing & Debugging
     test_context: Option<TestContext>,
     mocked_targets: HashSet<String>,
     debug_enabled: bool,
     // Async runtime support
     tokio_runtime: Arc<TokioRuntime>,
-    async_tasks: Arc<Mutex<HashMap<String, Arc<Mutex<Option<Value>>>>>>,
+    async_tasks: TaskTable,
     task_counter: Arc<Mutex<usize>>,
+    // v1.3 Networking & Streams
+    net: NetRegistry,
+    streams: StreamRegistry,
+    // Output lines collected instead of printed
+    captured: Option<Vec<String>>,
+    // Debugger called before each expression
+    debug_hook: Option<Box<dyn DebugHook>>,
 }
 
 /// Test execution context
@@ -112,23 +163,54 @@ impl Runtime {
         Runtime {
             variables: HashMap::new(),
             immutable_vars: HashSet::new(),
-            max_loop_iterations: 10000,
+            limits: Limits::default(),
+            meter: Meter::new(Limits::default()),
+            depth: 0,
             test_context: None,
             mocked_targets: HashSet::new(),
             debug_enabled: false,
             tokio_runtime,
             async_tasks: Arc::new(Mutex::new(HashMap::new())),
             task_counter: Arc::new(Mutex::new(0)),
+            net: NetRegistry::new(),
+            streams: StreamRegistry::new(),
+            captured: None,
+            debug_hook: None,
         }
     }
     
-    /// Set maximum loop iterations (for safety)
-    pub fn set_max_loop_iterations(&mut self, max: usize) {
-        self.max_loop_iterations = max;
+    /// Collect `📤`, `🪵` and `💾` lines instead of printing them
+    pub fn capture_output(&mut self) {
+        self.captured = Some(Vec::new());
+    }
+
+    /// Take the lines collected since `capture_output`
+    pub fn take_output(&mut self) -> Vec<String> {
+        self.captured.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn print_line(&mut self, line: String) {
+        match &mut self.captured {
+            Some(lines) => lines.push(line),
+            None => println!("{}", line),
+        }
+    }
+
+    /// Call a debugger before evaluating each expression
+    pub fn set_debug_hook(&mut self, hook: Box<dyn DebugHook>) {
+        self.debug_hook = Some(hook);
+    }
+
+    /// Set the execution budgets, metered from the next `execute`
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.meter = Meter::new(limits.clone());
+        self.limits = limits;
     }
 
     /// Execute an AST and return the result
     pub fn execute(&mut self, nodes: Vec<AstNode>) -> Result<Value> {
+        self.meter = Meter::new(self.limits.clone());
+        self.depth = 0;
         let mut last_value = Value::Null;
 
         for node in nodes {
@@ -138,8 +220,23 @@ impl Runtime {
         Ok(last_value)
     }
 
-    /// Evaluate a single AST node
+    /// Evaluate a single AST node within the execution budgets
     fn eval_node(&mut self, node: &AstNode) -> Result<Value> {
+        self.meter.step()?;
+        self.depth += 1;
+        let result = self.meter.check_depth(self.depth)
+            .and_then(|_| match self.debug_hook.as_mut() {
+                Some(hook) => hook.before(node, self.depth, &mut self.variables),
+                None => Ok(()),
+            })
+            .and_then(|_| self.evaluate(node));
+        self.depth -= 1;
+        let value = result?;
+        self.meter.check_value(&value)?;
+        Ok(value)
+    }
+
+    fn evaluate(&mut self, node: &AstNode) -> Result<Value> {
         match node {
             AstNode::Function { name, body } => {
                 // For now, just execute the body
@@ -163,18 +260,22 @@ impl Runtime {
 
             AstNode::Output(value) => {
                 let result = self.eval_node(value)?;
-                println!("Output: {:?}", result);
+                self.print_line(format!("Output: {:?}", result));
                 Ok(result)
             }
 
             AstNode::Pipe { source, operation } => {
                 let source_value = self.eval_node(source)?;
+                // `x ⇢ 🔚` asks whether x is at its end
+                if matches!(operation.as_ref(), AstNode::EndOfFile) {
+                    return Ok(Value::Boolean(self.streams.at_end(&source_value)?));
+                }
                 // Set a temporary variable for piped value
                 self.variables.insert("_pipe".to_string(), source_value);
                 self.eval_node(operation)
             }
 
-            AstNode::PipeInto { value, variable } => {
+            AstNode::PipeInto { value, variable, .. } => {
                 let val = self.eval_node(value)?;
                 // Check immutability before assignment
                 if self.immutable_vars.contains(variable) {
@@ -207,7 +308,7 @@ impl Runtime {
                 } else {
                     self.eval_node(value)?
                 };
-                println!("Persisting: {:?}", val);
+                self.print_line(format!("Persisting: {:?}", val));
                 Ok(Value::Boolean(true))
             }
 
@@ -227,6 +328,9 @@ impl Runtime {
             AstNode::Literal(lit) => match lit {
                 LiteralValue::String(s) => Ok(Value::String(s.clone())),
                 LiteralValue::Number(n) => Ok(Value::Number(*n)),
+                LiteralValue::Integer(n) => Ok(Value::Integer(*n)),
+                LiteralValue::Decimal(d) => Ok(Value::Decimal(*d)),
+                LiteralValue::Bytes(b) => Ok(Value::Bytes(b.clone())),
             },
 
             AstNode::Empty => Ok(Value::Null),
@@ -245,17 +349,10 @@ impl Runtime {
             
             // Control Flow & Iteration
             AstNode::Loop { condition, body } => {
-                // Execute loop with safety limit
-                let mut iterations = 0;
+                // Runaway loops are stopped by the step limit
                 let mut last_value = Value::Null;
                 
                 loop {
-                    if iterations >= self.max_loop_iterations {
-                        return Err(AetherError::RuntimeError(
-                            format!("Loop exceeded maximum iterations ({})", self.max_loop_iterations)
-                        ));
-                    }
-                    
                     // Check condition if present
                     if let Some(cond) = condition {
                         let cond_value = self.eval_node(cond)?;
@@ -270,8 +367,6 @@ impl Runtime {
                     if condition.is_none() && !last_value.is_truthy() {
                         break;
                     }
-                    
-                    iterations += 1;
                 }
                 
                 Ok(last_value)
@@ -392,6 +487,9 @@ impl Runtime {
                                 match lit {
                                     LiteralValue::String(s) => Value::String(s.clone()),
                                     LiteralValue::Number(n) => Value::Number(*n),
+                                    LiteralValue::Integer(n) => Value::Integer(*n),
+                                    LiteralValue::Decimal(d) => Value::Decimal(*d),
+                                    LiteralValue::Bytes(b) => Value::Bytes(b.clone()),
                                 }
                             }
                             AstNode::Output(value) => {
@@ -401,6 +499,9 @@ impl Runtime {
                                         match lit {
                                             LiteralValue::String(s) => Value::String(s.clone()),
                                             LiteralValue::Number(n) => Value::Number(*n),
+                                            LiteralValue::Integer(n) => Value::Integer(*n),
+                                            LiteralValue::Decimal(d) => Value::Decimal(*d),
+                                            LiteralValue::Bytes(b) => Value::Bytes(b.clone()),
                                         }
                                     }
                                     _ => Value::Null,
@@ -433,14 +534,14 @@ impl Runtime {
                         // Poll with timeout
                         let max_polls = 1000;
                         for _ in 0..max_polls {
-                            let tasks_guard = tasks.lock().unwrap();
-                            if let Some(task_result) = tasks_guard.get(&task_id) {
-                                let result_guard = task_result.lock().unwrap();
-                                if let Some(value) = result_guard.as_ref() {
-                                    return Ok(value.clone());
-                                }
+                            let finished = {
+                                let tasks_guard = tasks.lock().unwrap();
+                                tasks_guard.get(&task_id)
+                                    .and_then(|task_result| task_result.lock().unwrap().clone())
+                            };
+                            if let Some(value) = finished {
+                                return Ok(value);
                             }
-                            drop(tasks_guard);
                             
                             // Small delay before polling again
                             tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
@@ -532,6 +633,9 @@ impl Runtime {
                     let strings: Vec<String> = items.iter()
                         .filter_map(|v| v.as_string().map(|s| s.to_string()))
                         .collect();
+                    let len = strings.iter().map(String::len).sum::<usize>()
+                        .saturating_add(sep.len().saturating_mul(strings.len().saturating_sub(1)));
+                    self.meter.check_length(Limit::StringLength, "string", len)?;
                     Ok(Value::String(strings.join(&sep)))
                 } else {
                     Ok(Value::Null)
@@ -571,6 +675,9 @@ impl Runtime {
                 } else {
                     self.eval_node(left)?
                 };
+                if matches!(right.as_ref(), AstNode::EndOfFile) {
+                    return Ok(Value::Boolean(self.streams.at_end(&l)?));
+                }
                 let r = self.eval_node(right)?;
                 Ok(Value::Boolean(l == r))
             }
@@ -581,6 +688,9 @@ impl Runtime {
                 } else {
                     self.eval_node(left)?
                 };
+                if matches!(right.as_ref(), AstNode::EndOfFile) {
+                    return Ok(Value::Boolean(!self.streams.at_end(&l)?));
+                }
                 let r = self.eval_node(right)?;
                 Ok(Value::Boolean(l != r))
             }
@@ -654,7 +764,7 @@ impl Runtime {
             
             AstNode::Log { message } => {
                 let msg = self.eval_node(message)?;
-                println!("LOG: {:?}", msg);
+                self.print_line(format!("LOG: {:?}", msg));
                 Ok(Value::Null)
             }
             
@@ -853,193 +963,38 @@ impl Runtime {
             AstNode::Encrypt { data, key } => {
                 let data_val = self.eval_node(data)?;
                 let key_val = self.eval_node(key)?;
-                
-                let plaintext = match data_val {
-                    Value::String(s) => s.as_bytes().to_vec(),
-                    _ => return Err(AetherError::RuntimeError("Encrypt requires string data".to_string())),
-                };
-                
-                let key_str = match key_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Encrypt requires string key".to_string())),
-                };
-                
-                // Use SHA-256 to derive a 32-byte key from the string
-                let mut hasher = Sha256::new();
-                hasher.update(key_str.as_bytes());
-                let key_bytes = hasher.finalize();
-                
-                // Create cipher
-                let cipher = Aes256Gcm::new((&key_bytes).into());
-                
-                // Generate nonce
-                let nonce_bytes = rand::thread_rng().gen::<[u8; 12]>();
-                let nonce = Nonce::from_slice(&nonce_bytes);
-                
-                // Encrypt
-                match cipher.encrypt(nonce, plaintext.as_ref()) {
-                    Ok(ciphertext) => {
-                        // Combine nonce + ciphertext and encode as base64
-                        let mut combined = nonce_bytes.to_vec();
-                        combined.extend_from_slice(&ciphertext);
-                        let encoded = BASE64.encode(&combined);
-                        Ok(Value::String(encoded))
-                    }
-                    Err(e) => Err(AetherError::RuntimeError(format!("Encryption failed: {}", e))),
-                }
+                crypto::encrypt(&data_val, &key_val)
             }
             
             AstNode::Decrypt { data, key } => {
                 let data_val = self.eval_node(data)?;
                 let key_val = self.eval_node(key)?;
-                
-                let encrypted_str = match data_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Decrypt requires string data".to_string())),
-                };
-                
-                let key_str = match key_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Decrypt requires string key".to_string())),
-                };
-                
-                // Decode from base64
-                let combined = BASE64.decode(encrypted_str.as_bytes())
-                    .map_err(|e| AetherError::RuntimeError(format!("Invalid encrypted data: {}", e)))?;
-                
-                if combined.len() < 12 {
-                    return Err(AetherError::RuntimeError("Invalid encrypted data: too short".to_string()));
-                }
-                
-                // Extract nonce and ciphertext
-                let (nonce_bytes, ciphertext) = combined.split_at(12);
-                let nonce = Nonce::from_slice(nonce_bytes);
-                
-                // Derive key
-                let mut hasher = Sha256::new();
-                hasher.update(key_str.as_bytes());
-                let key_bytes = hasher.finalize();
-                
-                // Create cipher
-                let cipher = Aes256Gcm::new((&key_bytes).into());
-                
-                // Decrypt
-                match cipher.decrypt(nonce, ciphertext) {
-                    Ok(plaintext) => {
-                        let text = String::from_utf8(plaintext)
-                            .map_err(|e| AetherError::RuntimeError(format!("Invalid UTF-8: {}", e)))?;
-                        Ok(Value::String(text))
-                    }
-                    Err(e) => Err(AetherError::RuntimeError(format!("Decryption failed: {}", e))),
-                }
+                crypto::decrypt(&data_val, &key_val)
             }
             
             AstNode::Hash { data } => {
                 let data_val = self.eval_node(data)?;
-                
-                let bytes = match data_val {
-                    Value::String(s) => s.as_bytes().to_vec(),
-                    Value::Number(n) => n.to_string().as_bytes().to_vec(),
-                    _ => return Err(AetherError::RuntimeError("Hash requires string or number".to_string())),
-                };
-                
-                let mut hasher = Sha256::new();
-                hasher.update(&bytes);
-                let result = hasher.finalize();
-                
-                // Convert to hex string
-                let hex_string = format!("{:x}", result);
-                Ok(Value::String(hex_string))
+                crypto::hash(&data_val)
             }
             
             AstNode::Sign { data, key } => {
                 let data_val = self.eval_node(data)?;
                 let key_val = self.eval_node(key)?;
-                
-                let message = match data_val {
-                    Value::String(s) => s.as_bytes().to_vec(),
-                    _ => return Err(AetherError::RuntimeError("Sign requires string data".to_string())),
-                };
-                
-                let key_str = match key_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Sign requires string key".to_string())),
-                };
-                
-                // Derive signing key from string (in real impl, would use actual key)
-                let mut key_bytes = [0u8; 32];
-                let mut hasher = Sha256::new();
-                hasher.update(key_str.as_bytes());
-                key_bytes.copy_from_slice(&hasher.finalize()[..32]);
-                
-                let signing_key = SigningKey::from_bytes(&key_bytes);
-                let signature = signing_key.sign(&message);
-                
-                // Encode signature as base64
-                let sig_bytes = signature.to_bytes();
-                let encoded = BASE64.encode(&sig_bytes);
-                Ok(Value::String(encoded))
+                crypto::sign(&data_val, &key_val)
             }
             
             AstNode::VerifySignature { signature, data, key } => {
                 let sig_val = self.eval_node(signature)?;
                 let data_val = self.eval_node(data)?;
                 let key_val = self.eval_node(key)?;
-                
-                let sig_str = match sig_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Verify requires string signature".to_string())),
-                };
-                
-                let message = match data_val {
-                    Value::String(s) => s.as_bytes().to_vec(),
-                    _ => return Err(AetherError::RuntimeError("Verify requires string data".to_string())),
-                };
-                
-                let key_str = match key_val {
-                    Value::String(s) => s,
-                    _ => return Err(AetherError::RuntimeError("Verify requires string key".to_string())),
-                };
-                
-                // Decode signature
-                let sig_bytes = BASE64.decode(sig_str.as_bytes())
-                    .map_err(|e| AetherError::RuntimeError(format!("Invalid signature: {}", e)))?;
-                
-                if sig_bytes.len() != 64 {
-                    return Err(AetherError::RuntimeError("Invalid signature length".to_string()));
-                }
-                
-                let mut sig_array = [0u8; 64];
-                sig_array.copy_from_slice(&sig_bytes);
-                let signature = Signature::from_bytes(&sig_array);
-                
-                // Derive verifying key (in real impl, would be public key)
-                let mut key_bytes = [0u8; 32];
-                let mut hasher = Sha256::new();
-                hasher.update(key_str.as_bytes());
-                key_bytes.copy_from_slice(&hasher.finalize()[..32]);
-                
-                let signing_key = SigningKey::from_bytes(&key_bytes);
-                let verifying_key = signing_key.verifying_key();
-                
-                // Verify signature
-                match verifying_key.verify(&message, &signature) {
-                    Ok(_) => Ok(Value::Boolean(true)),
-                    Err(_) => Ok(Value::Boolean(false)),
-                }
+                crypto::verify(&sig_val, &data_val, &key_val)
             }
             
             // Math & Science (v1.2)
             AstNode::Power { base, exponent } => {
                 let base_val = self.eval_node(base)?;
                 let exp_val = self.eval_node(exponent)?;
-                
-                let b = base_val.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Power requires number base".to_string()))?;
-                let e = exp_val.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Power requires number exponent".to_string()))?;
-                
-                Ok(Value::Number(b.powf(e)))
+                numeric::power(&base_val, &exp_val)
             }
             
             AstNode::Root { value } => {
@@ -1088,11 +1043,7 @@ impl Runtime {
                 let path_str = path_val.as_string()
                     .ok_or_else(|| AetherError::RuntimeError("File path must be string".to_string()))?;
                 
-                // Return file object representation
-                let mut file_obj = HashMap::new();
-                file_obj.insert("type".to_string(), Value::String("file".to_string()));
-                file_obj.insert("path".to_string(), Value::String(path_str.to_string()));
-                Ok(Value::Object(file_obj))
+                Ok(stream::file_object(path_str))
             }
             
             AstNode::Directory { path } => {
@@ -1118,7 +1069,18 @@ impl Runtime {
             }
             
             AstNode::ReadContent { source } => {
-                let src = self.eval_node(source)?;
+                let src = if matches!(source.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(source)?
+                };
+                
+                if self.net.owns(&src) {
+                    return self.net.read(&src);
+                }
+                if self.streams.owns(&src) {
+                    return self.streams.read(&src);
+                }
                 
                 // Simulate reading content
                 if let Value::Object(obj) = src {
@@ -1139,7 +1101,18 @@ impl Runtime {
             
             AstNode::WriteContent { target, content } => {
                 let tgt = self.eval_node(target)?;
-                let cnt = self.eval_node(content)?;
+                let cnt = if matches!(content.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(content)?
+                };
+                
+                if self.net.owns(&tgt) {
+                    return self.net.write(&tgt, &cnt);
+                }
+                if self.streams.owns(&tgt) {
+                    return self.streams.write(&tgt, &cnt);
+                }
                 
                 let path = if let Value::Object(obj) = &tgt {
                     obj.get("path")
@@ -1156,7 +1129,18 @@ impl Runtime {
             
             AstNode::AppendContent { target, content } => {
                 let tgt = self.eval_node(target)?;
-                let cnt = self.eval_node(content)?;
+                let cnt = if matches!(content.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(content)?
+                };
+                
+                if self.net.owns(&tgt) {
+                    return self.net.write(&tgt, &cnt);
+                }
+                if self.streams.owns(&tgt) {
+                    return self.streams.write(&tgt, &cnt);
+                }
                 
                 let path = if let Value::Object(obj) = &tgt {
                     obj.get("path")
@@ -1174,6 +1158,13 @@ impl Runtime {
             AstNode::DeleteFile { target } => {
                 let tgt = self.eval_node(target)?;
                 
+                if self.net.owns(&tgt) {
+                    return self.net.close(&tgt);
+                }
+                if self.streams.owns(&tgt) {
+                    return self.streams.close(&tgt);
+                }
+                
                 let path = if let Value::Object(obj) = &tgt {
                     obj.get("path")
                         .and_then(|v| v.as_string())
@@ -1205,114 +1196,120 @@ impl Runtime {
             }
             
             // Streams & Buffers (v1.3)
-            AstNode::CreateStream { source } => {
-                let src = self.eval_node(source)?;
-                
-                // Create stream object
-                let mut stream_obj = HashMap::new();
-                stream_obj.insert("type".to_string(), Value::String("stream".to_string()));
-                stream_obj.insert("source".to_string(), src);
-                stream_obj.insert("position".to_string(), Value::Number(0.0));
-                Ok(Value::Object(stream_obj))
+            AstNode::CreateStream { source, options } => {
+                let src = if matches!(source.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(source)?
+                };
+                let opts = self.eval_node(options)?;
+                self.streams.open(&src, &opts, &self.net)
             }
             
             AstNode::CreateBuffer { size } => {
                 let sz = self.eval_node(size)?;
-                let size_num = sz.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Buffer size must be number".to_string()))?;
-                
-                // Create buffer object
-                let mut buffer_obj = HashMap::new();
-                buffer_obj.insert("type".to_string(), Value::String("buffer".to_string()));
-                buffer_obj.insert("size".to_string(), Value::Number(size_num));
-                buffer_obj.insert("data".to_string(), Value::Array(Vec::new()));
-                Ok(Value::Object(buffer_obj))
+                bytes::allocate(&sz, &self.meter)
+            }
+            
+            AstNode::ToBytes { value, encoding } => {
+                let val = if matches!(value.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(value)?
+                };
+                let enc = self.eval_node(encoding)?;
+                bytes::to_bytes(&val, &enc)
+            }
+            
+            AstNode::ToText { value, encoding } => {
+                let val = if matches!(value.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(value)?
+                };
+                let enc = self.eval_node(encoding)?;
+                bytes::to_text(&val, &enc)
             }
             
             AstNode::FlushBuffer { target } => {
-                let tgt = self.eval_node(target)?;
-                println!("Flushing buffer: {:?}", tgt);
+                let tgt = if matches!(target.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(target)?
+                };
+                
+                if self.streams.owns(&tgt) {
+                    return self.streams.flush(&tgt);
+                }
+                
+                // Anything else flushes program output
+                std::io::Write::flush(&mut std::io::stdout())?;
                 Ok(Value::Boolean(true))
             }
             
             AstNode::EndOfFile => {
-                Ok(Value::Boolean(true))
+                // The end-of-data marker; 📖 returns the same value at end of stream
+                Ok(Value::Null)
             }
             
             AstNode::SkipBytes { source, count } => {
-                let src = self.eval_node(source)?;
+                let src = if matches!(source.as_ref(), AstNode::Empty) {
+                    self.variables.get("_pipe").cloned().unwrap_or(Value::Null)
+                } else {
+                    self.eval_node(source)?
+                };
                 let cnt = self.eval_node(count)?;
-                let count_num = cnt.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Skip count must be number".to_string()))?;
                 
-                println!("Skipping {} bytes in stream: {:?}", count_num, src);
-                Ok(src)
+                if !self.streams.owns(&src) {
+                    return Err(AetherError::RuntimeError("Skip requires a stream".to_string()));
+                }
+                self.streams.skip(&src, &cnt)
             }
             
             // Networking (v1.3)
             AstNode::CreateSocket { socket_type } => {
                 let sock_type = self.eval_node(socket_type)?;
-                
-                // Create socket object
-                let mut socket_obj = HashMap::new();
-                socket_obj.insert("type".to_string(), Value::String("socket".to_string()));
-                socket_obj.insert("protocol".to_string(), sock_type);
-                socket_obj.insert("connected".to_string(), Value::Boolean(false));
-                Ok(Value::Object(socket_obj))
+                self.net.socket(&sock_type)
             }
             
             AstNode::ListenPort { port } => {
-                let port_val = self.eval_node(port)?;
-                let port_num = port_val.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Port must be number".to_string()))?;
-                
-                println!("Listening on port: {}", port_num);
+                if matches!(port.as_ref(), AstNode::Empty) {
+                    // Accept on the piped listener, or the most recent one
+                    let piped = self.variables.get("_pipe").cloned().unwrap_or(Value::Null);
+                    return match &piped {
+                        Value::Object(obj) if obj.get("type") == Some(&Value::String("listener".to_string())) => {
+                            self.net.accept(Some(&piped))
+                        }
+                        Value::Number(_) | Value::Integer(_) | Value::Decimal(_) | Value::String(_) => self.net.listen(&piped),
+                        _ => self.net.accept(None),
+                    };
+                }
                 
-                // Return listener object
-                let mut listener_obj = HashMap::new();
-                listener_obj.insert("type".to_string(), Value::String("listener".to_string()));
-                listener_obj.insert("port".to_string(), Value::Number(port_num));
-                listener_obj.insert("active".to_string(), Value::Boolean(true));
-                Ok(Value::Object(listener_obj))
+                let port_val = self.eval_node(port)?;
+                self.net.listen(&port_val)
             }
             
             AstNode::ConnectRemote { address } => {
                 let addr = self.eval_node(address)?;
-                let addr_str = addr.as_string()
-                    .ok_or_else(|| AetherError::RuntimeError("Connect address must be string".to_string()))?;
-                
-                println!("Connecting to: {}", addr_str);
-                
-                // Return connection object
-                let mut conn_obj = HashMap::new();
-                conn_obj.insert("type".to_string(), Value::String("connection".to_string()));
-                conn_obj.insert("address".to_string(), Value::String(addr_str.to_string()));
-                conn_obj.insert("connected".to_string(), Value::Boolean(true));
-                Ok(Value::Object(conn_obj))
+                self.net.connect(&addr)
             }
             
             AstNode::PortNumber { number } => {
                 let num = self.eval_node(number)?;
-                let port_num = num.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Port number must be numeric".to_string()))?;
-                
-                Ok(Value::Number(port_num))
+                if !num.is_numeric() {
+                    return Err(AetherError::RuntimeError("Port number must be numeric".to_string()));
+                }
+                Ok(num)
             }
             
             AstNode::CreatePacket { data } => {
                 let packet_data = self.eval_node(data)?;
-                
-                // Create packet object
-                let mut packet_obj = HashMap::new();
-                packet_obj.insert("type".to_string(), Value::String("packet".to_string()));
-                packet_obj.insert("data".to_string(), packet_data);
-                Ok(Value::Object(packet_obj))
+                Ok(net::packet(packet_data))
             }
             
             AstNode::Handshake { connection } => {
                 let conn = self.eval_node(connection)?;
-                println!("Performing handshake: {:?}", conn);
-                Ok(Value::Boolean(true))
+                self.net.handshake(&conn)
             }
             
             // Process & OS (v1.3)
@@ -1356,16 +1353,10 @@ impl Runtime {
             
             AstNode::MemoryAlloc { size } => {
                 let sz = self.eval_node(size)?;
-                let size_num = sz.as_number()
-                    .ok_or_else(|| AetherError::RuntimeError("Memory size must be number".to_string()))?;
-                
-                println!("Allocating {} bytes of memory", size_num);
-                
-                // Return memory object
-                let mut mem_obj = HashMap::new();
-                mem_obj.insert("type".to_string(), Value::String("memory".to_string()));
-                mem_obj.insert("size".to_string(), Value::Number(size_num));
-                Ok(Value::Object(mem_obj))
+                if sz.as_number().is_none() {
+                    return Err(AetherError::RuntimeError("Memory size must be number".to_string()));
+                }
+                bytes::allocate(&sz, &self.meter)
             }
             
             AstNode::ExitProgram { code } => {
@@ -1402,16 +1393,14 @@ impl Runtime {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
                 
-                let result = match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        match operator {
-                            ComparisonOp::GreaterThan => l > r,
-                            ComparisonOp::LessThan => l < r,
-                            ComparisonOp::GreaterEqual => l >= r,
-                            ComparisonOp::LessEqual => l <= r,
-                        }
-                    }
-                    _ => false,
+                let result = match numeric::compare(&left_val, &right_val) {
+                    Some(ordering) => match operator {
+                        ComparisonOp::GreaterThan => ordering.is_gt(),
+                        ComparisonOp::LessThan => ordering.is_lt(),
+                        ComparisonOp::GreaterEqual => ordering.is_ge(),
+                        ComparisonOp::LessEqual => ordering.is_le(),
+                    },
+                    None => false,
                 };
                 
                 Ok(Value::Boolean(result))
@@ -1420,49 +1409,13 @@ impl Runtime {
             // Bootstrap operations (v1.4 - for compiler self-hosting)
             AstNode::Length { value } => {
                 let val = self.eval_node(value)?;
-                match val {
-                    Value::String(s) => Ok(Value::Number(s.chars().count() as f64)),
-                    Value::Array(arr) => Ok(Value::Number(arr.len() as f64)),
-                    _ => Err(AetherError::RuntimeError(
-                        "Length requires string or array".to_string()
-                    ))
-                }
+                bytes::length(&val)
             }
             
             AstNode::Index { target, index } => {
                 let tgt = self.eval_node(target)?;
                 let idx = self.eval_node(index)?;
-                
-                let idx_num = match idx {
-                    Value::Number(n) => {
-                        if n < 0.0 || n.fract() != 0.0 {
-                            return Err(AetherError::RuntimeError(
-                                format!("Index must be a non-negative integer, got {}", n)
-                            ));
-                        }
-                        n as usize
-                    },
-                    _ => return Err(AetherError::RuntimeError("Index must be a number".to_string())),
-                };
-                
-                match tgt {
-                    Value::String(s) => {
-                        s.chars().nth(idx_num)
-                            .map(|c| Value::String(c.to_string()))
-                            .ok_or_else(|| AetherError::RuntimeError(
-                                format!("String index {} out of bounds", idx_num)
-                            ))
-                    }
-                    Value::Array(arr) => {
-                        arr.get(idx_num).cloned()
-                            .ok_or_else(|| AetherError::RuntimeError(
-                                format!("Array index {} out of bounds", idx_num)
-                            ))
-                    }
-                    _ => Err(AetherError::RuntimeError(
-                        "Index access requires string or array".to_string()
-                    ))
-                }
+                bytes::index(&tgt, &idx)
             }
             
             AstNode::ArrayPush { array, element } => {
@@ -1483,113 +1436,44 @@ impl Runtime {
             AstNode::Add { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l + r)),
-                    _ => Err(AetherError::RuntimeError(
-                        "Addition requires numeric values".to_string()
-                    ))
-                }
+                numeric::arithmetic(Arith::Add, &left_val, &right_val)
             }
             
             AstNode::Subtract { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l - r)),
-                    _ => Err(AetherError::RuntimeError(
-                        "Subtraction requires numeric values".to_string()
-                    ))
-                }
+                numeric::arithmetic(Arith::Sub, &left_val, &right_val)
             }
             
             AstNode::Multiply { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l * r)),
-                    _ => Err(AetherError::RuntimeError(
-                        "Multiplication requires numeric values".to_string()
-                    ))
-                }
+                numeric::arithmetic(Arith::Mul, &left_val, &right_val)
             }
             
             AstNode::Divide { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        if r == 0.0 {
-                            Err(AetherError::RuntimeError("Division by zero".to_string()))
-                        } else {
-                            Ok(Value::Number(l / r))
-                        }
-                    }
-                    _ => Err(AetherError::RuntimeError(
-                        "Division requires numeric values".to_string()
-                    ))
-                }
+                numeric::arithmetic(Arith::Div, &left_val, &right_val)
             }
             
             AstNode::Modulo { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                match (left_val, right_val) {
-                    (Value::Number(l), Value::Number(r)) => {
-                        if r == 0.0 {
-                            Err(AetherError::RuntimeError("Modulo by zero".to_string()))
-                        } else {
-                            Ok(Value::Number(l % r))
-                        }
-                    }
-                    _ => Err(AetherError::RuntimeError(
-                        "Modulo requires numeric values".to_string()
-                    ))
-                }
+                numeric::arithmetic(Arith::Rem, &left_val, &right_val)
             }
             
             AstNode::StringConcat { left, right } => {
                 let left_val = self.eval_node(left)?;
                 let right_val = self.eval_node(right)?;
-                
-                let left_str = match left_val {
-                    Value::String(s) => s,
-                    Value::Number(n) => n.to_string(),
-                    Value::Boolean(b) => b.to_string(),
-                    Value::Null => "null".to_string(),
-                    _ => format!("{:?}", left_val),
-                };
-                
-                let right_str = match right_val {
-                    Value::String(s) => s,
-                    Value::Number(n) => n.to_string(),
-                    Value::Boolean(b) => b.to_string(),
-                    Value::Null => "null".to_string(),
-                    _ => format!("{:?}", right_val),
-                };
-                
-                Ok(Value::String(format!("{}{}", left_str, right_str)))
+                bytes::concat(&left_val, &right_val, &self.meter)
             }
             
             AstNode::CharAt { target, index } => {
                 let tgt = self.eval_node(target)?;
                 let idx = self.eval_node(index)?;
                 
-                let idx_num = match idx {
-                    Value::Number(n) => {
-                        if n < 0.0 || n.fract() != 0.0 {
-                            return Err(AetherError::RuntimeError(
-                                format!("CharAt index must be a non-negative integer, got {}", n)
-                            ));
-                        }
-                        n as usize
-                    },
-                    _ => return Err(AetherError::RuntimeError("CharAt index must be a number".to_string())),
-                };
+                let idx_num = bytes::position(&idx, "CharAt index")?;
                 
                 match tgt {
                     Value::String(s) => {
@@ -1607,56 +1491,12 @@ impl Runtime {
             
             AstNode::Slice { target, start, end } => {
                 let tgt = self.eval_node(target)?;
-                let start_idx = match self.eval_node(start)? {
-                    Value::Number(n) => {
-                        if n < 0.0 || n.fract() != 0.0 {
-                            return Err(AetherError::RuntimeError(
-                                format!("Slice start must be a non-negative integer, got {}", n)
-                            ));
-                        }
-                        n as usize
-                    },
-                    _ => return Err(AetherError::RuntimeError("Slice start must be a number".to_string())),
-                };
-                
-                let end_idx = if let Some(end_node) = end {
-                    match self.eval_node(end_node)? {
-                        Value::Number(n) => {
-                            if n < 0.0 || n.fract() != 0.0 {
-                                return Err(AetherError::RuntimeError(
-                                    format!("Slice end must be a non-negative integer, got {}", n)
-                                ));
-                            }
-                            Some(n as usize)
-                        },
-                        _ => return Err(AetherError::RuntimeError("Slice end must be a number".to_string())),
-                    }
-                } else {
-                    None
+                let start = self.eval_node(start)?;
+                let end = match end {
+                    Some(end_node) => self.eval_node(end_node)?,
+                    None => Value::Null,
                 };
-                
-                match tgt {
-                    Value::String(s) => {
-                        let chars: Vec<char> = s.chars().collect();
-                        let end = end_idx.unwrap_or(chars.len());
-                        if start_idx <= end && end <= chars.len() {
-                            Ok(Value::String(chars[start_idx..end].iter().collect()))
-                        } else {
-                            Err(AetherError::RuntimeError("Slice indices out of bounds".to_string()))
-                        }
-                    }
-                    Value::Array(arr) => {
-                        let end = end_idx.unwrap_or(arr.len());
-                        if start_idx <= end && end <= arr.len() {
-                            Ok(Value::Array(arr[start_idx..end].to_vec()))
-                        } else {
-                            Err(AetherError::RuntimeError("Slice indices out of bounds".to_string()))
-                        }
-                    }
-                    _ => Err(AetherError::RuntimeError(
-                        "Slice requires string or array".to_string()
-                    ))
-                }
+                bytes::slice(&tgt, &start, &end)
             }
             
             AstNode::ArrayLiteral { elements } => {
@@ -1708,6 +1548,7 @@ impl Runtime {
             let mut request_builder = if let Some(body_val) = body {
                 match body_val {
                     Value::String(s) => request_builder.body(s),
+                    Value::Bytes(b) => request_builder.body(b),
                     Value::Object(map) => {
                         // Convert object to JSON string
                         let json_str = self.value_to_json_string(&Value::Object(map));
@@ -1722,17 +1563,17 @@ impl Runtime {
             };
             
             // Add custom headers if provided
-            if let Some(headers_val) = headers {
-                if let Value::Object(header_map) = headers_val {
-                    for (key, value) in header_map.iter() {
-                        let header_value = match value {
-                            Value::String(s) => s.clone(),
-                            Value::Number(n) => n.to_string(),
-                            Value::Boolean(b) => b.to_string(),
-                            _ => format!("{:?}", value),
-                        };
-                        request_builder = request_builder.header(key.as_str(), header_value);
-                    }
+            if let Some(Value::Object(header_map)) = headers {
+                for (key, value) in header_map.iter() {
+                    let header_value = match value {
+                        Value::String(s) => s.clone(),
+                        Value::Number(n) => n.to_string(),
+                        Value::Integer(n) => n.to_string(),
+                        Value::Decimal(d) => d.to_string(),
+                        Value::Boolean(b) => b.to_string(),
+                        _ => format!("{:?}", value),
+                    };
+                    request_builder = request_builder.header(key.as_str(), header_value);
                 }
             }
             
@@ -1744,7 +1585,7 @@ impl Runtime {
             
             // Build response object
             let mut response_obj = HashMap::new();
-            response_obj.insert("status".to_string(), Value::Number(response.status().as_u16() as f64));
+            response_obj.insert("status".to_string(), Value::Integer(response.status().as_u16() as i64));
             response_obj.insert("ok".to_string(), Value::Boolean(response.status().is_success()));
             
             // Extract response headers
@@ -1794,6 +1635,9 @@ impl Runtime {
                     serde_json::Value::String(n.to_string())
                 }
             }
+            Value::Integer(_) | Value::Decimal(_) => {
+                numeric::to_json(value).unwrap_or(serde_json::Value::Null)
+            }
             Value::Boolean(b) => serde_json::Value::Bool(*b),
             Value::Null => serde_json::Value::Null,
             Value::Array(items) => {
@@ -1808,6 +1652,10 @@ impl Runtime {
                     .collect();
                 serde_json::Value::Object(json_map)
             }
+            // JSON has no binary type; bytes travel as base64
+            Value::Bytes(b) => serde_json::Value::String(
+                bytes::encode(b, "base64").unwrap_or_default()
+            ),
             Value::AsyncTask(id) => {
                 // Represent async task as a string identifier in JSON
                 serde_json::Value::String(format!("AsyncTask({})", id))
@@ -1833,7 +1681,7 @@ impl Runtime {
         match json {
             serde_json::Value::Null => Value::Null,
             serde_json::Value::Bool(b) => Value::Boolean(*b),
-            serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
+            serde_json::Value::Number(n) => numeric::from_json(n),
             serde_json::Value::String(s) => Value::String(s.clone()),
             serde_json::Value::Array(arr) => {
                 let items: Vec<Value> = arr.iter()
@@ -1924,6 +1772,7 @@ mod tests {
         let node = AstNode::PipeInto {
             value: Box::new(AstNode::Literal(LiteralValue::String("test".to_string()))),
             variable: "x".to_string(),
+            annotation: None,
         };
 
         runtime.eval_node(&node).unwrap();
@@ -2130,11 +1979,11 @@ mod tests {
         // Define immutable variable
         let node = AstNode::Immutable {
             name: "PI".to_string(),
-            value: Box::new(AstNode::Literal(LiteralValue::Number(3.14159))),
+            value: Box::new(AstNode::Literal(LiteralValue::Number(std::f64::consts::PI))),
         };
         
         let result = runtime.eval_node(&node).unwrap();
-        assert_eq!(result, Value::Number(3.14159));
+        assert_eq!(result, Value::Number(std::f64::consts::PI));
         
         // Try to modify it - should fail
         let set_result = runtime.set_variable("PI".to_string(), Value::Number(3.0));
@@ -2202,17 +2051,100 @@ mod tests {
         let mut runtime = Runtime::new();
         let node = AstNode::CreateStream {
             source: Box::new(AstNode::Literal(LiteralValue::String("data".to_string()))),
+            options: Box::new(AstNode::Empty),
         };
         
         let result = runtime.eval_node(&node).unwrap();
         match result {
             Value::Object(obj) => {
                 assert_eq!(obj.get("type"), Some(&Value::String("stream".to_string())));
+                assert_eq!(obj.get("kind"), Some(&Value::String("memory".to_string())));
+                assert_eq!(obj.get("position"), Some(&Value::Number(0.0)));
             }
             _ => panic!("Expected stream object"),
         }
     }
     
+    #[test]
+    fn test_runtime_stream_lines_until_eof() {
+        let path = std::env::temp_dir().join(format!("aether_runtime_stream_{}.txt", std::process::id()));
+        std::fs::write(&path, "a\nb\nc\n").unwrap();
+        
+        let mut runtime = Runtime::new();
+        let source = format!(
+            "📄\"{}\" ⇢ 🌊\"lines\" ▷ s ⨠ \"\" ▷ out ⨠ ↻(s ≠ 🔚): (s ⇢ 📖 ▷ line ⨠ out ⧺ line ▷ out) ⨠ s ⇢ 🔚",
+            path.display()
+        );
+        let at_end = run_source(&mut runtime, &source).unwrap();
+        
+        assert_eq!(at_end, Value::Boolean(true));
+        assert_eq!(runtime.get_variable("out"), Some(&Value::String("abc".to_string())));
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_runtime_stream_buffered_write() {
+        let path = std::env::temp_dir().join(format!("aether_runtime_write_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        
+        let mut runtime = Runtime::new();
+        let source = format!("📄\"{}\" ⇢ 🌊\"write\" ▷ s ⨠ \"log line\" ⇢ 🖊️s", path.display());
+        assert_eq!(run_source(&mut runtime, &source).unwrap(), Value::Number(8.0));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        
+        run_source(&mut runtime, "s ⇢ 🌬️").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "log line");
+        assert_eq!(run_source(&mut runtime, "s ⇢ ⏭️(0 - 3) ⇢ 📖").unwrap(), Value::String("ine".to_string()));
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn test_runtime_bytes_operations() {
+        let mut runtime = Runtime::new();
+        let data = run_source(&mut runtime, "hex\"cafe\" ⧺ b64\"AAE=\" ▷ d").unwrap();
+        assert_eq!(data, Value::Bytes(vec![0xca, 0xfe, 0x00, 0x01]));
+        assert_eq!(run_source(&mut runtime, "📏d").unwrap(), Value::Number(4.0));
+        assert_eq!(run_source(&mut runtime, "d⟦1⟧").unwrap(), Value::Number(254.0));
+        assert_eq!(run_source(&mut runtime, "d⊢1:3⊣").unwrap(), Value::Bytes(vec![0xfe, 0x00]));
+        assert_eq!(run_source(&mut runtime, "d⊢2⊣ ⇢ S\"hex\"").unwrap(), Value::String("0001".to_string()));
+        assert!(run_source(&mut runtime, "d ⧺ \"x\"").is_err());
+        assert!(run_source(&mut runtime, "d⟦4⟧").is_err());
+    }
+    
+    #[test]
+    fn test_runtime_bytes_conversions() {
+        let mut runtime = Runtime::new();
+        assert_eq!(
+            run_source(&mut runtime, "\"héllo\" ⇢ 🧱 ⇢ S\"base64\"").unwrap(),
+            Value::String("aMOpbGxv".to_string())
+        );
+        assert_eq!(
+            run_source(&mut runtime, "\"aMOpbGxv\" ⇢ 🧱\"base64\" ⇢ S").unwrap(),
+            Value::String("héllo".to_string())
+        );
+        assert_eq!(run_source(&mut runtime, "🧱3").unwrap(), Value::Bytes(vec![0, 0, 0]));
+        assert_eq!(run_source(&mut runtime, "🐏2").unwrap(), Value::Bytes(vec![0, 0]));
+        assert_eq!(run_source(&mut runtime, "🧱[1, 2]").unwrap(), Value::Bytes(vec![1, 2]));
+        // Invalid UTF-8 must be converted with an explicit encoding
+        assert!(run_source(&mut runtime, "hex\"ff\" ⇢ S").is_err());
+        assert_eq!(run_source(&mut runtime, "hex\"ff\" ⇢ S\"latin1\"").unwrap(), Value::String("ÿ".to_string()));
+    }
+    
+    #[test]
+    fn test_runtime_bytes_through_stream_and_crypto() {
+        let mut runtime = Runtime::new();
+        let chunk = run_source(&mut runtime, "hex\"00ff10\" ⇢ 🌊🧱2 ▷ s ⨠ s ⇢ 📖").unwrap();
+        assert_eq!(chunk, Value::Bytes(vec![0x00, 0xff]));
+        assert_eq!(run_source(&mut runtime, "s ⇢ 📖").unwrap(), Value::Bytes(vec![0x10]));
+        
+        let plain = run_source(&mut runtime, "🔐 hex\"00ff\" \"k\" ▷ c ⨠ 🔓 c \"k\"").unwrap();
+        assert_eq!(plain, Value::Bytes(vec![0x00, 0xff]));
+        assert_eq!(
+            run_source(&mut runtime, "#️⃣ b64\"YWJj\"").unwrap(),
+            run_source(&mut runtime, "#️⃣ \"abc\"").unwrap()
+        );
+    }
+    
     #[test]
     fn test_runtime_socket() {
         let mut runtime = Runtime::new();
@@ -2233,20 +2165,76 @@ mod tests {
     #[test]
     fn test_runtime_listen_port() {
         let mut runtime = Runtime::new();
+        // Port 0 lets the OS pick a free port; the listener reports the real one
         let node = AstNode::ListenPort {
-            port: Box::new(AstNode::Literal(LiteralValue::Number(8080.0))),
+            port: Box::new(AstNode::Literal(LiteralValue::Number(0.0))),
         };
         
         let result = runtime.eval_node(&node).unwrap();
         match result {
             Value::Object(obj) => {
                 assert_eq!(obj.get("type"), Some(&Value::String("listener".to_string())));
-                assert_eq!(obj.get("port"), Some(&Value::Number(8080.0)));
+                assert_eq!(obj.get("active"), Some(&Value::Boolean(true)));
+                assert!(obj.get("port").and_then(|p| p.as_number()).unwrap() > 0.0);
             }
             _ => panic!("Expected listener object"),
         }
     }
     
+    fn run_source(runtime: &mut Runtime, source: &str) -> Result<Value> {
+        let tokens = crate::lexer::Lexer::new(source.to_string()).tokenize()?;
+        let ast = crate::parser::Parser::new(tokens).parse()?;
+        runtime.execute(ast)
+    }
+    
+    #[test]
+    fn test_runtime_tcp_loopback() {
+        let mut server = Runtime::new();
+        let listener = run_source(&mut server, "👂\"127.0.0.1:0\"").unwrap();
+        let port = match &listener {
+            Value::Object(obj) => obj.get("port").and_then(|p| p.as_number()).unwrap(),
+            _ => panic!("Expected listener object"),
+        };
+        
+        let client = std::thread::spawn(move || {
+            let mut client = Runtime::new();
+            let source = format!("📞\"127.0.0.1:{}\" ▷ c ⨠ \"ping\" ⇢ 🖊️c ⨠ c ⇢ 📖", port);
+            run_source(&mut client, &source).unwrap()
+        });
+        
+        let written = run_source(&mut server, "⏳👂 ▷ conn ⨠ conn ⇢ 📖 ▷ msg ⨠ \"pong\" ⇢ 🖊️conn").unwrap();
+        assert_eq!(written, Value::Number(4.0));
+        assert_eq!(server.get_variable("msg"), Some(&Value::String("ping".to_string())));
+        assert_eq!(run_source(&mut server, "🤝conn").unwrap(), Value::Boolean(true));
+        
+        assert_eq!(client.join().unwrap(), Value::String("pong".to_string()));
+        assert_eq!(run_source(&mut server, "🗑️conn").unwrap(), Value::Boolean(true));
+    }
+    
+    #[test]
+    fn test_runtime_udp_packet() {
+        let mut runtime = Runtime::new();
+        let receiver = run_source(&mut runtime, "👂\"udp://127.0.0.1:0\" ▷ rx").unwrap();
+        let port = match &receiver {
+            Value::Object(obj) => obj.get("port").and_then(|p| p.as_number()).unwrap(),
+            _ => panic!("Expected socket object"),
+        };
+        
+        let source = format!(
+            "🔌UDP ▷ tx ⨠ 📦{{to: \"127.0.0.1:{}\", data: \"hello\"}} ⇢ 🖊️tx",
+            port
+        );
+        assert_eq!(run_source(&mut runtime, &source).unwrap(), Value::Number(5.0));
+        
+        match run_source(&mut runtime, "rx ⇢ 📖").unwrap() {
+            Value::Object(obj) => {
+                assert_eq!(obj.get("type"), Some(&Value::String("packet".to_string())));
+                assert_eq!(obj.get("data"), Some(&Value::String("hello".to_string())));
+            }
+            other => panic!("Expected packet, got {:?}", other),
+        }
+    }
+    
     #[test]
     fn test_runtime_random() {
         let mut runtime = Runtime::new();
@@ -2263,8 +2251,8 @@ mod tests {
         if let (Value::Number(n1), Value::Number(n2)) = (result1, result2) {
             // With very high probability they're different, but we can't guarantee it
             // So just check they're in valid range
-            assert!(n1 >= 0.0 && n1 <= 1.0);
-            assert!(n2 >= 0.0 && n2 <= 1.0);
+            assert!((0.0..=1.0).contains(&n1));
+            assert!((0.0..=1.0).contains(&n2));
         }
     }
     
@@ -2290,8 +2278,6 @@ mod tests {
     #[test]
     fn test_runtime_loop() {
         let mut runtime = Runtime::new();
-        runtime.set_max_loop_iterations(5);
-        
         // Loop that breaks after a few iterations
         // We'll use a counter pattern
         runtime.set_variable("counter".to_string(), Value::Number(0.0)).unwrap();
@@ -2415,14 +2401,14 @@ mod tests {
         
         let encrypted = runtime.eval_node(&encrypt_node).unwrap();
         
-        // Should be a string
-        assert!(matches!(encrypted, Value::String(_)));
+        // Should be nonce + ciphertext bytes
+        assert!(matches!(encrypted, Value::Bytes(_)));
         
         // Decrypt
         let decrypt_node = AstNode::Decrypt {
             data: Box::new(AstNode::Literal(match encrypted {
-                Value::String(s) => LiteralValue::String(s),
-                _ => panic!("Expected string"),
+                Value::Bytes(b) => LiteralValue::Bytes(b),
+                _ => panic!("Expected bytes"),
             })),
             key: Box::new(AstNode::Literal(LiteralValue::String("my-key".to_string()))),
         };
@@ -2464,13 +2450,13 @@ mod tests {
         };
         
         let signature = runtime.eval_node(&sign_node).unwrap();
-        assert!(matches!(signature, Value::String(_)));
+        assert!(matches!(&signature, Value::Bytes(b) if b.len() == 64));
         
         // Verify
         let verify_node = AstNode::VerifySignature {
             signature: Box::new(AstNode::Literal(match signature {
-                Value::String(s) => LiteralValue::String(s),
-                _ => panic!("Expected string"),
+                Value::Bytes(b) => LiteralValue::Bytes(b),
+                _ => panic!("Expected bytes"),
             })),
             data: Box::new(AstNode::Literal(LiteralValue::String(message.to_string()))),
             key: Box::new(AstNode::Literal(LiteralValue::String(key.to_string()))),
@@ -3246,4 +3232,107 @@ mod tests {
         let result3 = runtime.eval_node(&node3).unwrap();
         assert_eq!(result3, Value::Boolean(false));
     }
+    
+    #[test]
+    fn test_runtime_integer_and_decimal_literals() {
+        let mut runtime = Runtime::new();
+        let result = run_source(&mut runtime, "19.99 * 3 ▷ total ⨠ 0.1 + 0.2 ▷ sum ⨠ 9007199254740993 + 1 ▷ id ⨠ 7 / 2").unwrap();
+        
+        assert!(matches!(result, Value::Decimal(d) if d.to_string() == "3.5"));
+        assert!(matches!(runtime.get_variable("total"), Some(Value::Decimal(d)) if d.to_string() == "59.97"));
+        assert_eq!(runtime.get_variable("sum"), Some(&Value::Decimal(Decimal::parse("0.3").unwrap())));
+        assert!(matches!(runtime.get_variable("id"), Some(Value::Integer(9_007_199_254_740_994))));
+        assert!(run_source(&mut runtime, "9223372036854775807 + 1").is_err());
+        assert!(matches!(run_source(&mut runtime, "📏\"abc\"").unwrap(), Value::Integer(3)));
+    }
+    
+    #[test]
+    fn test_runtime_json_numbers_are_faithful() {
+        let runtime = Runtime::new();
+        let parsed = runtime.parse_json_string(r#"{"id": 9007199254740993, "amount": 19.90, "rate": 0.5}"#).unwrap();
+        let obj = match &parsed {
+            Value::Object(obj) => obj,
+            other => panic!("Expected object, got {:?}", other),
+        };
+        assert!(matches!(obj.get("id"), Some(Value::Integer(9_007_199_254_740_993))));
+        assert!(matches!(obj.get("amount"), Some(Value::Decimal(d)) if d.to_string() == "19.90"));
+        
+        let json = runtime.value_to_json_string(&Value::Array(vec![
+            obj["id"].clone(),
+            obj["amount"].clone(),
+            Value::Number(0.25),
+        ]));
+        assert_eq!(json, "[9007199254740993,19.90,0.25]");
+    }
+    
+    #[test]
+    fn test_runtime_enforces_limits() {
+        use crate::limits::{Limit, Limits};
+        use std::time::Duration;
+        
+        let mut runtime = Runtime::new();
+        runtime.set_limits(Limits { max_steps: Some(1000), ..Limits::unlimited() });
+        let err = run_source(&mut runtime, "0 ▷ i ⨠ ↻(i < 1000000): (i + 1 ▷ i)").unwrap_err();
+        assert_eq!(err.to_string(), "step limit exceeded: more than 1000 steps");
+        
+        // Loops have no cap of their own
+        runtime.set_limits(Limits::unlimited());
+        assert_eq!(run_source(&mut runtime, "0 ▷ i ⨠ ↻(i < 20000): (i + 1 ▷ i) ⨠ i").unwrap(), Value::Integer(20000));
+        
+        runtime.set_limits(Limits { timeout: Some(Duration::from_millis(20)), ..Limits::unlimited() });
+        let err = run_source(&mut runtime, "0 ▷ i ⨠ ↻(i ≥ 0): (i + 1 ▷ i)").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::Time, _)));
+        
+        runtime.set_limits(Limits { max_stack_depth: Some(3), ..Limits::unlimited() });
+        assert!(run_source(&mut runtime, "1 + 2").is_ok());
+        let err = run_source(&mut runtime, "1 + (2 + (3 + 4))").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::StackDepth, _)));
+        
+        runtime.set_limits(Limits { max_collection_size: Some(2), max_string_length: Some(3), ..Limits::unlimited() });
+        let err = run_source(&mut runtime, "[1, 2, 3]").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::CollectionSize, _)));
+        let err = run_source(&mut runtime, "\"abcd\"").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::StringLength, _)));
+        assert!(run_source(&mut runtime, "[1, 2] ⨠ \"abc\"").is_ok());
+        
+        // Sizes the program chooses are refused before they are allocated
+        runtime.set_limits(Limits { max_string_length: Some(10), ..Limits::unlimited() });
+        let err = run_source(&mut runtime, "🧱100000000000 ▷ b").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::StringLength, _)));
+        let err = run_source(&mut runtime, "\"abcdef\" ⧺ \"ghijkl\"").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::StringLength, _)));
+        let err = run_source(&mut runtime, "[\"abcdef\", \"ghijkl\"] ⇢ 🔗\",\"").unwrap_err();
+        assert!(matches!(err, AetherError::LimitExceeded(Limit::StringLength, _)));
+        
+        // A huge exponent finishes well inside the deadline
+        runtime.set_limits(Limits { timeout: Some(Duration::from_secs(1)), ..Limits::unlimited() });
+        assert!(run_source(&mut runtime, "1.0 ⇢ ↑4000000000 ▷ x").is_ok());
+    }
+    
+    #[test]
+    fn test_runtime_debug_hook_sees_each_expression() {
+        use crate::debugger::DebugHook;
+        
+        // Records top-level statements and sets `b` on reaching 🐛
+        struct Hook(Arc<Mutex<Vec<String>>>);
+        impl DebugHook for Hook {
+            fn before(&mut self, node: &AstNode, depth: usize, variables: &mut HashMap<String, Value>) -> Result<()> {
+                if depth == 2 {
+                    let kind = format!("{:?}", node);
+                    self.0.lock().unwrap().push(kind.split(|c: char| !c.is_alphanumeric()).next().unwrap().to_string());
+                }
+                if *node == AstNode::Debug {
+                    variables.insert("b".to_string(), Value::Integer(40));
+                }
+                Ok(())
+            }
+        }
+        
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut runtime = Runtime::new();
+        runtime.set_debug_hook(Box::new(Hook(Arc::clone(&seen))));
+        let result = run_source(&mut runtime, "1 ▷ a ⨠ 🐛 ⨠ a + b").unwrap();
+        assert_eq!(result, Value::Integer(41));
+        assert_eq!(*seen.lock().unwrap(), vec!["PipeInto", "Debug", "Add"]);
+    }
 }
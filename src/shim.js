@@ -0,0 +1,446 @@
+// Aether runtime shim: the glyph operations JavaScript has no one-liner for.
+// `aether transpile --to js` puts this in front of every program; it needs Node.
+const childProcess = require("child_process");
+const crypto = require("crypto");
+const fs = require("fs");
+
+class AetherHalt extends Error {
+  // Thrown by 🛑 with the program's error code
+  constructor(code) {
+    super(`Halted with code: ${aether.show(code)}`);
+    this.code = code;
+  }
+}
+
+class Stream {
+  // A 🌊 stream over a file, string, buffer or stdin
+  constructor(source, options = null) {
+    this.lines = options === "lines";
+    this.binary = options === "bytes" || Buffer.isBuffer(options);
+    this.size = Buffer.isBuffer(options) ? options.length : typeof options === "number" ? options : 8192;
+    this.pending = Buffer.alloc(0);
+    this.position = 0;
+    this.memory = null;
+    this.fd = null;
+    if (source === null || (source.type === "file" && source.path === "-")) {
+      this.fd = 0;
+    } else if (typeof source === "string" || Buffer.isBuffer(source)) {
+      this.memory = Buffer.from(source);
+    } else if (source.type === "file") {
+      this.fd = fs.openSync(source.path, fs.existsSync(source.path) ? "r+" : "w+");
+    } else {
+      throw new TypeError("Stream source must be a file, string, buffer or stdin");
+    }
+  }
+
+  fill() {
+    if (this.memory !== null) {
+      const chunk = this.memory.subarray(this.position + this.pending.length, this.position + this.pending.length + 8192);
+      this.pending = Buffer.concat([this.pending, chunk]);
+      return chunk.length;
+    }
+    const chunk = Buffer.alloc(8192);
+    const position = this.fd === 0 ? null : this.position + this.pending.length;
+    const n = fs.readSync(this.fd, chunk, 0, chunk.length, position);
+    this.pending = Buffer.concat([this.pending, chunk.subarray(0, n)]);
+    return n;
+  }
+
+  take(n) {
+    const data = this.pending.subarray(0, n);
+    this.pending = this.pending.subarray(n);
+    this.position += n;
+    return data;
+  }
+
+  read() {
+    if (this.lines) {
+      let newline;
+      while ((newline = this.pending.indexOf(10)) < 0 && this.fill() > 0);
+      if (newline < 0) {
+        return this.pending.length ? this.take(this.pending.length).toString() : null;
+      }
+      return this.take(newline + 1).toString().replace(/\r?\n$/, "");
+    }
+    if (!this.pending.length && !this.fill()) {
+      return null;
+    }
+    const data = this.take(Math.min(this.size, this.pending.length));
+    return this.binary ? data : data.toString();
+  }
+
+  write(data) {
+    data = aether.raw(data);
+    if (this.memory !== null) {
+      throw new TypeError("Cannot write to a stream over a string");
+    }
+    fs.writeSync(this.fd, data, 0, data.length, this.fd === 0 ? null : this.position);
+    this.position += data.length;
+    this.pending = Buffer.alloc(0);
+    return data.length;
+  }
+
+  atEnd() {
+    return !this.pending.length && !this.fill();
+  }
+
+  skip(count) {
+    const size = this.memory !== null ? this.memory.length : fs.fstatSync(this.fd).size;
+    this.position = count === null ? size : this.position + count;
+    this.pending = Buffer.alloc(0);
+    return this;
+  }
+
+  flush() {
+    if (this.fd !== null && this.fd !== 0) {
+      fs.fsyncSync(this.fd);
+    }
+    return true;
+  }
+
+  close() {
+    if (this.fd !== null && this.fd !== 0) {
+      fs.closeSync(this.fd);
+    }
+    return true;
+  }
+}
+
+const aether = {
+  token: null,
+
+  show(value) {
+    // Text for a value, the way Aether prints it
+    if (value === null || value === undefined) return "null";
+    if (Buffer.isBuffer(value)) return value.toString("hex");
+    if (typeof value === "object") return JSON.stringify(value, (_, v) => (typeof v === "bigint" ? v.toString() : v));
+    return String(value);
+  },
+
+  raw(value) {
+    // The bytes a value stands for when written, hashed or sent
+    return Buffer.isBuffer(value) ? value : Buffer.from(aether.show(value));
+  },
+
+  truthy(value) {
+    // Aether's truthiness: empty arrays and objects are false too
+    if (Array.isArray(value) || Buffer.isBuffer(value)) return value.length > 0;
+    if (value !== null && typeof value === "object") return Object.keys(value).length > 0;
+    return Boolean(value);
+  },
+
+  // 📤 🪵 📥 J 🛑 💾
+
+  output(value) {
+    console.log(aether.show(value));
+    return value;
+  },
+
+  log(message) {
+    console.log("LOG: " + aether.show(message));
+    return null;
+  },
+
+  input() {
+    return process.stdin.isTTY ? "" : fs.readFileSync(0, "utf8");
+  },
+
+  jsonParse(text) {
+    if (text !== null && typeof text === "object") return text;
+    return text ? JSON.parse(text) : {};
+  },
+
+  halt(code) {
+    throw new AetherHalt(code);
+  },
+
+  persist(value) {
+    console.log("Persisting: " + aether.show(value));
+    return true;
+  },
+
+  // ⧺ ≈ 🧱 S
+
+  concat(left, right) {
+    if (Buffer.isBuffer(left) && Buffer.isBuffer(right)) return Buffer.concat([left, right]);
+    if (Buffer.isBuffer(left) || Buffer.isBuffer(right)) {
+      throw new TypeError("Cannot concatenate bytes with other values; convert with 🧱 or S first");
+    }
+    return aether.show(left) + aether.show(right);
+  },
+
+  approx(left, right) {
+    const epsilon = Number.EPSILON * 10;
+    return Math.abs(left - right) < Math.max(epsilon, (Math.abs(left) + Math.abs(right)) * epsilon);
+  },
+
+  buffer(size) {
+    return typeof size === "number" ? Buffer.alloc(size) : aether.toBytes(size);
+  },
+
+  toBytes(value, encoding = null) {
+    if (Buffer.isBuffer(value) || Array.isArray(value)) return Buffer.from(value);
+    encoding = encoding || "utf8";
+    if (encoding === "hex") return Buffer.from(value.replace(/[\s_]/g, ""), "hex");
+    return Buffer.from(value, encoding === "base64" ? "base64" : encoding === "latin1" ? "latin1" : "utf8");
+  },
+
+  toText(value, encoding = null) {
+    if (!Buffer.isBuffer(value)) return aether.show(value);
+    return value.toString(encoding || "utf8");
+  },
+
+  // 🌐 (Node has no synchronous fetch, so each request runs in a child process)
+
+  http(method, url, body = null, headers = null) {
+    headers = { ...(headers || {}) };
+    if (body !== null && typeof body === "object" && !Buffer.isBuffer(body)) {
+      headers["Content-Type"] = "application/json";
+      body = JSON.stringify(body);
+    }
+    const request = JSON.stringify({ method, url, headers, body: body === null ? null : aether.raw(body).toString("base64") });
+    const script = `
+      const r = ${request};
+      fetch(r.url, { method: r.method, headers: r.headers, body: r.body === null ? undefined : Buffer.from(r.body, "base64") })
+        .then(async (res) => process.stdout.write(JSON.stringify({
+          status: res.status, ok: res.ok, headers: Object.fromEntries(res.headers), body: await res.text(),
+        })))
+        .catch((e) => { process.stderr.write(String(e.cause || e)); process.exit(1); });`;
+    let response;
+    try {
+      response = JSON.parse(childProcess.execFileSync(process.execPath, ["-e", script], { encoding: "utf8", stdio: ["ignore", "pipe", "pipe"] }));
+    } catch (error) {
+      throw new Error(`HTTP request failed: ${error.stderr || error.message}`);
+    }
+    try {
+      response.json = JSON.parse(response.body);
+    } catch (_) {}
+    return response;
+  },
+
+  // #️⃣ 🔐 🔓 ✍️ 🔏
+
+  hash(data) {
+    return crypto.createHash("sha256").update(aether.raw(data)).digest("hex");
+  },
+
+  key(key) {
+    return crypto.createHash("sha256").update(key).digest();
+  },
+
+  signingKey(key) {
+    // Ed25519 keys from a 32-byte seed, wrapped in PKCS#8
+    const prefix = Buffer.from("302e020100300506032b657004220420", "hex");
+    return crypto.createPrivateKey({ key: Buffer.concat([prefix, aether.key(key)]), format: "der", type: "pkcs8" });
+  },
+
+  encrypt(data, key) {
+    const nonce = crypto.randomBytes(12);
+    const cipher = crypto.createCipheriv("aes-256-gcm", aether.key(key), nonce);
+    return Buffer.concat([nonce, cipher.update(aether.raw(data)), cipher.final(), cipher.getAuthTag()]);
+  },
+
+  decrypt(data, key) {
+    const decipher = crypto.createDecipheriv("aes-256-gcm", aether.key(key), data.subarray(0, 12));
+    decipher.setAuthTag(data.subarray(data.length - 16));
+    const plain = Buffer.concat([decipher.update(data.subarray(12, data.length - 16)), decipher.final()]);
+    const text = plain.toString();
+    return Buffer.from(text).equals(plain) ? text : plain;
+  },
+
+  sign(data, key) {
+    return crypto.sign(null, aether.raw(data), aether.signingKey(key));
+  },
+
+  verify(signature, data, key) {
+    return crypto.verify(null, aether.raw(data), crypto.createPublicKey(aether.signingKey(key)), signature);
+  },
+
+  // ⚡ ⏳ 🧵 📡 👁 (JavaScript runs one thing at a time, so tasks run when spawned)
+
+  spawn(body) {
+    return { task: body() };
+  },
+
+  wait(task) {
+    return task !== null && typeof task === "object" && "task" in task ? task.task : task;
+  },
+
+  emit(event) {
+    console.log("Emit event: " + aether.show(event));
+    return true;
+  },
+
+  watch(event, handler) {
+    console.log("Watch event: " + aether.show(event));
+    return handler();
+  },
+
+  // 🧩 🔑 📅
+
+  use(module) {
+    return true;
+  },
+
+  auth(token) {
+    aether.token = token;
+    return true;
+  },
+
+  now() {
+    return new Date().toISOString();
+  },
+
+  // 🧪 ⚖️ 🎭 ⏱️ 🐛
+
+  test(name, body) {
+    console.log(`Running test: ${name}`);
+    const result = body();
+    console.log(`Test '${name}' completed`);
+    return result;
+  },
+
+  check(condition) {
+    if (!aether.truthy(condition)) {
+      throw new Error(`Assertion failed: condition evaluated to ${aether.show(condition)}`);
+    }
+    return true;
+  },
+
+  mock(target) {
+    console.log("Mocked: " + aether.show(target));
+    return true;
+  },
+
+  bench(body) {
+    const start = performance.now();
+    const result = body();
+    console.log(`Benchmark: ${(performance.now() - start).toFixed(3)}ms`);
+    return result;
+  },
+
+  debug() {
+    console.log("DEBUG: Breakpoint hit");
+    return null;
+  },
+
+  // 📄 📂 📍 📖 🖊️ 🖇️ 🗑️ 🛂
+
+  file(path) {
+    const handle = { type: "file", path };
+    if (fs.existsSync(path)) handle.size = fs.statSync(path).size;
+    return handle;
+  },
+
+  directory(path) {
+    return { type: "directory", path };
+  },
+
+  path(path) {
+    return path;
+  },
+
+  target(target) {
+    return target !== null && typeof target === "object" ? target.path : target;
+  },
+
+  read(source) {
+    if (source instanceof Stream) return source.read();
+    const data = fs.readFileSync(aether.target(source));
+    const text = data.toString();
+    return Buffer.from(text).equals(data) ? text : data;
+  },
+
+  write(target, content) {
+    if (target instanceof Stream) return target.write(content);
+    fs.writeFileSync(aether.target(target), aether.raw(content));
+    return true;
+  },
+
+  append(target, content) {
+    if (target instanceof Stream) return target.write(content);
+    fs.appendFileSync(aether.target(target), aether.raw(content));
+    return true;
+  },
+
+  delete(target) {
+    if (target instanceof Stream) return target.close();
+    fs.unlinkSync(aether.target(target));
+    return true;
+  },
+
+  chmod(target, permission) {
+    fs.chmodSync(aether.target(target), parseInt(String(permission), 8));
+    return true;
+  },
+
+  // 🌊 🌬️ 🔚 ⏭️
+
+  stream(source, options = null) {
+    return source instanceof Stream ? source : new Stream(source, options);
+  },
+
+  flush(target) {
+    return target instanceof Stream ? target.flush() : true;
+  },
+
+  atEnd(value) {
+    if (value === null) return true;
+    return value instanceof Stream ? value.atEnd() : false;
+  },
+
+  skip(stream, count) {
+    return stream.skip(count);
+  },
+
+  // ⚙️ 🐚 🌍 👋 📶
+
+  process(command) {
+    return { type: "process", command };
+  },
+
+  shell(command) {
+    return childProcess.execSync(command, { encoding: "utf8" });
+  },
+
+  env(name) {
+    return process.env[name] ?? null;
+  },
+
+  exit(code) {
+    process.exit(Number(code || 0));
+  },
+
+  signal(signal, target) {
+    process.kill(target !== null && typeof target === "object" ? target.pid : target, signal);
+    return true;
+  },
+
+  // 🔌 👂 📞 📦 🤝 (sockets are out of the shim's scope)
+
+  socket(kind) {
+    throw new Error("🔌 sockets have no JavaScript equivalent in this shim");
+  },
+
+  listen(port) {
+    throw new Error("👂 listening has no JavaScript equivalent in this shim");
+  },
+
+  connect(address) {
+    throw new Error("📞 connections have no JavaScript equivalent in this shim");
+  },
+
+  packet(data) {
+    return { type: "packet", data };
+  },
+
+  handshake(connection) {
+    throw new Error("🤝 handshakes have no JavaScript equivalent in this shim");
+  },
+
+  // ⊞
+
+  push(array, element) {
+    return [...array, element];
+  },
+};
@@ -0,0 +1,418 @@
+# Aether runtime shim: the glyph operations Python has no one-liner for.
+# `aether transpile --to py` puts this in front of every program.
+import base64
+import hashlib
+import io
+import json
+import os
+import subprocess
+import sys
+import threading
+import time
+import urllib.error
+import urllib.request
+from concurrent.futures import Future, ThreadPoolExecutor
+from datetime import datetime, timezone
+
+
+class AetherHalt(Exception):
+    """Raised by 🛑 with the program's error code"""
+
+
+class Stream:
+    """A 🌊 stream over a file, string, bytes, process or stdin"""
+
+    def __init__(self, source, options=None):
+        self.lines = options == "lines"
+        self.binary = options == "bytes" or isinstance(options, (bytes, bytearray))
+        if isinstance(options, (bytes, bytearray)):
+            self.size = len(options)
+        elif isinstance(options, int) and not isinstance(options, bool):
+            self.size = options
+        else:
+            self.size = 8192
+        kind = source.get("type") if isinstance(source, dict) else None
+        self.process = None
+        if source is None or (kind == "file" and source["path"] == "-"):
+            self.file = sys.stdin.buffer
+        elif isinstance(source, str):
+            self.file = io.BytesIO(source.encode())
+        elif isinstance(source, (bytes, bytearray)):
+            self.file = io.BytesIO(bytes(source))
+        elif kind == "file":
+            path = source["path"]
+            if options == "write":
+                self.file = open(path, "w+b")
+            elif options == "append":
+                self.file = open(path, "a+b")
+            else:
+                # Read-only when the file can't be written, but never created
+                try:
+                    self.file = open(path, "r+b")
+                except PermissionError:
+                    self.file = open(path, "rb")
+        elif kind == "process":
+            self.process = subprocess.Popen(source["command"], shell=True, stdin=subprocess.PIPE, stdout=subprocess.PIPE)
+            self.file = self.process.stdout
+        elif isinstance(source, Stream):
+            self.__dict__ = source.__dict__
+        else:
+            raise TypeError("Stream source must be a file, string, bytes, process or stdin")
+
+    def read(self):
+        if self.lines:
+            line = self.file.readline()
+            if not line:
+                return None
+            return line.rstrip(b"\n").rstrip(b"\r").decode(errors="replace")
+        chunk = self.file.read(self.size)
+        if not chunk:
+            return None
+        return chunk if self.binary else chunk.decode(errors="replace")
+
+    def write(self, data):
+        data = aether.raw(data)
+        (self.process.stdin if self.process else self.file).write(data)
+        return len(data)
+
+    def at_end(self):
+        if hasattr(self.file, "peek"):
+            return not self.file.peek(1)
+        position = self.file.tell()
+        ended = not self.file.read(1)
+        self.file.seek(position)
+        return ended
+
+    def skip(self, count):
+        if count is None:
+            self.file.seek(0, io.SEEK_END)
+        elif self.file.seekable():
+            self.file.seek(count, io.SEEK_CUR)
+        else:
+            self.file.read(count)
+        return self
+
+    def flush(self):
+        (self.process.stdin if self.process else self.file).flush()
+        return True
+
+    def close(self):
+        self.file.close()
+        return True
+
+
+class aether:
+    """The shim's operations, called as `aether.name(...)`"""
+
+    lock = threading.RLock()
+    pool = ThreadPoolExecutor()
+    token = None
+
+    def show(value):
+        """Text for a value, the way Aether prints it"""
+        if value is None:
+            return "null"
+        if isinstance(value, bool):
+            return "true" if value else "false"
+        if isinstance(value, float) and value.is_integer():
+            return str(int(value))
+        if isinstance(value, (bytes, bytearray)):
+            return value.hex()
+        if isinstance(value, (list, dict)):
+            return json.dumps(value, default=str, ensure_ascii=False, separators=(",", ":"))
+        return str(value)
+
+    def raw(value):
+        """The bytes a value stands for when written, hashed or sent"""
+        if isinstance(value, (bytes, bytearray)):
+            return bytes(value)
+        return aether.show(value).encode()
+
+    # 📤 🪵 📥 J 🛑 💾
+
+    def output(value):
+        print(aether.show(value))
+        return value
+
+    def log(message):
+        print("LOG: " + aether.show(message))
+
+    def input():
+        return "" if sys.stdin.isatty() else sys.stdin.read()
+
+    def json_parse(text):
+        if isinstance(text, (dict, list)):
+            return text
+        return json.loads(text) if text else {}
+
+    def halt(code):
+        raise AetherHalt(code)
+
+    def persist(value):
+        print("Persisting: " + aether.show(value))
+        return True
+
+    # ⧺ ≈ 🧱 S
+
+    def concat(left, right):
+        if isinstance(left, (bytes, bytearray)) and isinstance(right, (bytes, bytearray)):
+            return bytes(left) + bytes(right)
+        if isinstance(left, (bytes, bytearray)) or isinstance(right, (bytes, bytearray)):
+            raise TypeError("Cannot concatenate bytes with other values; convert with 🧱 or S first")
+        return aether.show(left) + aether.show(right)
+
+    def approx(left, right):
+        epsilon = sys.float_info.epsilon * 10
+        left, right = float(left), float(right)
+        return abs(left - right) < max(epsilon, (abs(left) + abs(right)) * epsilon)
+
+    def buffer(size):
+        return bytes(size) if isinstance(size, int) else aether.to_bytes(size)
+
+    def to_bytes(value, encoding=None):
+        if isinstance(value, (bytes, bytearray)):
+            return bytes(value)
+        if isinstance(value, list):
+            return bytes(value)
+        encoding = encoding or "utf8"
+        if encoding == "hex":
+            return bytes.fromhex(value.replace("_", ""))
+        if encoding == "base64":
+            return base64.b64decode(value.strip())
+        return value.encode("latin-1" if encoding == "latin1" else "utf-8")
+
+    def to_text(value, encoding=None):
+        if not isinstance(value, (bytes, bytearray)):
+            return aether.show(value)
+        encoding = encoding or "utf8"
+        if encoding == "hex":
+            return value.hex()
+        if encoding == "base64":
+            return base64.b64encode(value).decode()
+        return value.decode("latin-1" if encoding == "latin1" else "utf-8")
+
+    # 🌐
+
+    def http(method, url, body=None, headers=None):
+        """Send a request, returning {status, ok, headers, body, json}"""
+        request = urllib.request.Request(url, method=method, headers=dict(headers or {}))
+        if isinstance(body, (dict, list)):
+            request.add_header("Content-Type", "application/json")
+            request.data = json.dumps(body).encode()
+        elif body is not None:
+            request.data = aether.raw(body)
+        try:
+            response = urllib.request.urlopen(request)
+        except urllib.error.HTTPError as error:
+            response = error
+        text = response.read().decode(errors="replace")
+        result = {
+            "status": response.status,
+            "ok": 200 <= response.status < 300,
+            "headers": dict(response.headers),
+            "body": text,
+        }
+        try:
+            result["json"] = json.loads(text)
+        except ValueError:
+            pass
+        return result
+
+    # #️⃣ 🔐 🔓 ✍️ 🔏 (encryption and signatures need the `cryptography` package)
+
+    def hash(data):
+        return hashlib.sha256(aether.raw(data)).hexdigest()
+
+    def key(key):
+        return hashlib.sha256(key.encode()).digest()
+
+    def encrypt(data, key):
+        from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+        nonce = os.urandom(12)
+        return nonce + AESGCM(aether.key(key)).encrypt(nonce, aether.raw(data), None)
+
+    def decrypt(data, key):
+        from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+        plain = AESGCM(aether.key(key)).decrypt(data[:12], data[12:], None)
+        try:
+            return plain.decode()
+        except UnicodeDecodeError:
+            return plain
+
+    def sign(data, key):
+        from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+        return Ed25519PrivateKey.from_private_bytes(aether.key(key)).sign(aether.raw(data))
+
+    def verify(signature, data, key):
+        from cryptography.exceptions import InvalidSignature
+        from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+        public = Ed25519PrivateKey.from_private_bytes(aether.key(key)).public_key()
+        try:
+            public.verify(signature, aether.raw(data))
+            return True
+        except InvalidSignature:
+            return False
+
+    # ⚡ ⏳ 🧵 📡 👁
+
+    def spawn(body):
+        return aether.pool.submit(body)
+
+    def wait(task):
+        return task.result() if isinstance(task, Future) else task
+
+    def emit(event):
+        print("Emit event: " + aether.show(event))
+        return True
+
+    def watch(event, handler):
+        print("Watch event: " + aether.show(event))
+        return handler()
+
+    # 🧩 🔑 📅
+
+    def use(module):
+        return True
+
+    def auth(token):
+        aether.token = token
+        return True
+
+    def now():
+        return datetime.now(timezone.utc).isoformat()
+
+    # 🧪 ⚖️ 🎭 ⏱️ 🐛
+
+    def test(name, body):
+        print(f"Running test: {name}")
+        result = body()
+        print(f"Test '{name}' completed")
+        return result
+
+    def check(condition):
+        if not condition:
+            raise AssertionError(f"Assertion failed: condition evaluated to {aether.show(condition)}")
+        return True
+
+    def mock(target):
+        print("Mocked: " + aether.show(target))
+        return True
+
+    def bench(body):
+        start = time.perf_counter()
+        result = body()
+        print(f"Benchmark: {(time.perf_counter() - start) * 1000:.3f}ms")
+        return result
+
+    def debug():
+        print("DEBUG: Breakpoint hit")
+
+    # 📄 📂 📍 📖 🖊️ 🖇️ 🗑️ 🛂
+
+    def file(path):
+        handle = {"type": "file", "path": path}
+        if os.path.exists(path):
+            handle["size"] = os.path.getsize(path)
+        return handle
+
+    def directory(path):
+        return {"type": "directory", "path": path}
+
+    def path(path):
+        return path
+
+    def target(target):
+        return target["path"] if isinstance(target, dict) else target
+
+    def read(source):
+        if isinstance(source, Stream):
+            return source.read()
+        with open(aether.target(source), "rb") as f:
+            data = f.read()
+        try:
+            return data.decode()
+        except UnicodeDecodeError:
+            return data
+
+    def write(target, content):
+        if isinstance(target, Stream):
+            return target.write(content)
+        with open(aether.target(target), "wb") as f:
+            f.write(aether.raw(content))
+        return True
+
+    def append(target, content):
+        if isinstance(target, Stream):
+            return target.write(content)
+        with open(aether.target(target), "ab") as f:
+            f.write(aether.raw(content))
+        return True
+
+    def delete(target):
+        if isinstance(target, Stream):
+            return target.close()
+        os.remove(aether.target(target))
+        return True
+
+    def chmod(target, permission):
+        os.chmod(aether.target(target), int(str(permission), 8))
+        return True
+
+    # 🌊 🌬️ 🔚 ⏭️
+
+    def stream(source, options=None):
+        return Stream(source, options)
+
+    def flush(target):
+        if isinstance(target, Stream):
+            return target.flush()
+        sys.stdout.flush()
+        return True
+
+    def at_end(value):
+        if value is None:
+            return True
+        return value.at_end() if isinstance(value, Stream) else False
+
+    def skip(stream, count):
+        return stream.skip(count)
+
+    # ⚙️ 🐚 🌍 👋 📶
+
+    def process(command):
+        return {"type": "process", "command": command}
+
+    def shell(command):
+        return subprocess.run(command, shell=True, capture_output=True, text=True).stdout
+
+    def env(name):
+        return os.environ.get(name)
+
+    def exit(code):
+        sys.exit(int(code or 0))
+
+    def signal(signal, target):
+        os.kill(target["pid"] if isinstance(target, dict) else target, signal)
+        return True
+
+    # 🔌 👂 📞 📦 🤝 (sockets are out of the shim's scope)
+
+    def socket(kind):
+        raise NotImplementedError("🔌 sockets have no Python equivalent in this shim")
+
+    def listen(port):
+        raise NotImplementedError("👂 listening has no Python equivalent in this shim")
+
+    def connect(address):
+        raise NotImplementedError("📞 connections have no Python equivalent in this shim")
+
+    def packet(data):
+        return {"type": "packet", "data": data}
+
+    def handshake(connection):
+        raise NotImplementedError("🤝 handshakes have no Python equivalent in this shim")
+
+    # ⊞
+
+    def push(array, element):
+        return array + [element]
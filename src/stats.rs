@@ -0,0 +1,175 @@
+//! Token density of Aether source (`aether stats`)
+//!
+//! A program is measured in graphemes, code points, bytes and estimated
+//! tokens, once as written, once as code with its comments taken out and
+//! once as its `Explainer` projection, the pseudo-code a reader would get
+//! instead. The glyphs it uses are costed one by one against their ASCII
+//! spellings, since a glyph that is one grapheme, such as `#️⃣`, can be
+//! three code points and several tokens.
+
+use crate::error::Result;
+use crate::explainer::Explainer;
+use crate::lexer::{Lexer, TokenType};
+use crate::parser::Parser;
+use crate::symbols::Symbol;
+use crate::tokenizer::Tokenizer;
+use std::ops::AddAssign;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The size of a text by each measure
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Size {
+    pub graphemes: usize,
+    pub code_points: usize,
+    pub bytes: usize,
+    pub tokens: usize,
+}
+
+impl Size {
+    pub fn of(text: &str, tokenizer: &dyn Tokenizer) -> Self {
+        Size {
+            graphemes: text.graphemes(true).count(),
+            code_points: text.chars().count(),
+            bytes: text.len(),
+            tokens: tokenizer.count(text),
+        }
+    }
+}
+
+impl AddAssign for Size {
+    fn add_assign(&mut self, other: Size) {
+        self.graphemes += other.graphemes;
+        self.code_points += other.code_points;
+        self.bytes += other.bytes;
+        self.tokens += other.tokens;
+    }
+}
+
+/// What a glyph costs each time a program writes it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphCost {
+    /// The glyph as written
+    pub glyph: String,
+    pub symbol: Symbol,
+    pub uses: usize,
+    pub code_points: usize,
+    pub bytes: usize,
+    pub tokens: usize,
+    /// Tokens in the symbol's ASCII spelling
+    pub ascii_tokens: usize,
+}
+
+impl GlyphCost {
+    /// Whether the glyph is several code points, which tokenizers split
+    /// into tokens of their own, and costs more than its ASCII spelling
+    pub fn is_expensive(&self) -> bool {
+        self.code_points > 1 && self.tokens > self.ascii_tokens
+    }
+}
+
+/// Measurements of one program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// The source as written
+    pub source: Size,
+    /// The source without comments or blank lines
+    pub code: Size,
+    /// The `Explainer` projection, if the program parses
+    pub explanation: Option<Size>,
+    /// The glyphs used, most tokens in all first
+    pub glyphs: Vec<GlyphCost>,
+}
+
+impl Stats {
+    /// Glyphs of several code points that cost more than their ASCII spellings
+    pub fn expensive_glyphs(&self) -> impl Iterator<Item = &GlyphCost> {
+        self.glyphs.iter().filter(|cost| cost.is_expensive())
+    }
+}
+
+/// Measure a program, counting tokens with `tokenizer`
+pub fn analyze(source: &str, tokenizer: &dyn Tokenizer) -> Result<Stats> {
+    let graphemes: Vec<&str> = source.graphemes(true).collect();
+    let tokens = Lexer::new(source.to_string()).tokenize_with_trivia()?;
+
+    let mut code = String::new();
+    let mut position = 0;
+    let mut glyphs: Vec<GlyphCost> = Vec::new();
+    for token in &tokens {
+        let written = graphemes[token.position..token.position + token.length].concat();
+        match &token.token_type {
+            TokenType::Comment(_) => {
+                code.push_str(&graphemes[position..token.position].concat());
+                position = token.position + token.length;
+            }
+            TokenType::Symbol(symbol) => {
+                let Some(ascii) = symbol.ascii() else { continue };
+                match glyphs.iter_mut().find(|cost| cost.glyph == written) {
+                    Some(cost) => cost.uses += 1,
+                    None => glyphs.push(GlyphCost {
+                        symbol: symbol.clone(),
+                        uses: 1,
+                        code_points: written.chars().count(),
+                        bytes: written.len(),
+                        tokens: tokenizer.count(&written),
+                        ascii_tokens: tokenizer.count(ascii),
+                        glyph: written,
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+    code.push_str(&graphemes[position..].concat());
+    let code: Vec<&str> = code.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect();
+    glyphs.sort_by_key(|cost| std::cmp::Reverse(cost.tokens * cost.uses));
+
+    let explanation = Lexer::new(source.to_string())
+        .tokenize()
+        .and_then(|tokens| Parser::new(tokens).parse())
+        .ok()
+        .map(|ast| Size::of(&Explainer::new().explain(&ast), tokenizer));
+
+    Ok(Stats {
+        source: Size::of(source, tokenizer),
+        code: Size::of(&code.join("\n"), tokenizer),
+        explanation,
+        glyphs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{Bpe, ByteEstimate};
+
+    #[test]
+    fn test_sizes() {
+        let size = Size::of("📤#️⃣é", &ByteEstimate);
+        assert_eq!(size, Size { graphemes: 3, code_points: 5, bytes: 13, tokens: 4 });
+
+        let source = "// greet\n📤\"hi\" // say it\n\n📤\"bye\"\n";
+        let stats = analyze(source, &ByteEstimate).unwrap();
+        assert_eq!(stats.source.bytes, source.len());
+        assert_eq!(stats.code.graphemes, "📤\"hi\"\n📤\"bye\"".graphemes(true).count());
+        assert!(stats.explanation.unwrap().tokens > stats.code.tokens);
+    }
+
+    #[test]
+    fn test_glyph_costs() {
+        let bpe = Bpe::bundled();
+        let stats = analyze("x ≔ #️⃣\"a\" ⨠ 📤x ⨠ 📤#️⃣\"b\"", &bpe).unwrap();
+        let hash = stats.glyphs.iter().find(|cost| cost.symbol == Symbol::Hash).unwrap();
+        assert_eq!((hash.uses, hash.code_points, hash.bytes), (2, 3, 7));
+        assert!(hash.is_expensive());
+        assert_eq!(hash.ascii_tokens, bpe.count("hash"));
+        assert_eq!(stats.glyphs.iter().find(|cost| cost.symbol == Symbol::Output).unwrap().uses, 2);
+        let expensive: Vec<&str> = stats.expensive_glyphs().map(|cost| cost.glyph.as_str()).collect();
+        assert_eq!(expensive, vec!["#️⃣"]);
+
+        // A program that doesn't parse is still measured
+        let stats = analyze("📤 ⨠", &bpe).unwrap();
+        assert_eq!(stats.explanation, None);
+        assert_eq!(stats.code.graphemes, 3);
+    }
+}
@@ -0,0 +1,728 @@
+//! Stream resources backing the stream symbols (🌊 🧱 🌬️ 🔚 ⏭️)
+//!
+//! A stream wraps a file, an in-memory string, a TCP connection, a child
+//! process's pipes or stdin. Like sockets, streams are exposed to programs as
+//! `Value::Object`s with a `"handle"` key that refers to state owned by a
+//! [`StreamRegistry`]. Reads are buffered and incremental, so a stream never
+//! holds more than a chunk of its source in memory; writes are buffered until
+//! the buffer fills or the stream is flushed with 🌬️ or closed with 🗑️.
+
+use crate::error::{AetherError, Result};
+use crate::net::{self, NetRegistry};
+use crate::numeric;
+use crate::runtime::Value;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::net::TcpStream;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Default size of a chunked read and of the write buffer
+pub const DEFAULT_CHUNK_SIZE: usize = 8192;
+
+/// Where a stream's bytes come from and go to
+enum Source {
+    File(File),
+    Memory(Cursor<Vec<u8>>),
+    Socket(TcpStream),
+    Process {
+        child: Child,
+        stdin: Option<ChildStdin>,
+        stdout: ChildStdout,
+    },
+    Stdin(io::Stdin),
+}
+
+impl Source {
+    fn kind(&self) -> &'static str {
+        match self {
+            Source::File(_) => "file",
+            Source::Memory(_) => "memory",
+            Source::Socket(_) => "socket",
+            Source::Process { .. } => "process",
+            Source::Stdin(_) => "stdin",
+        }
+    }
+
+    fn is_seekable(&self) -> bool {
+        matches!(self, Source::File(_) | Source::Memory(_))
+    }
+
+    fn read_raw(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::File(f) => f.read(buf),
+            Source::Memory(c) => c.read(buf),
+            Source::Socket(s) => s.read(buf),
+            Source::Process { stdout, .. } => stdout.read(buf),
+            Source::Stdin(s) => s.read(buf),
+        }
+    }
+
+    fn write_raw(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Source::File(f) => f.write_all(data),
+            Source::Memory(c) => c.write_all(data),
+            Source::Socket(s) => s.write_all(data),
+            Source::Process { stdin: Some(stdin), .. } => stdin.write_all(data),
+            Source::Process { stdin: None, .. } | Source::Stdin(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "stream is not writable",
+            )),
+        }
+    }
+
+    fn flush_raw(&mut self) -> io::Result<()> {
+        match self {
+            Source::File(f) => f.flush(),
+            Source::Socket(s) => s.flush(),
+            Source::Process { stdin: Some(stdin), .. } => stdin.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    fn seek_raw(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::File(f) => f.seek(pos),
+            Source::Memory(c) => c.seek(pos),
+            _ => Err(io::Error::new(io::ErrorKind::Unsupported, "stream is not seekable")),
+        }
+    }
+}
+
+/// How 📖 splits a stream's contents
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReadMode {
+    /// Up to this many bytes per read, as text
+    Chunks(usize),
+    /// One line per read, without its line terminator
+    Lines,
+    /// Up to this many bytes per read, as raw bytes
+    Binary(usize),
+}
+
+/// How a file stream opens its file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Access {
+    /// An existing file, writable if permissions allow
+    Existing,
+    /// Created, or emptied if it exists
+    Write,
+    /// Created if missing, with every write at the end
+    Append,
+}
+
+/// An open stream and its buffers
+struct Stream {
+    source: Source,
+    mode: ReadMode,
+    /// Bytes read from the source but not yet returned to the program
+    read_buf: Vec<u8>,
+    /// Bytes written by the program but not yet passed to the source
+    write_buf: Vec<u8>,
+    /// Write buffer size that triggers an automatic flush
+    capacity: usize,
+    /// Offset for seekable streams; bytes consumed for the others
+    position: u64,
+}
+
+impl Stream {
+    fn new(source: Source, mode: ReadMode, capacity: usize) -> Self {
+        Stream {
+            source,
+            mode,
+            read_buf: Vec::new(),
+            write_buf: Vec::new(),
+            capacity,
+            position: 0,
+        }
+    }
+
+    /// Read more bytes from the source into the read buffer
+    ///
+    /// Returns the number of bytes added; 0 means end of stream.
+    fn fill(&mut self) -> io::Result<usize> {
+        self.flush()?;
+        let size = match self.mode {
+            ReadMode::Chunks(n) | ReadMode::Binary(n) => n.max(1),
+            ReadMode::Lines => DEFAULT_CHUNK_SIZE,
+        };
+        let mut buf = vec![0u8; size];
+        let n = loop {
+            match self.source.read_raw(&mut buf) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                other => break other?,
+            }
+        };
+        self.read_buf.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    /// Remove `n` bytes from the front of the read buffer
+    fn consume(&mut self, n: usize) -> Vec<u8> {
+        self.position += n as u64;
+        self.read_buf.drain(..n).collect()
+    }
+
+    fn read(&mut self) -> io::Result<Option<Value>> {
+        match self.mode {
+            ReadMode::Chunks(size) => Ok(self.read_chunk(size.max(1))?.map(Value::String)),
+            ReadMode::Lines => Ok(self.read_line()?.map(Value::String)),
+            ReadMode::Binary(size) => Ok(self.read_binary(size.max(1))?.map(Value::Bytes)),
+        }
+    }
+
+    fn read_binary(&mut self, size: usize) -> io::Result<Option<Vec<u8>>> {
+        if self.read_buf.is_empty() && self.fill()? == 0 {
+            return Ok(None);
+        }
+        let take = size.min(self.read_buf.len());
+        Ok(Some(self.consume(take)))
+    }
+
+    fn read_chunk(&mut self, size: usize) -> io::Result<Option<String>> {
+        loop {
+            if self.read_buf.is_empty() && self.fill()? == 0 {
+                return Ok(None);
+            }
+            let take = size.min(self.read_buf.len());
+            // Never split a UTF-8 sequence across two reads
+            let take = match std::str::from_utf8(&self.read_buf[..take]) {
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                _ => take,
+            };
+            // A character wider than the chunk size is returned whole
+            let take = match take {
+                0 if self.read_buf.len() >= utf8_width(self.read_buf[0]) => utf8_width(self.read_buf[0]),
+                n => n,
+            };
+            if take > 0 {
+                let bytes = self.consume(take);
+                return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+            }
+            // Only part of a character is buffered; fetch the rest
+            if self.fill()? == 0 {
+                let rest = self.read_buf.len();
+                let bytes = self.consume(rest);
+                return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut searched = 0;
+        loop {
+            if let Some(i) = self.read_buf[searched..].iter().position(|&b| b == b'\n') {
+                let mut line = self.consume(searched + i + 1);
+                line.pop();
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                return Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+            searched = self.read_buf.len();
+            if self.fill()? == 0 {
+                if self.read_buf.is_empty() {
+                    return Ok(None);
+                }
+                let rest = self.read_buf.len();
+                let line = self.consume(rest);
+                return Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.source.is_seekable() && !self.read_buf.is_empty() {
+            // Give back read-ahead so the write lands at the logical position
+            self.source.seek_raw(SeekFrom::Start(self.position))?;
+            self.read_buf.clear();
+        }
+        self.write_buf.extend_from_slice(data);
+        if self.source.is_seekable() {
+            self.position += data.len() as u64;
+        }
+        if self.write_buf.len() >= self.capacity {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.write_buf.is_empty() {
+            let pending = std::mem::take(&mut self.write_buf);
+            self.source.write_raw(&pending)?;
+        }
+        self.source.flush_raw()
+    }
+
+    fn at_end(&mut self) -> io::Result<bool> {
+        Ok(self.read_buf.is_empty() && self.fill()? == 0)
+    }
+
+    /// Move the read position by `delta` bytes, or to the end when `None`
+    fn skip(&mut self, delta: Option<i64>) -> io::Result<()> {
+        self.flush()?;
+        if self.source.is_seekable() {
+            let target = match delta {
+                Some(d) => SeekFrom::Start(self.position.saturating_add_signed(d)),
+                None => SeekFrom::End(0),
+            };
+            self.position = self.source.seek_raw(target)?;
+            self.read_buf.clear();
+            return Ok(());
+        }
+
+        // Pipes and sockets can only move forward, by reading and discarding
+        let mut remaining = match delta {
+            Some(d) if d < 0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("cannot seek backwards in a {} stream", self.source.kind()),
+                ))
+            }
+            Some(d) => d as u64,
+            None => u64::MAX,
+        };
+        while remaining > 0 {
+            if self.read_buf.is_empty() && self.fill()? == 0 {
+                break;
+            }
+            let take = (remaining.min(self.read_buf.len() as u64)) as usize;
+            self.consume(take);
+            remaining -= take as u64;
+        }
+        Ok(())
+    }
+
+    fn close(mut self) -> io::Result<()> {
+        let flushed = self.flush();
+        if let Source::Process { child, stdin, .. } = &mut self.source {
+            // Closing stdin lets the child see end of input and exit
+            stdin.take();
+            child.wait()?;
+        }
+        flushed
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        let _ = self.flush();
+        if let Source::Process { child, stdin, .. } = &mut self.source {
+            stdin.take();
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Owner of all open streams, keyed by handle
+#[derive(Default)]
+pub struct StreamRegistry {
+    streams: HashMap<String, Stream>,
+    counter: usize,
+}
+
+impl StreamRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a stream over a source (source ⇢ 🌊options)
+    ///
+    /// Sources are file objects (path `"-"` is stdin), strings or bytes (read
+    /// from memory), connections from 📞 or 👂, process objects from ⚙️, or
+    /// null for stdin. Options pick the read mode: `"lines"` for line-by-line
+    /// reads, a number for the chunk size, `"bytes"` for binary chunks, or a
+    /// 🧱 buffer whose size is used for both binary reads and the write buffer.
+    /// Files must exist for these; `"write"` creates or empties a file and
+    /// `"append"` creates a missing one and writes at its end.
+    pub fn open(&mut self, source: &Value, options: &Value, net: &NetRegistry) -> Result<Value> {
+        let access = match options {
+            Value::String(s) if s == "write" => Access::Write,
+            Value::String(s) if s == "append" => Access::Append,
+            _ => Access::Existing,
+        };
+        let (mode, capacity) = match options {
+            Value::Null => (ReadMode::Chunks(DEFAULT_CHUNK_SIZE), DEFAULT_CHUNK_SIZE),
+            _ if access != Access::Existing => (ReadMode::Chunks(DEFAULT_CHUNK_SIZE), DEFAULT_CHUNK_SIZE),
+            Value::String(s) if s == "lines" => (ReadMode::Lines, DEFAULT_CHUNK_SIZE),
+            Value::String(s) if s == "chunks" => (ReadMode::Chunks(DEFAULT_CHUNK_SIZE), DEFAULT_CHUNK_SIZE),
+            Value::String(s) if s == "bytes" => (ReadMode::Binary(DEFAULT_CHUNK_SIZE), DEFAULT_CHUNK_SIZE),
+            n if n.is_numeric() => match numeric::as_integer(n).and_then(|n| usize::try_from(n).ok()) {
+                Some(size) if size >= 1 => (ReadMode::Chunks(size), DEFAULT_CHUNK_SIZE),
+                _ => return Err(AetherError::RuntimeError("Stream chunk size must be a positive integer".to_string())),
+            },
+            Value::Bytes(buffer) => {
+                if buffer.is_empty() {
+                    return Err(AetherError::RuntimeError("Stream buffer size must be positive".to_string()));
+                }
+                (ReadMode::Binary(buffer.len()), buffer.len())
+            }
+            _ => return Err(AetherError::RuntimeError(
+                "Stream options must be \"lines\", \"bytes\", \"write\", \"append\", a chunk size or a buffer".to_string()
+            )),
+        };
+
+        let source_obj = match source {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        };
+        let source_type = source_obj
+            .and_then(|obj| obj.get("type"))
+            .and_then(|t| t.as_string());
+
+        let opened = match (source, source_type) {
+            (Value::Null, _) => Source::Stdin(io::stdin()),
+            (Value::String(s), _) => Source::Memory(Cursor::new(s.clone().into_bytes())),
+            (Value::Bytes(b), _) => Source::Memory(Cursor::new(b.clone())),
+            (_, Some("file")) => {
+                let path = source_obj
+                    .and_then(|obj| obj.get("path"))
+                    .and_then(|p| p.as_string())
+                    .ok_or_else(|| AetherError::RuntimeError("Invalid file object for stream".to_string()))?;
+                if path == "-" {
+                    Source::Stdin(io::stdin())
+                } else {
+                    Source::File(open_file(path, access)?)
+                }
+            }
+            (_, Some("connection")) => Source::Socket(net.clone_connection(source)?),
+            (_, Some("process")) => {
+                let command = source_obj
+                    .and_then(|obj| obj.get("command"))
+                    .and_then(|c| c.as_string())
+                    .ok_or_else(|| AetherError::RuntimeError("Invalid process object for stream".to_string()))?;
+                spawn_process(command)?
+            }
+            (_, Some("stream")) if self.owns(source) => return Ok(source.clone()),
+            _ => return Err(AetherError::RuntimeError(
+                "Stream source must be a file, string, bytes, connection, process or stdin".to_string()
+            )),
+        };
+
+        let kind = opened.kind();
+        let pid = match &opened {
+            Source::Process { child, .. } => Some(child.id()),
+            _ => None,
+        };
+
+        self.counter += 1;
+        let handle = format!("stream_{}", self.counter);
+        self.streams.insert(handle.clone(), Stream::new(opened, mode, capacity));
+
+        let mut obj = HashMap::new();
+        obj.insert("type".to_string(), Value::String("stream".to_string()));
+        obj.insert("kind".to_string(), Value::String(kind.to_string()));
+        obj.insert("source".to_string(), source.clone());
+        obj.insert("position".to_string(), Value::Integer(0));
+        obj.insert("handle".to_string(), Value::String(handle));
+        if let Some(pid) = pid {
+            obj.insert("pid".to_string(), Value::Integer(pid as i64));
+        }
+        Ok(Value::Object(obj))
+    }
+
+    /// Read the next chunk or line (📖), or `Null` at end of stream
+    pub fn read(&mut self, stream: &Value) -> Result<Value> {
+        let s = self.get(stream)?;
+        let data = s.read()
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to read from stream: {}", e)))?;
+        Ok(data.unwrap_or(Value::Null))
+    }
+
+    /// Buffer data for writing (🖊️), returning the number of bytes accepted
+    pub fn write(&mut self, stream: &Value, data: &Value) -> Result<Value> {
+        let bytes = net::payload(data)?;
+        let s = self.get(stream)?;
+        s.write(&bytes)
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to write to stream: {}", e)))?;
+        Ok(Value::Integer(bytes.len() as i64))
+    }
+
+    /// Push buffered writes through to the underlying source (🌬️)
+    pub fn flush(&mut self, stream: &Value) -> Result<Value> {
+        let s = self.get(stream)?;
+        s.flush()
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to flush stream: {}", e)))?;
+        Ok(Value::Boolean(true))
+    }
+
+    /// Whether a value marks the end of data (🔚)
+    ///
+    /// Streams are at their end once no more bytes can be read; `Null`, which
+    /// 📖 returns at the end of a stream, always is.
+    pub fn at_end(&mut self, value: &Value) -> Result<bool> {
+        if value.is_null() {
+            return Ok(true);
+        }
+        if !self.owns(value) {
+            return Ok(false);
+        }
+        let s = self.get(value)?;
+        s.at_end()
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to read from stream: {}", e)))
+    }
+
+    /// Move a stream's position (⏭️), returning the stream with its new position
+    ///
+    /// Numbers move relative to the current position (backwards only on files
+    /// and strings); `Null`, i.e. `⏭️🔚`, moves to the end.
+    pub fn skip(&mut self, stream: &Value, count: &Value) -> Result<Value> {
+        let delta = match count {
+            Value::Null => None,
+            n => Some(numeric::as_integer(n)
+                .ok_or_else(|| AetherError::RuntimeError("Skip count must be a whole number".to_string()))?),
+        };
+        let s = self.get(stream)?;
+        s.skip(delta)
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to seek in stream: {}", e)))?;
+        let position = s.position;
+
+        let mut updated = match stream {
+            Value::Object(obj) => obj.clone(),
+            _ => HashMap::new(),
+        };
+        updated.insert("position".to_string(), Value::Integer(position as i64));
+        Ok(Value::Object(updated))
+    }
+
+    /// Flush and close a stream (🗑️)
+    pub fn close(&mut self, stream: &Value) -> Result<Value> {
+        let handle = net::handle_of(stream)
+            .ok_or_else(|| AetherError::RuntimeError("Expected a stream".to_string()))?;
+        let s = self.streams.remove(&handle)
+            .ok_or_else(|| AetherError::RuntimeError(format!("Stream {} is closed", handle)))?;
+        s.close()
+            .map_err(|e| AetherError::RuntimeError(format!("Failed to close stream: {}", e)))?;
+        Ok(Value::Boolean(true))
+    }
+
+    /// Whether a value refers to a stream owned by this registry
+    pub fn owns(&self, value: &Value) -> bool {
+        net::handle_of(value).is_some_and(|h| self.streams.contains_key(&h))
+    }
+
+    fn get(&mut self, stream: &Value) -> Result<&mut Stream> {
+        let handle = net::handle_of(stream)
+            .ok_or_else(|| AetherError::RuntimeError("Expected a stream".to_string()))?;
+        self.streams.get_mut(&handle)
+            .ok_or_else(|| AetherError::RuntimeError(format!("Stream {} is closed", handle)))
+    }
+}
+
+/// Build the object 📄 returns for a path, with its size when it exists
+pub fn file_object(path: &str) -> Value {
+    let mut obj = HashMap::new();
+    obj.insert("type".to_string(), Value::String("file".to_string()));
+    obj.insert("path".to_string(), Value::String(path.to_string()));
+    if let Ok(meta) = std::fs::metadata(path) {
+        obj.insert("size".to_string(), Value::Integer(meta.len() as i64));
+    }
+    Value::Object(obj)
+}
+
+/// Length of the UTF-8 sequence introduced by a leading byte
+fn utf8_width(first: u8) -> usize {
+    match first {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => 1,
+    }
+}
+
+/// Open a file for a stream; only writing and appending create it
+fn open_file(path: &str, access: Access) -> Result<File> {
+    let opened = match access {
+        // Read-only when the file can't be written, but never created
+        Access::Existing => OpenOptions::new().read(true).write(true).open(path).or_else(|e| match e.kind() {
+            io::ErrorKind::NotFound => Err(e),
+            _ => File::open(path),
+        }),
+        Access::Write => OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path),
+        Access::Append => OpenOptions::new().read(true).append(true).create(true).open(path),
+    };
+    opened.map_err(|e| AetherError::RuntimeError(format!("Failed to open stream on '{}': {}", path, e)))
+}
+
+fn spawn_process(command: &str) -> Result<Source> {
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(command);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(command);
+        c
+    };
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| AetherError::RuntimeError(format!("Failed to start process '{}': {}", command, e)))?;
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take()
+        .ok_or_else(|| AetherError::RuntimeError("Process has no stdout".to_string()))?;
+    Ok(Source::Process { child, stdin, stdout })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("aether_stream_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn open(streams: &mut StreamRegistry, source: Value, options: Value) -> Value {
+        streams.open(&source, &options, &NetRegistry::new()).unwrap()
+    }
+
+    #[test]
+    fn test_chunked_reads_until_eof() {
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, Value::String("abcdefg".to_string()), Value::Number(3.0));
+
+        assert_eq!(streams.read(&s).unwrap(), Value::String("abc".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("def".to_string()));
+        assert!(!streams.at_end(&s).unwrap());
+        assert_eq!(streams.read(&s).unwrap(), Value::String("g".to_string()));
+        assert!(streams.at_end(&s).unwrap());
+        assert_eq!(streams.read(&s).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_chunks_do_not_split_characters() {
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, Value::String("a以太".to_string()), Value::Number(2.0));
+
+        assert_eq!(streams.read(&s).unwrap(), Value::String("a".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("以".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("太".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_line_reads() {
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, Value::String("one\r\ntwo\nthree".to_string()), Value::String("lines".to_string()));
+
+        assert_eq!(streams.read(&s).unwrap(), Value::String("one".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("two".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("three".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_file_writes_are_buffered_until_flush() {
+        let path = temp_path("flush.txt");
+        let _ = std::fs::remove_file(&path);
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, file_object(&path), Value::String("write".to_string()));
+
+        assert_eq!(streams.write(&s, &Value::String("hello".to_string())).unwrap(), Value::Number(5.0));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        streams.flush(&s).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello");
+
+        streams.write(&s, &Value::String(" world".to_string())).unwrap();
+        streams.close(&s).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello world");
+        assert!(!streams.owns(&s));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reading_a_missing_file_fails() {
+        let path = temp_path("missing.txt");
+        let _ = std::fs::remove_file(&path);
+        let mut streams = StreamRegistry::new();
+        for options in [Value::Null, Value::String("lines".to_string())] {
+            let err = streams.open(&file_object(&path), &options, &NetRegistry::new()).unwrap_err();
+            assert!(err.to_string().contains("Failed to open stream"), "{}", err);
+        }
+        assert!(!std::path::Path::new(&path).exists());
+    }
+
+    #[test]
+    fn test_append_creates_and_appends() {
+        let path = temp_path("append.txt");
+        let _ = std::fs::remove_file(&path);
+        let mut streams = StreamRegistry::new();
+        for line in ["one\n", "two\n"] {
+            let s = open(&mut streams, file_object(&path), Value::String("append".to_string()));
+            streams.write(&s, &Value::String(line.to_string())).unwrap();
+            streams.close(&s).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_seek_within_file() {
+        let path = temp_path("seek.txt");
+        std::fs::write(&path, "0123456789").unwrap();
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, file_object(&path), Value::Number(2.0));
+
+        let moved = streams.skip(&s, &Value::Number(4.0)).unwrap();
+        match &moved {
+            Value::Object(obj) => assert_eq!(obj.get("position"), Some(&Value::Number(4.0))),
+            _ => panic!("Expected stream object"),
+        }
+        assert_eq!(streams.read(&s).unwrap(), Value::String("45".to_string()));
+        streams.skip(&s, &Value::Number(-6.0)).unwrap();
+        assert_eq!(streams.read(&s).unwrap(), Value::String("01".to_string()));
+
+        streams.write(&s, &Value::String("ab".to_string())).unwrap();
+        streams.skip(&s, &Value::Null).unwrap();
+        assert!(streams.at_end(&s).unwrap());
+        streams.close(&s).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "01ab456789");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_write_after_line_read_ignores_read_ahead() {
+        let path = temp_path("readahead.txt");
+        std::fs::write(&path, "ab\ncd\nef").unwrap();
+        let mut streams = StreamRegistry::new();
+        let s = open(&mut streams, file_object(&path), Value::String("lines".to_string()));
+
+        assert_eq!(streams.read(&s).unwrap(), Value::String("ab".to_string()));
+        streams.write(&s, &Value::String("XY".to_string())).unwrap();
+        streams.flush(&s).unwrap();
+        assert_eq!(streams.read(&s).unwrap(), Value::String("".to_string()));
+        assert_eq!(streams.read(&s).unwrap(), Value::String("ef".to_string()));
+        streams.close(&s).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "ab\nXY\nef");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_pipes() {
+        let mut streams = StreamRegistry::new();
+        let mut proc_obj = HashMap::new();
+        proc_obj.insert("type".to_string(), Value::String("process".to_string()));
+        proc_obj.insert("command".to_string(), Value::String("cat".to_string()));
+        let s = open(&mut streams, Value::Object(proc_obj), Value::String("lines".to_string()));
+
+        streams.write(&s, &Value::String("hello\n".to_string())).unwrap();
+        streams.flush(&s).unwrap();
+        assert_eq!(streams.read(&s).unwrap(), Value::String("hello".to_string()));
+        streams.close(&s).unwrap();
+    }
+
+    #[test]
+    fn test_at_end_of_plain_values() {
+        let mut streams = StreamRegistry::new();
+        assert!(streams.at_end(&Value::Null).unwrap());
+        assert!(!streams.at_end(&Value::String("data".to_string())).unwrap());
+    }
+}
@@ -3,6 +3,363 @@
 //! Aether uses UTF-8 symbols for high-density code representation.
 //! Each symbol represents a fundamental operation or concept.
 
+use crate::numeric::Decimal;
+use serde_json::{json, Value as Json};
+
+/// Every operation symbol with its glyph, ASCII spelling, category, arity,
+/// description and an example, in the order listings show them. The lexer,
+/// the explainer's glossary, `aether symbols` and the generated part of
+/// `docs/SYMBOL_REFERENCE.md` all read this table.
+pub const SYMBOLS: &[SymbolInfo] = &[
+    entry(Symbol::Function, "ƒ", "fn", Category::Functions, Arity::Block,
+        "Function definition", "ƒgreet: 📤\"hello\""),
+    entry(Symbol::Lambda, "λ", "lambda", Category::Functions, Arity::Block,
+        "Lambda/anonymous function", "λ: x ⨠ 📤x"),
+    entry(Symbol::MapArrow, "⇒", "=>", Category::Functions, Arity::Infix,
+        "Map/transform operation", "items ⇒ λ: x * 2"),
+    entry(Symbol::Pipe, "⇢", "->", Category::Functions, Arity::Infix,
+        "Pipe/flow data", "text ⇢ 📏 ▷ n"),
+    entry(Symbol::PipeInto, "▷", "|>", Category::Functions, Arity::Infix,
+        "Pipe into variable", "25 ▷ age"),
+
+    entry(Symbol::Input, "📥", "input", Category::Data, Arity::Nullary,
+        "Input/Request context", "📥 ⇢ J ▷ body"),
+    entry(Symbol::Output, "📤", "output", Category::Data, Arity::Prefix,
+        "Output/Response", "📤\"done\""),
+    entry(Symbol::Persist, "💾", "persist", Category::Data, Arity::Prefix,
+        "Persist to database", "💾user"),
+    entry(Symbol::Query, "🔍", "query", Category::Data, Arity::Prefix,
+        "Query/Search operation", "🔍users"),
+    entry(Symbol::JsonParse, "J", "J", Category::Data, Arity::Nullary,
+        "Parse JSON", "📥 ⇢ J ▷ body"),
+    entry(Symbol::StringType, "S", "S", Category::Data, Arity::Nullary,
+        "String type", "📥 ▷ name:S"),
+    entry(Symbol::NumberType, "N", "N", Category::Data, Arity::Nullary,
+        "Number type", "📥 ▷ age:N"),
+
+    entry(Symbol::Guard, "⁇", "guard", Category::Logic, Arity::Infix,
+        "Guard/null check", "user ⁇ 🛑400"),
+    entry(Symbol::Halt, "🛑", "halt", Category::Logic, Arity::Prefix,
+        "Halt/terminate with error", "🛑404"),
+    entry(Symbol::Success, "✓", "success", Category::Logic, Arity::Prefix,
+        "Success/validate", "✓user"),
+    entry(Symbol::Sequence, "⨠", ">>", Category::Logic, Arity::Infix,
+        "Sequence operations", "1 ▷ x ⨠ 📤x"),
+    entry(Symbol::If, "◇", "if", Category::Logic, Arity::Block,
+        "Conditional (if)", "◇(x > 5): 📤\"big\""),
+    entry(Symbol::ElseIf, "◈", "elif", Category::Logic, Arity::Block,
+        "Conditional alternative (else if)", "◇(x > 5): 📤\"big\" ◈(x > 2): 📤\"medium\""),
+    entry(Symbol::Else, "◆", "else", Category::Logic, Arity::Block,
+        "Default branch (else)", "◇(x > 5): 📤\"big\" ◆: 📤\"small\""),
+    entry(Symbol::Or, "⊕", "or", Category::Logic, Arity::Infix,
+        "Logical OR", "◇((x < 5) ⊕ (x > 15)): 📤\"out\""),
+    entry(Symbol::And, "⊗", "and", Category::Logic, Arity::Infix,
+        "Logical AND", "◇((x > 5) ⊗ (x < 15)): 📤\"in\""),
+    entry(Symbol::Not, "¬", "not", Category::Logic, Arity::Prefix,
+        "Logical NOT", "◇(¬flag): 📤\"off\""),
+
+    entry(Symbol::Array, "🗂", "array", Category::Collections, Arity::Prefix,
+        "Array/List", "🗂[1, 2, 3] ▷ xs"),
+    entry(Symbol::Map, "🗄", "map", Category::Collections, Arity::Prefix,
+        "Map/Dictionary", "🗄{name: \"Ada\"} ▷ user"),
+    entry(Symbol::Empty, "∅", "null", Category::Collections, Arity::Nullary,
+        "Empty/null value", "∅ ▷ value"),
+
+    entry(Symbol::HttpRequest, "🌐", "http", Category::Http, Arity::Prefix,
+        "HTTP request (generic)", "🌐\"https://example.com\" ▷ page"),
+    entry(Symbol::HttpHeaders, "🏷️", "headers", Category::Http, Arity::Prefix,
+        "HTTP headers (key-value pairs)", "🌐📥\"https://example.com\" 🏷️{accept: \"text/html\"}"),
+    entry(Symbol::HttpGet, "🌐📥", "http_get", Category::Http, Arity::Prefix,
+        "HTTP GET request", "🌐📥\"https://example.com/users\" ▷ users"),
+    entry(Symbol::HttpPost, "🌐📤", "http_post", Category::Http, Arity::Prefix,
+        "HTTP POST request", "🌐📤\"https://example.com/users\" ⇢ body ▷ created"),
+    entry(Symbol::HttpPut, "🌐🔄", "http_put", Category::Http, Arity::Prefix,
+        "HTTP PUT request", "🌐🔄\"https://example.com/users/1\" ⇢ body ▷ updated"),
+    entry(Symbol::HttpDelete, "🌐🗑️", "http_delete", Category::Http, Arity::Prefix,
+        "HTTP DELETE request", "🌐🗑️\"https://example.com/users/1\""),
+    entry(Symbol::HttpPatch, "🌐🔧", "http_patch", Category::Http, Arity::Prefix,
+        "HTTP PATCH request", "🌐🔧\"https://example.com/users/1\" ⇢ body"),
+    entry(Symbol::HttpHead, "🌐👁", "http_head", Category::Http, Arity::Prefix,
+        "HTTP HEAD request", "🌐👁\"https://example.com\" ▷ headers"),
+    entry(Symbol::HttpOptions, "🌐⚙️", "http_options", Category::Http, Arity::Prefix,
+        "HTTP OPTIONS request", "🌐⚙️\"https://example.com\" ▷ methods"),
+    entry(Symbol::Register, "®", "register", Category::Http, Arity::Nullary,
+        "Register/create", "ƒ®: 📥 ⇢ J ▷ user ⨠ 💾user"),
+
+    entry(Symbol::Loop, "↻", "loop", Category::Iteration, Arity::Block,
+        "Loop/While (unbounded loop)", "↻(n < 10): (n + 1 ▷ n)"),
+    entry(Symbol::ForEach, "∀", "each", Category::Iteration, Arity::Block,
+        "ForEach/Map over collection", "∀item: 📤item"),
+    entry(Symbol::Filter, "∃", "filter", Category::Iteration, Arity::Prefix,
+        "Filter/Find in collection", "users ⇢ ∃(active) ▷ current"),
+    entry(Symbol::Reduce, "∑", "reduce", Category::Iteration, Arity::Prefix,
+        "Reduce/Sum aggregation", "numbers ⇢ ∑ ▷ total"),
+    entry(Symbol::Try, "🛡", "try", Category::Iteration, Arity::Block,
+        "Try/Rescue exception handling", "🛡(🌐📥\"https://example.com\")"),
+    entry(Symbol::Retry, "♻", "retry", Category::Iteration, Arity::Block,
+        "Retry on failure", "♻3: 🌐📥\"https://example.com\""),
+
+    entry(Symbol::Async, "⚡", "async", Category::Concurrency, Arity::Block,
+        "Async execution/Trigger", "⚡📤\"working\" ▷ task"),
+    entry(Symbol::Await, "⏳", "await", Category::Concurrency, Arity::Prefix,
+        "Await async result", "⏳task ▷ result"),
+    entry(Symbol::Thread, "🧵", "thread", Category::Concurrency, Arity::Block,
+        "Thread/Task concurrent execution", "🧵📤\"working\""),
+    entry(Symbol::Lock, "🔒", "lock", Category::Concurrency, Arity::Block,
+        "Mutex/Lock critical section", "🔒(count + 1 ▷ count)"),
+    entry(Symbol::Emit, "📡", "emit", Category::Concurrency, Arity::Prefix,
+        "Emit/Signal event", "📡\"saved\""),
+    entry(Symbol::Watch, "👁", "watch", Category::Concurrency, Arity::Block,
+        "Watch/Listen to events", "👁\"saved\" 📤\"saw it\""),
+
+    entry(Symbol::Split, "✂", "split", Category::Manipulation, Arity::Prefix,
+        "Split/Slice string or array", "\"a,b\" ⇢ ✂\",\" ▷ parts"),
+    entry(Symbol::Join, "🔗", "join", Category::Manipulation, Arity::Prefix,
+        "Join/Concat elements", "parts ⇢ 🔗\", \" ▷ text"),
+    entry(Symbol::Regex, "✱", "regex", Category::Manipulation, Arity::Prefix,
+        "Regex/Pattern match", "email ⇢ ✱\"^[^@]+@\" ▷ valid"),
+    entry(Symbol::Equal, "≡", "==", Category::Manipulation, Arity::Infix,
+        "Strict equality comparison", "a ≡ b ▷ same"),
+    entry(Symbol::NotEqual, "≠", "!=", Category::Manipulation, Arity::Infix,
+        "Not equal comparison", "a ≠ b ▷ different"),
+    entry(Symbol::Immutable, "🧊", "const", Category::Manipulation, Arity::Prefix,
+        "Immutable/Const definition", "🧊 limit 100"),
+
+    entry(Symbol::Import, "🧩", "import", Category::System, Arity::Prefix,
+        "Import module/dependency", "🧩\"math\""),
+    entry(Symbol::Auth, "🔑", "auth", Category::System, Arity::Prefix,
+        "Authentication/Token", "🔑token"),
+    entry(Symbol::DateTime, "📅", "datetime", Category::System, Arity::Nullary,
+        "Date/Time operations", "📅 ▷ now"),
+    entry(Symbol::Random, "🎲", "random", Category::System, Arity::Nullary,
+        "Random number generation", "🎲 ▷ roll"),
+    entry(Symbol::Log, "🪵", "log", Category::System, Arity::Prefix,
+        "Log message", "🪵\"started\""),
+
+    entry(Symbol::Test, "🧪", "test", Category::Testing, Arity::Block,
+        "Test case/suite definition", "🧪\"adds\": ⚖️(1 + 1 ≡ 2)"),
+    entry(Symbol::Assert, "⚖️", "assert", Category::Testing, Arity::Prefix,
+        "Assert condition (fail if false)", "⚖️(total > 0)"),
+    entry(Symbol::Mock, "🎭", "mock", Category::Testing, Arity::Prefix,
+        "Mock external dependencies", "🎭\"database\""),
+    entry(Symbol::Benchmark, "⏱️", "bench", Category::Testing, Arity::Block,
+        "Measure execution time", "⏱️(📤\"work\") ▷ elapsed"),
+    entry(Symbol::Debug, "🐛", "debug", Category::Testing, Arity::Nullary,
+        "Debug mode/breakpoint", "🐛"),
+
+    entry(Symbol::Encrypt, "🔐", "encrypt", Category::Crypto, Arity::Prefix,
+        "Encrypt data", "🔐secret key ▷ sealed"),
+    entry(Symbol::Decrypt, "🔓", "decrypt", Category::Crypto, Arity::Prefix,
+        "Decrypt data", "🔓sealed key ▷ secret"),
+    entry(Symbol::Hash, "#️⃣", "hash", Category::Crypto, Arity::Prefix,
+        "Calculate hash value", "#️⃣password ▷ digest"),
+    entry(Symbol::Sign, "✍️", "sign", Category::Crypto, Arity::Prefix,
+        "Digital signature", "✍️document privateKey ▷ signature"),
+    entry(Symbol::Verify, "🔏", "verify", Category::Crypto, Arity::Prefix,
+        "Verify signature", "🔏signature document publicKey ▷ valid"),
+
+    entry(Symbol::Power, "↑", "^", Category::Math, Arity::Infix,
+        "Power operation (exponentiation)", "2 ⇢ ↑10 ▷ kb"),
+    entry(Symbol::Root, "√", "sqrt", Category::Math, Arity::Prefix,
+        "Square root", "16 ⇢ √ ▷ side"),
+    entry(Symbol::Approx, "≈", "~=", Category::Math, Arity::Infix,
+        "Approximate equality", "3.14159 ⇢ ≈3.1416 ▷ close"),
+    entry(Symbol::Infinity, "∞", "inf", Category::Math, Arity::Nullary,
+        "Infinity value", "∞ ▷ limit"),
+    entry(Symbol::Delta, "∆", "delta", Category::Math, Arity::Prefix,
+        "Change/difference value", "∆count 1"),
+
+    entry(Symbol::File, "📄", "file", Category::FileSystem, Arity::Prefix,
+        "File handle/object", "📄📍\"data.txt\" ▷ f"),
+    entry(Symbol::Dir, "📂", "dir", Category::FileSystem, Arity::Prefix,
+        "Directory/folder", "📂📍\"logs\" ▷ logs"),
+    entry(Symbol::Path, "📍", "path", Category::FileSystem, Arity::Prefix,
+        "Path resolution", "📍\"data.txt\" ▷ p"),
+    entry(Symbol::Read, "📖", "read", Category::FileSystem, Arity::Nullary,
+        "Read content from file or stream", "f ⇢ 📖 ▷ content"),
+    entry(Symbol::Write, "🖊️", "write", Category::FileSystem, Arity::Prefix,
+        "Write content (overwrite mode)", "\"hello\" ⇢ 🖊️f"),
+    entry(Symbol::Append, "🖇️", "append", Category::FileSystem, Arity::Prefix,
+        "Append content (append mode)", "\"more\" ⇢ 🖇️f"),
+    entry(Symbol::Delete, "🗑️", "delete", Category::FileSystem, Arity::Prefix,
+        "Delete file or resource", "🗑️f"),
+    entry(Symbol::Perm, "🛂", "perm", Category::FileSystem, Arity::Prefix,
+        "Permission control (chmod/chown)", "🛂(f, 644)"),
+
+    entry(Symbol::Stream, "🌊", "stream", Category::Streams, Arity::Prefix,
+        "Data stream (Readable/Writable)", "f ⇢ 🌊\"lines\" ▷ lines"),
+    entry(Symbol::Buffer, "🧱", "buffer", Category::Streams, Arity::Prefix,
+        "Binary buffer (Bytes/Blob)", "🧱1KB ▷ buf"),
+    entry(Symbol::Flush, "🌬️", "flush", Category::Streams, Arity::Nullary,
+        "Flush buffer", "s ⇢ 🌬️"),
+    entry(Symbol::Eof, "🔚", "eof", Category::Streams, Arity::Nullary,
+        "End of file/stream marker", "↻(s ≠ 🔚): (s ⇢ 📖 ▷ line)"),
+    entry(Symbol::Skip, "⏭️", "seek", Category::Streams, Arity::Prefix,
+        "Skip bytes/move pointer", "s ⇢ ⏭️🔚"),
+
+    entry(Symbol::Socket, "🔌", "socket", Category::Networking, Arity::Prefix,
+        "Network socket (TCP/UDP)", "🔌TCP ▷ sock"),
+    entry(Symbol::Listen, "👂", "listen", Category::Networking, Arity::Prefix,
+        "Listen on port (Server Bind)", "👂8080 ▷ server"),
+    entry(Symbol::Connect, "📞", "connect", Category::Networking, Arity::Prefix,
+        "Initiate connection (Client Connect)", "📞\"localhost:8080\" ▷ conn"),
+    entry(Symbol::Port, "🚪", "port", Category::Networking, Arity::Prefix,
+        "Port number", "🚪8080 ▷ port"),
+    entry(Symbol::Packet, "📦", "packet", Category::Networking, Arity::Prefix,
+        "Data packet (Datagram)", "📦\"ping\" ▷ packet"),
+    entry(Symbol::Handshake, "🤝", "handshake", Category::Networking, Arity::Prefix,
+        "Protocol handshake/establish connection", "🤝conn"),
+
+    entry(Symbol::Process, "⚙️", "process", Category::Process, Arity::Prefix,
+        "Process object", "⚙️\"ls\" ▷ proc"),
+    entry(Symbol::Shell, "🐚", "shell", Category::Process, Arity::Prefix,
+        "Execute shell command", "🐚\"ls -la\" ▷ listing"),
+    entry(Symbol::Env, "🌍", "env", Category::Process, Arity::Prefix,
+        "Environment variable (Get/Set)", "🌍\"HOME\" ▷ home"),
+    entry(Symbol::Memory, "🐏", "memory", Category::Process, Arity::Prefix,
+        "Memory operations/manual allocation", "🐏64 ▷ block"),
+    entry(Symbol::Exit, "👋", "exit", Category::Process, Arity::Prefix,
+        "Exit program (with exit code)", "👋0"),
+    entry(Symbol::Signal, "📶", "signal", Category::Process, Arity::Prefix,
+        "Send/capture system signal", "📶\"SIGTERM\" pid"),
+
+    entry(Symbol::Length, "📏", "len", Category::Operators, Arity::Prefix,
+        "Get length of string or array", "📏items ▷ n"),
+    entry(Symbol::IndexStart, "⟦", "[|", Category::Operators, Arity::Bracket,
+        "Start index access", "items⟦0⟧ ▷ first"),
+    entry(Symbol::IndexEnd, "⟧", "|]", Category::Operators, Arity::Bracket,
+        "End index access", "items⟦0⟧ ▷ first"),
+    entry(Symbol::Push, "⊞", "push", Category::Operators, Arity::Infix,
+        "Push element to array", "items ⊞ 4 ▷ items"),
+    entry(Symbol::Add, "+", "+", Category::Operators, Arity::Infix,
+        "Arithmetic addition", "5 + 3 ▷ sum"),
+    entry(Symbol::Subtract, "-", "-", Category::Operators, Arity::Infix,
+        "Arithmetic subtraction", "10 - 4 ▷ diff"),
+    entry(Symbol::Concat, "⧺", "++", Category::Operators, Arity::Infix,
+        "String concatenation", "\"Hello, \" ⧺ name ▷ greeting"),
+    entry(Symbol::CharStart, "⟨", "(|", Category::Operators, Arity::Bracket,
+        "Start character access", "word⟨0⟩ ▷ initial"),
+    entry(Symbol::CharEnd, "⟩", "|)", Category::Operators, Arity::Bracket,
+        "End character access", "word⟨0⟩ ▷ initial"),
+    entry(Symbol::SliceStart, "⊢", "[:", Category::Operators, Arity::Bracket,
+        "Start array slice", "items⊢1:3⊣ ▷ middle"),
+    entry(Symbol::SliceEnd, "⊣", ":]", Category::Operators, Arity::Bracket,
+        "End array slice", "items⊢1:3⊣ ▷ middle"),
+    entry(Symbol::Assign, "≔", ":=", Category::Operators, Arity::Infix,
+        "Variable assignment", "total ≔ 0"),
+    entry(Symbol::Multiply, "*", "*", Category::Operators, Arity::Infix,
+        "Arithmetic multiplication", "price * 3 ▷ subtotal"),
+    entry(Symbol::Divide, "/", "/", Category::Operators, Arity::Infix,
+        "Arithmetic division", "total / 4 ▷ share"),
+    entry(Symbol::Modulo, "%", "%", Category::Operators, Arity::Infix,
+        "Modulo operation", "n % 2 ▷ odd"),
+    entry(Symbol::GreaterEqual, "≥", ">=", Category::Operators, Arity::Infix,
+        "Greater than or equal comparison", "◇(age ≥ 18): 📤\"adult\""),
+    entry(Symbol::LessEqual, "≤", "<=", Category::Operators, Arity::Infix,
+        "Less than or equal comparison", "◇(age ≤ 12): 📤\"child\""),
+
+];
+
+/// A row of the symbol table
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    pub symbol: Symbol,
+    /// Canonical glyph, which `Symbol::to_str` gives
+    pub glyph: &'static str,
+    /// ASCII spelling, accepted by the lexer in ASCII alias mode
+    pub ascii: &'static str,
+    pub category: Category,
+    pub arity: Arity,
+    pub description: &'static str,
+    pub example: &'static str,
+}
+
+const fn entry(
+    symbol: Symbol,
+    glyph: &'static str,
+    ascii: &'static str,
+    category: Category,
+    arity: Arity,
+    description: &'static str,
+    example: &'static str,
+) -> SymbolInfo {
+    SymbolInfo { symbol, glyph, ascii, category, arity, description, example }
+}
+
+/// Group a symbol is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Functions,
+    Data,
+    Logic,
+    Collections,
+    Http,
+    Iteration,
+    Concurrency,
+    Manipulation,
+    System,
+    Testing,
+    Crypto,
+    Math,
+    FileSystem,
+    Streams,
+    Networking,
+    Process,
+    Operators,
+}
+
+impl Category {
+    /// Heading the category is listed under
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::Functions => "Function & Control Flow",
+            Category::Data => "Data Operations",
+            Category::Logic => "Logic & Conditionals",
+            Category::Collections => "Collections",
+            Category::Http => "Network Operations",
+            Category::Iteration => "Control Flow & Iteration",
+            Category::Concurrency => "Concurrency & Async",
+            Category::Manipulation => "Data Manipulation",
+            Category::System => "System & Environment",
+            Category::Testing => "Testing & Debugging",
+            Category::Crypto => "Security & Crypto",
+            Category::Math => "Math & Science",
+            Category::FileSystem => "File System",
+            Category::Streams => "Streams & Buffers",
+            Category::Networking => "Networking",
+            Category::Process => "Process & OS",
+            Category::Operators => "Operators & Access",
+        }
+    }
+
+    /// Name in snake case, such as `file_system`
+    pub fn name(self) -> String {
+        snake_case(&format!("{:?}", self))
+    }
+}
+
+/// Where a symbol's operands go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// No operands: `∅`, `📥`, `🎲`
+    Nullary,
+    /// Operands after the symbol: `📤x`, `¬flag`, `🔐data key`
+    Prefix,
+    /// One operand either side: `a ≡ b`, `x ▷ name`
+    Infix,
+    /// A bracket of a pair around an operand: `xs⟦0⟧`
+    Bracket,
+    /// A head, then a body: `◇(x > 5): …`, `ƒname: …`
+    Block,
+}
+
+impl Arity {
+    /// Name in snake case, such as `prefix`
+    pub fn name(self) -> String {
+        snake_case(&format!("{:?}", self))
+    }
+}
+
 /// Core symbols in the Aether language
 #[derive(Debug, Clone, PartialEq)]
 pub enum Symbol {
@@ -83,7 +440,7 @@ pub enum Symbol {
     HttpDelete,
     /// 🌐🔧 - HTTP PATCH request
     HttpPatch,
-    /// 🌐👁️ - HTTP HEAD request
+    /// 🌐👁 - HTTP HEAD request
     HttpHead,
     /// 🌐⚙️ - HTTP OPTIONS request
     HttpOptions,
@@ -165,7 +522,7 @@ pub enum Symbol {
     Hash,
     /// ✍️ - Sign
     Sign,
-    /// 🛡️ - Verify (different from 🛡 Try)
+    /// 🔏 - Verify
     Verify,
     
     // Math & Science (v1.2)
@@ -245,8 +602,14 @@ pub enum Symbol {
     // Literals
     /// String literal
     StringLiteral(String),
-    /// Number literal
+    /// Floating-point number literal
     NumberLiteral(f64),
+    /// Integer literal (`42`)
+    IntegerLiteral(i64),
+    /// Decimal literal (`19.99`, or a whole number too large for an integer)
+    DecimalLiteral(Decimal),
+    /// Bytes literal (hex"…" or b64"…")
+    BytesLiteral(Vec<u8>),
     
     // Special
     /// End of statement
@@ -294,411 +657,152 @@ pub enum Symbol {
 }
 
 impl Symbol {
+    /// Every operation symbol that has a glyph of its own
+    pub fn all() -> Vec<Symbol> {
+        SYMBOLS.iter().map(|info| info.symbol.clone()).collect()
+    }
+
+    /// The symbol's row of the table, for operation symbols
+    pub fn info(&self) -> Option<&'static SymbolInfo> {
+        SYMBOLS.iter().find(|info| info.symbol == *self)
+    }
+
+    /// English name of the symbol in snake case, such as `retry` for `♻`
+    pub fn name(&self) -> String {
+        let variant = format!("{:?}", self);
+        snake_case(variant.split('(').next().unwrap_or_default())
+    }
+
+    /// Canonical ASCII spelling of a symbol that has a glyph, accepted by
+    /// the lexer in ASCII alias mode
+    pub fn ascii(&self) -> Option<&'static str> {
+        self.info().map(|info| info.ascii)
+    }
+
+    /// The symbol with this ASCII spelling
+    pub fn from_ascii(s: &str) -> Option<Self> {
+        SYMBOLS.iter().find(|info| info.ascii == s).map(|info| info.symbol.clone())
+    }
+
+    /// The symbol a glyph spells, with or without variation selectors
+    ///
+    /// Editors and models add and strip U+FE0E and U+FE0F freely, so `🏷`
+    /// and `🏷️` are the same symbol here, while `from_str` accepts only the
+    /// canonical form `to_str` gives.
+    pub fn from_glyph(s: &str) -> Option<Self> {
+        Symbol::from_str(s).or_else(|| {
+            let bare = without_variation_selectors(s);
+            SYMBOLS
+                .iter()
+                .find(|info| without_variation_selectors(info.glyph) == bare)
+                .map(|info| info.symbol.clone())
+        })
+    }
+
     /// Convert a character/string to a Symbol
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "ƒ" => Some(Symbol::Function),
-            "λ" => Some(Symbol::Lambda),
-            "⇒" => Some(Symbol::MapArrow),
-            "⇢" => Some(Symbol::Pipe),
-            "▷" => Some(Symbol::PipeInto),
-            "📥" => Some(Symbol::Input),
-            "📤" => Some(Symbol::Output),
-            "💾" => Some(Symbol::Persist),
-            "🔍" => Some(Symbol::Query),
-            "J" => Some(Symbol::JsonParse),
-            "S" => Some(Symbol::StringType),
-            "N" => Some(Symbol::NumberType),
-            "⁇" => Some(Symbol::Guard),
-            "🛑" => Some(Symbol::Halt),
-            "✓" => Some(Symbol::Success),
-            "⨠" => Some(Symbol::Sequence),
-            "◇" => Some(Symbol::If),
-            "◈" => Some(Symbol::ElseIf),
-            "◆" => Some(Symbol::Else),
-            "⊕" => Some(Symbol::Or),
-            "⊗" => Some(Symbol::And),
-            "¬" => Some(Symbol::Not),
-            "🗂" => Some(Symbol::Array),
-            "🗄" => Some(Symbol::Map),
-            "∅" => Some(Symbol::Empty),
-            "🌐" => Some(Symbol::HttpRequest),
-            "🏷️" => Some(Symbol::HttpHeaders),
-            "®" => Some(Symbol::Register),
-            // Control Flow & Iteration
-            "↻" => Some(Symbol::Loop),
-            "∀" => Some(Symbol::ForEach),
-            "∃" => Some(Symbol::Filter),
-            "∑" => Some(Symbol::Reduce),
-            "🛡" => Some(Symbol::Try),
-            "♻" => Some(Symbol::Retry),
-            // Concurrency & Async
-            "⚡" => Some(Symbol::Async),
-            "⏳" => Some(Symbol::Await),
-            "🧵" => Some(Symbol::Thread),
-            "🔒" => Some(Symbol::Lock),
-            "📡" => Some(Symbol::Emit),
-            "👁" => Some(Symbol::Watch),
-            // Data Manipulation
-            "✂" => Some(Symbol::Split),
-            "🔗" => Some(Symbol::Join),
-            "✱" => Some(Symbol::Regex),
-            "≡" => Some(Symbol::Equal),
-            "≠" => Some(Symbol::NotEqual),
-            "🧊" => Some(Symbol::Immutable),
-            // System & Environment
-            "🧩" => Some(Symbol::Import),
-            "🔑" => Some(Symbol::Auth),
-            "📅" => Some(Symbol::DateTime),
-            "🎲" => Some(Symbol::Random),
-            "🪵" => Some(Symbol::Log),
-            // Testing & Debugging
-            "🧪" => Some(Symbol::Test),
-            "⚖️" => Some(Symbol::Assert),
-            "🎭" => Some(Symbol::Mock),
-            "⏱️" => Some(Symbol::Benchmark),
-            "🐛" => Some(Symbol::Debug),
-            // Security & Crypto
-            "🔐" => Some(Symbol::Encrypt),
-            "🔓" => Some(Symbol::Decrypt),
-            "#️⃣" => Some(Symbol::Hash),
-            "✍️" => Some(Symbol::Sign),
-            "🛡️" => Some(Symbol::Verify),
-            // Math & Science
-            "↑" => Some(Symbol::Power),
-            "√" => Some(Symbol::Root),
-            "≈" => Some(Symbol::Approx),
-            "∞" => Some(Symbol::Infinity),
-            "∆" => Some(Symbol::Delta),
-            // File System
-            "📄" => Some(Symbol::File),
-            "📂" => Some(Symbol::Dir),
-            "📍" => Some(Symbol::Path),
-            "📖" => Some(Symbol::Read),
-            "🖊️" => Some(Symbol::Write),
-            "🖇️" => Some(Symbol::Append),
-            "🗑️" => Some(Symbol::Delete),
-            "🛂" => Some(Symbol::Perm),
-            // Streams & Buffers
-            "🌊" => Some(Symbol::Stream),
-            "🧱" => Some(Symbol::Buffer),
-            "🌬️" => Some(Symbol::Flush),
-            "🔚" => Some(Symbol::Eof),
-            "⏭️" => Some(Symbol::Skip),
-            // Networking
-            "🔌" => Some(Symbol::Socket),
-            "👂" => Some(Symbol::Listen),
-            "📞" => Some(Symbol::Connect),
-            "🚪" => Some(Symbol::Port),
-            "📦" => Some(Symbol::Packet),
-            "🤝" => Some(Symbol::Handshake),
-            // Process & OS
-            "⚙️" => Some(Symbol::Process),
-            "🐚" => Some(Symbol::Shell),
-            "🌍" => Some(Symbol::Env),
-            "🐏" => Some(Symbol::Memory),
-            "👋" => Some(Symbol::Exit),
-            "📶" => Some(Symbol::Signal),
-            // Bootstrap operations (v1.4)
-            "📏" => Some(Symbol::Length),
-            "⟦" => Some(Symbol::IndexStart),
-            "⟧" => Some(Symbol::IndexEnd),
-            "⊞" => Some(Symbol::Push),
-            "+" => Some(Symbol::Add),
-            "-" => Some(Symbol::Subtract),
-            "⧺" => Some(Symbol::Concat),
-            "⟨" => Some(Symbol::CharStart),
-            "⟩" => Some(Symbol::CharEnd),
-            "⊢" => Some(Symbol::SliceStart),
-            "⊣" => Some(Symbol::SliceEnd),
-            "≔" => Some(Symbol::Assign),
-            // Extended bootstrap operations (v1.5)
-            "*" => Some(Symbol::Multiply),
-            "/" => Some(Symbol::Divide),
-            "%" => Some(Symbol::Modulo),
-            "≥" => Some(Symbol::GreaterEqual),
-            "≤" => Some(Symbol::LessEqual),
-            _ => None,
-        }
+        SYMBOLS.iter().find(|info| info.glyph == s).map(|info| info.symbol.clone())
     }
 
     /// Convert Symbol back to its UTF-8 representation
     pub fn to_str(&self) -> String {
         match self {
-            Symbol::Function => "ƒ".to_string(),
-            Symbol::Lambda => "λ".to_string(),
-            Symbol::MapArrow => "⇒".to_string(),
-            Symbol::Pipe => "⇢".to_string(),
-            Symbol::PipeInto => "▷".to_string(),
-            Symbol::Input => "📥".to_string(),
-            Symbol::Output => "📤".to_string(),
-            Symbol::Persist => "💾".to_string(),
-            Symbol::Query => "🔍".to_string(),
-            Symbol::JsonParse => "J".to_string(),
-            Symbol::StringType => "S".to_string(),
-            Symbol::NumberType => "N".to_string(),
-            Symbol::Guard => "⁇".to_string(),
-            Symbol::Halt => "🛑".to_string(),
-            Symbol::Success => "✓".to_string(),
-            Symbol::Sequence => "⨠".to_string(),
-            Symbol::If => "◇".to_string(),
-            Symbol::ElseIf => "◈".to_string(),
-            Symbol::Else => "◆".to_string(),
-            Symbol::Or => "⊕".to_string(),
-            Symbol::And => "⊗".to_string(),
-            Symbol::Not => "¬".to_string(),
-            Symbol::Array => "🗂".to_string(),
-            Symbol::Map => "🗄".to_string(),
-            Symbol::Empty => "∅".to_string(),
-            Symbol::HttpRequest => "🌐".to_string(),
-            Symbol::HttpHeaders => "🏷️".to_string(),
-            Symbol::HttpGet => "🌐📥".to_string(),
-            Symbol::HttpPost => "🌐📤".to_string(),
-            Symbol::HttpPut => "🌐🔄".to_string(),
-            Symbol::HttpDelete => "🌐🗑️".to_string(),
-            Symbol::HttpPatch => "🌐🔧".to_string(),
-            Symbol::HttpHead => "🌐👁️".to_string(),
-            Symbol::HttpOptions => "🌐⚙️".to_string(),
-            Symbol::Register => "®".to_string(),
-            // Control Flow & Iteration
-            Symbol::Loop => "↻".to_string(),
-            Symbol::ForEach => "∀".to_string(),
-            Symbol::Filter => "∃".to_string(),
-            Symbol::Reduce => "∑".to_string(),
-            Symbol::Try => "🛡".to_string(),
-            Symbol::Retry => "♻".to_string(),
-            // Concurrency & Async
-            Symbol::Async => "⚡".to_string(),
-            Symbol::Await => "⏳".to_string(),
-            Symbol::Thread => "🧵".to_string(),
-            Symbol::Lock => "🔒".to_string(),
-            Symbol::Emit => "📡".to_string(),
-            Symbol::Watch => "👁".to_string(),
-            // Data Manipulation
-            Symbol::Split => "✂".to_string(),
-            Symbol::Join => "🔗".to_string(),
-            Symbol::Regex => "✱".to_string(),
-            Symbol::Equal => "≡".to_string(),
-            Symbol::NotEqual => "≠".to_string(),
-            Symbol::Immutable => "🧊".to_string(),
-            // System & Environment
-            Symbol::Import => "🧩".to_string(),
-            Symbol::Auth => "🔑".to_string(),
-            Symbol::DateTime => "📅".to_string(),
-            Symbol::Random => "🎲".to_string(),
-            Symbol::Log => "🪵".to_string(),
-            // Testing & Debugging
-            Symbol::Test => "🧪".to_string(),
-            Symbol::Assert => "⚖️".to_string(),
-            Symbol::Mock => "🎭".to_string(),
-            Symbol::Benchmark => "⏱️".to_string(),
-            Symbol::Debug => "🐛".to_string(),
-            // Security & Crypto
-            Symbol::Encrypt => "🔐".to_string(),
-            Symbol::Decrypt => "🔓".to_string(),
-            Symbol::Hash => "#️⃣".to_string(),
-            Symbol::Sign => "✍️".to_string(),
-            Symbol::Verify => "🛡️".to_string(),
-            // Math & Science
-            Symbol::Power => "↑".to_string(),
-            Symbol::Root => "√".to_string(),
-            Symbol::Approx => "≈".to_string(),
-            Symbol::Infinity => "∞".to_string(),
-            Symbol::Delta => "∆".to_string(),
-            // File System
-            Symbol::File => "📄".to_string(),
-            Symbol::Dir => "📂".to_string(),
-            Symbol::Path => "📍".to_string(),
-            Symbol::Read => "📖".to_string(),
-            Symbol::Write => "🖊️".to_string(),
-            Symbol::Append => "🖇️".to_string(),
-            Symbol::Delete => "🗑️".to_string(),
-            Symbol::Perm => "🛂".to_string(),
-            // Streams & Buffers
-            Symbol::Stream => "🌊".to_string(),
-            Symbol::Buffer => "🧱".to_string(),
-            Symbol::Flush => "🌬️".to_string(),
-            Symbol::Eof => "🔚".to_string(),
-            Symbol::Skip => "⏭️".to_string(),
-            // Networking
-            Symbol::Socket => "🔌".to_string(),
-            Symbol::Listen => "👂".to_string(),
-            Symbol::Connect => "📞".to_string(),
-            Symbol::Port => "🚪".to_string(),
-            Symbol::Packet => "📦".to_string(),
-            Symbol::Handshake => "🤝".to_string(),
-            // Process & OS
-            Symbol::Process => "⚙️".to_string(),
-            Symbol::Shell => "🐚".to_string(),
-            Symbol::Env => "🌍".to_string(),
-            Symbol::Memory => "🐏".to_string(),
-            Symbol::Exit => "👋".to_string(),
-            Symbol::Signal => "📶".to_string(),
-            // Bootstrap operations (v1.4)
-            Symbol::Length => "📏".to_string(),
-            Symbol::IndexStart => "⟦".to_string(),
-            Symbol::IndexEnd => "⟧".to_string(),
-            Symbol::Push => "⊞".to_string(),
-            Symbol::Add => "+".to_string(),
-            Symbol::Subtract => "-".to_string(),
-            Symbol::Concat => "⧺".to_string(),
-            Symbol::CharStart => "⟨".to_string(),
-            Symbol::CharEnd => "⟩".to_string(),
-            Symbol::SliceStart => "⊢".to_string(),
-            Symbol::SliceEnd => "⊣".to_string(),
-            Symbol::Assign => "≔".to_string(),
-            // Extended bootstrap operations (v1.5)
-            Symbol::Multiply => "*".to_string(),
-            Symbol::Divide => "/".to_string(),
-            Symbol::Modulo => "%".to_string(),
-            Symbol::GreaterEqual => "≥".to_string(),
-            Symbol::LessEqual => "≤".to_string(),
             Symbol::Identifier(id) => id.clone(),
             Symbol::StringLiteral(s) => format!("\"{}\"", s),
             Symbol::NumberLiteral(n) => n.to_string(),
+            Symbol::IntegerLiteral(n) => n.to_string(),
+            Symbol::DecimalLiteral(d) => d.to_string(),
+            Symbol::BytesLiteral(b) => format!(
+                "hex\"{}\"",
+                b.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+            ),
             Symbol::Eos => ";".to_string(),
             Symbol::Separator => ",".to_string(),
+            symbol => symbol.info().expect("operation symbols are in the table").glyph.to_string(),
         }
     }
 
     /// Get a human-readable description of the symbol
     pub fn description(&self) -> &str {
         match self {
-            Symbol::Function => "Function definition",
-            Symbol::Lambda => "Lambda/anonymous function",
-            Symbol::MapArrow => "Map/transform operation",
-            Symbol::Pipe => "Pipe/flow data",
-            Symbol::PipeInto => "Pipe into variable",
-            Symbol::Input => "Input/Request context",
-            Symbol::Output => "Output/Response",
-            Symbol::Persist => "Persist to database",
-            Symbol::Query => "Query/Search operation",
-            Symbol::JsonParse => "Parse JSON",
-            Symbol::StringType => "String type",
-            Symbol::NumberType => "Number type",
-            Symbol::Guard => "Guard/null check",
-            Symbol::Halt => "Halt/terminate with error",
-            Symbol::Success => "Success/validate",
-            Symbol::Sequence => "Sequence operations",
-            Symbol::If => "Conditional (if)",
-            Symbol::ElseIf => "Conditional alternative (else if)",
-            Symbol::Else => "Default branch (else)",
-            Symbol::Or => "Logical OR",
-            Symbol::And => "Logical AND",
-            Symbol::Not => "Logical NOT",
-            Symbol::Array => "Array/List",
-            Symbol::Map => "Map/Dictionary",
-            Symbol::Empty => "Empty/null value",
-            Symbol::HttpRequest => "HTTP request (generic)",
-            Symbol::HttpHeaders => "HTTP headers (key-value pairs)",
-            Symbol::HttpGet => "HTTP GET request",
-            Symbol::HttpPost => "HTTP POST request",
-            Symbol::HttpPut => "HTTP PUT request",
-            Symbol::HttpDelete => "HTTP DELETE request",
-            Symbol::HttpPatch => "HTTP PATCH request",
-            Symbol::HttpHead => "HTTP HEAD request",
-            Symbol::HttpOptions => "HTTP OPTIONS request",
-            Symbol::Register => "Register/create",
-            // Control Flow & Iteration
-            Symbol::Loop => "Loop/While (unbounded loop)",
-            Symbol::ForEach => "ForEach/Map over collection",
-            Symbol::Filter => "Filter/Find in collection",
-            Symbol::Reduce => "Reduce/Sum aggregation",
-            Symbol::Try => "Try/Rescue exception handling",
-            Symbol::Retry => "Retry on failure",
-            // Concurrency & Async
-            Symbol::Async => "Async execution/Trigger",
-            Symbol::Await => "Await async result",
-            Symbol::Thread => "Thread/Task concurrent execution",
-            Symbol::Lock => "Mutex/Lock critical section",
-            Symbol::Emit => "Emit/Signal event",
-            Symbol::Watch => "Watch/Listen to events",
-            // Data Manipulation
-            Symbol::Split => "Split/Slice string or array",
-            Symbol::Join => "Join/Concat elements",
-            Symbol::Regex => "Regex/Pattern match",
-            Symbol::Equal => "Strict equality comparison",
-            Symbol::NotEqual => "Not equal comparison",
-            Symbol::Immutable => "Immutable/Const definition",
-            // System & Environment
-            Symbol::Import => "Import module/dependency",
-            Symbol::Auth => "Authentication/Token",
-            Symbol::DateTime => "Date/Time operations",
-            Symbol::Random => "Random number generation",
-            Symbol::Log => "Log message",
-            // Testing & Debugging
-            Symbol::Test => "Test case/suite definition",
-            Symbol::Assert => "Assert condition (fail if false)",
-            Symbol::Mock => "Mock external dependencies",
-            Symbol::Benchmark => "Measure execution time",
-            Symbol::Debug => "Debug mode/breakpoint",
-            // Security & Crypto
-            Symbol::Encrypt => "Encrypt data",
-            Symbol::Decrypt => "Decrypt data",
-            Symbol::Hash => "Calculate hash value",
-            Symbol::Sign => "Digital signature",
-            Symbol::Verify => "Verify signature",
-            // Math & Science
-            Symbol::Power => "Power operation (exponentiation)",
-            Symbol::Root => "Square root",
-            Symbol::Approx => "Approximate equality",
-            Symbol::Infinity => "Infinity value",
-            Symbol::Delta => "Change/difference value",
-            // File System
-            Symbol::File => "File handle/object",
-            Symbol::Dir => "Directory/folder",
-            Symbol::Path => "Path resolution",
-            Symbol::Read => "Read content from file or stream",
-            Symbol::Write => "Write content (overwrite mode)",
-            Symbol::Append => "Append content (append mode)",
-            Symbol::Delete => "Delete file or resource",
-            Symbol::Perm => "Permission control (chmod/chown)",
-            // Streams & Buffers
-            Symbol::Stream => "Data stream (Readable/Writable)",
-            Symbol::Buffer => "Binary buffer (Bytes/Blob)",
-            Symbol::Flush => "Flush buffer",
-            Symbol::Eof => "End of file/stream marker",
-            Symbol::Skip => "Skip bytes/move pointer",
-            // Networking
-            Symbol::Socket => "Network socket (TCP/UDP)",
-            Symbol::Listen => "Listen on port (Server Bind)",
-            Symbol::Connect => "Initiate connection (Client Connect)",
-            Symbol::Port => "Port number",
-            Symbol::Packet => "Data packet (Datagram)",
-            Symbol::Handshake => "Protocol handshake/establish connection",
-            // Process & OS
-            Symbol::Process => "Process object",
-            Symbol::Shell => "Execute shell command",
-            Symbol::Env => "Environment variable (Get/Set)",
-            Symbol::Memory => "Memory operations/manual allocation",
-            Symbol::Exit => "Exit program (with exit code)",
-            Symbol::Signal => "Send/capture system signal",
-            // Bootstrap operations (v1.4)
-            Symbol::Length => "Get length of string or array",
-            Symbol::IndexStart => "Start index access",
-            Symbol::IndexEnd => "End index access",
-            Symbol::Push => "Push element to array",
-            Symbol::Add => "Arithmetic addition",
-            Symbol::Subtract => "Arithmetic subtraction",
-            Symbol::Concat => "String concatenation",
-            Symbol::CharStart => "Start character access",
-            Symbol::CharEnd => "End character access",
-            Symbol::SliceStart => "Start array slice",
-            Symbol::SliceEnd => "End array slice",
-            Symbol::Assign => "Variable assignment",
-            // Extended bootstrap operations (v1.5)
-            Symbol::Multiply => "Arithmetic multiplication",
-            Symbol::Divide => "Arithmetic division",
-            Symbol::Modulo => "Modulo operation",
-            Symbol::GreaterEqual => "Greater than or equal comparison",
-            Symbol::LessEqual => "Less than or equal comparison",
             Symbol::Identifier(_) => "Identifier",
             Symbol::StringLiteral(_) => "String literal",
             Symbol::NumberLiteral(_) => "Number literal",
+            Symbol::IntegerLiteral(_) => "Integer literal",
+            Symbol::DecimalLiteral(_) => "Decimal literal",
+            Symbol::BytesLiteral(_) => "Bytes literal",
             Symbol::Eos => "End of statement",
             Symbol::Separator => "Separator",
+            symbol => symbol.info().expect("operation symbols are in the table").description,
+        }
+    }
+}
+
+/// `CamelCase` as `camel_case`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for ch in name.chars() {
+        if ch.is_uppercase() && !snake.is_empty() {
+            snake.push('_');
         }
+        snake.extend(ch.to_lowercase());
     }
+    snake
+}
+
+/// The symbol table as JSON, for `aether symbols --json`
+pub fn reference_json() -> Json {
+    let symbols: Vec<Json> = SYMBOLS
+        .iter()
+        .map(|info| {
+            json!({
+                "glyph": info.glyph,
+                "ascii": info.ascii,
+                "name": info.symbol.name(),
+                "category": info.category.name(),
+                "arity": info.arity.name(),
+                "description": info.description,
+                "example": info.example,
+            })
+        })
+        .collect();
+    Json::Array(symbols)
+}
+
+/// Markers around the table generated in `docs/SYMBOL_REFERENCE.md`
+pub const GENERATED_START: &str = "<!-- Generated by `aether symbols --markdown` from src/symbols.rs; do not edit -->";
+pub const GENERATED_END: &str = "<!-- End of generated table -->";
+
+/// The symbol table as Markdown, for `aether symbols --markdown` and the
+/// Symbol Table section of `docs/SYMBOL_REFERENCE.md`
+pub fn reference_markdown() -> String {
+    let code = |text: &str| format!("`{}`", text.replace('|', "\\|"));
+    let mut markdown = String::new();
+    let mut category = None;
+    for info in SYMBOLS {
+        if category != Some(info.category) {
+            category = Some(info.category);
+            markdown.push_str(&format!("\n### {}\n\n", info.category.title()));
+            markdown.push_str("| Glyph | ASCII | Name | Arity | Description | Example |\n");
+            markdown.push_str("|---|---|---|---|---|---|\n");
+        }
+        markdown.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            code(info.glyph),
+            code(info.ascii),
+            info.symbol.name(),
+            info.arity.name(),
+            info.description,
+            code(info.example)
+        ));
+    }
+    markdown.trim_start().to_string()
+}
+
+/// Text with the emoji and text presentation selectors removed
+pub fn without_variation_selectors(s: &str) -> String {
+    s.chars().filter(|c| !matches!(c, '\u{FE0E}' | '\u{FE0F}')).collect()
 }
 
 #[cfg(test)]
@@ -722,6 +826,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_all_symbols_have_glyphs_and_names() {
+        let all = Symbol::all();
+        for (i, symbol) in all.iter().enumerate() {
+            assert!(!all[..i].contains(symbol), "{:?} is listed twice", symbol);
+            assert_eq!(Symbol::from_str(&symbol.to_str()).as_ref(), Some(symbol));
+        }
+        assert_eq!(Symbol::Retry.name(), "retry");
+        assert_eq!(Symbol::GreaterEqual.name(), "greater_equal");
+        assert_eq!(Symbol::Identifier("x".to_string()).name(), "identifier");
+    }
+
+    #[test]
+    fn test_examples_use_their_symbols() {
+        for info in SYMBOLS {
+            let tokens = crate::lexer::Lexer::new(info.example.to_string())
+                .tokenize()
+                .unwrap_or_else(|err| panic!("example of {} does not lex: {}", info.glyph, err));
+            assert!(
+                tokens.iter().any(|token| token.token_type == crate::lexer::TokenType::Symbol(info.symbol.clone())),
+                "example of {} does not use it",
+                info.glyph
+            );
+        }
+    }
+
+    #[test]
+    fn test_reference_docs_match_the_table() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("docs/SYMBOL_REFERENCE.md");
+        let docs = std::fs::read_to_string(path).unwrap();
+        let start = docs.find(GENERATED_START).expect("the reference has a generated table") + GENERATED_START.len();
+        let end = docs.find(GENERATED_END).expect("the generated table ends");
+        assert_eq!(
+            docs[start..end].trim(),
+            reference_markdown().trim(),
+            "docs/SYMBOL_REFERENCE.md is out of date; regenerate it with `aether symbols --markdown`"
+        );
+
+        // Hand-written sections are headed by canonical glyphs
+        for heading in docs.lines().filter_map(|line| line.strip_prefix("### ")) {
+            if let Some((glyph, _)) = heading.split_once(" - ").filter(|(glyph, _)| !glyph.contains(' ')) {
+                assert!(Symbol::from_str(glyph).is_some(), "'{}' in '{}' is not a canonical glyph", glyph, heading);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_glyph_has_a_distinct_ascii_spelling() {
+        let mut spellings = std::collections::HashSet::new();
+        for symbol in Symbol::all() {
+            let ascii = symbol.ascii().unwrap_or_else(|| panic!("{:?} has no ASCII spelling", symbol));
+            assert!(ascii.is_ascii() && spellings.insert(ascii), "{} is not a distinct ASCII spelling", ascii);
+            assert_eq!(Symbol::from_ascii(ascii), Some(symbol));
+        }
+        assert_eq!(Symbol::from_ascii("retry"), Some(Symbol::Retry));
+        assert_eq!(Symbol::from_ascii("|>"), Some(Symbol::PipeInto));
+        assert_eq!(Symbol::from_ascii("retries"), None);
+        assert_eq!(Symbol::Identifier("x".to_string()).ascii(), None);
+    }
+
     #[test]
     fn test_symbol_descriptions() {
         assert_eq!(Symbol::Function.description(), "Function definition");
@@ -827,7 +991,7 @@ mod tests {
             (Symbol::Decrypt, "🔓", "Decrypt data"),
             (Symbol::Hash, "#️⃣", "Calculate hash value"),
             (Symbol::Sign, "✍️", "Digital signature"),
-            (Symbol::Verify, "🛡️", "Verify signature"),
+            (Symbol::Verify, "🔏", "Verify signature"),
         ];
         
         for (symbol, expected_str, expected_desc) in symbols {
@@ -855,17 +1019,26 @@ mod tests {
     }
     
     #[test]
-    fn test_verify_vs_try_distinction() {
-        // Ensure 🛡️ (Verify) is different from 🛡 (Try)
-        assert_ne!(Symbol::Verify.to_str(), Symbol::Try.to_str());
-        assert_eq!(Symbol::Verify.to_str(), "🛡️");
-        assert_eq!(Symbol::Try.to_str(), "🛡");
-        
-        // Test parsing
-        assert_eq!(Symbol::from_str("🛡️"), Some(Symbol::Verify));
+    fn test_glyphs_differ_without_variation_selectors() {
+        // Verify has a glyph of its own rather than 🛡 with U+FE0F
+        assert_eq!(Symbol::Verify.to_str(), "🔏");
         assert_eq!(Symbol::from_str("🛡"), Some(Symbol::Try));
+        assert_eq!(Symbol::from_glyph("🛡\u{FE0F}"), Some(Symbol::Try));
+
+        let mut bare: Vec<String> = SYMBOLS.iter().map(|info| without_variation_selectors(info.glyph)).collect();
+        bare.sort();
+        bare.dedup();
+        assert_eq!(bare.len(), SYMBOLS.len());
+
+        for glyph in SYMBOLS.iter().map(|info| info.glyph) {
+            let symbol = Symbol::from_str(glyph);
+            assert_eq!(Symbol::from_glyph(&without_variation_selectors(glyph)), symbol);
+            assert_eq!(Symbol::from_glyph(&format!("{}\u{FE0F}", without_variation_selectors(glyph))), symbol);
+        }
+        assert_eq!(Symbol::from_glyph("👁\u{FE0F}"), Some(Symbol::Watch));
+        assert_eq!(Symbol::from_glyph("\u{FE0F}"), None);
     }
-    
+
     #[test]
     fn test_file_system_symbols() {
         let symbols = vec![
@@ -949,7 +1122,7 @@ mod tests {
             (Symbol::HttpPut, "🌐🔄", "HTTP PUT request"),
             (Symbol::HttpDelete, "🌐🗑️", "HTTP DELETE request"),
             (Symbol::HttpPatch, "🌐🔧", "HTTP PATCH request"),
-            (Symbol::HttpHead, "🌐👁️", "HTTP HEAD request"),
+            (Symbol::HttpHead, "🌐👁", "HTTP HEAD request"),
             (Symbol::HttpOptions, "🌐⚙️", "HTTP OPTIONS request"),
         ];
         
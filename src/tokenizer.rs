@@ -0,0 +1,194 @@
+//! Estimated LLM token counts
+//!
+//! Models don't read graphemes; they read tokens, and a glyph that is one
+//! grapheme can be several tokens. `Tokenizer` is what `stats` counts with.
+//! `Bpe` is a byte-level byte-pair encoding like the ones models use, with a
+//! bundled vocabulary trained by `scripts/train_bpe.py` on Rust sources and
+//! Markdown, so it knows code and English but has never seen Aether.
+//! `ByteEstimate` is the usual rule of thumb of four bytes a token.
+
+use std::collections::HashMap;
+
+/// Merges for the bundled vocabulary, most frequent first
+const BUNDLED_MERGES: &str = include_str!("bpe.txt");
+
+/// Something that counts the tokens a text encodes to
+pub trait Tokenizer {
+    /// Name shown next to the counts
+    fn name(&self) -> &str;
+
+    /// Number of tokens in `text`
+    fn count(&self, text: &str) -> usize;
+}
+
+/// A byte-level byte-pair encoding
+pub struct Bpe {
+    /// New token for each pair that merges; lower tokens merge first
+    merges: HashMap<(u32, u32), u32>,
+}
+
+impl Bpe {
+    /// The vocabulary bundled with Aether
+    pub fn bundled() -> Self {
+        Self::from_merges(BUNDLED_MERGES)
+    }
+
+    /// A vocabulary from lines of `left right` merges, with bytes outside
+    /// printable ASCII written as `\xNN`
+    fn from_merges(text: &str) -> Self {
+        let mut ids: HashMap<Vec<u8>, u32> = (0..=255u8).map(|byte| (vec![byte], byte as u32)).collect();
+        let mut merges = HashMap::new();
+        for line in text.lines() {
+            let (left, right) = line.split_once(' ').expect("merge is two tokens");
+            let (left, right) = (unescape(left), unescape(right));
+            let id = ids.len() as u32;
+            merges.insert((ids[&left], ids[&right]), id);
+            ids.insert([left, right].concat(), id);
+        }
+        Bpe { merges }
+    }
+
+    fn count_piece(&self, piece: &str) -> usize {
+        let mut parts: Vec<u32> = piece.bytes().map(u32::from).collect();
+        while let Some((pair, id)) = parts
+            .windows(2)
+            .filter_map(|pair| self.merges.get(&(pair[0], pair[1])).map(|id| ((pair[0], pair[1]), *id)))
+            .min_by_key(|(_, id)| *id)
+        {
+            let mut merged = Vec::with_capacity(parts.len());
+            let mut i = 0;
+            while i < parts.len() {
+                if i + 1 < parts.len() && (parts[i], parts[i + 1]) == pair {
+                    merged.push(id);
+                    i += 2;
+                } else {
+                    merged.push(parts[i]);
+                    i += 1;
+                }
+            }
+            parts = merged;
+        }
+        parts.len()
+    }
+}
+
+impl Tokenizer for Bpe {
+    fn name(&self) -> &str {
+        "bpe"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        pieces(text).into_iter().map(|piece| self.count_piece(piece)).sum()
+    }
+}
+
+/// One token for every four bytes, rounded up
+pub struct ByteEstimate;
+
+impl Tokenizer for ByteEstimate {
+    fn name(&self) -> &str {
+        "bytes/4"
+    }
+
+    fn count(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+}
+
+/// Split text into the pieces merges stay within: a run of letters, up to
+/// three digits or a run of other characters, each taking a space before it,
+/// or a run of whitespace
+pub fn pieces(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(text.len(), |(offset, _)| *offset);
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut start = i;
+        if chars[i].1.is_whitespace() {
+            let mut j = i;
+            while j < chars.len() && chars[j].1.is_whitespace() {
+                j += 1;
+            }
+            // A space before anything else goes with it
+            if j < chars.len() && chars[j - 1].1 == ' ' {
+                if j - i > 1 {
+                    pieces.push(&text[offset(start)..offset(j - 1)]);
+                    start = j - 1;
+                }
+                i = j;
+            } else {
+                pieces.push(&text[offset(start)..offset(j)]);
+                i = j;
+                continue;
+            }
+        }
+        let c = chars[i].1;
+        let mut j = i + 1;
+        if c.is_alphabetic() {
+            while j < chars.len() && chars[j].1.is_alphabetic() {
+                j += 1;
+            }
+        } else if c.is_numeric() {
+            while j < chars.len() && j - i < 3 && chars[j].1.is_numeric() {
+                j += 1;
+            }
+        } else {
+            while j < chars.len() && !(chars[j].1.is_whitespace() || chars[j].1.is_alphanumeric()) {
+                j += 1;
+            }
+        }
+        pieces.push(&text[offset(start)..offset(j)]);
+        i = j;
+    }
+    pieces
+}
+
+fn unescape(token: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut rest = token.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'\\' && tail.len() >= 3 && tail[0] == b'x' {
+            let hex = std::str::from_utf8(&tail[1..3]).expect("escape is ASCII");
+            bytes.push(u8::from_str_radix(hex, 16).expect("escape is hex"));
+            rest = &tail[3..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pieces() {
+        assert_eq!(pieces("let x = 12345;\n    foo"), vec!["let", " x", " =", " 123", "45", ";", "\n   ", " foo"]);
+        assert_eq!(pieces("📤\"hi\"  ⇢ #️⃣\n"), vec!["📤\"", "hi", "\"", " ", " ⇢", " #️⃣", "\n"]);
+        assert_eq!(pieces("").len(), 0);
+    }
+
+    #[test]
+    fn test_bundled_bpe_counts() {
+        let bpe = Bpe::bundled();
+        assert_eq!(bpe.count(""), 0);
+        assert_eq!(bpe.count(" return"), 1);
+        // Common words are a token each; bytes it never saw are a token each
+        assert!(bpe.count("fn main() { println!(\"hello world\"); }") <= 12);
+        assert_eq!(bpe.count("\u{10FFFF}"), 4);
+        assert!(bpe.count("#️⃣") > bpe.count("hash"));
+        let text = "Output the result of the function";
+        assert_eq!(bpe.count(text), pieces(text).len());
+    }
+
+    #[test]
+    fn test_byte_estimate() {
+        assert_eq!(ByteEstimate.count(""), 0);
+        assert_eq!(ByteEstimate.count("abcde"), 2);
+        assert_eq!(ByteEstimate.count("📤"), 1);
+        assert_eq!(ByteEstimate.name(), "bytes/4");
+    }
+}
@@ -0,0 +1,1308 @@
+//! Transpiler from Aether to Python and JavaScript (`aether transpile`)
+//!
+//! Where the `Explainer` projects a program into pseudo-code, the transpiler
+//! projects it into a language reviewers already run. Pipes and `▷` bindings
+//! become variables, `◇/◈/◆` become if/elif/else, `🛡` becomes try/except
+//! and `∀/∃/∑` become loops, comprehensions and reductions. Glyphs with no
+//! one-line equivalent, such as `🌐` and `#️⃣`, call a small runtime shim
+//! (`shim.py`, `shim.js`) that is put in front of the program, so the output
+//! runs on its own.
+
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::normalize;
+use crate::parser::{AstNode, ComparisonOp, LiteralValue, Parser};
+use std::collections::BTreeSet;
+
+const PYTHON_SHIM: &str = include_str!("shim.py");
+const JAVASCRIPT_SHIM: &str = include_str!("shim.js");
+
+/// Names the generated Python can't bind: keywords, and builtins and
+/// modules the program or the shim use
+const PYTHON_RESERVED: &[&str] = &[
+    "AetherHalt", "Decimal", "Exception", "False", "Future", "None", "Stream", "ThreadPoolExecutor", "True",
+    "aether", "and", "as", "assert", "async", "await", "base64", "bool", "break", "bytes", "class",
+    "continue", "datetime", "def", "del", "dict", "elif", "else", "except", "filter", "finally", "float",
+    "for", "from", "functools", "global", "hashlib", "if", "import", "in", "int", "io", "is", "json",
+    "lambda", "len", "list", "math", "max", "min", "nonlocal", "not", "open", "or", "os", "pass", "print",
+    "raise", "random", "range", "re", "return", "str", "subprocess", "sum", "sys", "threading", "time",
+    "timezone", "try", "type", "urllib", "while", "with", "yield",
+];
+
+/// Names the generated JavaScript can't bind: reserved words, and globals
+/// the program or the shim use
+const JAVASCRIPT_RESERVED: &[&str] = &[
+    "AetherHalt", "Array", "Buffer", "Boolean", "Error", "Infinity", "JSON", "Math", "NaN", "Number",
+    "Object", "RegExp", "Stream", "String", "aether", "arguments", "await", "break", "case", "catch",
+    "childProcess", "class", "console", "const", "continue", "crypto", "debugger", "default", "delete",
+    "do", "else", "enum", "eval", "export", "extends", "false", "finally", "for", "fs", "function", "if",
+    "implements", "import", "in", "instanceof", "interface", "let", "new", "null", "package",
+    "performance", "private", "process", "protected", "public", "require", "return", "static", "super",
+    "switch", "this", "throw", "true", "try", "typeof", "undefined", "var", "void", "while", "with",
+    "yield",
+];
+
+/// Language to transpile to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Python,
+    JavaScript,
+}
+
+impl Target {
+    /// The target for a name such as `py` or `javascript`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "py" | "python" => Some(Target::Python),
+            "js" | "javascript" => Some(Target::JavaScript),
+            _ => None,
+        }
+    }
+
+    /// Extension of transpiled files
+    pub fn extension(self) -> &'static str {
+        match self {
+            Target::Python => "py",
+            Target::JavaScript => "js",
+        }
+    }
+}
+
+/// Transpile a program, putting the runtime shim in front of it
+pub fn transpile(source: &str, target: Target) -> Result<String> {
+    normalize::check_legacy_verify(source)?;
+    let tokens = Lexer::new(source.to_string()).tokenize()?;
+    let ast = Parser::new(tokens).parse()?;
+    Ok(Emitter::new(target).program(&ast))
+}
+
+/// How an expression binds as an operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prec {
+    /// A variable or literal, which can be repeated freely
+    Name,
+    /// A call, subscript or literal collection
+    Atom,
+    /// Anything that needs parentheses as an operand
+    Compound,
+}
+
+#[derive(Debug, Clone)]
+struct Expr {
+    text: String,
+    prec: Prec,
+}
+
+impl Expr {
+    fn name(text: impl Into<String>) -> Self {
+        Expr { text: text.into(), prec: Prec::Name }
+    }
+
+    fn atom(text: impl Into<String>) -> Self {
+        Expr { text: text.into(), prec: Prec::Atom }
+    }
+
+    fn compound(text: impl Into<String>) -> Self {
+        Expr { text: text.into(), prec: Prec::Compound }
+    }
+
+    fn operand(&self) -> String {
+        match self.prec {
+            Prec::Compound => format!("({})", self.text),
+            _ => self.text.clone(),
+        }
+    }
+}
+
+/// Where the value of a statement goes
+#[derive(Debug, Clone, Copy)]
+enum Sink<'a> {
+    Discard,
+    Assign(&'a str),
+    Return,
+}
+
+/// A function value: an inline lambda, or a function defined beforehand
+/// because its body needs statements
+enum Callable {
+    Inline(String),
+    Named(String),
+}
+
+struct Emitter {
+    target: Target,
+    lines: Vec<String>,
+    depth: usize,
+    temps: usize,
+    retries: usize,
+    /// Placeholder for the value piped into the operation being emitted
+    pipes: Vec<String>,
+    /// The variable bound last, which `⁇` guards outside a pipe
+    guarded: Option<String>,
+    /// Every variable the program assigns
+    declared: BTreeSet<String>,
+    /// Variables assigned in each function being emitted
+    scopes: Vec<BTreeSet<String>>,
+    imports: BTreeSet<&'static str>,
+}
+
+impl Emitter {
+    fn new(target: Target) -> Self {
+        Emitter {
+            target,
+            lines: Vec::new(),
+            depth: 0,
+            temps: 0,
+            retries: 0,
+            pipes: Vec::new(),
+            guarded: None,
+            declared: BTreeSet::new(),
+            scopes: Vec::new(),
+            imports: BTreeSet::new(),
+        }
+    }
+
+    fn program(mut self, ast: &[AstNode]) -> String {
+        for node in ast {
+            self.stmt(node, Sink::Discard);
+        }
+
+        let mut out = String::from(match self.target {
+            Target::Python => PYTHON_SHIM,
+            Target::JavaScript => JAVASCRIPT_SHIM,
+        });
+        match self.target {
+            Target::Python => {
+                out.push_str("\n\n# Program\n");
+                for import in &self.imports {
+                    out.push_str(import);
+                    out.push('\n');
+                }
+                if !self.imports.is_empty() {
+                    out.push('\n');
+                }
+            }
+            Target::JavaScript => {
+                out.push_str("\n// Program\n");
+                if !self.declared.is_empty() {
+                    let names: Vec<&str> = self.declared.iter().map(String::as_str).collect();
+                    out.push_str(&format!("let {};\n\n", names.join(", ")));
+                }
+            }
+        }
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+
+    fn python(&self) -> bool {
+        self.target == Target::Python
+    }
+
+    // Lines and blocks
+
+    fn push(&mut self, line: impl AsRef<str>) {
+        let indent = if self.python() { "    " } else { "  " };
+        self.lines.push(format!("{}{}", indent.repeat(self.depth), line.as_ref()));
+    }
+
+    /// A simple statement, with JavaScript's semicolon
+    fn statement(&mut self, code: impl AsRef<str>) {
+        let code = code.as_ref();
+        if self.python() {
+            self.push(code);
+        } else if code.starts_with('{') {
+            self.push(format!("({});", code));
+        } else {
+            self.push(format!("{};", code));
+        }
+    }
+
+    /// A compound statement: `header:` and an indented body in Python,
+    /// `header {` … `}` in JavaScript, with `else` and `catch` cuddled
+    fn block(&mut self, header: impl AsRef<str>, body: impl FnOnce(&mut Self)) {
+        let header = header.as_ref();
+        match self.target {
+            Target::Python => self.push(format!("{}:", header)),
+            Target::JavaScript => {
+                let close = format!("{}}}", "  ".repeat(self.depth));
+                if (header.starts_with("else") || header.starts_with("catch")) && self.lines.last() == Some(&close) {
+                    self.lines.pop();
+                    self.push(format!("}} {} {{", header));
+                } else {
+                    self.push(format!("{} {{", header));
+                }
+            }
+        }
+        self.depth += 1;
+        let mark = self.lines.len();
+        body(self);
+        if self.lines.len() == mark && self.python() {
+            self.push("pass");
+        }
+        self.depth -= 1;
+        if !self.python() {
+            self.push("}");
+        }
+    }
+
+    /// `if`, `elif` or `while` with a condition
+    fn header(&self, keyword: &str, condition: &str) -> String {
+        match (self.target, keyword) {
+            (Target::Python, _) => format!("{} {}", keyword, condition),
+            (Target::JavaScript, "elif") => format!("else if ({})", condition),
+            (Target::JavaScript, _) => format!("{} ({})", keyword, condition),
+        }
+    }
+
+    fn for_header(&self, variable: &str, collection: &str) -> String {
+        match self.target {
+            Target::Python => format!("for {} in {}", variable, collection),
+            Target::JavaScript => format!("for ({} of {})", variable, collection),
+        }
+    }
+
+    /// Emit into a separate buffer, returning the lines it produced
+    fn capture<R>(&mut self, emit: impl FnOnce(&mut Self) -> R) -> (Vec<String>, R) {
+        let mark = self.lines.len();
+        let result = emit(self);
+        (self.lines.split_off(mark), result)
+    }
+
+    /// Like `capture`, indented one level deeper for a block
+    fn capture_nested<R>(&mut self, emit: impl FnOnce(&mut Self) -> R) -> (Vec<String>, R) {
+        self.depth += 1;
+        let captured = self.capture(emit);
+        self.depth -= 1;
+        captured
+    }
+
+    // Names
+
+    fn declare(&mut self, name: &str) {
+        self.declared.insert(name.to_string());
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string());
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &str) {
+        self.declare(name);
+        self.statement(format!("{} = {}", name, value));
+    }
+
+    fn temp(&mut self, stem: &str) -> String {
+        self.temps += 1;
+        format!("_{}{}", stem, self.temps)
+    }
+
+    /// An Aether variable as an identifier the target can bind
+    fn name(&self, name: &str) -> String {
+        let name = match name.strip_prefix('∆') {
+            Some(rest) => format!("delta_{}", rest),
+            None => name.to_string(),
+        };
+        let reserved = match self.target {
+            Target::Python => PYTHON_RESERVED,
+            Target::JavaScript => JAVASCRIPT_RESERVED,
+        };
+        if reserved.contains(&name.as_str()) {
+            format!("{}_", name)
+        } else {
+            name
+        }
+    }
+
+    fn null(&self) -> Expr {
+        Expr::name(if self.python() { "None" } else { "null" })
+    }
+
+    /// A call into the shim, with JavaScript's names in camelCase
+    fn call(&self, function: &str, args: Vec<String>) -> Expr {
+        let function = match self.target {
+            Target::Python => function.to_string(),
+            Target::JavaScript => {
+                let mut parts = function.split('_');
+                let first = parts.next().unwrap_or_default().to_string();
+                parts.fold(first, |name, part| {
+                    let mut chars = part.chars();
+                    match chars.next() {
+                        Some(c) => name + &c.to_uppercase().collect::<String>() + chars.as_str(),
+                        None => name,
+                    }
+                })
+            }
+        };
+        Expr::atom(format!("aether.{}({})", function, args.join(", ")))
+    }
+
+    // Statements
+
+    fn sink(&mut self, sink: Sink, value: Expr) {
+        match sink {
+            Sink::Discard if value.prec == Prec::Name => {}
+            Sink::Discard => self.statement(value.text),
+            Sink::Assign(name) => self.assign(name, &value.text),
+            Sink::Return => self.statement(format!("return {}", value.text)),
+        }
+    }
+
+    fn stmt(&mut self, node: &AstNode, sink: Sink) {
+        match node {
+            AstNode::Sequence(steps) => match steps.split_last() {
+                Some((last, rest)) => {
+                    for step in rest {
+                        self.stmt(step, Sink::Discard);
+                    }
+                    self.stmt(last, sink);
+                }
+                None => {
+                    let null = self.null();
+                    self.sink(sink, null);
+                }
+            },
+
+            AstNode::Pipe { source, operation } if !matches!(operation.as_ref(), AstNode::EndOfFile) => {
+                self.pipe(source, |e| {
+                    e.stmt(operation, sink);
+                    e.null()
+                });
+            }
+
+            AstNode::IfThen { .. } => self.if_chain(node, sink),
+
+            AstNode::Guard { then_branch, .. } => {
+                let value = self.guarded_value();
+                let condition = if self.python() {
+                    format!("{} is None", value.operand())
+                } else {
+                    format!("{} === null", value.operand())
+                };
+                let header = self.header("if", &condition);
+                self.block(header, |e| e.stmt(then_branch, sink));
+                if !matches!(sink, Sink::Discard) {
+                    self.block("else", |e| e.sink(sink, value));
+                }
+            }
+
+            AstNode::Loop { condition, body } => {
+                let result = match sink {
+                    Sink::Discard => None,
+                    _ => {
+                        let result = self.temp("value");
+                        let null = self.null();
+                        self.assign(&result, &null.text);
+                        Some(result)
+                    }
+                };
+                let body_sink = result.as_deref().map_or(Sink::Discard, Sink::Assign);
+                match condition {
+                    Some(condition) => {
+                        let (lines, test) = self.capture_nested(|e| e.cond(condition));
+                        if lines.is_empty() {
+                            let header = self.header("while", &test);
+                            self.block(header, |e| e.stmt(body, body_sink));
+                        } else {
+                            // A condition that needs statements is checked at the top of the loop
+                            let header = self.header("while", if self.python() { "True" } else { "true" });
+                            self.block(header, |e| {
+                                e.lines.extend(lines);
+                                let stop = e.negate(&test);
+                                let header = e.header("if", &stop);
+                                e.block(header, |e| e.statement("break"));
+                                e.stmt(body, body_sink);
+                            });
+                        }
+                    }
+                    None => {
+                        // Without a condition, the loop runs until its body is falsy
+                        let last = match &result {
+                            Some(result) => result.clone(),
+                            None => self.temp("value"),
+                        };
+                        let header = self.header("while", if self.python() { "True" } else { "true" });
+                        self.block(header, |e| {
+                            e.stmt(body, Sink::Assign(&last));
+                            let test = e.truthy(&last);
+                            let stop = e.negate(&test);
+                            let header = e.header("if", &stop);
+                            e.block(header, |e| e.statement("break"));
+                        });
+                    }
+                }
+                if let Some(result) = result {
+                    self.sink(sink, Expr::name(result));
+                }
+            }
+
+            AstNode::ForEach { variable, collection, body } if matches!(sink, Sink::Discard) => {
+                let collection = self.collection(collection);
+                let variable = self.name(variable);
+                self.declare(&variable);
+                let header = self.for_header(&variable, &collection.text);
+                self.block(header, |e| e.stmt(body, Sink::Discard));
+            }
+
+            AstNode::TryRescue { try_body, rescue_body } => {
+                self.block("try", |e| e.stmt(try_body, sink));
+                let header = if self.python() { "except Exception" } else { "catch" };
+                self.block(header, |e| match rescue_body {
+                    Some(rescue) => e.stmt(rescue, sink),
+                    None if matches!(sink, Sink::Discard) => {}
+                    None => {
+                        let null = e.null();
+                        e.sink(sink, null);
+                    }
+                });
+            }
+
+            AstNode::Retry { max_attempts, body } => {
+                let attempts = max_attempts.unwrap_or(3);
+                self.retries += 1;
+                let attempt = match self.retries {
+                    1 => "_attempt".to_string(),
+                    n => format!("_attempt{}", n),
+                };
+                let header = match self.target {
+                    Target::Python => format!("for {} in range(1, {})", attempt, attempts + 1),
+                    Target::JavaScript => format!("for (let {0} = 1; ; {0}++)", attempt),
+                };
+                self.block(header, |e| {
+                    e.block("try", |e| {
+                        e.stmt(body, sink);
+                        if !matches!(sink, Sink::Return) {
+                            e.statement("break");
+                        }
+                    });
+                    let header = if e.python() { "except Exception" } else { "catch (error)" };
+                    e.block(header, |e| {
+                        let last = match e.target {
+                            Target::Python => format!("{} == {}", attempt, attempts),
+                            Target::JavaScript => format!("{} === {}", attempt, attempts),
+                        };
+                        let header = e.header("if", &last);
+                        e.block(header, |e| e.statement(if e.python() { "raise" } else { "throw error" }));
+                    });
+                });
+                self.retries -= 1;
+            }
+
+            AstNode::Function { name, body } => {
+                let name = self.name(name);
+                self.define(&name, &[], |e| e.stmt(body, Sink::Return));
+                self.sink(sink, Expr::atom(format!("{}()", name)));
+            }
+
+            AstNode::Lock { body } if self.python() => {
+                self.block("with aether.lock", |e| e.stmt(body, sink));
+            }
+
+            _ => {
+                let value = self.expr(node);
+                self.sink(sink, value);
+            }
+        }
+    }
+
+    /// `◇ ◈ ◆` as one if/elif/else chain
+    fn if_chain(&mut self, node: &AstNode, sink: Sink) {
+        let mut keyword = "if";
+        let mut current = node;
+        while let AstNode::IfThen { condition, then_branch, else_branch } = current {
+            let (lines, test) = self.capture(|e| e.cond(condition));
+            if keyword == "elif" && !lines.is_empty() {
+                // A condition that needs statements can't be an elif
+                self.block("else", |e| e.if_chain(current, sink));
+                return;
+            }
+            self.lines.extend(lines);
+            let header = self.header(keyword, &test);
+            self.block(header, |e| e.stmt(then_branch, sink));
+            match else_branch.as_deref() {
+                Some(next @ AstNode::IfThen { .. }) => {
+                    keyword = "elif";
+                    current = next;
+                }
+                Some(otherwise) => {
+                    self.block("else", |e| e.stmt(otherwise, sink));
+                    return;
+                }
+                None => {
+                    if !matches!(sink, Sink::Discard) {
+                        self.block("else", |e| {
+                            let null = e.null();
+                            e.sink(sink, null);
+                        });
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Define a function before the code that uses it; in Python, variables
+    /// it assigns are declared global, since Aether has one namespace
+    fn define(&mut self, name: &str, params: &[&str], body: impl FnOnce(&mut Self)) {
+        self.scopes.push(BTreeSet::new());
+        let (lines, ()) = self.capture_nested(body);
+        let assigned = self.scopes.pop().unwrap_or_default();
+        let header = match self.target {
+            Target::Python => format!("def {}({})", name, params.join(", ")),
+            Target::JavaScript => format!("function {}({})", name, params.join(", ")),
+        };
+        self.block(header, |e| {
+            if e.python() && !assigned.is_empty() {
+                let names: Vec<&str> = assigned.iter().map(String::as_str).collect();
+                e.push(format!("global {}", names.join(", ")));
+            }
+            e.lines.extend(lines);
+        });
+    }
+
+    /// A function of `params` returning `body`, or its truthiness with `condition`
+    fn callable(&mut self, params: &[&str], body: &AstNode, condition: bool) -> Callable {
+        let value = |e: &mut Self| if condition { Expr::compound(e.cond(body)) } else { e.expr(body) };
+        let (lines, inline) = self.capture_nested(value);
+        if lines.is_empty() {
+            let text = match (self.target, inline.text.starts_with('{')) {
+                (Target::JavaScript, true) => format!("({})", inline.text),
+                _ => inline.text,
+            };
+            return Callable::Inline(text);
+        }
+        let name = self.temp("f");
+        self.define(&name, params, |e| {
+            let result = value(e);
+            e.sink(Sink::Return, result);
+        });
+        Callable::Named(name)
+    }
+
+    /// A callable as a value: a lambda or the function's name
+    fn function(&self, params: &[&str], callable: Callable) -> String {
+        match (callable, self.target) {
+            (Callable::Named(name), _) => name,
+            (Callable::Inline(body), Target::Python) if params.is_empty() => format!("lambda: {}", body),
+            (Callable::Inline(body), Target::Python) => format!("lambda {}: {}", params.join(", "), body),
+            (Callable::Inline(body), Target::JavaScript) => format!("({}) => {}", params.join(", "), body),
+        }
+    }
+
+    /// A callable applied to its own parameter names, as in a comprehension
+    fn applied(&self, params: &[&str], callable: Callable) -> String {
+        match callable {
+            Callable::Inline(body) => body,
+            Callable::Named(name) => format!("{}({})", name, params.join(", ")),
+        }
+    }
+
+    /// Emit a statement-shaped node into a temporary and use that
+    fn hoisted(&mut self, node: &AstNode) -> Expr {
+        let result = self.temp("value");
+        self.stmt(node, Sink::Assign(&result));
+        Expr::name(result)
+    }
+
+    // Pipes
+
+    /// `source ⇢ operation`: the operation sees the source wherever it reads
+    /// the piped value. A variable or literal, or a value read once before
+    /// anything else runs, is written in place; otherwise it is bound to
+    /// `_pipe` first, like the runtime does.
+    fn pipe(&mut self, source: &AstNode, operation: impl FnOnce(&mut Self) -> Expr) -> Expr {
+        let value = self.expr(source);
+        let placeholder = format!("\u{1}{}\u{1}", self.pipes.len());
+        self.pipes.push(placeholder.clone());
+        let (mut lines, mut result) = self.capture(operation);
+        self.pipes.pop();
+
+        let uses: usize = lines.iter().chain([&result.text]).map(|line| line.matches(&placeholder).count()).sum();
+        let read_first = uses == 1 && lines.first().unwrap_or(&result.text).contains(&placeholder);
+        let replacement = if value.prec == Prec::Name || read_first {
+            value.operand()
+        } else if uses == 0 {
+            self.sink(Sink::Discard, value);
+            String::new()
+        } else {
+            self.assign("_pipe", &value.text);
+            "_pipe".to_string()
+        };
+        for line in &mut lines {
+            *line = line.replace(&placeholder, &replacement);
+        }
+        self.lines.extend(lines);
+        result.text = result.text.replace(&placeholder, &replacement);
+        result
+    }
+
+    fn piped(&self) -> Option<Expr> {
+        self.pipes.last().map(|placeholder| Expr::name(placeholder.clone()))
+    }
+
+    /// An operand the runtime reads from the pipe when it is left out
+    fn subject(&mut self, node: &AstNode) -> Expr {
+        match node {
+            AstNode::Empty => self.piped().unwrap_or_else(|| self.null()),
+            _ => self.expr(node),
+        }
+    }
+
+    /// What `∀` iterates: the pipe, or nothing outside one
+    fn collection(&mut self, node: &AstNode) -> Expr {
+        match (node, self.piped()) {
+            (AstNode::Empty, None) => Expr::atom("[]"),
+            _ => self.subject(node),
+        }
+    }
+
+    fn guarded_value(&self) -> Expr {
+        self.piped()
+            .or_else(|| self.guarded.clone().map(Expr::name))
+            .unwrap_or_else(|| self.null())
+    }
+
+    // Conditions
+
+    fn cond(&mut self, node: &AstNode) -> String {
+        let value = self.expr(node);
+        if self.python() || is_boolean(node) {
+            value.text
+        } else {
+            format!("aether.truthy({})", value.text)
+        }
+    }
+
+    fn truthy(&self, value: &str) -> String {
+        if self.python() {
+            value.to_string()
+        } else {
+            format!("aether.truthy({})", value)
+        }
+    }
+
+    fn negate(&self, condition: &str) -> String {
+        match self.target {
+            Target::Python => format!("not ({})", condition),
+            Target::JavaScript => format!("!({})", condition),
+        }
+    }
+
+    /// A value as a boolean, as the runtime's `⊗ ⊕ ¬` produce
+    fn boolean(&mut self, node: &AstNode) -> Expr {
+        let value = self.expr(node);
+        if is_boolean(node) {
+            value
+        } else if self.python() {
+            Expr::atom(format!("bool({})", value.text))
+        } else {
+            Expr::atom(format!("aether.truthy({})", value.text))
+        }
+    }
+
+    /// `⊗` and `⊕`, short-circuiting into an if when the right side needs statements
+    fn logical(&mut self, left: &AstNode, right: &AstNode, and: bool) -> Expr {
+        let left = self.boolean(left);
+        let (lines, right) = self.capture_nested(|e| e.boolean(right));
+        if lines.is_empty() {
+            let op = match (self.target, and) {
+                (Target::Python, true) => "and",
+                (Target::Python, false) => "or",
+                (Target::JavaScript, true) => "&&",
+                (Target::JavaScript, false) => "||",
+            };
+            return Expr::compound(format!("{} {} {}", left.operand(), op, right.operand()));
+        }
+        let result = self.temp("value");
+        self.assign(&result, &left.text);
+        let test = if and { result.clone() } else { self.negate(&result) };
+        let header = self.header("if", &test);
+        self.block(header, |e| {
+            e.lines.extend(lines);
+            e.assign(&result, &right.text);
+        });
+        Expr::name(result)
+    }
+
+    // Expressions
+
+    fn binary(&mut self, left: &AstNode, op: &str, right: &AstNode) -> Expr {
+        let left = self.expr(left);
+        let right = self.expr(right);
+        Expr::compound(format!("{} {} {}", left.operand(), op, right.operand()))
+    }
+
+    fn shim(&mut self, function: &str, args: &[&AstNode]) -> Expr {
+        let args = args.iter().map(|arg| self.expr(arg).text).collect();
+        self.call(function, args)
+    }
+
+    fn http(&mut self, method: &str, url: &AstNode, body: Option<&AstNode>, headers: Option<&AstNode>) -> Expr {
+        let mut args = vec![string(method), self.expr(url).text];
+        let body = body.map(|body| self.expr(body).text);
+        let headers = headers.map(|headers| self.expr(headers).text);
+        match (body, headers) {
+            (body, Some(headers)) => {
+                args.push(body.unwrap_or_else(|| self.null().text));
+                args.push(headers);
+            }
+            (Some(body), None) => args.push(body),
+            (None, None) => {}
+        }
+        self.call("http", args)
+    }
+
+    fn literal(&mut self, literal: &LiteralValue) -> Expr {
+        let py = self.python();
+        match literal {
+            LiteralValue::String(s) => Expr::name(string(s)),
+            LiteralValue::Integer(n) if !py && n.unsigned_abs() > (1 << 53) => Expr::name(format!("{}n", n)),
+            LiteralValue::Integer(n) => Expr::name(n.to_string()),
+            LiteralValue::Number(n) if n.is_infinite() || n.is_nan() => {
+                let text = match (py, n.is_nan(), n.is_sign_negative()) {
+                    (true, true, _) => "math.nan",
+                    (true, false, false) => "math.inf",
+                    (true, false, true) => "-math.inf",
+                    (false, true, _) => "NaN",
+                    (false, false, false) => "Infinity",
+                    (false, false, true) => "-Infinity",
+                };
+                if py {
+                    self.imports.insert("import math");
+                }
+                Expr::name(text)
+            }
+            LiteralValue::Number(n) => Expr::name(format!("{:?}", n)),
+            LiteralValue::Decimal(d) if py => {
+                self.imports.insert("from decimal import Decimal");
+                Expr::name(format!("Decimal(\"{}\")", d))
+            }
+            LiteralValue::Decimal(d) => Expr::name(d.to_string()),
+            LiteralValue::Bytes(b) => {
+                let hex: String = b.iter().map(|byte| format!("{:02x}", byte)).collect();
+                if py {
+                    Expr::atom(format!("bytes.fromhex(\"{}\")", hex))
+                } else {
+                    Expr::atom(format!("Buffer.from(\"{}\", \"hex\")", hex))
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, node: &AstNode) -> Expr {
+        let py = self.python();
+        match node {
+            AstNode::Literal(literal) => self.literal(literal),
+            AstNode::Variable(name) if name == "_pipe" && !self.pipes.is_empty() => self.guarded_value(),
+            AstNode::Variable(name) => Expr::name(self.name(name)),
+            AstNode::Empty | AstNode::EndOfFile => self.null(),
+            AstNode::Infinity => {
+                if py {
+                    self.imports.insert("import math");
+                }
+                Expr::name(if py { "math.inf" } else { "Infinity" })
+            }
+
+            AstNode::Sequence(steps) => match steps.split_last() {
+                Some((last, rest)) => {
+                    for step in rest {
+                        self.stmt(step, Sink::Discard);
+                    }
+                    self.expr(last)
+                }
+                None => self.null(),
+            },
+
+            AstNode::Pipe { source, operation } if matches!(operation.as_ref(), AstNode::EndOfFile) => {
+                self.shim("at_end", &[source])
+            }
+            AstNode::Pipe { source, operation } => self.pipe(source, |e| e.expr(operation)),
+
+            AstNode::PipeInto { value, variable, .. } | AstNode::Immutable { name: variable, value } => {
+                let name = self.name(variable);
+                let value = self.expr(value);
+                self.assign(&name, &value.text);
+                self.guarded = Some(name.clone());
+                Expr::name(name)
+            }
+            AstNode::Delta { name, value } => {
+                let name = self.name(&format!("∆{}", name));
+                let value = self.expr(value);
+                self.assign(&name, &value.text);
+                Expr::name(name)
+            }
+
+            AstNode::IfThen { condition, then_branch, else_branch } => {
+                let mark = self.lines.len();
+                let test = self.cond(condition);
+                let (then_lines, then_value) = self.capture(|e| e.expr(then_branch));
+                let (else_lines, else_value) = self.capture(|e| match else_branch {
+                    Some(otherwise) => e.expr(otherwise),
+                    None => e.null(),
+                });
+                if !(then_lines.is_empty() && else_lines.is_empty()) {
+                    self.lines.truncate(mark);
+                    return self.hoisted(node);
+                }
+                match self.target {
+                    Target::Python => Expr::compound(format!("{} if {} else {}", then_value.operand(), test, else_value.text)),
+                    Target::JavaScript => Expr::compound(format!("{} ? {} : {}", test, then_value.operand(), else_value.text)),
+                }
+            }
+
+            AstNode::Guard { then_branch, .. } => {
+                let value = self.guarded_value();
+                let (lines, fallback) = self.capture(|e| e.expr(then_branch));
+                if !lines.is_empty() {
+                    return self.hoisted(node);
+                }
+                match self.target {
+                    Target::Python => Expr::compound(format!(
+                        "{0} if {0} is not None else {1}",
+                        value.operand(),
+                        fallback.text
+                    )),
+                    Target::JavaScript => Expr::compound(format!("{} ?? {}", value.operand(), fallback.operand())),
+                }
+            }
+
+            AstNode::Loop { .. } | AstNode::TryRescue { .. } | AstNode::Retry { .. } => self.hoisted(node),
+            AstNode::Lock { .. } if py => self.hoisted(node),
+            AstNode::Lock { body } => self.expr(body),
+
+            AstNode::Function { name, body } => {
+                let name = self.name(name);
+                self.define(&name, &[], |e| e.stmt(body, Sink::Return));
+                Expr::atom(format!("{}()", name))
+            }
+
+            AstNode::ForEach { variable, collection, body } => {
+                let collection = self.collection(collection);
+                let variable = self.name(variable);
+                let mark = self.lines.len();
+                let (lines, value) = self.capture(|e| e.expr(body));
+                if lines.is_empty() {
+                    return match self.target {
+                        Target::Python => Expr::atom(format!("[{} for {} in {}]", value.text, variable, collection.text)),
+                        Target::JavaScript => Expr::atom(format!(
+                            "{}.map(({}) => {})",
+                            collection.operand(),
+                            variable,
+                            if value.text.starts_with('{') { format!("({})", value.text) } else { value.text }
+                        )),
+                    };
+                }
+                self.lines.truncate(mark);
+                let result = self.temp("items");
+                self.assign(&result, "[]");
+                self.declare(&variable);
+                let header = self.for_header(&variable, &collection.text);
+                self.block(header, |e| {
+                    let value = e.expr(body);
+                    e.statement(format!("{}.{}({})", result, if py { "append" } else { "push" }, value.text));
+                });
+                Expr::name(result)
+            }
+
+            AstNode::Filter { predicate } => {
+                let items = self.subject(&AstNode::Empty);
+                let test = self.callable(&["_item"], predicate, true);
+                match self.target {
+                    Target::Python => {
+                        let test = self.applied(&["_item"], test);
+                        Expr::atom(format!("[_item for _item in {} if {}]", items.text, test))
+                    }
+                    Target::JavaScript => {
+                        let test = self.function(&["_item"], test);
+                        Expr::atom(format!("{}.filter({})", items.operand(), test))
+                    }
+                }
+            }
+
+            AstNode::Reduce { operation, initial } => {
+                let items = self.subject(&AstNode::Empty);
+                let initial = self.expr(initial);
+                let step = self.callable(&["_acc", "_item"], operation, false);
+                let step = self.function(&["_acc", "_item"], step);
+                match self.target {
+                    Target::Python => {
+                        self.imports.insert("import functools");
+                        Expr::atom(format!("functools.reduce({}, {}, {})", step, items.text, initial.text))
+                    }
+                    Target::JavaScript => Expr::atom(format!("{}.reduce({}, {})", items.operand(), step, initial.text)),
+                }
+            }
+
+            AstNode::Async { body } | AstNode::Thread { body } => {
+                let task = self.callable(&[], body, false);
+                let task = self.function(&[], task);
+                self.call("spawn", vec![task])
+            }
+            AstNode::Await { expression } => self.shim("wait", &[expression]),
+            AstNode::Emit { event } => self.shim("emit", &[event]),
+            AstNode::Watch { event, handler } => {
+                let event = self.expr(event);
+                let handler = self.callable(&[], handler, false);
+                let handler = self.function(&[], handler);
+                self.call("watch", vec![event.text, handler])
+            }
+
+            AstNode::Test { name, body } => {
+                let body = self.callable(&[], body, false);
+                let body = self.function(&[], body);
+                self.call("test", vec![string(name), body])
+            }
+            AstNode::Benchmark { body } => {
+                let body = self.callable(&[], body, false);
+                let body = self.function(&[], body);
+                self.call("bench", vec![body])
+            }
+            AstNode::Assert { condition } => self.shim("check", &[condition]),
+            AstNode::Mock { target } => self.shim("mock", &[target]),
+            AstNode::Debug => self.call("debug", Vec::new()),
+
+            AstNode::Input => self.call("input", Vec::new()),
+            AstNode::Output(value) => self.shim("output", &[value]),
+            AstNode::Halt(code) => self.shim("halt", &[code]),
+            AstNode::Persist(value) => {
+                let value = self.subject(value);
+                self.call("persist", vec![value.text])
+            }
+            AstNode::JsonParse(source) => {
+                let text = match self.piped() {
+                    Some(piped) => piped,
+                    None => self.expr(source),
+                };
+                self.call("json_parse", vec![text.text])
+            }
+            AstNode::Log { message } => self.shim("log", &[message]),
+            AstNode::Import { module } => self.call("use", vec![string(module)]),
+            AstNode::Auth { token } => self.shim("auth", &[token]),
+            AstNode::DateTime => self.call("now", Vec::new()),
+            AstNode::Random if py => {
+                self.imports.insert("import random");
+                Expr::atom("random.random()")
+            }
+            AstNode::Random => Expr::atom("Math.random()"),
+
+            AstNode::HttpGet { url, headers } => self.http("GET", url, None, headers.as_deref()),
+            AstNode::HttpPost { url, body, headers } => self.http("POST", url, body.as_deref(), headers.as_deref()),
+            AstNode::HttpPut { url, body, headers } => self.http("PUT", url, body.as_deref(), headers.as_deref()),
+            AstNode::HttpDelete { url, headers } => self.http("DELETE", url, None, headers.as_deref()),
+            AstNode::HttpPatch { url, body, headers } => self.http("PATCH", url, body.as_deref(), headers.as_deref()),
+            AstNode::HttpHead { url, headers } => self.http("HEAD", url, None, headers.as_deref()),
+            AstNode::HttpOptions { url, headers } => self.http("OPTIONS", url, None, headers.as_deref()),
+
+            AstNode::Encrypt { data, key } => self.shim("encrypt", &[data, key]),
+            AstNode::Decrypt { data, key } => self.shim("decrypt", &[data, key]),
+            AstNode::Hash { data } => self.shim("hash", &[data]),
+            AstNode::Sign { data, key } => self.shim("sign", &[data, key]),
+            AstNode::VerifySignature { signature, data, key } => self.shim("verify", &[signature, data, key]),
+
+            AstNode::Power { base, exponent } => self.binary(base, "**", exponent),
+            AstNode::Root { value } => {
+                let value = self.expr(value);
+                if py {
+                    self.imports.insert("import math");
+                    Expr::atom(format!("math.sqrt({})", value.text))
+                } else {
+                    Expr::atom(format!("Math.sqrt({})", value.text))
+                }
+            }
+            AstNode::Approx { left, right } => self.shim("approx", &[left, right]),
+
+            AstNode::FileHandle { path } => self.shim("file", &[path]),
+            AstNode::Directory { path } => self.shim("directory", &[path]),
+            AstNode::PathResolve { path } => self.expr(path),
+            AstNode::ReadContent { source } => {
+                let source = self.subject(source);
+                self.call("read", vec![source.text])
+            }
+            AstNode::WriteContent { target, content } | AstNode::AppendContent { target, content } => {
+                let target_value = self.expr(target);
+                let content = self.subject(content);
+                let function = if matches!(node, AstNode::WriteContent { .. }) { "write" } else { "append" };
+                self.call(function, vec![target_value.text, content.text])
+            }
+            AstNode::DeleteFile { target } => self.shim("delete", &[target]),
+            AstNode::SetPermission { target, permission } => self.shim("chmod", &[target, permission]),
+
+            AstNode::CreateStream { source, options } => {
+                let mut args = vec![self.subject(source).text];
+                if !matches!(options.as_ref(), AstNode::Empty) {
+                    args.push(self.expr(options).text);
+                }
+                self.call("stream", args)
+            }
+            AstNode::CreateBuffer { size } | AstNode::MemoryAlloc { size } => self.shim("buffer", &[size]),
+            AstNode::ToBytes { value, encoding } | AstNode::ToText { value, encoding } => {
+                let mut args = vec![self.subject(value).text];
+                if !matches!(encoding.as_ref(), AstNode::Empty) {
+                    args.push(self.expr(encoding).text);
+                }
+                self.call(if matches!(node, AstNode::ToBytes { .. }) { "to_bytes" } else { "to_text" }, args)
+            }
+            AstNode::FlushBuffer { target } => {
+                let target = self.subject(target);
+                self.call("flush", vec![target.text])
+            }
+            AstNode::SkipBytes { source, count } => {
+                let source = self.subject(source);
+                let count = self.expr(count);
+                self.call("skip", vec![source.text, count.text])
+            }
+
+            AstNode::CreateSocket { socket_type } => self.shim("socket", &[socket_type]),
+            AstNode::ListenPort { port } => {
+                let port = self.subject(port);
+                self.call("listen", vec![port.text])
+            }
+            AstNode::ConnectRemote { address } => self.shim("connect", &[address]),
+            AstNode::PortNumber { number } => self.expr(number),
+            AstNode::CreatePacket { data } => self.shim("packet", &[data]),
+            AstNode::Handshake { connection } => self.shim("handshake", &[connection]),
+
+            AstNode::ProcessCreate { command } => self.shim("process", &[command]),
+            AstNode::ShellExec { command } => self.shim("shell", &[command]),
+            AstNode::EnvVar { name } => self.shim("env", &[name]),
+            AstNode::ExitProgram { code } => self.shim("exit", &[code]),
+            AstNode::SendSignal { signal, target } => self.shim("signal", &[signal, target]),
+
+            AstNode::Split { target, delimiter } => {
+                let target = self.subject(target);
+                let delimiter = match delimiter {
+                    Some(delimiter) => self.expr(delimiter).text,
+                    None => string(" "),
+                };
+                Expr::atom(format!("{}.split({})", target.operand(), delimiter))
+            }
+            AstNode::Join { elements, separator } => {
+                let elements = self.subject(elements);
+                let separator = match separator {
+                    Some(separator) => self.expr(separator),
+                    None => Expr::name(string("")),
+                };
+                if py {
+                    Expr::atom(format!("{}.join({})", separator.operand(), elements.text))
+                } else {
+                    Expr::atom(format!("{}.join({})", elements.operand(), separator.text))
+                }
+            }
+            AstNode::RegexMatch { pattern, target } => {
+                let pattern = self.expr(pattern);
+                let target = self.subject(target);
+                if py {
+                    self.imports.insert("import re");
+                    Expr::compound(format!("re.search({}, {}) is not None", pattern.text, target.text))
+                } else {
+                    Expr::atom(format!("new RegExp({}).test({})", pattern.text, target.text))
+                }
+            }
+
+            AstNode::Equal { left, right } | AstNode::NotEqual { left, right } => {
+                let equal = matches!(node, AstNode::Equal { .. });
+                let left = self.subject(left);
+                if matches!(right.as_ref(), AstNode::EndOfFile) {
+                    let at_end = self.call("at_end", vec![left.text]);
+                    return match (equal, py) {
+                        (true, _) => at_end,
+                        (false, true) => Expr::compound(format!("not {}", at_end.text)),
+                        (false, false) => Expr::compound(format!("!{}", at_end.text)),
+                    };
+                }
+                let right = self.expr(right);
+                let op = match (equal, py) {
+                    (true, true) => "==",
+                    (false, true) => "!=",
+                    (true, false) => "===",
+                    (false, false) => "!==",
+                };
+                Expr::compound(format!("{} {} {}", left.operand(), op, right.operand()))
+            }
+            AstNode::And { left, right } => self.logical(left, right, true),
+            AstNode::Or { left, right } => self.logical(left, right, false),
+            AstNode::Not { operand } => {
+                let value = self.boolean(operand);
+                if py {
+                    Expr::compound(format!("not {}", value.operand()))
+                } else {
+                    Expr::compound(format!("!{}", value.operand()))
+                }
+            }
+            AstNode::Comparison { left, operator, right } => {
+                let op = match operator {
+                    ComparisonOp::GreaterThan => ">",
+                    ComparisonOp::LessThan => "<",
+                    ComparisonOp::GreaterEqual => ">=",
+                    ComparisonOp::LessEqual => "<=",
+                };
+                self.binary(left, op, right)
+            }
+
+            AstNode::PropertyAccess { object, property } => {
+                let object = self.expr(object);
+                if py {
+                    Expr::atom(format!("{}.get({})", object.operand(), string(property)))
+                } else {
+                    Expr::atom(format!("{}.{}", object.operand(), property))
+                }
+            }
+            AstNode::Length { value } => {
+                let value = self.expr(value);
+                if py {
+                    Expr::atom(format!("len({})", value.text))
+                } else {
+                    Expr::atom(format!("{}.length", value.operand()))
+                }
+            }
+            AstNode::Index { target, index } | AstNode::CharAt { target, index } => {
+                let target = self.expr(target);
+                let index = self.expr(index);
+                Expr::atom(format!("{}[{}]", target.operand(), index.text))
+            }
+            AstNode::Slice { target, start, end } => {
+                let target = self.expr(target);
+                let start = self.expr(start);
+                let end = end.as_deref().map(|end| self.expr(end));
+                match (py, end) {
+                    (true, Some(end)) => Expr::atom(format!("{}[{}:{}]", target.operand(), start.text, end.text)),
+                    (true, None) => Expr::atom(format!("{}[{}:]", target.operand(), start.text)),
+                    (false, Some(end)) => Expr::atom(format!("{}.slice({}, {})", target.operand(), start.text, end.text)),
+                    (false, None) => Expr::atom(format!("{}.slice({})", target.operand(), start.text)),
+                }
+            }
+            AstNode::ArrayPush { array, element } => self.shim("push", &[array, element]),
+
+            AstNode::Add { left, right } => self.binary(left, "+", right),
+            AstNode::Subtract { left, right } => self.binary(left, "-", right),
+            AstNode::Multiply { left, right } => self.binary(left, "*", right),
+            AstNode::Divide { left, right } => self.binary(left, "/", right),
+            AstNode::Modulo { left, right } => self.binary(left, "%", right),
+            AstNode::StringConcat { left, right } if is_text(node) => {
+                let left = self.expr(left);
+                let right = self.expr(right);
+                Expr::compound(format!("{} + {}", left.text, right.text))
+            }
+            AstNode::StringConcat { left, right } => self.shim("concat", &[left, right]),
+
+            AstNode::ArrayLiteral { elements } => {
+                let elements: Vec<String> = elements.iter().map(|element| self.expr(element).text).collect();
+                Expr::atom(format!("[{}]", elements.join(", ")))
+            }
+            AstNode::ObjectLiteral { pairs } => {
+                let pairs: Vec<String> = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", string(key), self.expr(value).text))
+                    .collect();
+                Expr::atom(format!("{{{}}}", pairs.join(", ")))
+            }
+        }
+    }
+}
+
+/// Nodes whose value is always a boolean
+fn is_boolean(node: &AstNode) -> bool {
+    matches!(
+        node,
+        AstNode::Comparison { .. }
+            | AstNode::Equal { .. }
+            | AstNode::NotEqual { .. }
+            | AstNode::And { .. }
+            | AstNode::Or { .. }
+            | AstNode::Not { .. }
+            | AstNode::Approx { .. }
+            | AstNode::RegexMatch { .. }
+    )
+}
+
+/// String literals joined with `⧺`, which `+` joins the same way
+fn is_text(node: &AstNode) -> bool {
+    match node {
+        AstNode::Literal(LiteralValue::String(_)) => true,
+        AstNode::StringConcat { left, right } => is_text(left) && is_text(right),
+        _ => false,
+    }
+}
+
+/// A string literal, which Python and JavaScript both read as JSON writes it
+fn string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The program without the shim
+    fn program(source: &str, target: Target) -> String {
+        let out = transpile(source, target).unwrap();
+        let marker = if target == Target::Python { "# Program\n" } else { "// Program\n" };
+        out[out.find(marker).unwrap() + marker.len()..].to_string()
+    }
+
+    #[test]
+    fn test_bindings_and_pipes() {
+        let source = "\"a,b\" ⇢ ✂\",\" ⇢ 🔗\" | \" ▷ joined ⨠ 📤joined";
+        assert_eq!(program(source, Target::Python), "joined = \" | \".join(\"a,b\".split(\",\"))\naether.output(joined)\n");
+        assert_eq!(
+            program(source, Target::JavaScript),
+            "let joined;\n\njoined = \"a,b\".split(\",\").join(\" | \");\naether.output(joined);\n"
+        );
+
+        // A piped value read twice is bound once
+        assert_eq!(program("📥 ⇢ J ▷ data", Target::Python), "data = aether.json_parse(aether.input())\n");
+        assert_eq!(
+            program("🌍\"HOME\" ⇢ ⁇ \"/\" ▷ home", Target::Python),
+            "_pipe = aether.env(\"HOME\")\nhome = _pipe if _pipe is not None else \"/\"\n"
+        );
+        assert_eq!(program("2 ⇢ ↑3 ▷ x", Target::JavaScript), "let x;\n\nx = 2 ** 3;\n");
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let source = "7 ▷ score ⨠ ◇(score > 8): 📤\"A\" ◈(score > 5): 📤\"B\" ◆: 📤\"C\"";
+        assert_eq!(
+            program(source, Target::Python),
+            "score = 7\nif score > 8:\n    aether.output(\"A\")\nelif score > 5:\n    aether.output(\"B\")\nelse:\n    aether.output(\"C\")\n"
+        );
+        assert_eq!(
+            program(source, Target::JavaScript),
+            "let score;\n\nscore = 7;\nif (score > 8) {\n  aether.output(\"A\");\n} else if (score > 5) {\n  aether.output(\"B\");\n} else {\n  aether.output(\"C\");\n}\n"
+        );
+
+        // JavaScript tests Aether's truthiness, where [] is false
+        assert!(program("0 ▷ flag ⨠ ◇(¬flag): 📤1", Target::JavaScript).contains("if (!aether.truthy(flag)) {"));
+
+        let guarded = program("ƒ®: 📥 ⇢ J ▷ u ⁇ 🛑400 ⨠ 💾u", Target::Python);
+        assert!(guarded.contains("def register():\n    global u\n    u = aether.json_parse(aether.input())\n    return u\nregister()\n"));
+        assert!(guarded.contains("if u is None:\n    aether.halt(400)\n"));
+    }
+
+    #[test]
+    fn test_loops_and_errors() {
+        let python = program("[1, 2] ⇢ ∀x: 📤x ▷ shown ⨠ 🛡(🌐📥\"http://x\") ▷ r ⨠ ♻2: 📤r", Target::Python);
+        assert!(python.contains("shown = [aether.output(x) for x in [1, 2]]\n"));
+        assert!(python.contains("try:\n    _value1 = aether.http(\"GET\", \"http://x\")\nexcept Exception:\n    _value1 = None\nr = _value1\n"));
+        assert!(python.contains(
+            "for _attempt in range(1, 3):\n    try:\n        aether.output(r)\n        break\n    except Exception:\n        if _attempt == 2:\n            raise\n"
+        ));
+
+        let javascript = program("0 ▷ n ⨠ ↻(n < 3): (n + 1 ▷ n) ⨠ [1, 2, 3] ⇢ ∃(_item > 1) ▷ big", Target::JavaScript);
+        assert!(javascript.starts_with("let big, n;\n"));
+        assert!(javascript.contains("while (n < 3) {\n  n = n + 1;\n}\n"));
+        assert!(javascript.contains("big = [1, 2, 3].filter((_item) => _item > 1);\n"));
+    }
+
+    #[test]
+    fn test_names_and_literals() {
+        let python = program("\"x\" ▷ str ⨠ str ⇢ 📏 ▷ len ⨠ 19.99 ▷ price ⨠ 16 ⇢ √ ▷ root", Target::Python);
+        assert!(python.starts_with("from decimal import Decimal\nimport math\n\n"));
+        assert!(python.contains("str_ = \"x\"\nlen_ = len(str_)\nprice = Decimal(\"19.99\")\nroot = math.sqrt(16)\n"));
+
+        let javascript = program("9007199254740993 ▷ id ⨠ hex\"cafe\" ▷ magic ⨠ 🐚\"ls\" ▷ process", Target::JavaScript);
+        assert!(javascript.contains("id = 9007199254740993n;\nmagic = Buffer.from(\"cafe\", \"hex\");\nprocess_ = aether.shell(\"ls\");\n"));
+        assert_eq!(Target::from_name("python"), Some(Target::Python));
+        assert_eq!(Target::from_name("js").map(Target::extension), Some("js"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Bytecode verification for Aether
+//!
+//! Checks a `BytecodeProgram` before the VM runs it, so that corrupt or
+//! hostile `.aeb` files are rejected with the offending code position
+//! instead of failing part way through execution.
+
+use crate::bytecode::{BytecodeProgram, Instruction, Opcode, Operand};
+use crate::error::{AetherError, Result};
+use std::collections::HashMap;
+
+/// Verify a program's structure and stack usage
+///
+/// - every instruction decodes and its operands fit inside the code
+/// - jump and loop targets land on instruction boundaries
+/// - constant indices are inside the constant pool, and name a string
+///   except for `PushConstant`
+/// - along every path from the entry point the stack never underflows
+///
+/// Paths may leave unused values behind, as compiled sequences and loops do,
+/// but no path, including any number of trips around a loop, may pop more
+/// values than it pushed.
+pub fn verify(program: &BytecodeProgram) -> Result<()> {
+    let instructions = decode_all(program)?;
+    let index: HashMap<usize, usize> = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, instruction)| (instruction.offset, i))
+        .collect();
+    let end = program.code.len();
+
+    for instruction in &instructions {
+        for operand in &instruction.operands {
+            match operand {
+                Operand::Constant(constant) if *constant as usize >= program.constants.len() => {
+                    return Err(error(instruction, format!(
+                        "constant index {} is outside the pool of {}",
+                        constant,
+                        program.constants.len()
+                    )));
+                }
+                Operand::Constant(constant) if instruction.opcode != Opcode::PushConstant => {
+                    let constant = &program.constants[*constant as usize];
+                    if constant.as_str().is_none() {
+                        return Err(error(instruction, format!(
+                            "expects a string constant, found {} {}",
+                            constant.type_name(),
+                            constant
+                        )));
+                    }
+                }
+                Operand::Target(target) => {
+                    let target = *target as usize;
+                    if target > end {
+                        return Err(error(instruction, format!(
+                            "target {} is past the end of the code ({})",
+                            target, end
+                        )));
+                    }
+                    if target < end && !index.contains_key(&target) {
+                        return Err(error(instruction, format!(
+                            "target {} is inside an instruction",
+                            target
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    check_stack(&instructions, &index, end)
+}
+
+/// Decode the whole code section, instruction by instruction
+fn decode_all(program: &BytecodeProgram) -> Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+
+    while offset < program.code.len() {
+        let instruction = program.decode(offset).map_err(|err| match err {
+            AetherError::BytecodeError(message) if message.starts_with("Unknown opcode") => {
+                AetherError::BytecodeError(format!("{} at position {}", message, offset))
+            }
+            other => other,
+        })?;
+        offset += instruction.size;
+        instructions.push(instruction);
+    }
+
+    Ok(instructions)
+}
+
+/// Walk every path from the entry point tracking the lowest stack depth each
+/// instruction can be reached with
+fn check_stack(instructions: &[Instruction], index: &HashMap<usize, usize>, end: usize) -> Result<()> {
+    let mut depths: Vec<Option<usize>> = vec![None; instructions.len()];
+    let mut pending = vec![(0usize, 0usize)];
+
+    while let Some((offset, depth)) = pending.pop() {
+        // Running off the end of the code stops the VM like `End`
+        if offset == end {
+            continue;
+        }
+        let i = index[&offset];
+        let instruction = &instructions[i];
+
+        // Depths only shrink when revisited, so this terminates
+        if depths[i].is_some_and(|known| known <= depth) {
+            continue;
+        }
+        depths[i] = Some(depth);
+
+        let (pops, pushes) = stack_effect(instruction);
+        if depth < pops {
+            return Err(error(instruction, format!(
+                "needs {} stack value(s) but only {} available",
+                pops, depth
+            )));
+        }
+        let after = depth - pops + pushes;
+        let next = offset + instruction.size;
+        let target = instruction.operands.iter().find_map(|operand| match operand {
+            Operand::Target(target) => Some(*target as usize),
+            _ => None,
+        });
+
+        match instruction.opcode {
+            Opcode::End | Opcode::Halt | Opcode::Return | Opcode::ExitProgram => {}
+            Opcode::Jump | Opcode::LoopEnd => pending.extend(target.map(|t| (t, after))),
+            Opcode::JumpIfFalse | Opcode::JumpIfNull => {
+                pending.extend(target.map(|t| (t, after)));
+                pending.push((next, after));
+            }
+            Opcode::ForEach => {
+                // A null collection skips the body and pushes null after its LoopEnd
+                let skip = loop_exit(instructions, i).ok_or_else(|| {
+                    error(instruction, "has no matching LoopEnd".to_string())
+                })?;
+                pending.push((skip, after + 1));
+                pending.push((next, after));
+            }
+            _ => pending.push((next, after)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Position just after the LoopEnd that closes the ForEach at `start`
+fn loop_exit(instructions: &[Instruction], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for instruction in &instructions[start..] {
+        match instruction.opcode {
+            Opcode::ForEach | Opcode::LoopStart => depth += 1,
+            Opcode::LoopEnd => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(instruction.offset + instruction.size);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Values an instruction pops and pushes
+fn stack_effect(instruction: &Instruction) -> (usize, usize) {
+    match instruction.opcode {
+        Opcode::PushNull
+        | Opcode::PushBool
+        | Opcode::PushNumber
+        | Opcode::PushInteger
+        | Opcode::PushDecimal
+        | Opcode::PushString
+        | Opcode::PushConstant
+        | Opcode::LoadVar
+        | Opcode::Input
+        | Opcode::DateTime
+        | Opcode::Random
+        | Opcode::Import
+        | Opcode::Thread
+        | Opcode::Lock
+        | Opcode::Infinity => (0, 1),
+
+        Opcode::Pop
+        | Opcode::StoreVar
+        | Opcode::StoreImmutable
+        | Opcode::JumpIfFalse
+        | Opcode::Halt
+        | Opcode::Assert
+        | Opcode::ExitProgram
+        | Opcode::ForEach => (1, 0),
+
+        Opcode::Dup | Opcode::Query => (1, 2),
+        Opcode::Swap => (2, 2),
+
+        Opcode::Add
+        | Opcode::Sub
+        | Opcode::Mul
+        | Opcode::Div
+        | Opcode::Mod
+        | Opcode::Power
+        | Opcode::Equal
+        | Opcode::NotEqual
+        | Opcode::LessThan
+        | Opcode::GreaterThan
+        | Opcode::Approx
+        | Opcode::GreaterEqual
+        | Opcode::LessEqual
+        | Opcode::And
+        | Opcode::Or
+        | Opcode::Split
+        | Opcode::Join
+        | Opcode::Regex
+        | Opcode::Encrypt
+        | Opcode::Decrypt
+        | Opcode::FileWrite
+        | Opcode::FileAppend
+        | Opcode::SetPermission
+        | Opcode::HttpGet
+        | Opcode::HttpDelete
+        | Opcode::HttpHead
+        | Opcode::HttpOptions
+        | Opcode::SendSignal
+        | Opcode::Sign
+        | Opcode::CreateStream
+        | Opcode::ToBytes
+        | Opcode::ToText
+        | Opcode::Concat
+        | Opcode::Index
+        | Opcode::SkipBytes
+        | Opcode::Watch
+        | Opcode::RegexMatch
+        | Opcode::PropertyAccess => (2, 1),
+
+        Opcode::HttpPost | Opcode::HttpPut | Opcode::HttpPatch | Opcode::VerifySignature | Opcode::Slice => (3, 1),
+
+        Opcode::Root
+        | Opcode::Not
+        | Opcode::Output
+        | Opcode::JsonParse
+        | Opcode::Persist
+        | Opcode::JumpIfNull
+        | Opcode::Hash
+        | Opcode::Log
+        | Opcode::FileHandle
+        | Opcode::FileRead
+        | Opcode::Directory
+        | Opcode::PathResolve
+        | Opcode::DeleteFile
+        | Opcode::EnvVar
+        | Opcode::ProcessCreate
+        | Opcode::ShellExec
+        | Opcode::MemoryAlloc
+        | Opcode::CreateSocket
+        | Opcode::ListenPort
+        | Opcode::ConnectRemote
+        | Opcode::PortNumber
+        | Opcode::CreatePacket
+        | Opcode::Handshake
+        | Opcode::CreateBuffer
+        | Opcode::Length
+        | Opcode::FlushBuffer
+        | Opcode::EndOfFile
+        | Opcode::Emit
+        | Opcode::Auth => (1, 1),
+
+        Opcode::MakeArray => (count(instruction), 1),
+        Opcode::MakeObject => (count(instruction).saturating_mul(2), 1),
+
+        Opcode::Jump
+        | Opcode::Call
+        | Opcode::Return
+        | Opcode::LoopStart
+        | Opcode::LoopEnd
+        | Opcode::Filter
+        | Opcode::Reduce
+        | Opcode::TryStart
+        | Opcode::TryEnd
+        | Opcode::Retry
+        | Opcode::Async
+        | Opcode::Await
+        | Opcode::Delta
+        | Opcode::Debug
+        | Opcode::TestStart
+        | Opcode::Mock
+        | Opcode::BenchmarkStart
+        | Opcode::BenchmarkEnd
+        | Opcode::End => (0, 0),
+    }
+}
+
+fn count(instruction: &Instruction) -> usize {
+    match instruction.operands.first() {
+        Some(Operand::Count(count)) => *count as usize,
+        _ => 0,
+    }
+}
+
+fn error(instruction: &Instruction, message: String) -> AetherError {
+    AetherError::BytecodeError(format!(
+        "{:?} at position {}: {}",
+        instruction.opcode, instruction.offset, message
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::Compiler;
+
+    fn rejects(text: &str) -> String {
+        let program = BytecodeProgram::assemble(text).unwrap();
+        verify(&program).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_compiled_programs_verify() {
+        let source = "5 ▷ x ⨠ ◇(x > 1): 📤\"big\" ⨠ [1, 2] ⇢ ∀i: 📤i ⨠ ↻(x < 10): (x + 1 ▷ x)";
+        let program = Compiler::new().compile_source(source).unwrap();
+        assert!(verify(&program).is_ok());
+        let program = Compiler::new().compile_source("🧊 1 ▷ k ⨠ 2 ▷ k").unwrap();
+        assert!(verify(&program).is_ok());
+
+        // Whatever the compiler accepts, the verifier must too
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        for entry in std::fs::read_dir(root).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "ae") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            if let Ok(program) = Compiler::new().compile_source(&source) {
+                if let Err(err) = verify(&program) {
+                    panic!("{}: {}", path.display(), err);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_code() {
+        let mut program = BytecodeProgram::assemble("PushString \"a\"").unwrap();
+        program.code.truncate(3);
+        assert!(verify(&program).unwrap_err().to_string().contains("Truncated PushString operand at position 0"));
+
+        assert!(rejects("Pop\n.byte 0xEE").contains("Unknown opcode: 0xEE at position 1"));
+        assert!(rejects("PushString #3").contains("PushString at position 0: constant index 3 is outside the pool of 0"));
+        assert!(rejects("Jump @2\nPushNull").contains("Jump at position 0: target 2 is inside an instruction"));
+        assert!(rejects("Jump @99").contains("target 99 is past the end of the code (5)"));
+        assert!(rejects("PushNull\nForEach \"i\"\nEnd").contains("ForEach at position 1: has no matching LoopEnd"));
+    }
+
+    #[test]
+    fn test_rejects_stack_underflow_on_any_path() {
+        assert!(rejects("PushInteger 1\nAdd").contains("Add at position 9: needs 2 stack value(s) but only 1 available"));
+        // Only the path that skips the push underflows
+        assert!(rejects("
+            PushBool true
+            JumpIfFalse L0
+            PushNull
+        L0:
+            Output
+        ").contains("Output at position 8: needs 1 stack value(s) but only 0 available"));
+        // Each trip around the loop pops one more value than it pushes
+        assert!(rejects("
+            PushNull
+            PushNull
+        L0:
+            Pop
+            Jump L0
+        ").contains("Pop at position 2: needs 1 stack value(s) but only 0 available"));
+        // Code that is never reached is not stack checked
+        let program = BytecodeProgram::assemble("Jump L0\nPop\nL0:\nEnd").unwrap();
+        assert!(verify(&program).is_ok());
+    }
+}